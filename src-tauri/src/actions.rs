@@ -1,4 +1,8 @@
+use crate::audio_toolkit::diarization::{match_speaker, update_centroid};
+use crate::audio_toolkit::{SileroVad, SpeakerEmbedder, TimedWord, VoiceActivityDetector};
 use crate::managers::audio::AudioRecordingManager;
+use crate::managers::model::{ModelManager, SPEAKER_MODEL_ID};
+use crate::managers::session::{SessionManager, TranscriptSegment};
 use crate::managers::transcription::TranscriptionManager;
 use log::{debug, error, info};
 use std::sync::Arc;
@@ -30,8 +34,7 @@ pub async fn run_session_transcription_loop(
 ) {
     use crate::audio_toolkit::pipeline::{ChannelMode, Pipeline};
     use crate::audio_toolkit::text::{is_duplicate_segment, remove_prefix_overlap};
-    use crate::audio_toolkit::vad::SileroVad;
-    use crate::managers::session::{SessionAmplitudeEvent, SessionManager};
+    use crate::managers::session::SessionAmplitudeEvent;
     use tokio::time::{interval, Duration};
 
     let sm = app.state::<Arc<SessionManager>>();
@@ -47,41 +50,16 @@ pub async fn run_session_transcription_loop(
         speaker_energy_threshold, skip_mic_on_speaker_energy
     );
 
-    let aec = match crate::aec::AEC::new() {
-        Ok(a) => {
-            log::info!("AEC initialized successfully");
-            Some(a)
-        }
-        Err(e) => {
-            log::warn!("AEC init failed, running without echo cancellation: {}", e);
-            None
-        }
-    };
+    let aec = load_aec();
 
     // Initialize VAD for segmentation (does NOT filter audio, only detects speech transitions)
-    let vad: Option<Box<dyn crate::audio_toolkit::VoiceActivityDetector>> =
-        match app.path().resolve(
-            "resources/models/silero_vad_v4.onnx",
-            tauri::path::BaseDirectory::Resource,
-        ) {
-            Ok(vad_path) => match SileroVad::new(&vad_path, 0.15).map(|v| v.with_smoothing(2, 13)) {
-                Ok(silero) => {
-                    log::info!("VAD initialized successfully");
-                    Some(Box::new(silero))
-                }
-                Err(e) => {
-                    log::warn!(
-                        "VAD init failed, running without voice activity detection: {}",
-                        e
-                    );
-                    None
-                }
-            },
-            Err(e) => {
-                log::warn!("Failed to resolve VAD model path: {}", e);
-                None
-            }
-        };
+    let vad = load_session_vad(&app);
+
+    // Speaker embeddings for diarizing the mic channel (optional)
+    let mut embedder = load_speaker_embedder(&app);
+
+    // Attendee names of the meeting this session was scheduled from
+    let vocabulary = session_vocabulary(&sm, &session_id);
 
     // Both mic and speaker streams are already resampled to 16kHz,
     // so Pipeline resamplers act as identity (16k→16k).
@@ -93,12 +71,26 @@ pub async fn run_session_transcription_loop(
         ChannelMode::MicAndSpeaker,
     );
 
+    // Archive the raw mic and speaker streams for this pass so they can be
+    // replayed or re-transcribed later
+    let mut archive = match sm.start_recording_archive(&session_id, time_offset_ms) {
+        Ok(a) => Some(a),
+        Err(e) => {
+            log::warn!(
+                "Failed to start recording archive, audio will not be saved: {}",
+                e
+            );
+            None
+        }
+    };
+
     let session_start = Instant::now();
+    // Lets bookmarks be placed at "now" on the session timeline
+    sm.start_recording_clock(session_start, time_offset_ms);
     let mut tick = interval(Duration::from_millis(POLL_INTERVAL_MS));
     let mut pending_spk_samples: Vec<f32> = Vec::new();
     let mut spk_silent_polls: u32 = 0;
     let mut mic_chunk_start: Instant = session_start;
-    let mut spk_chunk_start: Instant = session_start;
     // Track whether we have any mic samples accumulated in the pipeline
     let mut mic_has_samples = false;
     // Track previous mic transcription for prefix overlap removal
@@ -135,6 +127,9 @@ pub async fn run_session_transcription_loop(
             if rm.is_recording() {
                 let final_chunk = rm.take_session_chunk();
                 if !final_chunk.is_empty() {
+                    if let Some(a) = archive.as_mut() {
+                        a.write_mic(&final_chunk);
+                    }
                     pipeline.push_mic(&final_chunk);
                 }
             }
@@ -142,6 +137,9 @@ pub async fn run_session_transcription_loop(
             // Flush remaining speaker audio
             let final_spk = sm.take_speaker_samples();
             if !final_spk.is_empty() {
+                if let Some(a) = archive.as_mut() {
+                    a.write_speaker(&final_spk);
+                }
                 pipeline.push_spk(&final_spk);
                 pending_spk_samples.extend_from_slice(&final_spk);
             }
@@ -151,11 +149,20 @@ pub async fn run_session_transcription_loop(
 
             // Transcribe remaining speaker first (so we can dedupe mic against it)
             if !pending_spk_samples.is_empty() {
-                let start_ms = spk_chunk_start.duration_since(session_start).as_millis() as i64
-                    + time_offset_ms;
-                if let Ok(text) = tm.transcribe_chunk(std::mem::take(&mut pending_spk_samples)) {
-                    if !text.is_empty() {
-                        let _ = sm.add_segment(&session_id, text, "speaker", start_ms, now);
+                let audio_len = pending_spk_samples.len();
+                if let Ok(timed) =
+                    tm.transcribe_chunk_timed(std::mem::take(&mut pending_spk_samples), &vocabulary)
+                {
+                    if !timed.text.is_empty() {
+                        let _ = add_timed_segment(
+                            &sm,
+                            &session_id,
+                            "speaker",
+                            timed.text,
+                            &timed.words,
+                            audio_len,
+                            now,
+                        );
                     }
                 }
             }
@@ -168,7 +175,10 @@ pub async fn run_session_transcription_loop(
             if !remaining_mic.is_empty() {
                 let start_ms = mic_chunk_start.duration_since(session_start).as_millis() as i64
                     + time_offset_ms;
-                if let Ok(text) = tm.transcribe_chunk(remaining_mic) {
+                let audio_len = remaining_mic.len();
+                let diarize_audio = embedder.is_some().then(|| remaining_mic.clone());
+                if let Ok(timed) = tm.transcribe_chunk_timed(remaining_mic, &vocabulary) {
+                    let text = timed.text;
                     if !text.is_empty() {
                         // Check for duplicates against speaker segments
                         let is_dup = sm
@@ -190,7 +200,24 @@ pub async fn run_session_transcription_loop(
                             .unwrap_or(false);
 
                         if !is_dup {
-                            let _ = sm.add_segment(&session_id, text, "mic", start_ms, now);
+                            let added = add_timed_segment(
+                                &sm,
+                                &session_id,
+                                "mic",
+                                text,
+                                &timed.words,
+                                audio_len,
+                                now,
+                            );
+                            if let (Ok(segment), Some(audio)) = (added, diarize_audio) {
+                                diarize_segment(
+                                    &sm,
+                                    &mut embedder,
+                                    &session_id,
+                                    segment.id,
+                                    &audio,
+                                );
+                            }
                         } else {
                             debug!("Skipping duplicate mic segment (final flush)");
                         }
@@ -198,6 +225,16 @@ pub async fn run_session_transcription_loop(
                 }
             }
 
+            if let Some(a) = archive.take() {
+                if let Err(e) = sm.finish_recording_archive(a) {
+                    error!("Failed to finish recording archive: {}", e);
+                }
+            }
+            if let Err(e) = sm.cleanup_old_recordings() {
+                error!("Failed to clean up old recordings: {}", e);
+            }
+
+            sm.stop_recording_clock(session_start);
             debug!("Session transcription loop ended for {}", session_id);
             let _ = app.emit("transcription-flush-complete", &session_id);
             break;
@@ -210,6 +247,9 @@ pub async fn run_session_transcription_loop(
                 mic_chunk_start = Instant::now();
             }
             mic_has_samples = true;
+            if let Some(a) = archive.as_mut() {
+                a.write_mic(&new_mic);
+            }
             pipeline.push_mic(&new_mic);
         }
 
@@ -224,8 +264,8 @@ pub async fn run_session_transcription_loop(
                 new_spk.len() as f32 / 16000.0,
                 (pending_spk_samples.len() + new_spk.len()) as f32 / 16000.0
             );
-            if pending_spk_samples.is_empty() {
-                spk_chunk_start = Instant::now();
+            if let Some(a) = archive.as_mut() {
+                a.write_speaker(&new_spk);
             }
             pipeline.push_spk(&new_spk);
             pending_spk_samples.extend_from_slice(&new_spk);
@@ -239,6 +279,10 @@ pub async fn run_session_transcription_loop(
             spk_silent_polls += 1;
         }
 
+        if let Some(a) = archive.as_mut() {
+            a.flush();
+        }
+
         // Poll pipeline for events (VAD transitions, amplitude updates)
         let pipeline_event = pipeline.poll_event();
 
@@ -358,12 +402,13 @@ pub async fn run_session_transcription_loop(
             // mic VAD triggers, speaker hasn't transcribed yet. Flush speaker first to
             // create segments that deduplication can find.
             if pending_spk_samples.len() >= MIN_CHUNK_SAMPLES / 4 {
-                let spk_start_ms = spk_chunk_start.duration_since(session_start).as_millis() as i64
-                    + time_offset_ms;
                 if !is_silence(&pending_spk_samples) {
-                    if let Ok(spk_text) =
-                        tm.transcribe_chunk(std::mem::take(&mut pending_spk_samples))
-                    {
+                    let audio_len = pending_spk_samples.len();
+                    if let Ok(timed) = tm.transcribe_chunk_timed(
+                        std::mem::take(&mut pending_spk_samples),
+                        &vocabulary,
+                    ) {
+                        let spk_text = timed.text;
                         if !spk_text.is_empty() {
                             info!(
                                 "Pre-flushed speaker audio for dedup: '{}'",
@@ -373,16 +418,21 @@ pub async fn run_session_transcription_loop(
                                     &spk_text
                                 }
                             );
-                            let _ =
-                                sm.add_segment(&session_id, spk_text, "speaker", spk_start_ms, now);
+                            let _ = add_timed_segment(
+                                &sm,
+                                &session_id,
+                                "speaker",
+                                spk_text,
+                                &timed.words,
+                                audio_len,
+                                now,
+                            );
                         }
                     }
                 } else {
                     pending_spk_samples.clear();
                 }
                 spk_silent_polls = 0;
-                // Reset speaker chunk start after pre-flush
-                spk_chunk_start = Instant::now();
             }
 
             let start_ms =
@@ -430,8 +480,10 @@ pub async fn run_session_transcription_loop(
             }
 
             let audio_len = mic_audio.len();
-            match tm.transcribe_chunk(mic_audio) {
-                Ok(text) => {
+            let diarize_audio = embedder.is_some().then(|| mic_audio.clone());
+            match tm.transcribe_chunk_timed(mic_audio, &vocabulary) {
+                Ok(timed) => {
+                    let text = timed.text;
                     info!(
                         "Transcription result: {} samples -> '{}' ({} chars)",
                         audio_len,
@@ -472,13 +524,28 @@ pub async fn run_session_transcription_loop(
                                 .unwrap_or(false);
 
                             if !is_dup {
-                                let _ = sm.add_segment(
+                                // Overlap removal only drops leading words, so the
+                                // remaining words keep the tail of the timings
+                                let kept = deduped_text.split_whitespace().count();
+                                let words = &timed.words[timed.words.len().saturating_sub(kept)..];
+                                let added = add_timed_segment(
+                                    &sm,
                                     &session_id,
-                                    deduped_text.clone(),
                                     "mic",
-                                    start_ms,
+                                    deduped_text.clone(),
+                                    words,
+                                    audio_len,
                                     now,
                                 );
+                                if let (Ok(segment), Some(audio)) = (added, &diarize_audio) {
+                                    diarize_segment(
+                                        &sm,
+                                        &mut embedder,
+                                        &session_id,
+                                        segment.id,
+                                        audio,
+                                    );
+                                }
                                 // Update previous text for next overlap removal
                                 previous_mic_text = text;
                             } else {
@@ -507,13 +574,19 @@ pub async fn run_session_transcription_loop(
                 continue;
             }
 
-            let start_ms =
-                spk_chunk_start.duration_since(session_start).as_millis() as i64 + time_offset_ms;
-
-            match tm.transcribe_chunk(std::mem::take(&mut pending_spk_samples)) {
-                Ok(text) => {
-                    if !text.is_empty() {
-                        let _ = sm.add_segment(&session_id, text, "speaker", start_ms, now);
+            let audio_len = pending_spk_samples.len();
+            match tm.transcribe_chunk_timed(std::mem::take(&mut pending_spk_samples), &vocabulary) {
+                Ok(timed) => {
+                    if !timed.text.is_empty() {
+                        let _ = add_timed_segment(
+                            &sm,
+                            &session_id,
+                            "speaker",
+                            timed.text,
+                            &timed.words,
+                            audio_len,
+                            now,
+                        );
                     }
                 }
                 Err(e) => {
@@ -525,6 +598,749 @@ pub async fn run_session_transcription_loop(
     }
 }
 
+/// Transcribes an audio file into an existing session, emitting
+/// `audio-import-progress` while it runs and `audio-import-complete` at the end.
+///
+/// The file is decoded, resampled to 16 kHz and split into utterances with
+/// Silero VAD, so every segment's start_ms/end_ms matches its position in the
+/// file. With `split_stereo`, a two-channel file is transcribed as left = mic
+/// and right = speaker; otherwise all channels are mixed down to mic.
+///
+/// Runs synchronously - call it from a dedicated thread.
+pub fn run_audio_file_import(
+    app: AppHandle,
+    session_id: String,
+    file_path: String,
+    split_stereo: bool,
+) {
+    use crate::managers::session::AudioImportCompleteEvent;
+
+    let sm = app.state::<Arc<SessionManager>>();
+
+    let (segment_count, error) =
+        match import_audio_file(&app, &session_id, &file_path, split_stereo) {
+            Ok(count) => (count, None),
+            Err(e) => {
+                error!("Audio import of {} failed: {}", file_path, e);
+                (0, Some(e.to_string()))
+            }
+        };
+
+    let cancelled = sm.is_offline_job_cancelled();
+    sm.finish_offline_job();
+
+    info!(
+        "Audio import finished for {}: {} segments (cancelled: {})",
+        session_id, segment_count, cancelled
+    );
+    let _ = app.emit(
+        "audio-import-complete",
+        AudioImportCompleteEvent {
+            session_id,
+            segment_count,
+            cancelled,
+            error,
+        },
+    );
+}
+
+fn import_audio_file(
+    app: &AppHandle,
+    session_id: &str,
+    file_path: &str,
+    split_stereo: bool,
+) -> anyhow::Result<u32> {
+    use crate::audio_toolkit::audio::{decode_audio_file, resample_to_16k};
+    use crate::audio_toolkit::split_on_speech;
+    use crate::managers::session::AudioImportProgressEvent;
+
+    let sm = app.state::<Arc<SessionManager>>();
+    let tm = app.state::<Arc<TranscriptionManager>>();
+
+    let decoded = decode_audio_file(file_path)?;
+    let tracks: Vec<(&str, Vec<f32>)> = if split_stereo && decoded.channels.len() == 2 {
+        vec![
+            (
+                "mic",
+                resample_to_16k(&decoded.channels[0], decoded.sample_rate),
+            ),
+            (
+                "speaker",
+                resample_to_16k(&decoded.channels[1], decoded.sample_rate),
+            ),
+        ]
+    } else {
+        vec![(
+            "mic",
+            resample_to_16k(&decoded.to_mono(), decoded.sample_rate),
+        )]
+    };
+    drop(decoded);
+
+    let to_ms = |samples: usize| (samples * 1000 / WHISPER_RATE) as i64;
+    let total_samples: usize = tracks.iter().map(|(_, samples)| samples.len()).sum();
+    let mut processed_samples = 0usize;
+    let mut segment_count = 0u32;
+
+    let vad_path = app.path().resolve(
+        "resources/models/silero_vad_v4.onnx",
+        tauri::path::BaseDirectory::Resource,
+    )?;
+
+    let mut embedder = load_speaker_embedder(app);
+    let vocabulary = session_vocabulary(&sm, session_id);
+
+    for (source, samples) in &tracks {
+        let chunks = match SileroVad::new(&vad_path, 0.15).map(|v| v.with_smoothing(2, 13)) {
+            Ok(mut vad) => split_on_speech(
+                &mut vad as &mut dyn VoiceActivityDetector,
+                samples,
+                MIN_CHUNK_SAMPLES,
+                MAX_CHUNK_SAMPLES,
+            ),
+            Err(e) => {
+                log::warn!(
+                    "VAD init failed, importing in fixed {}s chunks: {}",
+                    MAX_CHUNK_SAMPLES / WHISPER_RATE,
+                    e
+                );
+                (0..samples.len())
+                    .step_by(MAX_CHUNK_SAMPLES)
+                    .map(|start| start..(start + MAX_CHUNK_SAMPLES).min(samples.len()))
+                    .collect()
+            }
+        };
+        debug!(
+            "Import {}: {} chunks from {}",
+            source,
+            chunks.len(),
+            file_path
+        );
+
+        for chunk in chunks {
+            if sm.is_offline_job_cancelled() {
+                info!("Audio import cancelled for {}", session_id);
+                return Ok(segment_count);
+            }
+
+            let audio = &samples[chunk.clone()];
+            if !is_silence(audio) {
+                let timed = tm.transcribe_chunk_timed(audio.to_vec(), &vocabulary)?;
+                if !timed.text.is_empty() {
+                    let segment = add_timed_segment(
+                        &sm,
+                        session_id,
+                        source,
+                        timed.text,
+                        &timed.words,
+                        audio.len(),
+                        to_ms(chunk.end),
+                    )?;
+                    if *source == "mic" {
+                        diarize_segment(&sm, &mut embedder, session_id, segment.id, audio);
+                    }
+                    segment_count += 1;
+                }
+            }
+
+            let _ = app.emit(
+                "audio-import-progress",
+                AudioImportProgressEvent {
+                    session_id: session_id.to_string(),
+                    processed_ms: to_ms(processed_samples + chunk.end),
+                    total_ms: to_ms(total_samples),
+                },
+            );
+        }
+
+        processed_samples += samples.len();
+    }
+
+    Ok(segment_count)
+}
+
+/// Re-transcribes a session's archived audio with `model_id`, storing the
+/// result as a new transcript revision. The live transcript is left untouched
+/// until the revision is applied. Emits `retranscription-progress` while it
+/// runs and `retranscription-complete` at the end.
+///
+/// Runs synchronously - call it from a dedicated thread.
+pub fn run_session_retranscription(app: AppHandle, session_id: String, model_id: String) {
+    use crate::managers::session::RetranscriptionCompleteEvent;
+
+    let sm = app.state::<Arc<SessionManager>>();
+    let tm = app.state::<Arc<TranscriptionManager>>();
+    let previous_model = tm.get_current_model();
+
+    let mut revision_id = None;
+    let result = replay_session_audio(&app, &session_id, &model_id, &mut revision_id);
+
+    // Hand the engine back in the state the job found it
+    match previous_model {
+        Some(previous) if previous != model_id => {
+            if let Err(e) = tm.load_model(&previous) {
+                error!(
+                    "Failed to reload {} after re-transcription: {}",
+                    previous, e
+                );
+            }
+        }
+        Some(_) => {}
+        None => {
+            if let Err(e) = tm.unload_model() {
+                error!("Failed to unload re-transcription model: {}", e);
+            }
+        }
+    }
+    sm.return_engine();
+
+    let cancelled = sm.is_offline_job_cancelled();
+    let (segment_count, error) = match result {
+        Ok(count) => (count, None),
+        Err(e) => {
+            error!("Re-transcription of {} failed: {}", session_id, e);
+            (0, Some(e.to_string()))
+        }
+    };
+
+    // Don't leave half-finished revisions behind
+    if cancelled || error.is_some() {
+        if let Some(id) = revision_id.take() {
+            if let Err(e) = sm.delete_transcript_revision(id) {
+                error!("Failed to delete partial transcript revision {}: {}", id, e);
+            }
+        }
+    }
+    sm.finish_offline_job();
+
+    info!(
+        "Re-transcription finished for {}: {} segments (cancelled: {})",
+        session_id, segment_count, cancelled
+    );
+    let _ = app.emit(
+        "retranscription-complete",
+        RetranscriptionCompleteEvent {
+            session_id,
+            revision_id,
+            segment_count,
+            cancelled,
+            error,
+        },
+    );
+}
+
+fn replay_session_audio(
+    app: &AppHandle,
+    session_id: &str,
+    model_id: &str,
+    revision_id: &mut Option<i64>,
+) -> anyhow::Result<u32> {
+    use crate::audio_toolkit::audio::{decode_audio_file, resample_to_16k};
+    use std::collections::BTreeMap;
+
+    let sm = app.state::<Arc<SessionManager>>();
+    let tm = app.state::<Arc<TranscriptionManager>>();
+
+    // Pair the archived mic/speaker files of each recording pass. Files of one
+    // pass share the `<timestamp>_` prefix of their file name. Encrypted files
+    // are decoded from a scratch copy that is removed right after.
+    let plaintext_dir =
+        std::env::temp_dir().join(format!("talky-retranscribe-{}", uuid::Uuid::new_v4()));
+    let mut passes: BTreeMap<(i64, String), (Vec<f32>, Vec<f32>)> = BTreeMap::new();
+    for recording in sm.get_audio_recordings(session_id)? {
+        let stem = recording
+            .file_name
+            .split('_')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        let decoded = sm
+            .plaintext_recording_path(&recording, &plaintext_dir)
+            .and_then(decode_audio_file);
+        let _ = std::fs::remove_dir_all(&plaintext_dir);
+        let decoded = decoded?;
+        let samples = resample_to_16k(&decoded.to_mono(), decoded.sample_rate);
+        let pass = passes.entry((recording.offset_ms, stem)).or_default();
+        if recording.channel == "speaker" {
+            pass.1 = samples;
+        } else {
+            pass.0 = samples;
+        }
+    }
+    if passes.is_empty() {
+        anyhow::bail!("No archived audio for this session");
+    }
+
+    if tm.get_current_model().as_deref() != Some(model_id) || !tm.is_model_loaded() {
+        tm.load_model(model_id)?;
+    }
+
+    let id = sm.create_transcript_revision(session_id, "retranscription", Some(model_id))?;
+    *revision_id = Some(id);
+
+    let total_ms = passes
+        .values()
+        .map(|(mic, spk)| (mic.len().max(spk.len()) * 1000 / WHISPER_RATE) as i64)
+        .sum();
+    let mut replay = SessionReplay {
+        app,
+        sm: &sm,
+        tm: &tm,
+        session_id,
+        revision_id: id,
+        embedder: load_speaker_embedder(app),
+        speakers: sm.get_speaker_centroids(session_id)?,
+        vocabulary: session_vocabulary(&sm, session_id),
+        speaker_segments: Vec::new(),
+        previous_mic_text: String::new(),
+        segment_count: 0,
+        processed_ms: 0,
+        total_ms,
+    };
+
+    for ((offset_ms, _), (mic, spk)) in &passes {
+        replay.replay_pass(mic, spk, *offset_ms)?;
+        if sm.is_offline_job_cancelled() {
+            info!("Re-transcription cancelled for {}", session_id);
+            break;
+        }
+    }
+
+    sm.store_revision_speakers(id, &replay.speakers)?;
+    Ok(replay.segment_count)
+}
+
+/// Feeds archived audio through the live pipeline as if it were arriving in
+/// real time, one poll interval at a time, and writes the segments into a
+/// transcript revision. Chunking, AEC, VAD triggers, speaker flushing and
+/// mic/speaker deduplication mirror `run_session_transcription_loop`, with the
+/// sample clock standing in for wall-clock time.
+struct SessionReplay<'a> {
+    app: &'a AppHandle,
+    sm: &'a SessionManager,
+    tm: &'a TranscriptionManager,
+    session_id: &'a str,
+    revision_id: i64,
+    embedder: Option<SpeakerEmbedder>,
+    /// (label, centroid, segment count), starting from the session's stored
+    /// speakers. Clustered here rather than in `session_speakers` so the live
+    /// speakers only change if the revision is applied.
+    speakers: Vec<(String, Vec<f32>, i64)>,
+    vocabulary: Vec<String>,
+    /// (text, start_ms, end_ms) of speaker segments, for mic echo deduplication
+    speaker_segments: Vec<(String, i64, i64)>,
+    previous_mic_text: String,
+    segment_count: u32,
+    processed_ms: i64,
+    total_ms: i64,
+}
+
+impl SessionReplay<'_> {
+    fn replay_pass(&mut self, mic: &[f32], spk: &[f32], offset_ms: i64) -> anyhow::Result<()> {
+        use crate::audio_toolkit::pipeline::{ChannelMode, Pipeline};
+        use crate::managers::session::RetranscriptionProgressEvent;
+
+        let settings = crate::settings::get_settings(self.app);
+        let mut pipeline = Pipeline::new(
+            WHISPER_RATE as u32,
+            WHISPER_RATE as u32,
+            load_session_vad(self.app),
+            load_aec(),
+            ChannelMode::MicAndSpeaker,
+        );
+
+        let step = WHISPER_RATE * POLL_INTERVAL_MS as usize / 1000;
+        let total = mic.len().max(spk.len());
+        let to_ms = |samples: usize| offset_ms + (samples * 1000 / WHISPER_RATE) as i64;
+
+        let mut pos = 0usize;
+        let mut pending_spk_samples: Vec<f32> = Vec::new();
+        let mut spk_silent_polls: u32 = 0;
+        let mut mic_has_samples = false;
+        // Each pass starts a fresh overlap window, as a new recording would
+        self.previous_mic_text.clear();
+
+        while pos < total {
+            if self.sm.is_offline_job_cancelled() {
+                return Ok(());
+            }
+
+            let end = (pos + step).min(total);
+            let new_mic = &mic[pos.min(mic.len())..end.min(mic.len())];
+            let new_spk = &spk[pos.min(spk.len())..end.min(spk.len())];
+            pos = end;
+            let now = to_ms(pos);
+
+            if !new_mic.is_empty() {
+                mic_has_samples = true;
+                pipeline.push_mic(new_mic);
+            }
+
+            if !new_spk.is_empty() {
+                pipeline.push_spk(new_spk);
+                pending_spk_samples.extend_from_slice(new_spk);
+                if is_silence(new_spk) {
+                    spk_silent_polls += 1;
+                } else {
+                    spk_silent_polls = 0;
+                }
+            } else {
+                spk_silent_polls += 1;
+            }
+
+            let pipeline_event = pipeline.poll_event();
+
+            let accumulated = pipeline.accumulated_mic_len();
+            let force_flush = accumulated >= MAX_CHUNK_SAMPLES;
+            let vad_trigger = accumulated >= MIN_CHUNK_SAMPLES && pipeline_event.mic_speech_ended;
+
+            if mic_has_samples && (force_flush || vad_trigger) {
+                // Flush speaker first so the mic chunk can be deduplicated against it
+                if pending_spk_samples.len() >= MIN_CHUNK_SAMPLES / 4 {
+                    if !is_silence(&pending_spk_samples) {
+                        self.transcribe_speaker(std::mem::take(&mut pending_spk_samples), now)?;
+                    } else {
+                        pending_spk_samples.clear();
+                    }
+                    spk_silent_polls = 0;
+                }
+
+                pipeline.apply_aec_to_accumulated();
+
+                let mic_audio = if settings.skip_mic_on_speaker_energy {
+                    const WINDOW_MS: usize = 400;
+                    let (filtered_mic, windows_zeroed) = pipeline.take_filtered_mic(
+                        settings.speaker_energy_threshold,
+                        WINDOW_MS,
+                        OVERLAP_SAMPLES,
+                    );
+                    let total_windows =
+                        (filtered_mic.len().saturating_sub(1) / (WINDOW_MS * 16) + 1).max(1);
+                    if windows_zeroed == total_windows && total_windows > 1 {
+                        mic_has_samples = false;
+                        continue;
+                    }
+                    filtered_mic
+                } else {
+                    let (mic, _spk) = pipeline.take_with_overlap(OVERLAP_SAMPLES);
+                    mic
+                };
+
+                if !is_silence(&mic_audio) {
+                    self.transcribe_mic(mic_audio, now, true)?;
+                }
+                mic_has_samples = false;
+            }
+
+            let spk_should_transcribe = pending_spk_samples.len() >= MAX_CHUNK_SAMPLES
+                || (pending_spk_samples.len() >= MIN_CHUNK_SAMPLES
+                    && spk_silent_polls >= SPK_SILENCE_FLUSH_POLLS);
+            if spk_should_transcribe {
+                if is_silence(&pending_spk_samples) {
+                    pending_spk_samples.clear();
+                } else {
+                    self.transcribe_speaker(std::mem::take(&mut pending_spk_samples), now)?;
+                }
+                spk_silent_polls = 0;
+            }
+
+            let _ = self.app.emit(
+                "retranscription-progress",
+                RetranscriptionProgressEvent {
+                    session_id: self.session_id.to_string(),
+                    processed_ms: self.processed_ms + to_ms(pos) - offset_ms,
+                    total_ms: self.total_ms,
+                },
+            );
+        }
+
+        // End of the pass: flush what's left, speaker first
+        let now = to_ms(total);
+        if !pending_spk_samples.is_empty() {
+            self.transcribe_speaker(std::mem::take(&mut pending_spk_samples), now)?;
+        }
+        pipeline.apply_aec_to_accumulated();
+        let (remaining_mic, _remaining_spk) = pipeline.take_all_accumulated();
+        if !remaining_mic.is_empty() {
+            self.transcribe_mic(remaining_mic, now, false)?;
+        }
+
+        self.processed_ms += to_ms(total) - offset_ms;
+        Ok(())
+    }
+
+    fn transcribe_speaker(&mut self, audio: Vec<f32>, end_ms: i64) -> anyhow::Result<()> {
+        let audio_len = audio.len();
+        let timed = self.tm.transcribe_chunk_timed(audio, &self.vocabulary)?;
+        if timed.text.is_empty() {
+            return Ok(());
+        }
+
+        let (start_ms, end_ms, words) = place_words(&timed.words, audio_len, end_ms);
+        self.speaker_segments
+            .push((timed.text.clone(), start_ms, end_ms));
+        self.add_segment("speaker", timed.text, None, start_ms, end_ms, &words)
+    }
+
+    /// `remove_overlap` matches the live loop: overlap trimming applies to
+    /// regular chunks but not to the final flush.
+    fn transcribe_mic(
+        &mut self,
+        audio: Vec<f32>,
+        end_ms: i64,
+        remove_overlap: bool,
+    ) -> anyhow::Result<()> {
+        use crate::audio_toolkit::text::{is_duplicate_segment, remove_prefix_overlap};
+
+        let audio_len = audio.len();
+        let chunk_start_ms = end_ms - (audio_len * 1000 / WHISPER_RATE) as i64;
+        let diarize_audio = self.embedder.is_some().then(|| audio.clone());
+        let timed = self.tm.transcribe_chunk_timed(audio, &self.vocabulary)?;
+        if timed.text.is_empty() {
+            return Ok(());
+        }
+
+        let text = if remove_overlap && !self.previous_mic_text.is_empty() {
+            remove_prefix_overlap(&timed.text, &self.previous_mic_text, 2)
+        } else {
+            timed.text.clone()
+        };
+        if text.is_empty() {
+            return Ok(());
+        }
+
+        // Speaker channel is authoritative - skip mic if it's just echo
+        let is_dup = self
+            .speaker_segments
+            .iter()
+            .filter(|(_, _, seg_end)| *seg_end >= chunk_start_ms - 5000)
+            .any(|(seg_text, seg_start, seg_end)| {
+                is_duplicate_segment(
+                    &text,
+                    chunk_start_ms,
+                    end_ms,
+                    seg_text,
+                    *seg_start,
+                    *seg_end,
+                    0.80, // similarity threshold
+                    300,  // time overlap threshold in ms
+                )
+            });
+        if is_dup {
+            debug!("Skipping duplicate mic segment (re-transcription)");
+            return Ok(());
+        }
+
+        let kept = text.split_whitespace().count();
+        let words = &timed.words[timed.words.len().saturating_sub(kept)..];
+        let (start_ms, end_ms, words) = place_words(words, audio_len, end_ms);
+
+        let speaker = match (self.embedder.as_mut(), diarize_audio) {
+            (Some(embedder), Some(audio)) if audio.len() >= MIN_CHUNK_SAMPLES => embedder
+                .embed(&audio)
+                .map(|embedding| identify_replay_speaker(&mut self.speakers, &embedding))
+                .map_err(|e| log::warn!("Speaker identification failed: {}", e))
+                .ok(),
+            _ => None,
+        };
+
+        if remove_overlap {
+            self.previous_mic_text = timed.text;
+        }
+        self.add_segment("mic", text, speaker, start_ms, end_ms, &words)
+    }
+
+    fn add_segment(
+        &mut self,
+        source: &str,
+        text: String,
+        speaker: Option<String>,
+        start_ms: i64,
+        end_ms: i64,
+        words: &[TimedWord],
+    ) -> anyhow::Result<()> {
+        use crate::managers::session::TranscriptRevisionSegment;
+
+        self.sm.add_revision_segment(
+            &TranscriptRevisionSegment {
+                id: 0,
+                revision_id: self.revision_id,
+                text,
+                source: source.to_string(),
+                start_ms,
+                end_ms,
+                speaker,
+            },
+            words,
+        )?;
+        self.segment_count += 1;
+        Ok(())
+    }
+}
+
+/// One step of online clustering over a replay's own speakers, mirroring
+/// `SessionManager::identify_speaker`. Returns the speaker label.
+fn identify_replay_speaker(
+    speakers: &mut Vec<(String, Vec<f32>, i64)>,
+    embedding: &[f32],
+) -> String {
+    let centroids: Vec<Vec<f32>> = speakers.iter().map(|(_, c, _)| c.clone()).collect();
+    match match_speaker(&centroids, embedding) {
+        Some(index) => {
+            let (label, centroid, count) = &mut speakers[index];
+            update_centroid(centroid, embedding, *count as u32);
+            *count += 1;
+            label.clone()
+        }
+        None => {
+            let label = format!("Speaker {}", speakers.len() + 1);
+            speakers.push((label.clone(), embedding.to_vec(), 1));
+            label
+        }
+    }
+}
+
+/// Stores a transcribed chunk as a segment with its word timings moved onto the
+/// session timeline. The chunk's audio is taken to end at `end_ms`; the segment
+/// spans its first to last word, or the whole chunk if the engine gave no timings.
+fn add_timed_segment(
+    sm: &SessionManager,
+    session_id: &str,
+    source: &str,
+    text: String,
+    words: &[TimedWord],
+    audio_len: usize,
+    end_ms: i64,
+) -> anyhow::Result<TranscriptSegment> {
+    let (start_ms, end_ms, words) = place_words(words, audio_len, end_ms);
+    sm.add_segment_with_words(session_id, text, source, start_ms, end_ms, &words)
+}
+
+/// Moves chunk-relative word timings onto the session timeline, given that the
+/// chunk's `audio_len` samples end at `end_ms`. Returns the segment span (first
+/// to last word, or the whole chunk without timings) and the placed words.
+fn place_words(words: &[TimedWord], audio_len: usize, end_ms: i64) -> (i64, i64, Vec<TimedWord>) {
+    let origin_ms = end_ms - (audio_len * 1000 / WHISPER_RATE) as i64;
+    let words: Vec<TimedWord> = words
+        .iter()
+        .map(|w| TimedWord {
+            text: w.text.clone(),
+            start_ms: origin_ms + w.start_ms,
+            end_ms: origin_ms + w.end_ms,
+            estimated: w.estimated,
+        })
+        .collect();
+
+    let (start_ms, end_ms) = match (words.first(), words.last()) {
+        (Some(first), Some(last)) => (first.start_ms, last.end_ms.max(first.start_ms)),
+        _ => (origin_ms, end_ms),
+    };
+    (start_ms, end_ms, words)
+}
+
+fn load_aec() -> Option<crate::aec::AEC> {
+    match crate::aec::AEC::new() {
+        Ok(a) => {
+            log::info!("AEC initialized successfully");
+            Some(a)
+        }
+        Err(e) => {
+            log::warn!("AEC init failed, running without echo cancellation: {}", e);
+            None
+        }
+    }
+}
+
+/// Silero VAD tuned for session segmentation (used by the live loop and replays)
+fn load_session_vad(app: &AppHandle) -> Option<Box<dyn VoiceActivityDetector>> {
+    let vad_path = match app.path().resolve(
+        "resources/models/silero_vad_v4.onnx",
+        tauri::path::BaseDirectory::Resource,
+    ) {
+        Ok(path) => path,
+        Err(e) => {
+            log::warn!("Failed to resolve VAD model path: {}", e);
+            return None;
+        }
+    };
+
+    match SileroVad::new(&vad_path, 0.15).map(|v| v.with_smoothing(2, 13)) {
+        Ok(silero) => {
+            log::info!("VAD initialized successfully");
+            Some(Box::new(silero))
+        }
+        Err(e) => {
+            log::warn!(
+                "VAD init failed, running without voice activity detection: {}",
+                e
+            );
+            None
+        }
+    }
+}
+
+/// Loads the speaker-embedding model used to diarize the mic channel.
+/// Diarization is skipped when the model isn't downloaded or fails to load.
+fn load_speaker_embedder(app: &AppHandle) -> Option<SpeakerEmbedder> {
+    let mm = app.state::<Arc<ModelManager>>();
+    let Ok(path) = mm.get_model_path(SPEAKER_MODEL_ID) else {
+        info!("Speaker embedding model not downloaded, mic diarization disabled");
+        return None;
+    };
+
+    match SpeakerEmbedder::new(&path) {
+        Ok(embedder) => {
+            info!("Speaker embedding model loaded");
+            Some(embedder)
+        }
+        Err(e) => {
+            log::warn!(
+                "Speaker embedding init failed, mic diarization disabled: {}",
+                e
+            );
+            None
+        }
+    }
+}
+
+/// Words from the attendee names of the session's calendar event, so the
+/// transcriber corrects towards how participants' names are spelled.
+pub fn session_vocabulary(sm: &SessionManager, session_id: &str) -> Vec<String> {
+    match sm.get_session_attendee_names(session_id) {
+        Ok(names) => crate::managers::calendar::name_vocabulary(&names),
+        Err(e) => {
+            log::warn!("Failed to load attendees for {}: {}", session_id, e);
+            Vec::new()
+        }
+    }
+}
+
+/// Labels a mic segment with a session speaker. Errors only cost the label,
+/// so they are logged rather than returned.
+fn diarize_segment(
+    sm: &SessionManager,
+    embedder: &mut Option<SpeakerEmbedder>,
+    session_id: &str,
+    segment_id: i64,
+    audio: &[f32],
+) {
+    let Some(embedder) = embedder.as_mut() else {
+        return;
+    };
+    if audio.len() < MIN_CHUNK_SAMPLES {
+        return;
+    }
+
+    match embedder.embed(audio) {
+        Ok(embedding) => {
+            if let Err(e) = sm.assign_segment_speaker(session_id, segment_id, &embedding) {
+                error!("Failed to assign speaker to segment {}: {}", segment_id, e);
+            }
+        }
+        Err(e) => log::warn!("Speaker embedding failed for segment {}: {}", segment_id, e),
+    }
+}
+
 /// Returns true if the chunk's RMS energy is below a quiet threshold (~-40 dB).
 fn is_silence(samples: &[f32]) -> bool {
     if samples.is_empty() {
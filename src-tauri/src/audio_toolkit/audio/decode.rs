@@ -0,0 +1,151 @@
+use anyhow::Result;
+use log::{debug, warn};
+use std::fs::File;
+use std::path::Path;
+use std::time::Duration;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+use super::FrameResampler;
+
+/// Audio decoded from a file, kept planar so channels can be routed separately
+pub struct DecodedAudio {
+    pub sample_rate: u32,
+    pub channels: Vec<Vec<f32>>,
+}
+
+impl DecodedAudio {
+    /// Average all channels down to a single mono channel
+    pub fn to_mono(&self) -> Vec<f32> {
+        match self.channels.len() {
+            0 => Vec::new(),
+            1 => self.channels[0].clone(),
+            n => {
+                let len = self.channels.iter().map(|c| c.len()).min().unwrap_or(0);
+                (0..len)
+                    .map(|i| self.channels.iter().map(|c| c[i]).sum::<f32>() / n as f32)
+                    .collect()
+            }
+        }
+    }
+
+    pub fn duration_ms(&self) -> i64 {
+        let frames = self.channels.first().map(|c| c.len()).unwrap_or(0);
+        (frames as i64 * 1000) / self.sample_rate.max(1) as i64
+    }
+}
+
+/// Decode a WAV, FLAC, MP3 or OGG/Vorbis file into f32 samples.
+/// Corrupt packets are skipped rather than failing the whole file.
+pub fn decode_audio_file<P: AsRef<Path>>(file_path: P) -> Result<DecodedAudio> {
+    let path = file_path.as_ref();
+    let file = File::open(path)?;
+    let mss = MediaSourceStream::new(Box::new(file), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(ext);
+    }
+
+    let probed = symphonia::default::get_probe().format(
+        &hint,
+        mss,
+        &FormatOptions::default(),
+        &MetadataOptions::default(),
+    )?;
+    let mut format = probed.format;
+
+    let track = format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| anyhow::anyhow!("No audio track found in {:?}", path))?;
+    let track_id = track.id;
+    let sample_rate = track
+        .codec_params
+        .sample_rate
+        .ok_or_else(|| anyhow::anyhow!("Unknown sample rate in {:?}", path))?;
+
+    let mut decoder =
+        symphonia::default::get_codecs().make(&track.codec_params, &DecoderOptions::default())?;
+
+    let mut channels: Vec<Vec<f32>> = Vec::new();
+    let mut sample_buf: Option<SampleBuffer<f32>> = None;
+
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                break;
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            Err(SymphoniaError::DecodeError(e)) => {
+                warn!("Skipping undecodable packet in {:?}: {}", path, e);
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let spec = *decoded.spec();
+        let channel_count = spec.channels.count();
+        if channels.len() != channel_count {
+            channels.resize(channel_count, Vec::new());
+        }
+
+        let needed = decoded.capacity() * channel_count;
+        if sample_buf.as_ref().map(|b| b.capacity()).unwrap_or(0) < needed {
+            sample_buf = Some(SampleBuffer::new(decoded.capacity() as u64, spec));
+        }
+        let buf = sample_buf.as_mut().unwrap();
+        buf.copy_interleaved_ref(decoded);
+
+        for frame in buf.samples().chunks(channel_count) {
+            for (channel, sample) in channels.iter_mut().zip(frame) {
+                channel.push(*sample);
+            }
+        }
+    }
+
+    debug!(
+        "Decoded {:?}: {} channel(s) at {} Hz, {} frames",
+        path,
+        channels.len(),
+        sample_rate,
+        channels.first().map(|c| c.len()).unwrap_or(0)
+    );
+
+    Ok(DecodedAudio {
+        sample_rate,
+        channels,
+    })
+}
+
+/// Resample a whole buffer to 16 kHz with the same resampler used for live capture.
+/// The zero padding `FrameResampler::finish` adds is trimmed off so the output
+/// length matches the input duration.
+pub fn resample_to_16k(samples: &[f32], in_hz: u32) -> Vec<f32> {
+    const OUT_HZ: usize = 16000;
+
+    let expected_len = (samples.len() as u64 * OUT_HZ as u64 / in_hz.max(1) as u64) as usize;
+    let mut out = Vec::with_capacity(expected_len + OUT_HZ / 10);
+    let mut resampler = FrameResampler::new(in_hz as usize, OUT_HZ, Duration::from_millis(30));
+
+    resampler.push(samples, |frame| out.extend_from_slice(frame));
+    resampler.finish(|frame| out.extend_from_slice(frame));
+
+    out.truncate(expected_len);
+    out
+}
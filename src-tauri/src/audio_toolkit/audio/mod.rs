@@ -1,12 +1,14 @@
 // Re-export all audio components
+mod decode;
 mod device;
 mod recorder;
 mod resampler;
 mod utils;
 mod visualizer;
 
+pub use decode::{decode_audio_file, resample_to_16k, DecodedAudio};
 pub use device::{list_input_devices, list_output_devices, CpalDeviceInfo};
 pub use recorder::AudioRecorder;
 pub use resampler::FrameResampler;
-pub use utils::save_wav_file;
+pub use utils::{save_wav_file, WavStreamWriter};
 pub use visualizer::AudioVisualiser;
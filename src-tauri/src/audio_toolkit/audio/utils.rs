@@ -1,16 +1,23 @@
 use anyhow::Result;
 use hound::{WavSpec, WavWriter};
 use log::debug;
+use std::fs::File;
+use std::io::BufWriter;
 use std::path::Path;
 
-/// Save audio samples as a WAV file
-pub async fn save_wav_file<P: AsRef<Path>>(file_path: P, samples: &[f32]) -> Result<()> {
-    let spec = WavSpec {
+/// 16 kHz mono 16-bit PCM, the format used for every WAV file we write
+fn whisper_wav_spec() -> WavSpec {
+    WavSpec {
         channels: 1,
         sample_rate: 16000,
         bits_per_sample: 16,
         sample_format: hound::SampleFormat::Int,
-    };
+    }
+}
+
+/// Save audio samples as a WAV file
+pub async fn save_wav_file<P: AsRef<Path>>(file_path: P, samples: &[f32]) -> Result<()> {
+    let spec = whisper_wav_spec();
 
     let mut writer = WavWriter::create(file_path.as_ref(), spec)?;
 
@@ -24,3 +31,45 @@ pub async fn save_wav_file<P: AsRef<Path>>(file_path: P, samples: &[f32]) -> Res
     debug!("Saved WAV file: {:?}", file_path.as_ref());
     Ok(())
 }
+
+/// Incrementally writes 16 kHz mono samples to a WAV file so long recordings
+/// never have to be held in memory. `flush` rewrites the header, so the file
+/// stays playable even if the app exits before `finalize` is called.
+pub struct WavStreamWriter {
+    writer: WavWriter<BufWriter<File>>,
+    samples_written: u64,
+}
+
+impl WavStreamWriter {
+    pub fn create<P: AsRef<Path>>(file_path: P) -> Result<Self> {
+        let writer = WavWriter::create(file_path.as_ref(), whisper_wav_spec())?;
+        Ok(Self {
+            writer,
+            samples_written: 0,
+        })
+    }
+
+    pub fn write(&mut self, samples: &[f32]) -> Result<()> {
+        for sample in samples {
+            let sample_i16 = (sample * i16::MAX as f32) as i16;
+            self.writer.write_sample(sample_i16)?;
+        }
+        self.samples_written += samples.len() as u64;
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    pub fn samples_written(&self) -> u64 {
+        self.samples_written
+    }
+
+    /// Finish the file and return the total number of samples written
+    pub fn finalize(self) -> Result<u64> {
+        self.writer.finalize()?;
+        Ok(self.samples_written)
+    }
+}
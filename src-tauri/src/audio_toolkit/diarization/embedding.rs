@@ -0,0 +1,51 @@
+use anyhow::Result;
+use ndarray::Axis;
+use ort::session::builder::GraphOptimizationLevel;
+use ort::{session::Session, value::TensorRef};
+use std::path::Path;
+
+use super::fbank::compute_fbank;
+
+/// Speaker-embedding model run through ONNX Runtime.
+///
+/// Expects a WeSpeaker-style export: one input of shape (batch, frames, 80)
+/// holding fbank features and one output of shape (batch, dim) holding the
+/// embedding.
+pub struct SpeakerEmbedder {
+    session: Session,
+}
+
+impl SpeakerEmbedder {
+    pub fn new<P: AsRef<Path>>(model_path: P) -> Result<Self> {
+        let session = Session::builder()?
+            .with_intra_threads(1)?
+            .with_inter_threads(1)?
+            .with_optimization_level(GraphOptimizationLevel::Level3)?
+            .commit_from_file(model_path)?;
+        Ok(Self { session })
+    }
+
+    /// Compute an L2-normalized voice embedding for a chunk of 16 kHz audio
+    pub fn embed(&mut self, samples: &[f32]) -> Result<Vec<f32>> {
+        let feats = compute_fbank(samples);
+        if feats.nrows() == 0 {
+            anyhow::bail!("Audio too short for a speaker embedding");
+        }
+        let input = feats.insert_axis(Axis(0));
+
+        let outputs = self
+            .session
+            .run(ort::inputs![TensorRef::from_array_view(input.view())?])?;
+        let mut embedding: Vec<f32> = outputs[0]
+            .try_extract_array::<f32>()?
+            .iter()
+            .copied()
+            .collect();
+
+        let norm = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            embedding.iter_mut().for_each(|x| *x /= norm);
+        }
+        Ok(embedding)
+    }
+}
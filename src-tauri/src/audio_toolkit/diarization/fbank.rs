@@ -0,0 +1,112 @@
+use ndarray::Array2;
+use realfft::num_complex::Complex;
+use realfft::RealFftPlanner;
+
+const SAMPLE_RATE: f32 = 16000.0;
+const FRAME_LENGTH: usize = 400; // 25ms
+const FRAME_SHIFT: usize = 160; // 10ms
+const FFT_SIZE: usize = 512;
+const PREEMPHASIS: f32 = 0.97;
+const LOW_FREQ: f32 = 20.0;
+
+/// Number of mel bins produced by `compute_fbank`
+pub const NUM_MEL_BINS: usize = 80;
+
+fn mel_scale(freq: f32) -> f32 {
+    1127.0 * (1.0 + freq / 700.0).ln()
+}
+
+/// Triangular mel filters over the FFT bins (Kaldi layout, Nyquist bin excluded)
+fn mel_banks() -> Vec<Vec<f32>> {
+    let num_fft_bins = FFT_SIZE / 2;
+    let fft_bin_width = SAMPLE_RATE / FFT_SIZE as f32;
+    let mel_low = mel_scale(LOW_FREQ);
+    let mel_high = mel_scale(SAMPLE_RATE / 2.0);
+    let mel_delta = (mel_high - mel_low) / (NUM_MEL_BINS + 1) as f32;
+
+    (0..NUM_MEL_BINS)
+        .map(|bin| {
+            let left = mel_low + bin as f32 * mel_delta;
+            let center = left + mel_delta;
+            let right = center + mel_delta;
+            (0..num_fft_bins)
+                .map(|i| {
+                    let mel = mel_scale(fft_bin_width * i as f32);
+                    if mel > left && mel < right {
+                        if mel <= center {
+                            (mel - left) / (center - left)
+                        } else {
+                            (right - mel) / (right - center)
+                        }
+                    } else {
+                        0.0
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Compute Kaldi-compatible log mel filterbank features for 16 kHz audio,
+/// with per-utterance mean normalization. This is the input speaker-embedding
+/// models trained with WeSpeaker expect.
+///
+/// Returns an array of shape (frames, `NUM_MEL_BINS`). Audio shorter than one
+/// 25ms frame produces zero frames.
+pub fn compute_fbank(samples: &[f32]) -> Array2<f32> {
+    if samples.len() < FRAME_LENGTH {
+        return Array2::zeros((0, NUM_MEL_BINS));
+    }
+    let num_frames = 1 + (samples.len() - FRAME_LENGTH) / FRAME_SHIFT;
+
+    let window: Vec<f32> = (0..FRAME_LENGTH)
+        .map(|i| {
+            0.54 - 0.46 * (2.0 * std::f32::consts::PI * i as f32 / (FRAME_LENGTH - 1) as f32).cos()
+        })
+        .collect();
+    let banks = mel_banks();
+
+    let mut planner = RealFftPlanner::<f32>::new();
+    let fft = planner.plan_fft_forward(FFT_SIZE);
+    let mut buffer = vec![0.0f32; FFT_SIZE];
+    let mut spectrum = vec![Complex::new(0.0f32, 0.0f32); FFT_SIZE / 2 + 1];
+    let mut power = vec![0.0f32; FFT_SIZE / 2];
+
+    let mut feats = Array2::<f32>::zeros((num_frames, NUM_MEL_BINS));
+    for f in 0..num_frames {
+        // Models are trained on 16-bit PCM scale
+        let frame = &samples[f * FRAME_SHIFT..f * FRAME_SHIFT + FRAME_LENGTH];
+        let mean = frame.iter().sum::<f32>() / FRAME_LENGTH as f32 * 32768.0;
+
+        buffer.fill(0.0);
+        for (i, &s) in frame.iter().enumerate() {
+            buffer[i] = s * 32768.0 - mean;
+        }
+        for i in (1..FRAME_LENGTH).rev() {
+            buffer[i] -= PREEMPHASIS * buffer[i - 1];
+        }
+        buffer[0] -= PREEMPHASIS * buffer[0];
+        for (s, w) in buffer.iter_mut().zip(&window) {
+            *s *= w;
+        }
+
+        if fft.process(&mut buffer, &mut spectrum).is_err() {
+            continue;
+        }
+        for (p, c) in power.iter_mut().zip(&spectrum) {
+            *p = c.norm_sqr();
+        }
+
+        for (m, bank) in banks.iter().enumerate() {
+            let energy: f32 = bank.iter().zip(&power).map(|(w, p)| w * p).sum();
+            feats[[f, m]] = energy.max(f32::EPSILON).ln();
+        }
+    }
+
+    // Cepstral mean normalization
+    if let Some(means) = feats.mean_axis(ndarray::Axis(0)) {
+        feats -= &means;
+    }
+
+    feats
+}
@@ -0,0 +1,101 @@
+mod embedding;
+mod fbank;
+
+pub use embedding::SpeakerEmbedder;
+pub use fbank::{compute_fbank, NUM_MEL_BINS};
+
+/// Cosine similarity above which an embedding joins an existing speaker
+pub const SPEAKER_SIMILARITY_THRESHOLD: f32 = 0.45;
+
+/// Upper bound on distinct speakers per session. Once reached, new voices are
+/// assigned to the closest existing speaker instead of opening a new one.
+pub const MAX_SPEAKERS: usize = 8;
+
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a * norm_b)
+}
+
+/// Online clustering step: pick the speaker centroid an embedding belongs to.
+///
+/// Returns `Some(index)` of the matching centroid, or `None` if the embedding
+/// should start a new speaker. Centroids whose dimension doesn't match (e.g.
+/// after switching embedding models) are ignored.
+pub fn match_speaker(centroids: &[Vec<f32>], embedding: &[f32]) -> Option<usize> {
+    let best = centroids
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| c.len() == embedding.len())
+        .map(|(i, c)| (i, cosine_similarity(c, embedding)))
+        .max_by(|a, b| a.1.total_cmp(&b.1));
+
+    match best {
+        Some((i, similarity)) if similarity >= SPEAKER_SIMILARITY_THRESHOLD => Some(i),
+        Some((i, _)) if centroids.len() >= MAX_SPEAKERS => Some(i),
+        _ => None,
+    }
+}
+
+/// Fold a new embedding into a speaker centroid as a running mean over `count`
+/// previous embeddings.
+pub fn update_centroid(centroid: &mut [f32], embedding: &[f32], count: u32) {
+    let weight = count as f32;
+    for (c, e) in centroid.iter_mut().zip(embedding) {
+        *c = (*c * weight + e) / (weight + 1.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_speaker_joins_similar_or_opens_new() {
+        let centroids = vec![vec![1.0, 0.0], vec![0.0, 1.0]];
+        assert_eq!(match_speaker(&centroids, &[0.9, 0.1]), Some(0));
+        assert_eq!(match_speaker(&centroids, &[0.1, 0.9]), Some(1));
+        assert_eq!(match_speaker(&centroids, &[-1.0, 0.0]), None);
+        assert_eq!(match_speaker(&[], &[1.0, 0.0]), None);
+    }
+
+    #[test]
+    fn test_match_speaker_caps_speaker_count() {
+        let mut centroids: Vec<Vec<f32>> = (0..MAX_SPEAKERS).map(|_| vec![1.0, 0.0]).collect();
+        centroids[3] = vec![0.0, 1.0];
+        assert_eq!(match_speaker(&centroids, &[-1.0, 0.1]), Some(3));
+    }
+
+    #[test]
+    fn test_fbank_shape() {
+        let samples: Vec<f32> = (0..16000).map(|i| (i as f32 * 0.05).sin() * 0.1).collect();
+        let feats = compute_fbank(&samples);
+        assert_eq!(feats.dim(), (98, NUM_MEL_BINS));
+        assert!(compute_fbank(&samples[..100]).is_empty());
+    }
+
+    #[test]
+    fn test_embedder_loads_model_and_normalizes() {
+        // Stand-in for the WeSpeaker export: same input and output layout,
+        // max-pools the fbank frames into an 80-dim "embedding"
+        let model = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/src/audio_toolkit/diarization/data/max_fbank.onnx"
+        );
+        let mut embedder = SpeakerEmbedder::new(model).unwrap();
+
+        let samples: Vec<f32> = (0..16000).map(|i| (i as f32 * 0.05).sin() * 0.1).collect();
+        let embedding = embedder.embed(&samples).unwrap();
+        assert_eq!(embedding.len(), NUM_MEL_BINS);
+        let norm = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
+        assert!((norm - 1.0).abs() < 1e-4);
+        assert!(cosine_similarity(&embedding, &embedder.embed(&samples).unwrap()) > 0.999);
+
+        assert!(embedder.embed(&samples[..100]).is_err());
+        assert!(SpeakerEmbedder::new("missing.onnx").is_err());
+    }
+}
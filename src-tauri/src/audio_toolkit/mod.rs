@@ -1,5 +1,6 @@
 pub mod audio;
 pub mod constants;
+pub mod diarization;
 pub mod pipeline;
 pub mod preprocessing;
 #[cfg(any(target_os = "macos", target_os = "windows"))]
@@ -10,10 +11,15 @@ pub mod vad;
 
 pub use audio::{
     list_input_devices, list_output_devices, save_wav_file, AudioRecorder, CpalDeviceInfo,
+    WavStreamWriter,
 };
+pub use diarization::SpeakerEmbedder;
 pub use preprocessing::AudioPreprocessor;
 pub use text::{
-    apply_custom_words, filter_transcription_output, is_hallucination, remove_prefix_overlap,
+    align_word_timings, apply_custom_words, filter_transcription_output, is_hallucination,
+    remove_prefix_overlap, spread_words, TimedWord,
 };
 pub use utils::get_cpal_host;
-pub use vad::{SileroVad, VadState, VadTransition, VoiceActivityDetector, VAD_CHUNK_SIZE};
+pub use vad::{
+    split_on_speech, SileroVad, VadState, VadTransition, VoiceActivityDetector, VAD_CHUNK_SIZE,
+};
@@ -464,6 +464,123 @@ pub fn is_duplicate_segment(
     }
 }
 
+/// A word with timings in milliseconds, relative to the start of the audio it came from
+#[derive(Clone, Debug, PartialEq)]
+pub struct TimedWord {
+    pub text: String,
+    pub start_ms: i64,
+    pub end_ms: i64,
+    /// The timings were spread over a longer span rather than reported by the
+    /// engine for this word
+    pub estimated: bool,
+}
+
+/// Splits a timed span of text into words, dividing the span between them in
+/// proportion to their length. Used for engines that only report segment timings.
+pub fn spread_words(text: &str, start_ms: i64, end_ms: i64) -> Vec<TimedWord> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let total_chars: usize = words.iter().map(|w| w.chars().count()).sum();
+    if total_chars == 0 {
+        return Vec::new();
+    }
+
+    let span = (end_ms - start_ms).max(0);
+    let mut chars_before = 0usize;
+    words
+        .into_iter()
+        .map(|word| {
+            let word_start = start_ms + span * chars_before as i64 / total_chars as i64;
+            chars_before += word.chars().count();
+            let word_end = start_ms + span * chars_before as i64 / total_chars as i64;
+            TimedWord {
+                text: word.to_string(),
+                start_ms: word_start,
+                end_ms: word_end,
+                estimated: true,
+            }
+        })
+        .collect()
+}
+
+/// Carries engine word timings over to the final text of a segment.
+///
+/// The text that gets stored has been through custom word correction, filler
+/// removal and overlap trimming, so it no longer matches the engine's words
+/// one-to-one. Each final word takes the timing of the next engine word that
+/// matches it (ignoring case and punctuation); words with no match are placed
+/// in the gap between their matched neighbours.
+pub fn align_word_timings(text: &str, engine_words: &[TimedWord]) -> Vec<TimedWord> {
+    const LOOKAHEAD: usize = 4;
+
+    let normalize = |w: &str| -> String {
+        w.chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(|c| c.to_lowercase())
+            .collect()
+    };
+
+    let final_words: Vec<&str> = text.split_whitespace().collect();
+    if final_words.is_empty() || engine_words.is_empty() {
+        return Vec::new();
+    }
+
+    // First pass: match final words to engine words in order
+    let mut matched: Vec<Option<usize>> = vec![None; final_words.len()];
+    let mut next = 0usize;
+    for (i, word) in final_words.iter().enumerate() {
+        let key = normalize(word);
+        if key.is_empty() {
+            continue;
+        }
+        let window_end = (next + LOOKAHEAD).min(engine_words.len());
+        if let Some(offset) = engine_words[next..window_end]
+            .iter()
+            .position(|w| normalize(&w.text) == key)
+        {
+            matched[i] = Some(next + offset);
+            next += offset + 1;
+        }
+    }
+
+    // Second pass: interpolate unmatched words between their neighbours
+    let first_start = engine_words[0].start_ms;
+    let last_end = engine_words[engine_words.len() - 1].end_ms;
+    let mut result: Vec<TimedWord> = Vec::with_capacity(final_words.len());
+    let mut i = 0;
+    while i < final_words.len() {
+        if let Some(idx) = matched[i] {
+            result.push(TimedWord {
+                text: final_words[i].to_string(),
+                start_ms: engine_words[idx].start_ms,
+                end_ms: engine_words[idx].end_ms,
+                estimated: engine_words[idx].estimated,
+            });
+            i += 1;
+            continue;
+        }
+
+        let run_end = (i..final_words.len())
+            .find(|&j| matched[j].is_some())
+            .unwrap_or(final_words.len());
+        let gap_start = result.last().map(|w| w.end_ms).unwrap_or(first_start);
+        let gap_end = matched
+            .get(run_end)
+            .copied()
+            .flatten()
+            .map(|idx| engine_words[idx].start_ms)
+            .unwrap_or(last_end)
+            .max(gap_start);
+        result.extend(spread_words(
+            &final_words[i..run_end].join(" "),
+            gap_start,
+            gap_end,
+        ));
+        i = run_end;
+    }
+
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -770,4 +887,44 @@ mod tests {
         let result = apply_custom_words("tg is here", &vec!["TG".to_string()], 0.21);
         assert_eq!(result, "TG is here");
     }
+
+    #[test]
+    fn test_spread_words_proportional() {
+        let words = spread_words("ab abcd", 0, 600);
+        assert_eq!(words.len(), 2);
+        assert_eq!((words[0].start_ms, words[0].end_ms), (0, 200));
+        assert_eq!((words[1].start_ms, words[1].end_ms), (200, 600));
+        assert!(words.iter().all(|w| w.estimated));
+    }
+
+    #[test]
+    fn test_align_word_timings_after_filtering() {
+        let engine: Vec<TimedWord> = [
+            ("So", 0, 100),
+            ("um,", 100, 300),
+            ("hello", 300, 500),
+            ("wrold.", 500, 800),
+        ]
+        .iter()
+        .map(|&(text, start_ms, end_ms)| TimedWord {
+            text: text.to_string(),
+            start_ms,
+            end_ms,
+            estimated: false,
+        })
+        .collect();
+
+        let words = align_word_timings("So hello world.", &engine);
+        let timings: Vec<(&str, i64, i64)> = words
+            .iter()
+            .map(|w| (w.text.as_str(), w.start_ms, w.end_ms))
+            .collect();
+        assert_eq!(
+            timings,
+            vec![("So", 0, 100), ("hello", 300, 500), ("world.", 500, 800)]
+        );
+        // "world." matches no engine word, so its timing fills the gap
+        let estimated: Vec<bool> = words.iter().map(|w| w.estimated).collect();
+        assert_eq!(estimated, vec![false, false, true]);
+    }
 }
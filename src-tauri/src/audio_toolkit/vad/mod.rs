@@ -1,4 +1,5 @@
 use anyhow::Result;
+use std::ops::Range;
 
 /// Represents a transition in voice activity state
 #[derive(Clone, Debug, PartialEq)]
@@ -45,3 +46,156 @@ pub trait VoiceActivityDetector: Send + Sync {
 mod silero;
 
 pub use silero::{SileroVad, VAD_CHUNK_SIZE};
+
+/// Split a complete 16 kHz recording into utterance-sized sample ranges.
+///
+/// Used for offline audio (imports, re-transcription) where the whole buffer is
+/// available up front. A chunk opens on speech start and closes on speech end
+/// once it is at least `min_samples` long, and is force-split at `max_samples`
+/// just like the live pipeline. Silence between chunks is skipped.
+pub fn split_on_speech(
+    vad: &mut dyn VoiceActivityDetector,
+    samples: &[f32],
+    min_samples: usize,
+    max_samples: usize,
+) -> Vec<Range<usize>> {
+    // Keep a little audio before the detected onset so first syllables aren't clipped
+    const PRE_ROLL_SAMPLES: usize = VAD_CHUNK_SIZE * 10;
+
+    vad.reset();
+    let mut chunks = Vec::new();
+    let mut chunk_start: Option<usize> = None;
+    let mut last_end = 0;
+
+    for (i, frame) in samples.chunks_exact(VAD_CHUNK_SIZE).enumerate() {
+        let frame_start = i * VAD_CHUNK_SIZE;
+        let frame_end = frame_start + VAD_CHUNK_SIZE;
+
+        let transition = vad.process_frame(frame).unwrap_or(VadTransition::None);
+
+        match (chunk_start, transition) {
+            (None, VadTransition::SpeechStart) => {
+                chunk_start = Some(frame_start.saturating_sub(PRE_ROLL_SAMPLES).max(last_end));
+            }
+            (Some(start), VadTransition::SpeechEnd) if frame_end - start >= min_samples => {
+                chunks.push(start..frame_end);
+                chunk_start = None;
+                last_end = frame_end;
+            }
+            _ => {}
+        }
+
+        if let Some(start) = chunk_start {
+            if frame_end - start >= max_samples {
+                chunks.push(start..frame_end);
+                chunk_start = vad.is_speaking().then_some(frame_end);
+                last_end = frame_end;
+            }
+        }
+    }
+
+    if let Some(start) = chunk_start {
+        chunks.push(start..samples.len());
+    }
+
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Replays a fixed speech/silence pattern, one entry per frame
+    struct ScriptedVad {
+        script: Vec<bool>,
+        pos: usize,
+        state: VadState,
+    }
+
+    impl VoiceActivityDetector for ScriptedVad {
+        fn process_frame(&mut self, _frame: &[f32]) -> Result<VadTransition> {
+            let speech = self.script.get(self.pos).copied().unwrap_or(false);
+            self.pos += 1;
+            Ok(match (self.state, speech) {
+                (VadState::Silence, true) => {
+                    self.state = VadState::Speech;
+                    VadTransition::SpeechStart
+                }
+                (VadState::Speech, false) => {
+                    self.state = VadState::Silence;
+                    VadTransition::SpeechEnd
+                }
+                _ => VadTransition::None,
+            })
+        }
+
+        fn state(&self) -> VadState {
+            self.state
+        }
+
+        fn probability(&self) -> f32 {
+            0.0
+        }
+
+        fn reset(&mut self) {
+            self.pos = 0;
+            self.state = VadState::Silence;
+        }
+    }
+
+    fn scripted(pattern: &[(bool, usize)]) -> ScriptedVad {
+        let script = pattern
+            .iter()
+            .flat_map(|&(speech, frames)| std::iter::repeat(speech).take(frames))
+            .collect();
+        ScriptedVad {
+            script,
+            pos: 0,
+            state: VadState::Silence,
+        }
+    }
+
+    #[test]
+    fn test_split_on_speech_skips_silence_between_utterances() {
+        let mut vad = scripted(&[
+            (false, 40),
+            (true, 50),
+            (false, 40),
+            (true, 50),
+            (false, 20),
+        ]);
+        let samples = vec![0.0; 200 * VAD_CHUNK_SIZE];
+
+        let chunks = split_on_speech(
+            &mut vad,
+            &samples,
+            40 * VAD_CHUNK_SIZE,
+            1000 * VAD_CHUNK_SIZE,
+        );
+
+        assert_eq!(
+            chunks,
+            vec![
+                30 * VAD_CHUNK_SIZE..91 * VAD_CHUNK_SIZE,
+                120 * VAD_CHUNK_SIZE..181 * VAD_CHUNK_SIZE,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_on_speech_force_splits_long_speech() {
+        let mut vad = scripted(&[(true, 100)]);
+        let samples = vec![0.0; 100 * VAD_CHUNK_SIZE];
+
+        let chunks = split_on_speech(&mut vad, &samples, 10 * VAD_CHUNK_SIZE, 40 * VAD_CHUNK_SIZE);
+
+        assert_eq!(
+            chunks,
+            vec![
+                0..40 * VAD_CHUNK_SIZE,
+                40 * VAD_CHUNK_SIZE..80 * VAD_CHUNK_SIZE,
+                80 * VAD_CHUNK_SIZE..100 * VAD_CHUNK_SIZE,
+            ]
+        );
+    }
+}
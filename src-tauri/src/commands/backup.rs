@@ -0,0 +1,67 @@
+use crate::managers::backup::{BackupInfo, BackupManager};
+use crate::managers::session::SessionManager;
+use crate::settings::get_settings;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, State};
+
+/// Use `dir` when given, otherwise the configured backup directory
+fn resolve_backup_dir(app: &AppHandle, dir: Option<String>) -> Result<PathBuf, String> {
+    dir.or_else(|| get_settings(app).backup_directory)
+        .map(PathBuf::from)
+        .ok_or_else(|| "No backup folder selected".to_string())
+}
+
+/// Back up both databases, attachments and archived audio into a timestamped
+/// archive. Old backups in the folder are rotated per `backup_keep_count`.
+#[tauri::command]
+#[specta::specta]
+pub async fn create_backup(
+    app: AppHandle,
+    dir: Option<String>,
+    backup_manager: State<'_, Arc<BackupManager>>,
+) -> Result<BackupInfo, String> {
+    let dir = resolve_backup_dir(&app, dir)?;
+    let backup = backup_manager
+        .create_backup(&dir)
+        .map_err(|e| e.to_string())?;
+    backup_manager
+        .rotate_backups(&dir, get_settings(&app).backup_keep_count as usize)
+        .map_err(|e| e.to_string())?;
+    Ok(backup)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_backups(
+    app: AppHandle,
+    dir: Option<String>,
+    backup_manager: State<'_, Arc<BackupManager>>,
+) -> Result<Vec<BackupInfo>, String> {
+    let dir = resolve_backup_dir(&app, dir)?;
+    backup_manager.list_backups(&dir).map_err(|e| e.to_string())
+}
+
+/// Replace all current data with a backup. Refused while a session is recording
+/// or an import or re-transcription is running. `passphrase` unlocks an
+/// encrypted backup taken under a different key than the current storage.
+#[tauri::command]
+#[specta::specta]
+pub async fn restore_backup(
+    app: AppHandle,
+    file_path: String,
+    passphrase: Option<String>,
+    backup_manager: State<'_, Arc<BackupManager>>,
+) -> Result<(), String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    if sm.get_active_session_id().is_some() {
+        return Err("End the current session before restoring a backup".to_string());
+    }
+    // Hold the offline job slot so no import or re-transcription starts mid-restore
+    if !sm.begin_offline_job() {
+        return Err("Wait for the running import or re-transcription to finish".to_string());
+    }
+    let result = backup_manager.restore_backup(&PathBuf::from(file_path), passphrase.as_deref());
+    sm.finish_offline_job();
+    result.map_err(|e| e.to_string())
+}
@@ -0,0 +1,40 @@
+use crate::managers::calendar::CalendarManager;
+use crate::managers::session::{CalendarImportSummary, SessionCalendarEvent, SessionManager};
+use std::path::Path;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, State};
+
+/// Import the events of an .ics file as scheduled sessions. Importing the same
+/// file again updates them.
+#[tauri::command]
+#[specta::specta]
+pub async fn import_calendar_file(
+    path: String,
+    calendar_manager: State<'_, Arc<CalendarManager>>,
+) -> Result<CalendarImportSummary, String> {
+    calendar_manager
+        .import_file(Path::new(&path))
+        .map_err(|e| e.to_string())
+}
+
+/// Import every .ics file in the calendar folder now, changed or not
+#[tauri::command]
+#[specta::specta]
+pub async fn sync_calendar_folder(
+    calendar_manager: State<'_, Arc<CalendarManager>>,
+) -> Result<CalendarImportSummary, String> {
+    calendar_manager
+        .sync_folder(true)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_session_calendar_event(
+    app: AppHandle,
+    session_id: String,
+) -> Result<Option<SessionCalendarEvent>, String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.get_session_calendar_event(&session_id)
+        .map_err(|e| e.to_string())
+}
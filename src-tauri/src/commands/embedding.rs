@@ -0,0 +1,17 @@
+use crate::managers::embedding::{EmbeddingManager, SemanticHit};
+use std::sync::Arc;
+use tauri::State;
+
+/// Transcript segments and notes paragraphs closest in meaning to `query`, even
+/// when they share no words with it. Runs entirely on-device.
+#[tauri::command]
+#[specta::specta]
+pub async fn semantic_search(
+    query: String,
+    limit: Option<u32>,
+    embedding_manager: State<'_, Arc<EmbeddingManager>>,
+) -> Result<Vec<SemanticHit>, String> {
+    embedding_manager
+        .search(&query, limit.unwrap_or(20) as usize)
+        .map_err(|e| e.to_string())
+}
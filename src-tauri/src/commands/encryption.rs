@@ -0,0 +1,73 @@
+use crate::managers::session::{SessionManager, StorageEncryptionStatus};
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_storage_encryption_status(app: AppHandle) -> StorageEncryptionStatus {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.get_storage_encryption_status()
+}
+
+/// Unlock encrypted session storage. Startup housekeeping that needs the database
+/// is run here since it was skipped while locked.
+#[tauri::command]
+#[specta::specta]
+pub async fn unlock_storage(app: AppHandle, passphrase: String) -> Result<(), String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.unlock_storage(&passphrase).map_err(|e| e.to_string())?;
+
+    if let Err(e) = sm.cleanup_old_recordings() {
+        log::warn!("Failed to clean up old recordings: {}", e);
+    }
+    if let Err(e) = sm.purge_expired_trash() {
+        log::warn!("Failed to purge expired trash: {}", e);
+    }
+    if let Err(e) = sm.expire_scheduled_sessions() {
+        log::warn!("Failed to remove past scheduled sessions: {}", e);
+    }
+    Ok(())
+}
+
+/// Lock encrypted session storage until the passphrase is entered again.
+/// Decrypted attachment copies are deleted.
+#[tauri::command]
+#[specta::specta]
+pub async fn lock_storage(app: AppHandle) -> Result<(), String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    with_storage_idle(&sm, || sm.lock_storage())
+}
+
+/// Run a storage conversion while holding the offline job slot, so nothing writes
+/// to the database or stored files halfway through
+fn with_storage_idle(
+    sm: &SessionManager,
+    convert: impl FnOnce() -> anyhow::Result<()>,
+) -> Result<(), String> {
+    if sm.get_active_session_id().is_some() {
+        return Err("End the current session before changing storage encryption".to_string());
+    }
+    if !sm.begin_offline_job() {
+        return Err("Wait for the running import or re-transcription to finish".to_string());
+    }
+    let result = convert();
+    sm.finish_offline_job();
+    result.map_err(|e| e.to_string())
+}
+
+/// Encrypt the session database, attachments and archived recordings with a key
+/// derived from `passphrase`. The passphrase can't be recovered if it is lost.
+#[tauri::command]
+#[specta::specta]
+pub async fn enable_storage_encryption(app: AppHandle, passphrase: String) -> Result<(), String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    with_storage_idle(&sm, || sm.enable_storage_encryption(&passphrase))
+}
+
+/// Convert encrypted storage back to plaintext files
+#[tauri::command]
+#[specta::specta]
+pub async fn disable_storage_encryption(app: AppHandle, passphrase: String) -> Result<(), String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    with_storage_idle(&sm, || sm.disable_storage_encryption(&passphrase))
+}
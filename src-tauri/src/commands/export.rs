@@ -1,12 +1,31 @@
-use crate::managers::session::SessionManager;
-use chrono::{Local, TimeZone};
-use once_cell::sync::Lazy;
-use regex::Regex;
-use std::collections::HashSet;
+use crate::audio_toolkit::{spread_words, TimedWord};
+use crate::document::json::{
+    folder_chain, session_export_schema, AttachmentExport, SessionExport, SCHEMA_FILE,
+    SCHEMA_VERSION,
+};
+use crate::document::template::{
+    render_template, TemplateActionItem, TemplateAttachment, TemplateContext, TemplateSegment,
+    TemplateSession,
+};
+use crate::document::vault::{
+    self, note_markdown, NoteMetadata, TagStyle, VaultNote, VaultSync, VaultSyncResult,
+};
+use crate::document::{
+    strip_tags, ChatThread, DocumentFormat, ExportDocument, NotesSection, QaMessage, TranscriptLine,
+};
+use crate::helpers::subtitles::{render_subtitles, segment_cues, SubtitleFormat};
+use crate::managers::folders::Folder;
+use crate::managers::session::{SessionManager, TranscriptSegment};
+use crate::settings::{get_settings, ExportTemplate};
+use chrono::{Local, TimeZone, Utc};
+use serde::Deserialize;
+use specta::Type;
+use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::io::{BufWriter, Write};
 use std::path::Path;
 use std::sync::Arc;
-use tauri::State;
+use tauri::{AppHandle, State};
 
 /// Characters invalid for filenames on Windows/macOS/Linux
 fn sanitize_filename(name: &str) -> String {
@@ -18,38 +37,43 @@ fn sanitize_filename(name: &str) -> String {
         .collect()
 }
 
-static TAG_RE: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"\*{0,2}\[(?:noted|ai)\]\*{0,2} ").unwrap());
-static BOLD_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\*{4}").unwrap());
-
-/// Strip [ai] and [noted] tags (with optional bold wrapping) from notes content
-fn strip_tags(content: &str) -> String {
-    content
-        .lines()
-        .map(|line| {
-            let stripped = TAG_RE.replace_all(line, "");
-            BOLD_RE.replace_all(&stripped, "").to_string()
-        })
-        .collect::<Vec<_>>()
-        .join("\n")
-}
-
-/// Format milliseconds as HH:MM
+/// Format milliseconds as HH:MM:SS
 fn format_timestamp(ms: i64) -> String {
     let total_seconds = ms / 1000;
     let hours = total_seconds / 3600;
     let minutes = (total_seconds % 3600) / 60;
-    format!("{:02}:{:02}", hours, minutes)
+    let seconds = total_seconds % 60;
+    format!("{:02}:{:02}:{:02}", hours, minutes, seconds)
+}
+
+/// Which parts of a session an export includes
+#[derive(Clone, Debug, Deserialize, Type)]
+pub struct ExportSections {
+    pub notes: bool,
+    pub enhanced: bool,
+    pub transcript: bool,
+    #[serde(default)]
+    pub chat: bool,
 }
 
-/// Generate markdown content for a session with configurable sections
-fn generate_markdown(
+impl ExportSections {
+    /// From the export commands' flags; chat is left out unless requested
+    fn new(notes: bool, enhanced: bool, transcript: bool, chat: Option<bool>) -> Self {
+        Self {
+            notes,
+            enhanced,
+            transcript,
+            chat: chat.unwrap_or(false),
+        }
+    }
+}
+
+/// Lay out a session for export with configurable sections
+fn build_document(
     session_manager: &SessionManager,
     session_id: &str,
-    include_notes: bool,
-    include_enhanced: bool,
-    include_transcript: bool,
-) -> Result<String, String> {
+    sections: &ExportSections,
+) -> Result<ExportDocument, String> {
     let session = session_manager
         .get_session(session_id)
         .map_err(|e| e.to_string())?
@@ -63,141 +87,107 @@ fn generate_markdown(
         .get_meeting_notes(session_id)
         .map_err(|e| e.to_string())?;
 
-    let mut md = String::new();
-
-    // Title
-    md.push_str(&format!("# {}\n\n", session.title));
-
-    // Metadata
     let date = Local
         .timestamp_opt(session.started_at, 0)
         .single()
         .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
         .unwrap_or_else(|| "Unknown".to_string());
 
-    md.push_str(&format!("**Date:** {}\n", date));
+    let mut document = ExportDocument {
+        title: session.title,
+        date,
+        tags: tags.into_iter().map(|t| t.name).collect(),
+        ..Default::default()
+    };
 
-    if !tags.is_empty() {
-        let tag_names: Vec<_> = tags.iter().map(|t| t.name.as_str()).collect();
-        md.push_str(&format!("**Tags:** {}\n", tag_names.join(", ")));
-    }
-
-    md.push_str("\n---\n\n");
-
-    // Determine which notes sections are actually available
-    let has_user_notes = notes
+    // Only notes with content besides their tags are exported
+    let has_content = |content: &String| !strip_tags(content).trim().is_empty();
+    let user_notes = notes
         .as_ref()
-        .and_then(|n| n.user_notes.as_ref())
-        .map(|c| !c.trim().is_empty())
-        .unwrap_or(false);
-    let has_enhanced_notes = notes
+        .and_then(|n| n.user_notes.clone())
+        .filter(|content| sections.notes && has_content(content));
+    let enhanced_notes = notes
         .as_ref()
-        .and_then(|n| n.enhanced_notes.as_ref())
-        .map(|c| !strip_tags(c).trim().is_empty())
-        .unwrap_or(false);
-
-    // Count how many notes sections will actually be included
-    let notes_section_count =
-        (include_notes && has_user_notes) as u8 + (include_enhanced && has_enhanced_notes) as u8;
-
-    // Notes section
-    if include_notes && has_user_notes {
-        if let Some(ref meeting_notes) = notes {
-            if let Some(ref content) = meeting_notes.user_notes {
-                let stripped = strip_tags(content);
-                if !stripped.trim().is_empty() {
-                    let heading = if notes_section_count > 1 {
-                        "## Notes\n\n"
-                    } else {
-                        "## Notes\n\n"
-                    };
-                    md.push_str(heading);
-                    md.push_str(&stripped);
-                    md.push_str("\n\n");
-                }
-            }
-        }
+        .and_then(|n| n.enhanced_notes.clone())
+        .filter(|content| sections.enhanced && has_content(content));
+
+    // Enhanced notes are just "Notes" unless the user's notes are exported too
+    let enhanced_heading = if user_notes.is_some() {
+        "Enhanced Notes"
+    } else {
+        "Notes"
+    };
+    if let Some(content) = user_notes {
+        document.notes.push(NotesSection {
+            heading: "Notes".to_string(),
+            content,
+        });
     }
-
-    // Enhanced Notes section
-    if include_enhanced && has_enhanced_notes {
-        if let Some(ref meeting_notes) = notes {
-            if let Some(ref content) = meeting_notes.enhanced_notes {
-                let stripped = strip_tags(content);
-                if !stripped.trim().is_empty() {
-                    let heading = if notes_section_count > 1 {
-                        "## Enhanced Notes\n\n"
-                    } else {
-                        "## Notes\n\n"
-                    };
-                    md.push_str(heading);
-                    md.push_str(&stripped);
-                    md.push_str("\n\n");
-                }
-            }
-        }
+    if let Some(content) = enhanced_notes {
+        document.notes.push(NotesSection {
+            heading: enhanced_heading.to_string(),
+            content,
+        });
     }
 
-    // Transcript section
-    if include_transcript {
+    if sections.transcript {
         let segments = session_manager
             .get_session_transcript(session_id)
             .map_err(|e| e.to_string())?;
-
-        if !segments.is_empty() {
-            md.push_str("## Transcript\n\n");
-            for segment in &segments {
-                let timestamp = format_timestamp(segment.start_ms);
-                let label = if segment.source == "mic" {
-                    "[You]"
+        document.transcript = segments
+            .iter()
+            .map(|segment| TranscriptLine {
+                timestamp: format_timestamp(segment.start_ms),
+                label: if segment.source == "mic" {
+                    "You"
                 } else {
-                    "[Other]"
-                };
-                md.push_str(&format!(
-                    "[{}] {}: {}\n\n",
-                    timestamp,
-                    label,
-                    segment.text.trim()
-                ));
-            }
-        }
+                    "Other"
+                }
+                .to_string(),
+                text: segment.text.trim().to_string(),
+            })
+            .collect();
     }
 
-    Ok(md)
-}
+    // Q&A from persisted chat threads
+    if sections.chat {
+        let mut threads = session_manager
+            .get_chat_threads(session_id)
+            .map_err(|e| e.to_string())?;
+        threads.sort_by_key(|t| t.created_at);
 
-#[tauri::command]
-#[specta::specta]
-pub async fn export_note_as_markdown(
-    session_id: String,
-    file_path: String,
-    include_notes: bool,
-    include_enhanced: bool,
-    include_transcript: bool,
-    session_manager: State<'_, Arc<SessionManager>>,
-) -> Result<(), String> {
-    let markdown = generate_markdown(
-        &session_manager,
-        &session_id,
-        include_notes,
-        include_enhanced,
-        include_transcript,
-    )?;
+        for thread in threads {
+            let messages = session_manager
+                .get_chat_messages(&thread.id)
+                .map_err(|e| e.to_string())?;
+            if messages.is_empty() {
+                continue;
+            }
 
-    fs::write(&file_path, markdown).map_err(|e| format!("Failed to write file: {}", e))?;
+            document.chat.push(ChatThread {
+                title: thread.title,
+                messages: messages
+                    .into_iter()
+                    .map(|message| QaMessage {
+                        question: message.role == "user",
+                        content: message.content.trim().to_string(),
+                    })
+                    .collect(),
+            });
+        }
+    }
 
-    log::info!("Exported note {} to {}", session_id, file_path);
-    Ok(())
+    Ok(document)
 }
 
-#[tauri::command]
-#[specta::specta]
-pub async fn export_all_notes_as_markdown(
-    directory_path: String,
-    include_notes: bool,
-    include_enhanced: bool,
-    include_transcript: bool,
-    session_manager: State<'_, Arc<SessionManager>>,
+/// Export every session into `directory_path` as `{YYYY-MM-DD} {Title}.{extension}`,
+/// with `render` producing a session's file content from its id. Sessions that
+/// fail to export are logged and skipped. Returns the number exported.
+fn export_all_sessions(
+    session_manager: &SessionManager,
+    directory_path: &str,
+    extension: &str,
+    render: impl Fn(&str) -> Result<Vec<u8>, String>,
 ) -> Result<u32, String> {
     let sessions = session_manager.get_sessions().map_err(|e| e.to_string())?;
 
@@ -205,7 +195,7 @@ pub async fn export_all_notes_as_markdown(
         return Ok(0);
     }
 
-    let dir_path = Path::new(&directory_path);
+    let dir_path = Path::new(directory_path);
     if !dir_path.exists() {
         fs::create_dir_all(dir_path).map_err(|e| format!("Failed to create directory: {}", e))?;
     }
@@ -214,7 +204,7 @@ pub async fn export_all_notes_as_markdown(
     let mut exported_count: u32 = 0;
 
     for session in sessions {
-        // Generate filename: {YYYY-MM-DD} {Title}.md using local time
+        // Generate filename: {YYYY-MM-DD} {Title} using local time
         let date_str = Local
             .timestamp_opt(session.started_at, 0)
             .single()
@@ -233,24 +223,18 @@ pub async fn export_all_notes_as_markdown(
         }
         used_names.insert(final_name.to_lowercase());
 
-        let file_path = dir_path.join(format!("{}.md", final_name));
+        let file_path = dir_path.join(format!("{}.{}", final_name, extension));
 
-        match generate_markdown(
-            &session_manager,
-            &session.id,
-            include_notes,
-            include_enhanced,
-            include_transcript,
-        ) {
-            Ok(markdown) => {
-                if let Err(e) = fs::write(&file_path, markdown) {
+        match render(&session.id) {
+            Ok(content) => {
+                if let Err(e) = fs::write(&file_path, content) {
                     log::error!("Failed to export {}: {}", session.id, e);
                     continue;
                 }
                 exported_count += 1;
             }
             Err(e) => {
-                log::error!("Failed to generate markdown for {}: {}", session.id, e);
+                log::error!("Failed to generate {} for {}: {}", extension, session.id, e);
                 continue;
             }
         }
@@ -259,3 +243,562 @@ pub async fn export_all_notes_as_markdown(
     log::info!("Exported {} notes to {}", exported_count, directory_path);
     Ok(exported_count)
 }
+
+/// Export a session as Markdown, or through one of the user's export
+/// templates if `template_id` is set, in which case the template decides what
+/// is included
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+#[specta::specta]
+pub async fn export_note_as_markdown(
+    app: AppHandle,
+    session_id: String,
+    file_path: String,
+    include_notes: bool,
+    include_enhanced: bool,
+    include_transcript: bool,
+    include_chat: Option<bool>,
+    template_id: Option<String>,
+    session_manager: State<'_, Arc<SessionManager>>,
+) -> Result<(), String> {
+    let content = match &template_id {
+        Some(template_id) => {
+            let template = export_template(&app, template_id)?;
+            let folder_paths =
+                vault::folder_paths(&session_manager.get_folders().map_err(|e| e.to_string())?);
+            let context = build_template_context(
+                &session_manager,
+                &session_id,
+                &environment_names(&app),
+                &folder_paths,
+            )?;
+            render_template(&template.template, &context).map_err(|e| e.to_string())?
+        }
+        None => {
+            let sections = ExportSections::new(
+                include_notes,
+                include_enhanced,
+                include_transcript,
+                include_chat,
+            );
+            build_document(&session_manager, &session_id, &sections)?.to_markdown()
+        }
+    };
+
+    fs::write(&file_path, content).map_err(|e| format!("Failed to write file: {}", e))?;
+
+    log::info!("Exported note {} to {}", session_id, file_path);
+    Ok(())
+}
+
+/// Export every session as Markdown, or through one of the user's export
+/// templates if `template_id` is set, named with the template's extension
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+#[specta::specta]
+pub async fn export_all_notes_as_markdown(
+    app: AppHandle,
+    directory_path: String,
+    include_notes: bool,
+    include_enhanced: bool,
+    include_transcript: bool,
+    include_chat: Option<bool>,
+    template_id: Option<String>,
+    session_manager: State<'_, Arc<SessionManager>>,
+) -> Result<u32, String> {
+    let Some(template_id) = template_id else {
+        let sections = ExportSections::new(
+            include_notes,
+            include_enhanced,
+            include_transcript,
+            include_chat,
+        );
+        return export_all_sessions(&session_manager, &directory_path, "md", |session_id| {
+            Ok(build_document(&session_manager, session_id, &sections)?
+                .to_markdown()
+                .into_bytes())
+        });
+    };
+
+    let template = export_template(&app, &template_id)?;
+    let environments = environment_names(&app);
+    let folder_paths =
+        vault::folder_paths(&session_manager.get_folders().map_err(|e| e.to_string())?);
+    export_all_sessions(
+        &session_manager,
+        &directory_path,
+        &template.extension,
+        |session_id| {
+            let context =
+                build_template_context(&session_manager, session_id, &environments, &folder_paths)?;
+            render_template(&template.template, &context)
+                .map(String::into_bytes)
+                .map_err(|e| e.to_string())
+        },
+    )
+}
+
+/// Export a session as a Word or PDF document. Unlike Markdown export, notes
+/// keep the [noted]/[ai] distinction, with [ai] lines shown in grey.
+#[tauri::command]
+#[specta::specta]
+pub async fn export_note_as_document(
+    session_id: String,
+    file_path: String,
+    format: DocumentFormat,
+    sections: ExportSections,
+    session_manager: State<'_, Arc<SessionManager>>,
+) -> Result<(), String> {
+    let content = build_document(&session_manager, &session_id, &sections)?
+        .render(format)
+        .map_err(|e| e.to_string())?;
+
+    fs::write(&file_path, content).map_err(|e| format!("Failed to write file: {}", e))?;
+
+    log::info!("Exported note {} to {}", session_id, file_path);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn export_all_notes_as_documents(
+    directory_path: String,
+    format: DocumentFormat,
+    sections: ExportSections,
+    session_manager: State<'_, Arc<SessionManager>>,
+) -> Result<u32, String> {
+    export_all_sessions(
+        &session_manager,
+        &directory_path,
+        format.extension(),
+        |session_id| {
+            build_document(&session_manager, session_id, &sections)?
+                .render(format)
+                .map_err(|e| e.to_string())
+        },
+    )
+}
+
+/// Model environment names by id
+fn environment_names(app: &AppHandle) -> HashMap<String, String> {
+    get_settings(app)
+        .model_environments
+        .into_iter()
+        .map(|environment| (environment.id, environment.name))
+        .collect()
+}
+
+fn export_template(app: &AppHandle, template_id: &str) -> Result<ExportTemplate, String> {
+    get_settings(app)
+        .export_templates
+        .into_iter()
+        .find(|t| t.id == template_id)
+        .ok_or_else(|| format!("Export template not found: {}", template_id))
+}
+
+/// Gather what export templates can use for a session. `environments` and
+/// `folder_paths` map ids to names, as `environment_names` and
+/// `vault::folder_paths` return them.
+fn build_template_context(
+    session_manager: &SessionManager,
+    session_id: &str,
+    environments: &HashMap<String, String>,
+    folder_paths: &HashMap<String, Vec<String>>,
+) -> Result<TemplateContext, String> {
+    let session = session_manager
+        .get_session(session_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Session not found: {}", session_id))?;
+    let started = Local.timestamp_opt(session.started_at, 0).single();
+    let tags = session_manager
+        .get_session_tags(session_id)
+        .map_err(|e| e.to_string())?;
+    let notes = session_manager
+        .get_meeting_notes(session_id)
+        .map_err(|e| e.to_string())?;
+    let notes_text = |content: Option<String>| {
+        content
+            .map(|content| strip_tags(&content).trim().to_string())
+            .filter(|content| !content.is_empty())
+    };
+
+    let speaker_names = speaker_names(session_manager, session_id)?;
+    let transcript = session_manager
+        .get_session_transcript(session_id)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|segment| TemplateSegment {
+            timestamp: format_timestamp(segment.start_ms),
+            start_ms: segment.start_ms,
+            end_ms: segment.end_ms,
+            speaker: speaker_label(&segment, &speaker_names),
+            text: segment.text.trim().to_string(),
+            source: segment.source,
+        })
+        .collect();
+    let attachments = session_manager
+        .get_attachments(session_id)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|attachment| TemplateAttachment {
+            filename: attachment.filename,
+            mime_type: attachment.mime_type,
+            file_size: attachment.file_size,
+            text: attachment.extracted_text,
+        })
+        .collect();
+    let action_items = session_manager
+        .get_session_action_items(session_id)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|item| TemplateActionItem {
+            text: item.text,
+            owner: item.owner,
+            due_date: item.due_date,
+            status: item.status,
+        })
+        .collect();
+
+    Ok(TemplateContext {
+        session: TemplateSession {
+            date: started
+                .map(|dt| dt.format("%Y-%m-%d").to_string())
+                .unwrap_or_else(|| "Unknown".to_string()),
+            time: started
+                .map(|dt| dt.format("%H:%M").to_string())
+                .unwrap_or_default(),
+            duration_minutes: session
+                .ended_at
+                .map(|ended_at| (ended_at - session.started_at + 30) / 60),
+            environment: session
+                .environment_id
+                .as_ref()
+                .and_then(|id| environments.get(id).cloned()),
+            id: session.id,
+            title: session.title,
+            started_at: session.started_at,
+            ended_at: session.ended_at,
+        },
+        tags: tags.into_iter().map(|t| t.name).collect(),
+        folder: session
+            .folder_id
+            .as_ref()
+            .and_then(|folder_id| folder_paths.get(folder_id))
+            .map(|names| names.join("/")),
+        notes: notes_text(notes.as_ref().and_then(|n| n.user_notes.clone())),
+        enhanced_notes: notes_text(notes.and_then(|n| n.enhanced_notes)),
+        transcript,
+        attachments,
+        action_items,
+    })
+}
+
+/// Collect a session for JSON export. `folders` are all folders, to resolve
+/// the session's folder path.
+fn build_session_export(
+    session_manager: &SessionManager,
+    session_id: &str,
+    folders: &[Folder],
+) -> Result<SessionExport, String> {
+    let session = session_manager
+        .get_session(session_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Session not found: {}", session_id))?;
+
+    Ok(SessionExport {
+        schema_version: SCHEMA_VERSION,
+        folders: session
+            .folder_id
+            .as_deref()
+            .map(|folder_id| folder_chain(folders, folder_id))
+            .unwrap_or_default(),
+        tags: session_manager
+            .get_session_tags(session_id)
+            .map_err(|e| e.to_string())?,
+        notes: session_manager
+            .get_meeting_notes(session_id)
+            .map_err(|e| e.to_string())?,
+        transcript: session_manager
+            .get_session_transcript(session_id)
+            .map_err(|e| e.to_string())?,
+        attachments: session_manager
+            .get_attachments(session_id)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(|attachment| AttachmentExport {
+                filename: attachment.filename,
+                mime_type: attachment.mime_type,
+                file_size: attachment.file_size,
+                created_at: attachment.created_at,
+            })
+            .collect(),
+        session,
+    })
+}
+
+/// Write the JSON Schema of exports into the directory of `file_path`
+fn write_export_schema(file_path: &Path) -> Result<(), String> {
+    let schema_path = file_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join(SCHEMA_FILE);
+    let schema =
+        serde_json::to_string_pretty(&session_export_schema()).map_err(|e| e.to_string())?;
+    fs::write(&schema_path, schema).map_err(|e| format!("Failed to write schema: {}", e))
+}
+
+/// Export a session as JSON for other tools, with its JSON Schema written
+/// next to it
+#[tauri::command]
+#[specta::specta]
+pub async fn export_note_as_json(
+    session_id: String,
+    file_path: String,
+    session_manager: State<'_, Arc<SessionManager>>,
+) -> Result<(), String> {
+    let folders = session_manager.get_folders().map_err(|e| e.to_string())?;
+    let export = build_session_export(&session_manager, &session_id, &folders)?;
+    let json = serde_json::to_string_pretty(&export).map_err(|e| e.to_string())?;
+
+    fs::write(&file_path, json).map_err(|e| format!("Failed to write file: {}", e))?;
+    write_export_schema(Path::new(&file_path))?;
+
+    log::info!("Exported note {} to {}", session_id, file_path);
+    Ok(())
+}
+
+/// Export every session into one NDJSON file, one session per line, with the
+/// JSON Schema of a line written next to it. Sessions are written as they are
+/// read, so large libraries are never held in memory at once. Sessions that
+/// fail to export are logged and skipped. Returns the number exported.
+#[tauri::command]
+#[specta::specta]
+pub async fn export_all_notes_as_ndjson(
+    file_path: String,
+    session_manager: State<'_, Arc<SessionManager>>,
+) -> Result<u32, String> {
+    let sessions = session_manager.get_sessions().map_err(|e| e.to_string())?;
+    let folders = session_manager.get_folders().map_err(|e| e.to_string())?;
+
+    let file = fs::File::create(&file_path).map_err(|e| format!("Failed to create file: {}", e))?;
+    let mut writer = BufWriter::new(file);
+    let mut exported_count: u32 = 0;
+
+    for session in sessions {
+        let export = match build_session_export(&session_manager, &session.id, &folders) {
+            Ok(export) => export,
+            Err(e) => {
+                log::error!("Failed to export {} as JSON: {}", session.id, e);
+                continue;
+            }
+        };
+        serde_json::to_writer(&mut writer, &export)
+            .map_err(|e| format!("Failed to write file: {}", e))?;
+        writer
+            .write_all(b"\n")
+            .map_err(|e| format!("Failed to write file: {}", e))?;
+        exported_count += 1;
+    }
+
+    writer
+        .flush()
+        .map_err(|e| format!("Failed to write file: {}", e))?;
+    write_export_schema(Path::new(&file_path))?;
+
+    log::info!("Exported {} notes to {}", exported_count, file_path);
+    Ok(exported_count)
+}
+
+/// A folder or file name that is safe as one component of a vault path. Names
+/// can't climb out of the vault or become hidden files.
+fn path_component(name: &str) -> String {
+    let name = sanitize_filename(name);
+    let name = name
+        .trim()
+        .trim_start_matches('.')
+        .trim_end_matches(['.', ' ']);
+    if name.is_empty() {
+        "Untitled".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+/// Sync every session into the Obsidian or Logseq vault at `vault_path`: one
+/// note per session with YAML frontmatter, in directories mirroring its folder.
+/// Only notes whose session changed since the last sync are rewritten, notes of
+/// deleted sessions are removed unless they were edited in the vault, and
+/// notes the user renamed or moved are found again by their frontmatter id.
+/// Notes edited in the vault are never overwritten; they are reported as
+/// conflicts instead.
+#[tauri::command]
+#[specta::specta]
+pub async fn sync_notes_to_vault(
+    app: AppHandle,
+    vault_path: String,
+    tag_style: TagStyle,
+    sections: ExportSections,
+    session_manager: State<'_, Arc<SessionManager>>,
+) -> Result<VaultSyncResult, String> {
+    let root = Path::new(&vault_path);
+    fs::create_dir_all(root).map_err(|e| format!("Failed to create directory: {}", e))?;
+
+    let options = format!(
+        "notes={} enhanced={} transcript={} chat={} tags={:?}",
+        sections.notes, sections.enhanced, sections.transcript, sections.chat, tag_style
+    );
+    let mut sync = VaultSync::start(root, options, Utc::now().timestamp());
+
+    let environments = environment_names(&app);
+    let folder_paths =
+        vault::folder_paths(&session_manager.get_folders().map_err(|e| e.to_string())?);
+    let updated_at = session_manager
+        .get_session_updated_at()
+        .map_err(|e| e.to_string())?;
+    let sessions = session_manager.get_sessions().map_err(|e| e.to_string())?;
+
+    for session in sessions {
+        let tags = session_manager
+            .get_session_tags(&session.id)
+            .map_err(|e| e.to_string())?;
+        let folder = session
+            .folder_id
+            .as_ref()
+            .and_then(|folder_id| folder_paths.get(folder_id));
+        let metadata = NoteMetadata {
+            id: session.id.clone(),
+            title: session.title.clone(),
+            date: Local
+                .timestamp_opt(session.started_at, 0)
+                .single()
+                .map(|dt| dt.format("%Y-%m-%dT%H:%M").to_string())
+                .unwrap_or_else(|| "Unknown".to_string()),
+            duration_minutes: session
+                .ended_at
+                .map(|ended_at| (ended_at - session.started_at + 30) / 60),
+            tags: tags.into_iter().map(|t| t.name).collect(),
+            folder: folder.map(|names| names.join("/")),
+            environment: session
+                .environment_id
+                .as_ref()
+                .and_then(|id| environments.get(id).cloned()),
+        };
+        let date = metadata.date.get(..10).unwrap_or(&metadata.date);
+        let note = VaultNote {
+            file_name: format!("{} {}", date, path_component(&metadata.title)),
+            folder_dirs: folder
+                .map(|names| names.iter().map(|name| path_component(name)).collect())
+                .unwrap_or_default(),
+            updated_at: updated_at
+                .get(&session.id)
+                .copied()
+                .unwrap_or(session.started_at),
+            metadata,
+        };
+        sync.sync_note(note, |metadata| {
+            let document = build_document(&session_manager, &metadata.id, &sections)
+                .map_err(anyhow::Error::msg)?;
+            Ok(note_markdown(&document, metadata, tag_style))
+        });
+    }
+
+    let result = sync.finish().map_err(|e| e.to_string())?;
+    log::info!(
+        "Synced vault {}: {} written, {} unchanged, {} removed, {} conflicts",
+        vault_path,
+        result.written,
+        result.unchanged,
+        result.removed,
+        result.conflicts.len()
+    );
+    Ok(result)
+}
+
+/// Names given to the session's diarized speakers, by label
+fn speaker_names(
+    session_manager: &SessionManager,
+    session_id: &str,
+) -> Result<HashMap<String, String>, String> {
+    Ok(session_manager
+        .get_session_speakers(session_id)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter_map(|speaker| speaker.name.map(|name| (speaker.label, name)))
+        .collect())
+}
+
+/// Label of a segment: the diarized speaker's name, "You" for the microphone
+/// or "Other" for system audio
+fn speaker_label(segment: &TranscriptSegment, speaker_names: &HashMap<String, String>) -> String {
+    if segment.source != "mic" {
+        return "Other".to_string();
+    }
+    match &segment.speaker {
+        Some(label) => speaker_names.get(label).unwrap_or(label).clone(),
+        None => "You".to_string(),
+    }
+}
+
+/// Generate SRT or WebVTT captions for a session, optionally only for one source
+fn generate_subtitles(
+    session_manager: &SessionManager,
+    session_id: &str,
+    format: SubtitleFormat,
+    source: Option<&str>,
+) -> Result<String, String> {
+    let segments = session_manager
+        .get_session_transcript(session_id)
+        .map_err(|e| e.to_string())?;
+    let speaker_names = speaker_names(session_manager, session_id)?;
+
+    let mut cues = Vec::new();
+    for segment in segments
+        .iter()
+        .filter(|segment| source.is_none_or(|source| segment.source == source))
+    {
+        // Segments from engines without word timings get them estimated
+        let words: Vec<TimedWord> = if segment.words.is_empty() {
+            spread_words(&segment.text, segment.start_ms, segment.end_ms)
+        } else {
+            segment
+                .words
+                .iter()
+                .map(|word| TimedWord {
+                    text: word.text.clone(),
+                    start_ms: word.start_ms,
+                    end_ms: word.end_ms,
+                    estimated: word.estimated,
+                })
+                .collect()
+        };
+        cues.extend(segment_cues(
+            &words,
+            &speaker_label(segment, &speaker_names),
+        ));
+    }
+    if cues.is_empty() {
+        return Err("No transcript to export".to_string());
+    }
+
+    cues.sort_by_key(|cue| cue.start_ms);
+    Ok(render_subtitles(&cues, format))
+}
+
+/// Export a session's transcript as SRT or WebVTT captions. `source` ("mic" or
+/// "speaker") limits the captions to one audio source.
+#[tauri::command]
+#[specta::specta]
+pub async fn export_session_subtitles(
+    session_id: String,
+    file_path: String,
+    format: SubtitleFormat,
+    source: Option<String>,
+    session_manager: State<'_, Arc<SessionManager>>,
+) -> Result<(), String> {
+    let subtitles = generate_subtitles(&session_manager, &session_id, format, source.as_deref())?;
+
+    fs::write(&file_path, subtitles).map_err(|e| format!("Failed to write file: {}", e))?;
+
+    log::info!("Exported subtitles of {} to {}", session_id, file_path);
+    Ok(())
+}
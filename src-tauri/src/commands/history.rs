@@ -1,6 +1,7 @@
 use crate::managers::history::{HistoryEntry, HistoryManager};
+use crate::managers::session::SessionManager;
 use std::sync::Arc;
-use tauri::{AppHandle, State};
+use tauri::{AppHandle, Manager, State};
 
 #[tauri::command]
 #[specta::specta]
@@ -54,6 +55,9 @@ pub async fn update_history_limit(
     history_manager
         .cleanup_old_entries()
         .map_err(|e| e.to_string())?;
+    app.state::<Arc<SessionManager>>()
+        .cleanup_old_recordings()
+        .map_err(|e| e.to_string())?;
 
     Ok(())
 }
@@ -83,6 +87,9 @@ pub async fn update_recording_retention_period(
     history_manager
         .cleanup_old_entries()
         .map_err(|e| e.to_string())?;
+    app.state::<Arc<SessionManager>>()
+        .cleanup_old_recordings()
+        .map_err(|e| e.to_string())?;
 
     Ok(())
 }
@@ -1,4 +1,8 @@
 pub mod audio;
+pub mod backup;
+pub mod calendar;
+pub mod embedding;
+pub mod encryption;
 pub mod export;
 pub mod history;
 pub mod models;
@@ -6,6 +10,7 @@ pub mod session;
 pub mod settings;
 pub mod transcription;
 
+use crate::managers::encryption::KEY_FILE_NAME;
 use crate::settings::{get_settings, write_settings, AppSettings, LogLevel};
 use crate::utils::cancel_current_operation;
 use std::path::PathBuf;
@@ -280,6 +285,14 @@ pub fn set_data_directory(
             );
         }
 
+        // The key file holds the salt an encrypted sessions.db is opened with
+        let source_key_file = source_dir.join(KEY_FILE_NAME);
+        let target_key_file = target_dir.join(KEY_FILE_NAME);
+        if source_key_file.exists() && !target_key_file.exists() {
+            std::fs::copy(&source_key_file, &target_key_file)
+                .map_err(|e| format!("Failed to copy {}: {}", KEY_FILE_NAME, e))?;
+        }
+
         // Also copy WAL files if they exist (for SQLite)
         for wal_ext in &["-wal", "-shm"] {
             let source_sessions_wal = source_dir.join(format!("sessions.db{}", wal_ext));
@@ -12,6 +12,14 @@ pub async fn get_available_models(
     Ok(model_manager.get_available_models())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub async fn get_feature_models(
+    model_manager: State<'_, Arc<ModelManager>>,
+) -> Result<Vec<ModelInfo>, String> {
+    Ok(model_manager.get_feature_models())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub async fn get_model_info(
@@ -1,10 +1,19 @@
 use crate::llm_client::{ChatMessage, ContentPart, ImageUrl};
+use crate::managers::action_items::{parse_extracted_action_items, ActionItem, ActionItemFilter};
 use crate::managers::audio::AudioRecordingManager;
+use crate::managers::bookmarks::{Bookmark, SessionTimeline};
+use crate::managers::folders::{Folder, SmartFolder, SmartFolderQuery};
+use crate::managers::retrieval::{
+    cited_labels, AnswerCitation, CrossSessionAnswer, RetrievedPassage, SessionScope,
+};
 use crate::managers::session::{
-    Attachment, Folder, MeetingNotes, Session, SessionManager, Tag, TranscriptSegment,
+    Attachment, AudioRecording, ChatThread, ChatThreadMessage, MeetingNotes, MeetingNotesDiff,
+    MeetingNotesRevision, SearchHit, Session, SessionManager, SessionSpeaker, Tag,
+    TranscriptRevision, TranscriptRevisionSegment, TranscriptSegment, TranscriptSegmentEdit,
 };
 use crate::managers::transcription::TranscriptionManager;
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use std::collections::HashMap;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use tauri::{AppHandle, Manager};
@@ -57,10 +66,12 @@ pub async fn flush_pending_audio(app: AppHandle, session_id: String) -> Result<(
         return Ok(());
     }
 
+    let vocabulary = crate::actions::session_vocabulary(&sm, &session_id);
+
     // Take whatever mic audio has accumulated
     let mic_chunk = rm.take_session_chunk();
     if !mic_chunk.is_empty() {
-        if let Ok(text) = tm.transcribe_chunk(mic_chunk) {
+        if let Ok(text) = tm.transcribe_chunk(mic_chunk, &vocabulary) {
             if !text.is_empty() {
                 let _ = sm.add_segment(&session_id, text, "mic", 0, 0);
             }
@@ -70,7 +81,7 @@ pub async fn flush_pending_audio(app: AppHandle, session_id: String) -> Result<(
     // Take whatever speaker audio has accumulated
     let spk_chunk = sm.take_speaker_samples();
     if !spk_chunk.is_empty() {
-        if let Ok(text) = tm.transcribe_chunk(spk_chunk) {
+        if let Ok(text) = tm.transcribe_chunk(spk_chunk, &vocabulary) {
             if !text.is_empty() {
                 let _ = sm.add_segment(&session_id, text, "speaker", 0, 0);
             }
@@ -80,6 +91,84 @@ pub async fn flush_pending_audio(app: AppHandle, session_id: String) -> Result<(
     Ok(())
 }
 
+/// Display names for a session's diarized speakers, keyed by label
+fn session_speaker_names(sm: &SessionManager, session_id: &str) -> HashMap<String, String> {
+    sm.get_session_speakers(session_id)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|speaker| speaker.name.map(|name| (speaker.label, name)))
+        .collect()
+}
+
+/// Transcript label for the LLM: `[Other]` for system audio, `[Mic]` for the
+/// microphone, or `[Mic: <speaker>]` once a mic segment has been diarized.
+fn transcript_label(seg: &TranscriptSegment, speaker_names: &HashMap<String, String>) -> String {
+    source_label(&seg.source, seg.speaker.as_ref(), speaker_names)
+}
+
+fn source_label(
+    source: &str,
+    speaker: Option<&String>,
+    speaker_names: &HashMap<String, String>,
+) -> String {
+    if source != "mic" {
+        return "[Other]".to_string();
+    }
+    match speaker {
+        Some(label) => format!("[Mic: {}]", speaker_names.get(label).unwrap_or(label)),
+        None => "[Mic]".to_string(),
+    }
+}
+
+/// Explains the bookmark lines of `timeline_text` to the enhance prompt
+const BOOKMARKS_CONTEXT: &str = "\n\nBOOKMARKS: Lines marked ★ BOOKMARK are moments the user flagged as important \
+     while recording, with the user's label if they gave one. They refer to what was being said just before. \
+     Make sure the notes cover each bookmarked moment and give those points emphasis.";
+
+/// Prompt context for a session's bookmarks, empty when it has none
+fn bookmarks_context(bookmarks: &[Bookmark]) -> &'static str {
+    if bookmarks.is_empty() {
+        ""
+    } else {
+        BOOKMARKS_CONTEXT
+    }
+}
+
+/// Timestamped transcript for the LLM. Each bookmark follows the segments that
+/// started before it.
+fn timeline_text(
+    segments: &[TranscriptSegment],
+    bookmarks: &[Bookmark],
+    speaker_names: &HashMap<String, String>,
+) -> String {
+    let mut lines = Vec::with_capacity(segments.len() + bookmarks.len());
+    let mut bookmarks = bookmarks.iter().peekable();
+    for seg in segments {
+        while let Some(bookmark) = bookmarks.next_if(|b| b.offset_ms < seg.start_ms) {
+            lines.push(bookmark_line(bookmark));
+        }
+        lines.push(format!(
+            "[{}] {}: {}",
+            format_ms_timestamp(seg.start_ms),
+            transcript_label(seg, speaker_names),
+            seg.text
+        ));
+    }
+    lines.extend(bookmarks.map(bookmark_line));
+    lines.join("\n")
+}
+
+fn bookmark_line(bookmark: &Bookmark) -> String {
+    match &bookmark.label {
+        Some(label) => format!(
+            "[{}] ★ BOOKMARK: {}",
+            format_ms_timestamp(bookmark.offset_ms),
+            label
+        ),
+        None => format!("[{}] ★ BOOKMARK", format_ms_timestamp(bookmark.offset_ms)),
+    }
+}
+
 fn format_ms_timestamp(ms: i64) -> String {
     let total_secs = ms / 1000;
     let hours = total_secs / 3600;
@@ -109,24 +198,13 @@ pub async fn generate_session_summary(
 
     let settings = crate::settings::get_settings(&app);
 
-    // Build timestamped transcript
-    let transcript_text: String = segments
-        .iter()
-        .map(|seg| {
-            let label = if seg.source == "mic" {
-                "[Mic]"
-            } else {
-                "[Other]"
-            };
-            format!(
-                "[{}] {}: {}",
-                format_ms_timestamp(seg.start_ms),
-                label,
-                seg.text
-            )
-        })
-        .collect::<Vec<_>>()
-        .join("\n");
+    // Build timestamped transcript, with the user's bookmarks in place
+    let speaker_names = session_speaker_names(&sm, &session_id);
+    let bookmarks = sm
+        .get_session_bookmarks(&session_id)
+        .map_err(|e| e.to_string())?;
+    let transcript_text = timeline_text(&segments, &bookmarks, &speaker_names);
+    let is_diarized = segments.iter().any(|seg| seg.speaker.is_some());
 
     // Fetch user notes
     let user_notes = sm
@@ -148,12 +226,20 @@ pub async fn generate_session_summary(
 
     let mut system_message = include_str!("../../resources/prompts/enhance_notes.txt").to_string();
 
-    // Inject custom words (+ user name) into the prompt for vocabulary correction
+    // Inject custom words (+ user name, meeting attendees) into the prompt for vocabulary correction
     let mut vocab = settings.custom_words.clone();
     let user_name_trimmed = settings.user_name.trim().to_string();
     if !user_name_trimmed.is_empty() && !vocab.contains(&user_name_trimmed) {
         vocab.push(user_name_trimmed);
     }
+    for name in sm
+        .get_session_attendee_names(&session_id)
+        .map_err(|e| e.to_string())?
+    {
+        if !vocab.contains(&name) {
+            vocab.push(name);
+        }
+    }
     if !vocab.is_empty() {
         system_message.push_str(&format!(
             "\n\nDOMAIN VOCABULARY: The following terms are important and should be spelled exactly as shown: {}\nIf the transcript contains misspellings or misheard versions of these terms, correct them.",
@@ -179,6 +265,13 @@ pub async fn generate_session_summary(
          \nIf only [Mic] segments appear, multiple speakers are likely mixed together. \
          Do not assume one person said everything."
     );
+    if is_diarized {
+        system_message.push_str(
+            "\n- [Mic: <speaker>] = a voice told apart on-device within the microphone audio. \
+             Treat different speakers as different people, but the split is automatic and can be wrong.",
+        );
+    }
+    system_message.push_str(bookmarks_context(&bookmarks));
 
     let notes_section = if user_notes.trim().is_empty() {
         "No notes were taken.".to_string()
@@ -268,24 +361,13 @@ pub async fn generate_session_summary_stream(
 
     let settings = crate::settings::get_settings(&app);
 
-    // Build timestamped transcript
-    let transcript_text: String = segments
-        .iter()
-        .map(|seg| {
-            let label = if seg.source == "mic" {
-                "[Mic]"
-            } else {
-                "[Other]"
-            };
-            format!(
-                "[{}] {}: {}",
-                format_ms_timestamp(seg.start_ms),
-                label,
-                seg.text
-            )
-        })
-        .collect::<Vec<_>>()
-        .join("\n");
+    // Build timestamped transcript, with the user's bookmarks in place
+    let speaker_names = session_speaker_names(&sm, &session_id);
+    let bookmarks = sm
+        .get_session_bookmarks(&session_id)
+        .map_err(|e| e.to_string())?;
+    let transcript_text = timeline_text(&segments, &bookmarks, &speaker_names);
+    let is_diarized = segments.iter().any(|seg| seg.speaker.is_some());
 
     // Fetch user notes
     let user_notes = sm
@@ -307,12 +389,20 @@ pub async fn generate_session_summary_stream(
 
     let mut system_message = include_str!("../../resources/prompts/enhance_notes.txt").to_string();
 
-    // Inject custom words (+ user name) into the prompt for vocabulary correction
+    // Inject custom words (+ user name, meeting attendees) into the prompt for vocabulary correction
     let mut vocab = settings.custom_words.clone();
     let user_name_trimmed = settings.user_name.trim().to_string();
     if !user_name_trimmed.is_empty() && !vocab.contains(&user_name_trimmed) {
         vocab.push(user_name_trimmed);
     }
+    for name in sm
+        .get_session_attendee_names(&session_id)
+        .map_err(|e| e.to_string())?
+    {
+        if !vocab.contains(&name) {
+            vocab.push(name);
+        }
+    }
     if !vocab.is_empty() {
         system_message.push_str(&format!(
             "\n\nDOMAIN VOCABULARY: The following terms are important and should be spelled exactly as shown: {}\nIf the transcript contains misspellings or misheard versions of these terms, correct them.",
@@ -338,6 +428,13 @@ pub async fn generate_session_summary_stream(
          \nIf only [Mic] segments appear, multiple speakers are likely mixed together. \
          Do not assume one person said everything."
     );
+    if is_diarized {
+        system_message.push_str(
+            "\n- [Mic: <speaker>] = a voice told apart on-device within the microphone audio. \
+             Treat different speakers as different people, but the split is automatic and can be wrong.",
+        );
+    }
+    system_message.push_str(bookmarks_context(&bookmarks));
 
     let notes_section = if user_notes.trim().is_empty() {
         "No notes were taken.".to_string()
@@ -355,7 +452,7 @@ pub async fn generate_session_summary_stream(
     for att in &attachments {
         if att.mime_type.starts_with("image/") {
             // Include images directly if they exist
-            if let Ok(bytes) = std::fs::read(&att.file_path) {
+            if let Ok(bytes) = sm.read_attachment(att) {
                 let base64_data = BASE64.encode(&bytes);
                 let data_url = format!("data:{};base64,{}", att.mime_type, base64_data);
                 image_parts.push(ContentPart::ImageUrl {
@@ -511,11 +608,18 @@ pub async fn generate_session_summary_stream(
     Ok(())
 }
 
+/// Returned while a re-transcription has its own model loaded
+const ENGINE_BORROWED: &str =
+    "Wait for the re-transcription to finish, or cancel it, before recording";
+
 /// Create a new session (Note) without starting recording.
 #[tauri::command]
 #[specta::specta]
 pub fn start_session(app: AppHandle, title: Option<String>) -> Result<Session, String> {
     let sm = app.state::<Arc<SessionManager>>();
+    if sm.is_engine_borrowed() {
+        return Err(ENGINE_BORROWED.to_string());
+    }
     sm.reset_speaker_state();
 
     // Get the default environment ID from settings
@@ -538,6 +642,9 @@ pub fn start_session_recording(app: AppHandle, session_id: String) -> Result<(),
     if sm.get_active_session_id().as_deref() != Some(&session_id) {
         return Err("Session is not active".to_string());
     }
+    if sm.is_engine_borrowed() {
+        return Err(ENGINE_BORROWED.to_string());
+    }
 
     // Reset speaker state for each recording pass
     sm.reset_speaker_state();
@@ -554,7 +661,11 @@ pub fn start_session_recording(app: AppHandle, session_id: String) -> Result<(),
         tm.initiate_model_load();
     }
 
-    rm.start_session_recording().map_err(|e| e.to_string())?;
+    // Checked again under the borrow lock, in case a re-transcription took
+    // the engine since the check above
+    sm.unless_engine_borrowed(|| rm.start_session_recording())
+        .ok_or_else(|| ENGINE_BORROWED.to_string())?
+        .map_err(|e| e.to_string())?;
 
     // Spawn speaker capture task (macOS and Windows)
     #[cfg(any(target_os = "macos", target_os = "windows"))]
@@ -601,6 +712,162 @@ pub fn stop_session_recording(app: AppHandle, session_id: String) -> Result<(),
     Ok(())
 }
 
+/// Import an audio file (WAV, FLAC, MP3 or OGG) as a new, completed session.
+/// Transcription runs in the background and reports through the
+/// `audio-import-progress` and `audio-import-complete` events.
+/// With `split_stereo`, the left channel is treated as mic and the right as speaker.
+#[tauri::command]
+#[specta::specta]
+pub fn import_audio_file(
+    app: AppHandle,
+    file_path: String,
+    title: Option<String>,
+    split_stereo: bool,
+) -> Result<Session, String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    let tm = app.state::<Arc<crate::managers::transcription::TranscriptionManager>>();
+
+    let path = std::path::Path::new(&file_path);
+    if !path.is_file() {
+        return Err(format!("File not found: {}", file_path));
+    }
+
+    if !sm.begin_offline_job() {
+        return Err("An audio import or re-transcription is already running".to_string());
+    }
+
+    let title = title.unwrap_or_else(|| {
+        path.file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| "Imported Audio".to_string())
+    });
+
+    let settings = crate::settings::get_settings(&app);
+    let session = match sm.create_imported_session(title, settings.default_environment_id.clone()) {
+        Ok(session) => session,
+        Err(e) => {
+            sm.finish_offline_job();
+            return Err(e.to_string());
+        }
+    };
+
+    if settings.debug_disable_model_loading {
+        log::warn!("Model loading disabled by debug flag");
+    } else {
+        tm.initiate_model_load();
+    }
+
+    let app_clone = app.clone();
+    let sid = session.id.clone();
+    std::thread::spawn(move || {
+        crate::actions::run_audio_file_import(app_clone, sid, file_path, split_stereo);
+    });
+
+    Ok(session)
+}
+
+/// Cancel the running audio import. Segments transcribed so far are kept.
+#[tauri::command]
+#[specta::specta]
+pub fn cancel_audio_import(app: AppHandle) -> Result<(), String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.cancel_offline_job();
+    Ok(())
+}
+
+/// Re-transcribe a session's archived audio with another model. Runs in the
+/// background and produces a transcript revision; the live transcript only
+/// changes once the revision is applied.
+#[tauri::command]
+#[specta::specta]
+pub fn retranscribe_session(
+    app: AppHandle,
+    session_id: String,
+    model_id: String,
+) -> Result<(), String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    let rm = app.state::<Arc<AudioRecordingManager>>();
+    let mm = app.state::<Arc<crate::managers::model::ModelManager>>();
+
+    match mm.get_model_info(&model_id) {
+        Some(info) if info.is_downloaded => {}
+        Some(_) => return Err(format!("Model {} is not downloaded", model_id)),
+        None => return Err(format!("Unknown model: {}", model_id)),
+    }
+
+    let recordings = sm
+        .get_audio_recordings(&session_id)
+        .map_err(|e| e.to_string())?;
+    if recordings.is_empty() {
+        return Err("This session has no archived audio".to_string());
+    }
+
+    if !sm.begin_offline_job() {
+        return Err("An audio import or re-transcription is already running".to_string());
+    }
+    let recording =
+        || sm.get_active_session_id().as_deref() == Some(&session_id) || rm.is_recording();
+    if !sm.try_borrow_engine(recording) {
+        sm.finish_offline_job();
+        return Err("Stop recording before re-transcribing".to_string());
+    }
+
+    std::thread::spawn(move || {
+        crate::actions::run_session_retranscription(app, session_id, model_id);
+    });
+
+    Ok(())
+}
+
+/// Cancel the running re-transcription. The partial revision is discarded.
+#[tauri::command]
+#[specta::specta]
+pub fn cancel_retranscription(app: AppHandle) -> Result<(), String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.cancel_offline_job();
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_transcript_revisions(
+    app: AppHandle,
+    session_id: String,
+) -> Result<Vec<TranscriptRevision>, String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.get_transcript_revisions(&session_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_transcript_revision_segments(
+    app: AppHandle,
+    revision_id: i64,
+) -> Result<Vec<TranscriptRevisionSegment>, String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.get_transcript_revision_segments(revision_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Replace the session's transcript with a revision. The current transcript
+/// is kept as a revision of its own so the swap can be undone.
+#[tauri::command]
+#[specta::specta]
+pub fn apply_transcript_revision(app: AppHandle, revision_id: i64) -> Result<(), String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.apply_transcript_revision(revision_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn delete_transcript_revision(app: AppHandle, revision_id: i64) -> Result<(), String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.delete_transcript_revision(revision_id)
+        .map_err(|e| e.to_string())
+}
+
 #[cfg(any(target_os = "macos", target_os = "windows"))]
 pub fn spawn_speaker_capture(
     buffer: Arc<std::sync::Mutex<Vec<f32>>>,
@@ -707,6 +974,20 @@ pub fn search_sessions(app: AppHandle, query: String) -> Result<Vec<Session>, St
     sm.search_sessions(&query).map_err(|e| e.to_string())
 }
 
+/// Ranked full-text search over transcripts, notes and attachment text.
+/// Transcript hits carry the segment id and start_ms so the UI can jump to them.
+#[tauri::command]
+#[specta::specta]
+pub fn search_full_text(
+    app: AppHandle,
+    query: String,
+    limit: Option<u32>,
+) -> Result<Vec<SearchHit>, String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.search_full_text(&query, limit.unwrap_or(50))
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn get_sessions(app: AppHandle) -> Result<Vec<Session>, String> {
@@ -732,160 +1013,629 @@ pub fn get_session_transcript(
         .map_err(|e| e.to_string())
 }
 
+/// Fix a segment's text and/or move it to the other source. Omitted fields
+/// are left unchanged.
 #[tauri::command]
 #[specta::specta]
-pub fn get_active_session(app: AppHandle) -> Result<Option<Session>, String> {
+pub fn update_transcript_segment(
+    app: AppHandle,
+    segment_id: i64,
+    text: Option<String>,
+    source: Option<String>,
+) -> Result<TranscriptSegment, String> {
     let sm = app.state::<Arc<SessionManager>>();
-    if let Some(id) = sm.get_active_session_id() {
-        sm.get_session(&id).map_err(|e| e.to_string())
-    } else {
-        Ok(None)
-    }
+    sm.update_segment(segment_id, text.as_deref(), source.as_deref())
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 #[specta::specta]
-pub fn delete_session(app: AppHandle, session_id: String) -> Result<(), String> {
+pub fn delete_transcript_segment(app: AppHandle, segment_id: i64) -> Result<(), String> {
     let sm = app.state::<Arc<SessionManager>>();
-    sm.delete_session(&session_id).map_err(|e| e.to_string())
+    sm.delete_segment(segment_id).map_err(|e| e.to_string())
 }
 
+/// Split a segment before the word at `word_index` (0-based, whitespace-separated).
 #[tauri::command]
 #[specta::specta]
-pub fn update_session_title(
+pub fn split_transcript_segment(
     app: AppHandle,
-    session_id: String,
-    title: String,
-) -> Result<(), String> {
+    segment_id: i64,
+    word_index: u32,
+) -> Result<Vec<TranscriptSegment>, String> {
     let sm = app.state::<Arc<SessionManager>>();
-    sm.update_session_title(&session_id, &title)
-        .map_err(|e| e.to_string())
+    let (first, second) = sm
+        .split_segment(segment_id, word_index as usize)
+        .map_err(|e| e.to_string())?;
+    Ok(vec![first, second])
 }
 
 #[tauri::command]
 #[specta::specta]
-pub fn update_session_environment(
+pub fn merge_transcript_segments(
     app: AppHandle,
-    session_id: String,
-    environment_id: Option<String>,
-) -> Result<(), String> {
+    segment_ids: Vec<i64>,
+) -> Result<TranscriptSegment, String> {
     let sm = app.state::<Arc<SessionManager>>();
-    sm.update_session_environment(&session_id, environment_id.as_deref())
-        .map_err(|e| e.to_string())
+    sm.merge_segments(&segment_ids).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 #[specta::specta]
-pub fn get_meeting_notes(
+pub fn get_transcript_segment_edits(
     app: AppHandle,
     session_id: String,
-) -> Result<Option<MeetingNotes>, String> {
+) -> Result<Vec<TranscriptSegmentEdit>, String> {
     let sm = app.state::<Arc<SessionManager>>();
-    sm.get_meeting_notes(&session_id).map_err(|e| e.to_string())
+    sm.get_segment_edits(&session_id).map_err(|e| e.to_string())
 }
 
+/// Undo a transcript edit. Only the session's most recent edit can be reverted.
 #[tauri::command]
 #[specta::specta]
-pub fn save_meeting_notes(
-    app: AppHandle,
-    session_id: String,
-    summary: Option<String>,
-    action_items: Option<String>,
-    decisions: Option<String>,
-    user_notes: Option<String>,
-) -> Result<(), String> {
+pub fn revert_transcript_segment_edit(app: AppHandle, edit_id: i64) -> Result<(), String> {
     let sm = app.state::<Arc<SessionManager>>();
-    sm.save_meeting_notes(
-        &session_id,
-        summary,
-        action_items,
-        decisions,
-        user_notes,
-        None,
-        None,
-    )
-    .map_err(|e| e.to_string())
+    sm.revert_segment_edit(edit_id).map_err(|e| e.to_string())
 }
 
+/// List the speakers diarization found in a session's mic audio.
 #[tauri::command]
 #[specta::specta]
-pub fn save_user_notes(app: AppHandle, session_id: String, notes: String) -> Result<(), String> {
+pub fn get_session_speakers(
+    app: AppHandle,
+    session_id: String,
+) -> Result<Vec<SessionSpeaker>, String> {
     let sm = app.state::<Arc<SessionManager>>();
-    sm.save_meeting_notes(&session_id, None, None, None, Some(notes), None, None)
+    sm.get_session_speakers(&session_id)
         .map_err(|e| e.to_string())
 }
 
+/// Rename a diarized speaker. Pass `None` or an empty name to reset it.
 #[tauri::command]
 #[specta::specta]
-pub fn save_enhanced_notes(
+pub fn rename_session_speaker(
     app: AppHandle,
     session_id: String,
-    notes: String,
+    label: String,
+    name: Option<String>,
 ) -> Result<(), String> {
     let sm = app.state::<Arc<SessionManager>>();
-    sm.save_meeting_notes(&session_id, None, None, None, None, Some(notes), Some(true))
+    sm.rename_session_speaker(&session_id, &label, name.as_deref())
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 #[specta::specta]
-pub fn get_user_notes(app: AppHandle, session_id: String) -> Result<Option<String>, String> {
+pub fn get_active_session(app: AppHandle) -> Result<Option<Session>, String> {
     let sm = app.state::<Arc<SessionManager>>();
-    let notes = sm
-        .get_meeting_notes(&session_id)
-        .map_err(|e| e.to_string())?;
-    Ok(notes.and_then(|n| n.user_notes))
+    if let Some(id) = sm.get_active_session_id() {
+        sm.get_session(&id).map_err(|e| e.to_string())
+    } else {
+        Ok(None)
+    }
 }
 
-// ==================== Folder Commands ====================
-
+/// Move a session to the trash. It can be restored until it is purged.
 #[tauri::command]
 #[specta::specta]
-pub fn create_folder(
-    app: AppHandle,
-    name: String,
-    color: Option<String>,
-) -> Result<Folder, String> {
+pub fn delete_session(app: AppHandle, session_id: String) -> Result<(), String> {
     let sm = app.state::<Arc<SessionManager>>();
-    sm.create_folder(name, color).map_err(|e| e.to_string())
+
+    // Deleting the session being recorded ends it first
+    if sm.get_active_session_id().as_deref() == Some(&session_id) {
+        end_session(app.clone())?;
+    }
+
+    sm.trash_session(&session_id).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 #[specta::specta]
-pub fn update_folder(
-    app: AppHandle,
-    folder_id: String,
-    name: String,
-    color: Option<String>,
-) -> Result<(), String> {
+pub fn get_trashed_sessions(app: AppHandle) -> Result<Vec<Session>, String> {
     let sm = app.state::<Arc<SessionManager>>();
-    sm.update_folder(&folder_id, name, color)
-        .map_err(|e| e.to_string())
+    sm.get_trashed_sessions().map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 #[specta::specta]
-pub fn delete_folder(app: AppHandle, folder_id: String) -> Result<(), String> {
+pub fn restore_session(app: AppHandle, session_id: String) -> Result<Session, String> {
     let sm = app.state::<Arc<SessionManager>>();
-    sm.delete_folder(&folder_id).map_err(|e| e.to_string())
+    sm.restore_session(&session_id).map_err(|e| e.to_string())
 }
 
+/// Permanently delete a session from the trash, including its attachment files.
 #[tauri::command]
 #[specta::specta]
-pub fn get_folders(app: AppHandle) -> Result<Vec<Folder>, String> {
+pub fn purge_session(app: AppHandle, session_id: String) -> Result<(), String> {
     let sm = app.state::<Arc<SessionManager>>();
-    sm.get_folders().map_err(|e| e.to_string())
+    let in_trash = sm
+        .get_session(&session_id)
+        .map_err(|e| e.to_string())?
+        .is_some_and(|s| s.status == "deleted");
+    if !in_trash {
+        return Err("Only sessions in the trash can be permanently deleted".to_string());
+    }
+    sm.delete_session(&session_id).map_err(|e| e.to_string())
 }
 
+/// Permanently delete everything in the trash. Returns how many sessions were removed.
 #[tauri::command]
 #[specta::specta]
-pub fn move_session_to_folder(
-    app: AppHandle,
-    session_id: String,
-    folder_id: Option<String>,
-) -> Result<(), String> {
+pub fn empty_trash(app: AppHandle) -> Result<u32, String> {
     let sm = app.state::<Arc<SessionManager>>();
-    sm.move_session_to_folder(&session_id, folder_id)
+    sm.empty_trash()
+        .map(|count| count as u32)
+        .map_err(|e| e.to_string())
+}
+
+/// Export a session to a `.talky` bundle. The extension is added when missing.
+#[tauri::command]
+#[specta::specta]
+pub fn export_session_bundle(
+    app: AppHandle,
+    session_id: String,
+    file_path: String,
+) -> Result<(), String> {
+    let mut dest = std::path::PathBuf::from(file_path);
+    if dest.extension().is_none() {
+        dest.set_extension(crate::managers::bundle::BUNDLE_EXTENSION);
+    }
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.export_session_bundle(&session_id, &dest)
+        .map_err(|e| e.to_string())
+}
+
+/// Import a `.talky` bundle as a new session
+#[tauri::command]
+#[specta::specta]
+pub fn import_session_bundle(app: AppHandle, file_path: String) -> Result<Session, String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.import_session_bundle(std::path::Path::new(&file_path))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn update_session_title(
+    app: AppHandle,
+    session_id: String,
+    title: String,
+) -> Result<(), String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.update_session_title(&session_id, &title)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn update_session_environment(
+    app: AppHandle,
+    session_id: String,
+    environment_id: Option<String>,
+) -> Result<(), String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.update_session_environment(&session_id, environment_id.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_meeting_notes(
+    app: AppHandle,
+    session_id: String,
+) -> Result<Option<MeetingNotes>, String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.get_meeting_notes(&session_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn save_meeting_notes(
+    app: AppHandle,
+    session_id: String,
+    summary: Option<String>,
+    action_items: Option<String>,
+    decisions: Option<String>,
+    user_notes: Option<String>,
+) -> Result<(), String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.save_meeting_notes(
+        &session_id,
+        summary,
+        action_items,
+        decisions,
+        user_notes,
+        None,
+        None,
+    )
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn save_user_notes(app: AppHandle, session_id: String, notes: String) -> Result<(), String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.save_meeting_notes(&session_id, None, None, None, Some(notes), None, None)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn save_enhanced_notes(
+    app: AppHandle,
+    session_id: String,
+    notes: String,
+) -> Result<(), String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.save_meeting_notes(&session_id, None, None, None, None, Some(notes), Some(true))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_meeting_notes_revisions(
+    app: AppHandle,
+    session_id: String,
+) -> Result<Vec<MeetingNotesRevision>, String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.get_meeting_notes_revisions(&session_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_meeting_notes_revision(
+    app: AppHandle,
+    revision_id: i64,
+) -> Result<Option<MeetingNotesRevision>, String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.get_meeting_notes_revision(revision_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Restore user notes and enhanced notes from a revision.
+#[tauri::command]
+#[specta::specta]
+pub fn restore_meeting_notes_revision(
+    app: AppHandle,
+    revision_id: i64,
+) -> Result<MeetingNotes, String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.restore_meeting_notes_revision(revision_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Line diff between two notes revisions, from `from_revision_id` to `to_revision_id`.
+#[tauri::command]
+#[specta::specta]
+pub fn diff_meeting_notes_revisions(
+    app: AppHandle,
+    from_revision_id: i64,
+    to_revision_id: i64,
+) -> Result<MeetingNotesDiff, String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.diff_meeting_notes_revisions(from_revision_id, to_revision_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_user_notes(app: AppHandle, session_id: String) -> Result<Option<String>, String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    let notes = sm
+        .get_meeting_notes(&session_id)
+        .map_err(|e| e.to_string())?;
+    Ok(notes.and_then(|n| n.user_notes))
+}
+
+// ==================== Action Item Commands ====================
+
+/// Extract action items from the transcript with the session's summarisation
+/// model, replacing earlier extracted items the user hasn't touched
+#[tauri::command]
+#[specta::specta]
+pub async fn extract_action_items(
+    app: AppHandle,
+    session_id: String,
+) -> Result<Vec<ActionItem>, String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    let segments = sm
+        .get_session_transcript(&session_id)
+        .map_err(|e| e.to_string())?;
+    if segments.is_empty() {
+        return Err("No transcript segments to extract action items from".to_string());
+    }
+
+    let session = sm
+        .get_session(&session_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Session not found".to_string())?;
+    let settings = crate::settings::get_settings(&app);
+    let (base_url, api_key, model) = settings
+        .get_summarisation_config(session.environment_id.as_deref())
+        .ok_or_else(|| "No summarisation model configured. Please configure a model in Settings → Model Environments.".to_string())?;
+
+    // Segment ids let the model point at where each task was agreed
+    let speaker_names = session_speaker_names(&sm, &session_id);
+    let transcript_text: String = segments
+        .iter()
+        .map(|seg| {
+            format!(
+                "[#{}] [{}] {}: {}",
+                seg.id,
+                format_ms_timestamp(seg.start_ms),
+                transcript_label(seg, &speaker_names),
+                seg.text
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let user_notes = sm
+        .get_meeting_notes(&session_id)
+        .map_err(|e| e.to_string())?
+        .and_then(|n| n.user_notes)
+        .filter(|n| !n.trim().is_empty())
+        .unwrap_or_else(|| "No notes were taken.".to_string());
+    let meeting_date = chrono::DateTime::from_timestamp(session.started_at, 0)
+        .map(|d| {
+            d.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d (%A)")
+                .to_string()
+        })
+        .unwrap_or_default();
+
+    let mut system_message =
+        include_str!("../../resources/prompts/extract_action_items.txt").to_string();
+    if !settings.user_name.trim().is_empty() {
+        system_message.push_str(&format!(
+            "\n\n{} recorded this meeting; their microphone audio is labeled [Mic].",
+            settings.user_name.trim()
+        ));
+    }
+    let user_message = format!(
+        "Meeting date: {}\n\n<user_notes>\n{}\n</user_notes>\n\n<transcript>\n{}\n</transcript>",
+        meeting_date, user_notes, transcript_text
+    );
+    let messages = vec![
+        ChatMessage::text("system", system_message),
+        ChatMessage::text("user", user_message),
+    ];
+
+    let reply = crate::llm_client::send_structured_completion(
+        &base_url,
+        &api_key,
+        &model,
+        messages,
+        "action_items",
+        action_items_schema(),
+    )
+    .await?
+    .ok_or_else(|| "LLM returned no content".to_string())?;
+
+    let items = parse_extracted_action_items(&reply).map_err(|e| e.to_string())?;
+    log::info!(
+        "[action-items] Extracted {} items for session {}",
+        items.len(),
+        session_id
+    );
+    sm.replace_extracted_action_items(&session_id, items)
+        .map_err(|e| e.to_string())
+}
+
+/// JSON schema for the extraction reply. Strict mode needs every property
+/// listed as required, so optional fields are nullable instead.
+fn action_items_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "action_items": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "text": { "type": "string" },
+                        "owner": { "type": ["string", "null"] },
+                        "due_date": { "type": ["string", "null"] },
+                        "source_segment_id": { "type": ["integer", "null"] }
+                    },
+                    "required": ["text", "owner", "due_date", "source_segment_id"],
+                    "additionalProperties": false
+                }
+            }
+        },
+        "required": ["action_items"],
+        "additionalProperties": false
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_session_action_items(
+    app: AppHandle,
+    session_id: String,
+) -> Result<Vec<ActionItem>, String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.get_session_action_items(&session_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Action items across all sessions, e.g. everything open for one owner
+#[tauri::command]
+#[specta::specta]
+pub fn query_action_items(
+    app: AppHandle,
+    filter: ActionItemFilter,
+) -> Result<Vec<ActionItem>, String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.query_action_items(&filter).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn create_action_item(
+    app: AppHandle,
+    session_id: String,
+    text: String,
+    owner: Option<String>,
+    due_date: Option<String>,
+) -> Result<ActionItem, String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.create_action_item(&session_id, &text, owner, due_date)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn update_action_item(
+    app: AppHandle,
+    item_id: String,
+    text: String,
+    owner: Option<String>,
+    due_date: Option<String>,
+    status: String,
+) -> Result<ActionItem, String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.update_action_item(&item_id, &text, owner, due_date, &status)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_action_item_status(
+    app: AppHandle,
+    item_id: String,
+    status: String,
+) -> Result<ActionItem, String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.set_action_item_status(&item_id, &status)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn delete_action_item(app: AppHandle, item_id: String) -> Result<(), String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.delete_action_item(&item_id).map_err(|e| e.to_string())
+}
+
+// ==================== Bookmark Commands ====================
+
+/// Bookmark a moment of a session. Leave `offset_ms` unset to bookmark the
+/// current moment of the running recording.
+#[tauri::command]
+#[specta::specta]
+pub fn create_bookmark(
+    app: AppHandle,
+    session_id: String,
+    offset_ms: Option<i64>,
+    label: Option<String>,
+    color: Option<String>,
+) -> Result<Bookmark, String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.create_bookmark(&session_id, offset_ms, label, color)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn update_bookmark(
+    app: AppHandle,
+    bookmark_id: String,
+    label: Option<String>,
+    color: Option<String>,
+) -> Result<Bookmark, String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.update_bookmark(&bookmark_id, label, color)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn delete_bookmark(app: AppHandle, bookmark_id: String) -> Result<(), String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.delete_bookmark(&bookmark_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_session_bookmarks(app: AppHandle, session_id: String) -> Result<Vec<Bookmark>, String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.get_session_bookmarks(&session_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Transcript segments and bookmarks of a session, for showing them together
+#[tauri::command]
+#[specta::specta]
+pub fn get_session_timeline(app: AppHandle, session_id: String) -> Result<SessionTimeline, String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.get_session_timeline(&session_id)
+        .map_err(|e| e.to_string())
+}
+
+// ==================== Folder Commands ====================
+
+#[tauri::command]
+#[specta::specta]
+pub fn create_folder(
+    app: AppHandle,
+    name: String,
+    color: Option<String>,
+    parent_id: Option<String>,
+) -> Result<Folder, String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.create_folder(name, color, parent_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn update_folder(
+    app: AppHandle,
+    folder_id: String,
+    name: String,
+    color: Option<String>,
+) -> Result<(), String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.update_folder(&folder_id, name, color)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn delete_folder(app: AppHandle, folder_id: String) -> Result<(), String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.delete_folder(&folder_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_folders(app: AppHandle) -> Result<Vec<Folder>, String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.get_folders().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn move_folder(
+    app: AppHandle,
+    folder_id: String,
+    parent_id: Option<String>,
+) -> Result<(), String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.move_folder(&folder_id, parent_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn move_session_to_folder(
+    app: AppHandle,
+    session_id: String,
+    folder_id: Option<String>,
+) -> Result<(), String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.move_session_to_folder(&session_id, folder_id)
         .map_err(|e| e.to_string())
 }
 
@@ -900,6 +1650,84 @@ pub fn get_sessions_by_folder(
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn get_sessions_in_folder_tree(
+    app: AppHandle,
+    folder_id: String,
+) -> Result<Vec<Session>, String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.get_sessions_in_folder_tree(&folder_id)
+        .map_err(|e| e.to_string())
+}
+
+// ==================== Smart Folder Commands ====================
+
+#[tauri::command]
+#[specta::specta]
+pub fn create_smart_folder(
+    app: AppHandle,
+    name: String,
+    color: Option<String>,
+    query: SmartFolderQuery,
+) -> Result<SmartFolder, String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.create_smart_folder(name, color, query)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn update_smart_folder(
+    app: AppHandle,
+    smart_folder_id: String,
+    name: String,
+    color: Option<String>,
+    query: SmartFolderQuery,
+) -> Result<(), String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.update_smart_folder(&smart_folder_id, name, color, query)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn delete_smart_folder(app: AppHandle, smart_folder_id: String) -> Result<(), String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.delete_smart_folder(&smart_folder_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_smart_folders(app: AppHandle) -> Result<Vec<SmartFolder>, String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.get_smart_folders().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_smart_folder_sessions(
+    app: AppHandle,
+    smart_folder_id: String,
+) -> Result<Vec<Session>, String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.get_smart_folder_sessions(&smart_folder_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Run a smart folder query without saving it, for previews while editing
+#[tauri::command]
+#[specta::specta]
+pub fn preview_smart_folder(
+    app: AppHandle,
+    query: SmartFolderQuery,
+) -> Result<Vec<Session>, String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.evaluate_smart_folder_query(&query)
+        .map_err(|e| e.to_string())
+}
+
 // ==================== Tag Commands ====================
 
 #[tauri::command]
@@ -986,6 +1814,202 @@ pub fn get_sessions_by_tag(app: AppHandle, tag_id: String) -> Result<Vec<Session
     sm.get_sessions_by_tag(&tag_id).map_err(|e| e.to_string())
 }
 
+// ==================== Audio Recording Commands ====================
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_audio_recordings(
+    app: AppHandle,
+    session_id: String,
+) -> Result<Vec<AudioRecording>, String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.get_audio_recordings(&session_id)
+        .map_err(|e| e.to_string())
+}
+
+// ==================== Chat Commands ====================
+
+#[tauri::command]
+#[specta::specta]
+pub fn create_chat_thread(
+    app: AppHandle,
+    session_id: String,
+    title: Option<String>,
+) -> Result<ChatThread, String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.create_chat_thread(&session_id, title)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_chat_threads(app: AppHandle, session_id: String) -> Result<Vec<ChatThread>, String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.get_chat_threads(&session_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn rename_chat_thread(app: AppHandle, thread_id: String, title: String) -> Result<(), String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.rename_chat_thread(&thread_id, &title)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn delete_chat_thread(app: AppHandle, thread_id: String) -> Result<(), String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.delete_chat_thread(&thread_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn add_chat_message(
+    app: AppHandle,
+    thread_id: String,
+    role: String,
+    content: String,
+) -> Result<ChatThreadMessage, String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.add_chat_message(&thread_id, &role, content)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_chat_messages(
+    app: AppHandle,
+    thread_id: String,
+) -> Result<Vec<ChatThreadMessage>, String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.get_chat_messages(&thread_id).map_err(|e| e.to_string())
+}
+
+/// Transcript segments and notes across sessions that are relevant to a question
+#[tauri::command]
+#[specta::specta]
+pub fn retrieve_session_passages(
+    app: AppHandle,
+    question: String,
+    scope: SessionScope,
+) -> Result<Vec<RetrievedPassage>, String> {
+    let sm = app.state::<Arc<SessionManager>>();
+    sm.retrieve_passages(&question, &scope)
+        .map_err(|e| e.to_string())
+}
+
+/// Answer a question over all sessions in `scope` with the chat model. The answer
+/// cites its sources with `[n]` markers, which `citations` resolves to the session,
+/// transcript segment and timestamp they came from.
+#[tauri::command]
+#[specta::specta]
+pub async fn ask_across_sessions(
+    app: AppHandle,
+    question: String,
+    scope: SessionScope,
+) -> Result<CrossSessionAnswer, String> {
+    let question = question.trim();
+    if question.is_empty() {
+        return Err("Question is empty".to_string());
+    }
+
+    let sm = app.state::<Arc<SessionManager>>();
+    let passages = sm
+        .retrieve_passages(question, &scope)
+        .map_err(|e| e.to_string())?;
+    if passages.is_empty() {
+        return Ok(CrossSessionAnswer {
+            answer: "None of the meetings in scope mention this.".to_string(),
+            citations: Vec::new(),
+        });
+    }
+
+    let settings = crate::settings::get_settings(&app);
+    let (base_url, api_key, model) = settings.get_chat_config(None).ok_or_else(|| {
+        "No chat model configured. Please configure a model in Settings → Model Environments."
+            .to_string()
+    })?;
+
+    // Number every excerpt line so the answer can cite it
+    let mut excerpts = String::new();
+    let mut labelled: Vec<&RetrievedPassage> = Vec::new();
+    let mut speaker_names: HashMap<String, HashMap<String, String>> = HashMap::new();
+    for passage in &passages {
+        if labelled
+            .last()
+            .is_none_or(|last| last.session_id != passage.session_id)
+        {
+            let date = chrono::DateTime::from_timestamp(passage.session_started_at, 0)
+                .map(|d| {
+                    d.with_timezone(&chrono::Local)
+                        .format("%Y-%m-%d")
+                        .to_string()
+                })
+                .unwrap_or_default();
+            excerpts.push_str(&format!("\n## {} ({})\n", passage.session_title, date));
+        }
+        labelled.push(passage);
+
+        let label = labelled.len();
+        match (passage.start_ms, passage.source.as_deref()) {
+            (Some(start_ms), Some(source)) => {
+                let names = speaker_names
+                    .entry(passage.session_id.clone())
+                    .or_insert_with(|| session_speaker_names(&sm, &passage.session_id));
+                excerpts.push_str(&format!(
+                    "[{}] [{}] {}: {}\n",
+                    label,
+                    format_ms_timestamp(start_ms),
+                    source_label(source, passage.speaker.as_ref(), names),
+                    passage.text
+                ));
+            }
+            _ => excerpts.push_str(&format!("[{}] (notes) {}\n", label, passage.text)),
+        }
+    }
+
+    let user_message = format!(
+        "Today is {}.\n\n<excerpts>{}</excerpts>\n\nQuestion: {}",
+        chrono::Local::now().format("%Y-%m-%d (%A)"),
+        excerpts,
+        question
+    );
+    let messages = vec![
+        ChatMessage::text(
+            "system",
+            include_str!("../../resources/prompts/ask_across_sessions.txt"),
+        ),
+        ChatMessage::text("user", user_message),
+    ];
+
+    let answer = crate::llm_client::send_chat_completion(&base_url, &api_key, &model, messages)
+        .await?
+        .ok_or_else(|| "LLM returned no content".to_string())?;
+
+    let citations: Vec<AnswerCitation> = cited_labels(&answer)
+        .into_iter()
+        .filter_map(|label| {
+            let passage = labelled.get((label as usize).checked_sub(1)?)?;
+            Some(AnswerCitation {
+                label,
+                session_id: passage.session_id.clone(),
+                session_title: passage.session_title.clone(),
+                segment_id: passage.segment_id,
+                start_ms: passage.start_ms,
+            })
+        })
+        .collect();
+    log::info!(
+        "[ask] {} excerpts from {} passages, {} citations",
+        labelled.len(),
+        passages.len(),
+        citations.len()
+    );
+
+    Ok(CrossSessionAnswer { answer, citations })
+}
+
 // ==================== Attachment Commands ====================
 
 #[tauri::command]
@@ -1033,8 +2057,13 @@ pub fn open_attachment(app: AppHandle, attachment_id: String) -> Result<(), Stri
         .map_err(|e| e.to_string())?
         .ok_or_else(|| "Attachment not found".to_string())?;
 
+    // Encrypted attachments are opened from a decrypted temporary copy
+    let path = sm
+        .attachment_open_path(&attachment)
+        .map_err(|e| e.to_string())?;
+
     // Open file in default application
-    tauri_plugin_opener::open_path(&attachment.file_path, None::<&str>).map_err(|e| e.to_string())
+    tauri_plugin_opener::open_path(path, None::<&str>).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -1051,7 +2080,8 @@ pub async fn extract_pdf_text(app: AppHandle, attachment_id: String) -> Result<S
     }
 
     // Extract text from PDF
-    let text = pdf_extract::extract_text(&attachment.file_path)
+    let bytes = sm.read_attachment(&attachment).map_err(|e| e.to_string())?;
+    let text = pdf_extract::extract_text_from_mem(&bytes)
         .map_err(|e| format!("Failed to extract PDF text: {}", e))?;
 
     // Limit text to reasonable size (200 pages worth, roughly 500KB)
@@ -1080,3 +2110,74 @@ pub async fn extract_pdf_text(app: AppHandle, attachment_id: String) -> Result<S
     Ok(text)
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(
+        start_ms: i64,
+        source: &str,
+        speaker: Option<&str>,
+        text: &str,
+    ) -> TranscriptSegment {
+        TranscriptSegment {
+            id: start_ms,
+            session_id: "s".to_string(),
+            text: text.to_string(),
+            source: source.to_string(),
+            start_ms,
+            end_ms: start_ms + 2_000,
+            created_at: 0,
+            speaker: speaker.map(str::to_string),
+            words: Vec::new(),
+        }
+    }
+
+    fn bookmark(offset_ms: i64, label: Option<&str>) -> Bookmark {
+        Bookmark {
+            id: offset_ms.to_string(),
+            session_id: "s".to_string(),
+            offset_ms,
+            label: label.map(str::to_string),
+            color: None,
+            created_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_timeline_text_places_bookmarks_after_earlier_segments() {
+        let segments = vec![
+            segment(0, "speaker", None, "Let's talk pricing."),
+            segment(5_000, "mic", Some("A"), "Annual plans get 20% off."),
+            segment(65_000, "mic", None, "Next topic."),
+        ];
+        let bookmarks = vec![
+            bookmark(7_000, Some("Discount")),
+            bookmark(65_000, None),
+            bookmark(3_600_000, None),
+        ];
+        let names = HashMap::from([("A".to_string(), "Sam".to_string())]);
+
+        assert_eq!(
+            timeline_text(&segments, &bookmarks, &names),
+            "[00:00] [Other]: Let's talk pricing.\n\
+             [00:05] [Mic: Sam]: Annual plans get 20% off.\n\
+             [00:07] ★ BOOKMARK: Discount\n\
+             [01:05] [Mic]: Next topic.\n\
+             [01:05] ★ BOOKMARK\n\
+             [01:00:00] ★ BOOKMARK"
+        );
+        assert_eq!(
+            timeline_text(&segments, &[], &names).lines().count(),
+            segments.len()
+        );
+    }
+
+    #[test]
+    fn test_bookmarks_context_explains_bookmark_lines() {
+        assert_eq!(bookmarks_context(&[]), "");
+        let context = bookmarks_context(&[bookmark(0, None)]);
+        let marker = bookmark_line(&bookmark(0, None));
+        assert!(context.contains(marker.trim_start_matches("[00:00] ")));
+    }
+}
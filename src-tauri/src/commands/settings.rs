@@ -1,6 +1,10 @@
+use crate::document::template::check_template;
+use crate::managers::backup::BackupManager;
+use crate::managers::calendar::CalendarManager;
 use crate::managers::session::SessionManager;
 use crate::settings::{
-    get_settings, write_settings, FontSize, LLMPrompt, ModelEnvironment, WordSuggestion,
+    get_settings, write_settings, BackupInterval, ExportTemplate, FontSize, LLMPrompt,
+    ModelEnvironment, WordSuggestion,
 };
 use crate::tray::update_tray_menu;
 use log::info;
@@ -158,6 +162,75 @@ pub fn set_post_process_selected_prompt(app: AppHandle, id: String) -> Result<()
     Ok(())
 }
 
+/// Validate an export template before it is saved. Returns the extension
+/// without a leading dot.
+fn check_export_template(extension: &str, template: &str) -> Result<String, String> {
+    let extension = extension.trim().trim_start_matches('.');
+    if extension.is_empty() || !extension.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(format!("Invalid file extension: {}", extension));
+    }
+    check_template(template).map_err(|e| format!("Invalid template: {}", e))?;
+    Ok(extension.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn add_export_template(
+    app: AppHandle,
+    name: String,
+    extension: String,
+    template: String,
+) -> Result<ExportTemplate, String> {
+    let extension = check_export_template(&extension, &template)?;
+    let mut settings = get_settings(&app);
+    let new_template = ExportTemplate {
+        id: uuid::Uuid::new_v4().to_string(),
+        name,
+        extension,
+        template,
+    };
+    settings.export_templates.push(new_template.clone());
+    write_settings(&app, settings);
+    Ok(new_template)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn update_export_template(
+    app: AppHandle,
+    id: String,
+    name: String,
+    extension: String,
+    template: String,
+) -> Result<(), String> {
+    let extension = check_export_template(&extension, &template)?;
+    let mut settings = get_settings(&app);
+    if let Some(existing) = settings.export_templates.iter_mut().find(|t| t.id == id) {
+        existing.name = name;
+        existing.extension = extension;
+        existing.template = template;
+        write_settings(&app, settings);
+        Ok(())
+    } else {
+        Err(format!("Export template not found: {}", id))
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn delete_export_template(app: AppHandle, id: String) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    let original_len = settings.export_templates.len();
+    settings.export_templates.retain(|t| t.id != id);
+
+    if settings.export_templates.len() < original_len {
+        write_settings(&app, settings);
+        Ok(())
+    } else {
+        Err(format!("Export template not found: {}", id))
+    }
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn update_custom_words(app: AppHandle, words: Vec<String>) -> Result<(), String> {
@@ -194,6 +267,91 @@ pub fn change_update_checks_setting(app: AppHandle, enabled: bool) -> Result<(),
     Ok(())
 }
 
+/// Set how many days deleted sessions stay in the trash (0 keeps them forever)
+/// and purge anything that is now past that.
+#[tauri::command]
+#[specta::specta]
+pub fn change_trash_retention_days_setting(app: AppHandle, days: u32) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.trash_retention_days = days;
+    write_settings(&app, settings);
+
+    app.state::<Arc<SessionManager>>()
+        .purge_expired_trash()
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_backup_directory_setting(app: AppHandle, dir: Option<String>) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.backup_directory = dir;
+    write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_backup_interval_setting(
+    app: AppHandle,
+    interval: BackupInterval,
+) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.backup_interval = interval;
+    write_settings(&app, settings);
+    Ok(())
+}
+
+/// Set how many backups to keep (0 keeps all) and rotate the backup folder
+#[tauri::command]
+#[specta::specta]
+pub fn change_backup_keep_count_setting(app: AppHandle, count: u32) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.backup_keep_count = count;
+    let dir = settings.backup_directory.clone();
+    write_settings(&app, settings);
+
+    if let Some(dir) = dir {
+        app.state::<Arc<BackupManager>>()
+            .rotate_backups(std::path::Path::new(&dir), count as usize)
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Set the folder watched for .ics files and import it right away
+#[tauri::command]
+#[specta::specta]
+pub fn change_calendar_folder_setting(app: AppHandle, dir: Option<String>) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.calendar_folder = dir;
+    write_settings(&app, settings);
+
+    app.state::<Arc<CalendarManager>>()
+        .sync_folder(true)
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Set the global shortcut that bookmarks the running recording. The setting is
+/// only saved once the shortcut is registered.
+#[tauri::command]
+#[specta::specta]
+pub fn change_bookmark_shortcut_setting(
+    app: AppHandle,
+    shortcut: Option<String>,
+) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    crate::shortcut::change_bookmark_shortcut(
+        &app,
+        settings.bookmark_shortcut.as_deref(),
+        shortcut.as_deref(),
+    )?;
+    settings.bookmark_shortcut = shortcut;
+    write_settings(&app, settings);
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn change_copy_as_bullets_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
@@ -0,0 +1,255 @@
+use super::{Block, ExportDocument, Line, Origin, Span, AI_COLOR};
+use anyhow::Result;
+use std::io::{Cursor, Write};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/><Override PartName="/word/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.styles+xml"/><Override PartName="/word/numbering.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.numbering+xml"/><Override PartName="/docProps/core.xml" ContentType="application/vnd.openxmlformats-package.core-properties+xml"/></Types>"#;
+
+const PACKAGE_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="word/document.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/package/2006/relationships/metadata/core-properties" Target="docProps/core.xml"/></Relationships>"#;
+
+const DOCUMENT_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/numbering" Target="numbering.xml"/></Relationships>"#;
+
+/// Body text is 11pt with a little space after each paragraph. Headings use
+/// Word's built-in style ids so they show up in the navigation pane.
+const STYLES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:styles xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:docDefaults><w:rPrDefault><w:rPr><w:rFonts w:ascii="Calibri" w:hAnsi="Calibri" w:eastAsia="Calibri" w:cs="Calibri"/><w:sz w:val="22"/><w:szCs w:val="22"/></w:rPr></w:rPrDefault><w:pPrDefault><w:pPr><w:spacing w:after="120" w:line="264" w:lineRule="auto"/></w:pPr></w:pPrDefault></w:docDefaults><w:style w:type="paragraph" w:default="1" w:styleId="Normal"><w:name w:val="Normal"/><w:qFormat/></w:style><w:style w:type="paragraph" w:styleId="Title"><w:name w:val="Title"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:qFormat/><w:pPr><w:spacing w:after="200"/></w:pPr><w:rPr><w:b/><w:sz w:val="44"/><w:szCs w:val="44"/></w:rPr></w:style><w:style w:type="paragraph" w:styleId="Heading1"><w:name w:val="heading 1"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:qFormat/><w:pPr><w:keepNext/><w:spacing w:before="360" w:after="120"/><w:outlineLvl w:val="0"/></w:pPr><w:rPr><w:b/><w:sz w:val="32"/><w:szCs w:val="32"/></w:rPr></w:style><w:style w:type="paragraph" w:styleId="Heading2"><w:name w:val="heading 2"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:qFormat/><w:pPr><w:keepNext/><w:spacing w:before="240" w:after="80"/><w:outlineLvl w:val="1"/></w:pPr><w:rPr><w:b/><w:sz w:val="26"/><w:szCs w:val="26"/></w:rPr></w:style><w:style w:type="paragraph" w:styleId="Heading3"><w:name w:val="heading 3"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:qFormat/><w:pPr><w:keepNext/><w:spacing w:before="200" w:after="60"/><w:outlineLvl w:val="2"/></w:pPr><w:rPr><w:b/><w:sz w:val="23"/><w:szCs w:val="23"/></w:rPr></w:style></w:styles>"#;
+
+/// Bullet characters of nested list levels, repeating
+const BULLETS: [&str; 3] = ["•", "◦", "▪"];
+/// List indent per nesting level, in twentieths of a point
+const LIST_INDENT: usize = 360;
+
+/// Render the document as a Word (.docx) file
+pub fn render(doc: &ExportDocument) -> Result<Vec<u8>> {
+    let parts = [
+        ("[Content_Types].xml", CONTENT_TYPES.to_string()),
+        ("_rels/.rels", PACKAGE_RELS.to_string()),
+        ("docProps/core.xml", core_xml(&doc.title)),
+        ("word/_rels/document.xml.rels", DOCUMENT_RELS.to_string()),
+        ("word/styles.xml", STYLES.to_string()),
+        ("word/numbering.xml", numbering_xml()),
+        ("word/document.xml", document_xml(&doc.blocks())),
+    ];
+
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    // The parts are a few kB of XML, not worth compressing
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+    for (name, content) in parts {
+        zip.start_file(name, options)?;
+        zip.write_all(content.as_bytes())?;
+    }
+    Ok(zip.finish()?.into_inner())
+}
+
+fn core_xml(title: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<cp:coreProperties xmlns:cp="http://schemas.openxmlformats.org/package/2006/metadata/core-properties" xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>{}</dc:title></cp:coreProperties>"#,
+        escape_xml(title)
+    )
+}
+
+/// One bulleted list definition with a level per nesting depth
+fn numbering_xml() -> String {
+    let levels: String = (0..9)
+        .map(|level| {
+            format!(
+                r#"<w:lvl w:ilvl="{}"><w:start w:val="1"/><w:numFmt w:val="bullet"/><w:lvlText w:val="{}"/><w:lvlJc w:val="left"/><w:pPr><w:ind w:left="{}" w:hanging="{}"/></w:pPr></w:lvl>"#,
+                level,
+                BULLETS[level % BULLETS.len()],
+                LIST_INDENT * (level + 1),
+                LIST_INDENT
+            )
+        })
+        .collect();
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:numbering xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:abstractNum w:abstractNumId="0"><w:multiLevelType w:val="hybridMultilevel"/>{}</w:abstractNum><w:num w:numId="1"><w:abstractNumId w:val="0"/></w:num></w:numbering>"#,
+        levels
+    )
+}
+
+fn document_xml(blocks: &[Block]) -> String {
+    let mut body = String::new();
+    for block in blocks {
+        match block {
+            Block::Title(title) => {
+                body.push_str(&paragraph(
+                    r#"<w:pStyle w:val="Title"/>"#,
+                    &run(&Span::plain(title), None),
+                ));
+            }
+            Block::Heading { level, line } => {
+                body.push_str(&paragraph(
+                    &format!(r#"<w:pStyle w:val="Heading{}"/>"#, (*level).clamp(1, 3)),
+                    &runs(line),
+                ));
+            }
+            Block::Paragraph(line) => body.push_str(&paragraph("", &runs(line))),
+            Block::ListItem {
+                depth,
+                number: None,
+                line,
+            } => {
+                body.push_str(&paragraph(
+                    &format!(
+                        r#"<w:numPr><w:ilvl w:val="{}"/><w:numId w:val="1"/></w:numPr><w:spacing w:after="40"/>"#,
+                        (*depth).min(8)
+                    ),
+                    &runs(line),
+                ));
+            }
+            // Numbers are written out rather than left to Word, so each list
+            // keeps the numbering it had in the notes
+            Block::ListItem {
+                depth,
+                number: Some(number),
+                line,
+            } => {
+                let mut numbered = line.clone();
+                numbered
+                    .spans
+                    .insert(0, Span::plain(format!("{}.\t", number)));
+                body.push_str(&paragraph(
+                    &format!(
+                        r#"<w:spacing w:after="40"/><w:ind w:left="{}" w:hanging="{}"/>"#,
+                        LIST_INDENT * (depth + 1),
+                        LIST_INDENT
+                    ),
+                    &runs(&numbered),
+                ));
+            }
+            Block::Note(text) => {
+                let span = Span {
+                    text: text.clone(),
+                    italic: true,
+                    ..Default::default()
+                };
+                let properties = format!(
+                    r#"{}<w:sz w:val="18"/><w:szCs w:val="18"/>"#,
+                    color(AI_COLOR)
+                );
+                body.push_str(&paragraph("", &run(&span, Some(&properties))));
+            }
+        }
+    }
+
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:body>{}<w:sectPr><w:pgSz w:w="11906" w:h="16838"/><w:pgMar w:top="1134" w:right="1134" w:bottom="1134" w:left="1134" w:header="709" w:footer="709" w:gutter="0"/></w:sectPr></w:body></w:document>"#,
+        body
+    )
+}
+
+fn paragraph(properties: &str, runs: &str) -> String {
+    if properties.is_empty() {
+        format!("<w:p>{}</w:p>", runs)
+    } else {
+        format!("<w:p><w:pPr>{}</w:pPr>{}</w:p>", properties, runs)
+    }
+}
+
+/// Runs of a line; [ai] lines are coloured
+fn runs(line: &Line) -> String {
+    let extra = (line.origin == Some(Origin::Ai)).then(|| color(AI_COLOR));
+    line.spans
+        .iter()
+        .map(|span| run(span, extra.as_deref()))
+        .collect()
+}
+
+fn run(span: &Span, extra_properties: Option<&str>) -> String {
+    let mut properties = String::new();
+    if span.bold {
+        properties.push_str("<w:b/>");
+    }
+    if span.italic {
+        properties.push_str("<w:i/>");
+    }
+    properties.push_str(extra_properties.unwrap_or_default());
+    let properties = if properties.is_empty() {
+        properties
+    } else {
+        format!("<w:rPr>{}</w:rPr>", properties)
+    };
+
+    // Tabs must be their own element
+    let text = span
+        .text
+        .split('\t')
+        .map(|part| format!(r#"<w:t xml:space="preserve">{}</w:t>"#, escape_xml(part)))
+        .collect::<Vec<_>>()
+        .join("<w:tab/>");
+    format!("<w:r>{}{}</w:r>", properties, text)
+}
+
+fn color([r, g, b]: [u8; 3]) -> String {
+    format!(r#"<w:color w:val="{:02X}{:02X}{:02X}"/>"#, r, g, b)
+}
+
+/// XML-safe text: escapes markup and drops control characters XML can't hold
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c if c.is_control() && !matches!(c, '\t' | '\n' | '\r') => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{NotesSection, AI_LEGEND};
+    use super::*;
+    use std::io::Read;
+    use zip::ZipArchive;
+
+    #[test]
+    fn test_render_styles_headings_bullets_and_ai_lines() {
+        let doc = ExportDocument {
+            title: "Planning".to_string(),
+            date: "2025-03-01 10:00".to_string(),
+            notes: vec![NotesSection {
+                heading: "Notes".to_string(),
+                content: "### Budget\n- [noted] Q3 budget\n  - [ai] Board signoff".to_string(),
+            }],
+            ..Default::default()
+        };
+        let mut archive = ZipArchive::new(Cursor::new(render(&doc).unwrap())).unwrap();
+        let mut xml = String::new();
+        archive
+            .by_name("word/document.xml")
+            .unwrap()
+            .read_to_string(&mut xml)
+            .unwrap();
+
+        let text = |text: &str| format!(r#"<w:t xml:space="preserve">{}</w:t>"#, text);
+        assert!(xml.contains(&format!(
+            r#"<w:p><w:pPr><w:pStyle w:val="Heading1"/></w:pPr><w:r>{}</w:r></w:p>"#,
+            text("Notes")
+        )));
+        assert!(xml.contains(&format!(
+            r#"<w:p><w:pPr><w:pStyle w:val="Heading3"/></w:pPr><w:r>{}</w:r></w:p>"#,
+            text("Budget")
+        )));
+        assert!(xml.contains(&format!(
+            r#"<w:p><w:pPr><w:numPr><w:ilvl w:val="0"/><w:numId w:val="1"/></w:numPr><w:spacing w:after="40"/></w:pPr><w:r>{}</w:r></w:p>"#,
+            text("Q3 budget")
+        )));
+        assert!(xml.contains(&format!(
+            r#"<w:p><w:pPr><w:numPr><w:ilvl w:val="1"/><w:numId w:val="1"/></w:numPr><w:spacing w:after="40"/></w:pPr><w:r><w:rPr><w:color w:val="6B7280"/></w:rPr>{}</w:r></w:p>"#,
+            text("Board signoff")
+        )));
+        assert!(xml.contains(&text(AI_LEGEND)));
+    }
+}
@@ -0,0 +1,526 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use ttf_parser::{name_id, Face, Tag};
+
+/// TrueType fonts that come with the OS and cover far more than Windows-1252
+#[cfg(target_os = "macos")]
+const SYSTEM_FONTS: &[&str] = &[
+    "/System/Library/Fonts/Supplemental/Arial Unicode.ttf",
+    "/Library/Fonts/Arial Unicode.ttf",
+    "/System/Library/Fonts/Supplemental/Arial.ttf",
+];
+#[cfg(target_os = "windows")]
+const SYSTEM_FONTS: &[&str] = &[
+    "C:\\Windows\\Fonts\\arial.ttf",
+    "C:\\Windows\\Fonts\\seguisym.ttf",
+    "C:\\Windows\\Fonts\\msyh.ttc",
+    "C:\\Windows\\Fonts\\malgun.ttf",
+];
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const SYSTEM_FONTS: &[&str] = &[
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/dejavu-sans-fonts/DejaVuSans.ttf",
+    "/usr/share/fonts/TTF/DejaVuSans.ttf",
+    "/usr/share/fonts/truetype/noto/NotoSans-Regular.ttf",
+    "/usr/share/fonts/truetype/droid/DroidSansFallbackFull.ttf",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Glyph {
+    pub id: u16,
+    /// Advance width in 1/1000 em
+    pub width: f32,
+}
+
+/// Font-wide metrics in 1/1000 em, as a PDF font descriptor wants them
+pub struct Metrics {
+    pub ascent: f32,
+    pub descent: f32,
+    pub cap_height: f32,
+    pub italic_angle: f32,
+    pub bbox: [f32; 4],
+}
+
+/// A TrueType font for the characters the standard PDF fonts can't show
+pub struct UnicodeFont {
+    data: Vec<u8>,
+    /// The glyph of each character of the document the font has
+    glyphs: BTreeMap<char, Glyph>,
+}
+
+impl UnicodeFont {
+    /// The system font that has the most of `chars`, unless none has any
+    pub fn find(chars: &BTreeSet<char>) -> Option<Self> {
+        let mut best: Option<UnicodeFont> = None;
+        for path in SYSTEM_FONTS {
+            let Some(font) = Self::load(Path::new(path), chars) else {
+                continue;
+            };
+            if best
+                .as_ref()
+                .is_none_or(|best| font.glyphs.len() > best.glyphs.len())
+            {
+                let complete = font.glyphs.len() == chars.len();
+                best = Some(font);
+                if complete {
+                    break;
+                }
+            }
+        }
+        best.filter(|font| !font.glyphs.is_empty())
+    }
+
+    /// The font at `path` with its glyphs for `chars`. Only fonts with
+    /// TrueType outlines can be embedded this way; for a collection the first
+    /// font is used.
+    pub fn load(path: &Path, chars: &BTreeSet<char>) -> Option<Self> {
+        let data = std::fs::read(path).ok()?;
+        let glyphs = {
+            let face = Face::parse(&data, 0).ok()?;
+            face.raw_face().table(Tag::from_bytes(b"glyf"))?;
+            let scale = 1000.0 / face.units_per_em() as f32;
+            chars
+                .iter()
+                .filter_map(|&c| {
+                    let id = face.glyph_index(c)?;
+                    let width = face.glyph_hor_advance(id).unwrap_or(0) as f32 * scale;
+                    Some((c, Glyph { id: id.0, width }))
+                })
+                .collect()
+        };
+        Some(UnicodeFont { data, glyphs })
+    }
+
+    pub fn glyph(&self, c: char) -> Option<Glyph> {
+        self.glyphs.get(&c).copied()
+    }
+
+    /// Each character the font is used for, with its glyph
+    pub fn glyphs(&self) -> impl Iterator<Item = (char, Glyph)> + '_ {
+        self.glyphs.iter().map(|(&c, &glyph)| (c, glyph))
+    }
+
+    fn face(&self) -> Face<'_> {
+        Face::parse(&self.data, 0).expect("font was parsed when loaded")
+    }
+
+    /// PostScript name, as PDF font names allow it
+    pub fn name(&self) -> String {
+        let name = self
+            .face()
+            .names()
+            .into_iter()
+            .filter(|name| name.name_id == name_id::POST_SCRIPT_NAME)
+            .find_map(|name| name.to_string())
+            .unwrap_or_default();
+        let name: String = name
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || *c == '-')
+            .collect();
+        if name.is_empty() {
+            "Unicode".to_string()
+        } else {
+            name
+        }
+    }
+
+    pub fn metrics(&self) -> Metrics {
+        let face = self.face();
+        let scale = 1000.0 / face.units_per_em() as f32;
+        let bbox = face.global_bounding_box();
+        Metrics {
+            ascent: face.ascender() as f32 * scale,
+            descent: face.descender() as f32 * scale,
+            cap_height: face.capital_height().unwrap_or(face.ascender()) as f32 * scale,
+            italic_angle: face.italic_angle(),
+            bbox: [bbox.x_min, bbox.y_min, bbox.x_max, bbox.y_max].map(|v| v as f32 * scale),
+        }
+    }
+
+    /// The font file with every glyph but the ones in use emptied, so
+    /// embedding a large system font costs little more than those glyphs.
+    /// Glyph ids stay the same, and tables a PDF reader doesn't use are
+    /// dropped.
+    pub fn subset(&self) -> Option<Vec<u8>> {
+        let face = self.face();
+        let raw = face.raw_face();
+        let table = |tag: &[u8; 4]| raw.table(Tag::from_bytes(tag));
+        let head = table(b"head")?;
+        let loca = table(b"loca")?;
+        let glyf = table(b"glyf")?;
+        let long_loca = read_u16(head, 50)? == 1;
+        let offset = |id: usize| -> Option<usize> {
+            if long_loca {
+                read_u32(loca, id * 4).map(|offset| offset as usize)
+            } else {
+                read_u16(loca, id * 2).map(|offset| offset as usize * 2)
+            }
+        };
+        let outline = |id: u16| glyf.get(offset(id as usize)?..offset(id as usize + 1)?);
+
+        // .notdef, the glyphs in use and the glyphs composites are built from
+        let mut keep = BTreeSet::new();
+        let mut pending: Vec<u16> = std::iter::once(0)
+            .chain(self.glyphs.values().map(|glyph| glyph.id))
+            .collect();
+        while let Some(id) = pending.pop() {
+            if keep.insert(id) {
+                pending.extend(components(outline(id).unwrap_or_default()));
+            }
+        }
+
+        let mut new_glyf = Vec::new();
+        let mut new_loca = Vec::new();
+        for id in 0..face.number_of_glyphs() {
+            new_loca.extend((new_glyf.len() as u32).to_be_bytes());
+            if keep.contains(&id) {
+                new_glyf.extend_from_slice(outline(id)?);
+                new_glyf.resize(new_glyf.len().next_multiple_of(4), 0);
+            }
+        }
+        new_loca.extend((new_glyf.len() as u32).to_be_bytes());
+
+        let mut new_head = head.to_vec();
+        // No file checksum adjustment, and the new loca has 32-bit offsets
+        new_head.get_mut(8..12)?.fill(0);
+        new_head
+            .get_mut(50..52)?
+            .copy_from_slice(&1u16.to_be_bytes());
+
+        let mut tables = vec![
+            (*b"head", new_head),
+            (*b"loca", new_loca),
+            (*b"glyf", new_glyf),
+        ];
+        for tag in [b"hhea", b"hmtx", b"maxp", b"cvt ", b"fpgm", b"prep"] {
+            if let Some(data) = table(tag) {
+                tables.push((*tag, data.to_vec()));
+            }
+        }
+        Some(write_font(tables))
+    }
+}
+
+/// Glyphs a composite glyph is built from
+fn components(outline: &[u8]) -> Vec<u16> {
+    const ARGS_ARE_WORDS: u16 = 0x0001;
+    const HAS_SCALE: u16 = 0x0008;
+    const MORE_COMPONENTS: u16 = 0x0020;
+    const HAS_XY_SCALE: u16 = 0x0040;
+    const HAS_TWO_BY_TWO: u16 = 0x0080;
+
+    let mut ids = Vec::new();
+    // Simple glyphs have a contour count of zero or more
+    if read_u16(outline, 0).is_none_or(|contours| (contours as i16) >= 0) {
+        return ids;
+    }
+    let mut pos = 10;
+    while let (Some(flags), Some(id)) = (read_u16(outline, pos), read_u16(outline, pos + 2)) {
+        ids.push(id);
+        pos += if flags & ARGS_ARE_WORDS != 0 { 8 } else { 6 };
+        pos += if flags & HAS_SCALE != 0 {
+            2
+        } else if flags & HAS_XY_SCALE != 0 {
+            4
+        } else if flags & HAS_TWO_BY_TWO != 0 {
+            8
+        } else {
+            0
+        };
+        if flags & MORE_COMPONENTS == 0 {
+            break;
+        }
+    }
+    ids
+}
+
+/// A TrueType file of `tables`
+fn write_font(mut tables: Vec<([u8; 4], Vec<u8>)>) -> Vec<u8> {
+    tables.sort_by_key(|(tag, _)| *tag);
+    let count = tables.len() as u16;
+    let entry_selector = 15 - count.leading_zeros() as u16;
+    let search_range = (1 << entry_selector) * 16;
+
+    let mut header = Vec::new();
+    header.extend(0x0001_0000u32.to_be_bytes());
+    for value in [
+        count,
+        search_range,
+        entry_selector,
+        count * 16 - search_range,
+    ] {
+        header.extend(value.to_be_bytes());
+    }
+    let header_len = 12 + tables.len() * 16;
+    let mut body = Vec::new();
+    for (tag, data) in &tables {
+        header.extend(tag);
+        header.extend(checksum(data).to_be_bytes());
+        header.extend(((header_len + body.len()) as u32).to_be_bytes());
+        header.extend((data.len() as u32).to_be_bytes());
+        body.extend(data);
+        body.resize(body.len().next_multiple_of(4), 0);
+    }
+    header.extend(body);
+    header
+}
+
+fn checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |sum, chunk| {
+        let mut word = [0; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+fn read_u16(data: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(data.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn read_u32(data: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(data.get(at..at + 4)?.try_into().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ttf_parser::{GlyphId, OutlineBuilder};
+    use uuid::Uuid;
+
+    /// A simple glyph of one closed contour through `points`
+    fn simple_glyph(points: &[(i16, i16)]) -> Vec<u8> {
+        let xs = points.iter().map(|p| p.0);
+        let ys = points.iter().map(|p| p.1);
+        let mut glyph = Vec::new();
+        for value in [
+            1,
+            xs.clone().min().unwrap(),
+            ys.clone().min().unwrap(),
+            xs.max().unwrap(),
+            ys.max().unwrap(),
+        ] {
+            glyph.extend(value.to_be_bytes());
+        }
+        glyph.extend((points.len() as u16 - 1).to_be_bytes());
+        // No instructions, and every point on the curve with word deltas
+        glyph.extend(0u16.to_be_bytes());
+        glyph.extend(std::iter::repeat_n(0x01, points.len()));
+        for axis in [0, 1] {
+            let mut last = 0;
+            for point in points {
+                let value = if axis == 0 { point.0 } else { point.1 };
+                glyph.extend((value - last).to_be_bytes());
+                last = value;
+            }
+        }
+        glyph
+    }
+
+    /// A composite of glyph 1 at (0, 0), with word arguments and a scale, and
+    /// glyph 4 shifted by byte arguments
+    fn composite_glyph() -> Vec<u8> {
+        const ARGS_ARE_WORDS: u16 = 0x0001;
+        const ARGS_ARE_XY: u16 = 0x0002;
+        const HAS_SCALE: u16 = 0x0008;
+        const MORE_COMPONENTS: u16 = 0x0020;
+
+        let mut glyph = Vec::new();
+        for value in [-1i16, 0, 0, 600, 900] {
+            glyph.extend(value.to_be_bytes());
+        }
+        for value in [
+            ARGS_ARE_WORDS | ARGS_ARE_XY | HAS_SCALE | MORE_COMPONENTS,
+            1,
+            0,
+            0,
+            0x4000,
+            ARGS_ARE_XY,
+            4,
+        ] {
+            glyph.extend(value.to_be_bytes());
+        }
+        glyph.extend([20, 100]);
+        glyph
+    }
+
+    /// A font with .notdef, A, B, Á (a composite of A and glyph 4, an accent
+    /// with no character of its own) and C, whose loca has 16-bit offsets
+    fn fixture_font() -> Vec<u8> {
+        let glyphs = [
+            simple_glyph(&[(50, 0), (50, 700), (450, 700), (450, 0)]),
+            simple_glyph(&[(0, 0), (300, 700), (600, 0)]),
+            simple_glyph(&[(60, 0), (60, 700), (500, 700), (500, 0)]),
+            composite_glyph(),
+            simple_glyph(&[(250, 750), (350, 800), (300, 750)]),
+            simple_glyph(&[(60, 0), (60, 700), (550, 700), (550, 350)]),
+        ];
+        let mut glyf = Vec::new();
+        let mut loca = Vec::new();
+        for glyph in &glyphs {
+            loca.extend((glyf.len() as u16 / 2).to_be_bytes());
+            glyf.extend(glyph);
+            glyf.resize(glyf.len().next_multiple_of(2), 0);
+        }
+        loca.extend((glyf.len() as u16 / 2).to_be_bytes());
+
+        let mut head = vec![0u8; 54];
+        head[..4].copy_from_slice(&0x0001_0000u32.to_be_bytes());
+        head[8..12].copy_from_slice(&0x1234_5678u32.to_be_bytes());
+        head[12..16].copy_from_slice(&0x5F0F_3CF5u32.to_be_bytes());
+        head[18..20].copy_from_slice(&1000u16.to_be_bytes());
+        head[40..44].copy_from_slice(&[0x02, 0x58, 0x03, 0x84]);
+
+        let mut hhea = vec![0u8; 36];
+        hhea[..4].copy_from_slice(&0x0001_0000u32.to_be_bytes());
+        hhea[4..6].copy_from_slice(&800i16.to_be_bytes());
+        hhea[6..8].copy_from_slice(&(-200i16).to_be_bytes());
+        hhea[34..36].copy_from_slice(&(glyphs.len() as u16).to_be_bytes());
+
+        let mut maxp = 0x0000_5000u32.to_be_bytes().to_vec();
+        maxp.extend((glyphs.len() as u16).to_be_bytes());
+
+        let mut hmtx = Vec::new();
+        for _ in &glyphs {
+            hmtx.extend(600u16.to_be_bytes());
+            hmtx.extend(0i16.to_be_bytes());
+        }
+
+        // One Windows Unicode subtable of format 6, mapping U+0041 to U+00C1
+        let mut map = vec![0u16; 0xC1 - 0x41 + 1];
+        map[0] = 1;
+        map[1] = 2;
+        map[2] = 5;
+        map[0xC1 - 0x41] = 3;
+        let mut cmap = Vec::new();
+        for value in [0u16, 1, 3, 1] {
+            cmap.extend(value.to_be_bytes());
+        }
+        cmap.extend(12u32.to_be_bytes());
+        for value in [6, 10 + map.len() as u16 * 2, 0, 0x41, map.len() as u16] {
+            cmap.extend(value.to_be_bytes());
+        }
+        for id in map {
+            cmap.extend(id.to_be_bytes());
+        }
+
+        write_font(vec![
+            (*b"head", head),
+            (*b"hhea", hhea),
+            (*b"maxp", maxp),
+            (*b"hmtx", hmtx),
+            (*b"cmap", cmap),
+            (*b"loca", loca),
+            (*b"glyf", glyf),
+        ])
+    }
+
+    /// The path segments of a glyph outline, as text
+    #[derive(Default)]
+    struct Segments(Vec<String>);
+
+    impl OutlineBuilder for Segments {
+        fn move_to(&mut self, x: f32, y: f32) {
+            self.0.push(format!("M{x},{y}"));
+        }
+        fn line_to(&mut self, x: f32, y: f32) {
+            self.0.push(format!("L{x},{y}"));
+        }
+        fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+            self.0.push(format!("Q{x1},{y1},{x},{y}"));
+        }
+        fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+            self.0.push(format!("C{x1},{y1},{x2},{y2},{x},{y}"));
+        }
+        fn close(&mut self) {
+            self.0.push("Z".to_string());
+        }
+    }
+
+    fn outline(face: &Face, id: u16) -> Option<Vec<String>> {
+        let mut segments = Segments::default();
+        face.outline_glyph(GlyphId(id), &mut segments)?;
+        Some(segments.0)
+    }
+
+    fn load_fixture(chars: &str) -> UnicodeFont {
+        let path = std::env::temp_dir().join(format!("talky-font-test-{}.ttf", Uuid::new_v4()));
+        std::fs::write(&path, fixture_font()).unwrap();
+        let font = UnicodeFont::load(&path, &chars.chars().collect());
+        let _ = std::fs::remove_file(&path);
+        font.unwrap()
+    }
+
+    #[test]
+    fn test_components_reads_every_component() {
+        assert_eq!(components(&composite_glyph()), vec![1, 4]);
+        assert!(components(&simple_glyph(&[(0, 0), (1, 1), (2, 0)])).is_empty());
+        assert!(components(&[]).is_empty());
+        // A truncated composite yields the components that are there
+        assert_eq!(components(&composite_glyph()[..20]), vec![1]);
+    }
+
+    #[test]
+    fn test_subset_keeps_used_glyphs_and_their_components() {
+        let font = load_fixture("ÁB");
+        assert_eq!(font.glyph('Á').map(|g| g.id), Some(3));
+        assert_eq!(font.glyph('B').map(|g| g.id), Some(2));
+        assert_eq!(font.glyph('B').map(|g| g.width), Some(600.0));
+
+        let subset = font.subset().unwrap();
+        assert!(subset.len() < font.data.len());
+        let original = font.face();
+        let face = Face::parse(&subset, 0).unwrap();
+        assert_eq!(face.number_of_glyphs(), original.number_of_glyphs());
+        assert_eq!(face.units_per_em(), 1000);
+        assert_eq!(face.glyph_hor_advance(GlyphId(3)), Some(600));
+        // Glyph ids are looked up by the PDF, so the cmap isn't needed
+        assert!(face.raw_face().table(Tag::from_bytes(b"cmap")).is_none());
+
+        // .notdef, B, Á and the glyphs Á is built from keep their outlines
+        for id in [0, 1, 2, 3, 4] {
+            let kept = outline(&face, id);
+            assert!(kept.is_some(), "glyph {}", id);
+            assert_eq!(kept, outline(&original, id), "glyph {}", id);
+        }
+        // Á is A with the accent moved by its offset
+        let composite = outline(&face, 3).unwrap();
+        let contours: Vec<_> = composite.iter().filter(|s| s.starts_with('M')).collect();
+        assert_eq!(contours, ["M0,0", "M270,850"]);
+        // C isn't used
+        assert!(outline(&original, 5).is_some());
+        assert_eq!(outline(&face, 5), None);
+    }
+
+    #[test]
+    fn test_subset_writes_valid_table_directory() {
+        let subset = load_fixture("B").subset().unwrap();
+
+        let count = read_u16(&subset, 4).unwrap() as usize;
+        let mut tags = Vec::new();
+        for entry in 0..count {
+            let at = 12 + entry * 16;
+            let tag = subset[at..at + 4].to_vec();
+            let offset = read_u32(&subset, at + 8).unwrap() as usize;
+            let length = read_u32(&subset, at + 12).unwrap() as usize;
+            assert_eq!(offset % 4, 0);
+            assert_eq!(
+                read_u32(&subset, at + 4),
+                Some(checksum(&subset[offset..offset + length]))
+            );
+            tags.push(String::from_utf8(tag).unwrap());
+        }
+        assert_eq!(tags, ["glyf", "head", "hhea", "hmtx", "loca", "maxp"]);
+        // searchRange, entrySelector and rangeShift for 6 tables
+        assert_eq!(read_u16(&subset, 6), Some(64));
+        assert_eq!(read_u16(&subset, 8), Some(2));
+        assert_eq!(read_u16(&subset, 10), Some(32));
+
+        // The checksum adjustment is cleared and loca switches to 32-bit offsets
+        let face = Face::parse(&subset, 0).unwrap();
+        let head = face.raw_face().table(Tag::from_bytes(b"head")).unwrap();
+        assert_eq!(read_u32(head, 8), Some(0));
+        assert_eq!(read_u16(head, 50), Some(1));
+        let loca = face.raw_face().table(Tag::from_bytes(b"loca")).unwrap();
+        assert_eq!(loca.len(), (face.number_of_glyphs() as usize + 1) * 4);
+    }
+}
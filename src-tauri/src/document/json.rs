@@ -0,0 +1,301 @@
+use crate::managers::folders::Folder;
+use crate::managers::session::{MeetingNotes, Session, Tag, TranscriptSegment};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use specta::datatype::{
+    DataType, EnumRepr, EnumType, EnumVariants, LiteralType, NamedFields, PrimitiveType,
+    StructFields, UnnamedFields,
+};
+use specta::{Type, TypeCollection};
+use std::collections::{HashMap, HashSet};
+
+/// Version of the `SessionExport` layout. Bump it whenever a field is renamed,
+/// removed or changes type, so tools reading exports can tell the layouts apart.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Written next to JSON and NDJSON exports
+pub const SCHEMA_FILE: &str = "session-export.schema.json";
+
+/// One session as exported to JSON, or as one line of an NDJSON export
+#[derive(Clone, Debug, Serialize, Type)]
+pub struct SessionExport {
+    pub schema_version: u32,
+    pub session: Session,
+    /// The session's folder and its parents, top level first
+    pub folders: Vec<Folder>,
+    pub tags: Vec<Tag>,
+    pub notes: Option<MeetingNotes>,
+    pub transcript: Vec<TranscriptSegment>,
+    /// The files themselves are not exported
+    pub attachments: Vec<AttachmentExport>,
+}
+
+/// An attachment's metadata, without where it is stored or its extracted text
+#[derive(Clone, Debug, Serialize, Type)]
+pub struct AttachmentExport {
+    pub filename: String,
+    pub mime_type: String,
+    pub file_size: i64,
+    pub created_at: i64,
+}
+
+/// The folder `folder_id` and its parents, top level first
+pub fn folder_chain(folders: &[Folder], folder_id: &str) -> Vec<Folder> {
+    let by_id: HashMap<&str, &Folder> = folders.iter().map(|f| (f.id.as_str(), f)).collect();
+    let mut chain = Vec::new();
+    let mut seen = HashSet::new();
+    let mut current = by_id.get(folder_id).copied();
+    // A broken parent link ends the chain instead of looping
+    while let Some(folder) = current.filter(|f| seen.insert(f.id.as_str())) {
+        chain.push(folder.clone());
+        current = folder
+            .parent_id
+            .as_deref()
+            .and_then(|id| by_id.get(id).copied());
+    }
+    chain.reverse();
+    chain
+}
+
+/// JSON Schema (draft 2020-12) of `SessionExport`, generated from the specta
+/// definitions of the exported types
+pub fn session_export_schema() -> Value {
+    let mut types = TypeCollection::default();
+    types.register::<SessionExport>();
+
+    let mut defs = Map::new();
+    for (_, named) in &types {
+        let mut schema = data_type_schema(&named.inner);
+        describe(&mut schema, named.docs());
+        defs.insert(named.name().to_string(), schema);
+    }
+    if let Some(properties) = defs
+        .get_mut("SessionExport")
+        .and_then(|schema| schema.get_mut("properties"))
+    {
+        properties["schema_version"] = json!({ "const": SCHEMA_VERSION });
+    }
+
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "Talky session export",
+        "$ref": "#/$defs/SessionExport",
+        "$defs": defs,
+    })
+}
+
+/// Doc comments become descriptions
+fn describe(schema: &mut Value, docs: &str) {
+    let description = docs
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    if let (Some(schema), false) = (schema.as_object_mut(), description.is_empty()) {
+        schema.insert("description".to_string(), Value::String(description));
+    }
+}
+
+fn data_type_schema(data_type: &DataType) -> Value {
+    match data_type {
+        DataType::Any | DataType::Unknown | DataType::Generic(_) => json!({}),
+        DataType::Primitive(primitive) => json!({ "type": primitive_type(primitive) }),
+        DataType::Literal(literal) => literal_schema(literal),
+        DataType::List(list) => json!({ "type": "array", "items": data_type_schema(list.ty()) }),
+        DataType::Map(map) => json!({
+            "type": "object",
+            "additionalProperties": data_type_schema(map.value_ty()),
+        }),
+        DataType::Nullable(inner) => {
+            json!({ "anyOf": [data_type_schema(inner), { "type": "null" }] })
+        }
+        DataType::Struct(structure) => match structure.fields() {
+            StructFields::Unit => json!({ "type": "null" }),
+            StructFields::Unnamed(fields) => unnamed_schema(fields),
+            StructFields::Named(fields) => object_schema(fields),
+        },
+        DataType::Enum(enumeration) => enum_schema(enumeration),
+        DataType::Tuple(tuple) => tuple_schema(tuple.elements().iter().collect()),
+        DataType::Reference(reference) => {
+            json!({ "$ref": format!("#/$defs/{}", reference.name()) })
+        }
+    }
+}
+
+fn primitive_type(primitive: &PrimitiveType) -> &'static str {
+    match primitive {
+        PrimitiveType::f32 | PrimitiveType::f64 => "number",
+        PrimitiveType::bool => "boolean",
+        PrimitiveType::char | PrimitiveType::String => "string",
+        _ => "integer",
+    }
+}
+
+fn literal_schema(literal: &LiteralType) -> Value {
+    let value = match literal {
+        LiteralType::i8(v) => json!(v),
+        LiteralType::i16(v) => json!(v),
+        LiteralType::i32(v) => json!(v),
+        LiteralType::u8(v) => json!(v),
+        LiteralType::u16(v) => json!(v),
+        LiteralType::u32(v) => json!(v),
+        LiteralType::f32(v) => json!(v),
+        LiteralType::f64(v) => json!(v),
+        LiteralType::bool(v) => json!(v),
+        LiteralType::String(v) => json!(v),
+        LiteralType::char(v) => json!(v),
+        _ => Value::Null,
+    };
+    json!({ "const": value })
+}
+
+/// Arrays of fixed length; the empty tuple serializes as `null`
+fn tuple_schema(elements: Vec<&DataType>) -> Value {
+    if elements.is_empty() {
+        return json!({ "type": "null" });
+    }
+    json!({
+        "type": "array",
+        "prefixItems": elements.into_iter().map(data_type_schema).collect::<Vec<_>>(),
+        "items": false,
+    })
+}
+
+/// Newtypes serialize as their only field, other tuple structs as arrays
+fn unnamed_schema(fields: &UnnamedFields) -> Value {
+    let types: Vec<&DataType> = fields.fields().iter().filter_map(|f| f.ty()).collect();
+    match types.as_slice() {
+        [only] if fields.fields().len() == 1 => data_type_schema(only),
+        _ => tuple_schema(types),
+    }
+}
+
+fn object_schema(fields: &NamedFields) -> Value {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    let mut flattened = Vec::new();
+    for (name, field) in fields.fields() {
+        // Skipped fields have no type
+        let Some(data_type) = field.ty() else {
+            continue;
+        };
+        let mut schema = data_type_schema(data_type);
+        if field.flatten() {
+            flattened.push(schema);
+            continue;
+        }
+        describe(&mut schema, field.docs());
+        if !field.optional() {
+            required.push(name.to_string());
+        }
+        properties.insert(name.to_string(), schema);
+    }
+
+    let object = json!({ "type": "object", "properties": properties, "required": required });
+    if flattened.is_empty() {
+        object
+    } else {
+        flattened.insert(0, object);
+        json!({ "allOf": flattened })
+    }
+}
+
+/// One alternative per variant, laid out like serde's enum representations
+fn enum_schema(enumeration: &EnumType) -> Value {
+    let variants = enumeration
+        .variants()
+        .iter()
+        .filter(|(_, variant)| !variant.skip())
+        .map(|(name, variant)| {
+            let name = name.to_string();
+            let inner = match variant.inner() {
+                EnumVariants::Unit => None,
+                EnumVariants::Named(fields) => Some(object_schema(fields)),
+                EnumVariants::Unnamed(fields) => Some(unnamed_schema(fields)),
+            };
+            let mut schema = match (enumeration.repr(), inner) {
+                (EnumRepr::Untagged, inner) => inner.unwrap_or(json!({ "type": "null" })),
+                (EnumRepr::External, None) => json!({ "const": name }),
+                (EnumRepr::External, Some(inner)) => json!({
+                    "type": "object",
+                    "properties": { name.clone(): inner },
+                    "required": [name],
+                    "additionalProperties": false,
+                }),
+                (EnumRepr::Internal { tag }, inner) => {
+                    let tagged = json!({
+                        "type": "object",
+                        "properties": { tag.to_string(): { "const": name } },
+                        "required": [tag],
+                    });
+                    match inner {
+                        Some(inner) => json!({ "allOf": [tagged, inner] }),
+                        None => tagged,
+                    }
+                }
+                (EnumRepr::Adjacent { tag, content }, inner) => {
+                    let mut properties = Map::new();
+                    let mut required = vec![tag.to_string()];
+                    properties.insert(tag.to_string(), json!({ "const": name }));
+                    if let Some(inner) = inner {
+                        properties.insert(content.to_string(), inner);
+                        required.push(content.to_string());
+                    }
+                    json!({ "type": "object", "properties": properties, "required": required })
+                }
+            };
+            describe(&mut schema, variant.docs());
+            schema
+        })
+        .collect::<Vec<_>>();
+    json!({ "oneOf": variants })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_export_schema_covers_exported_types() {
+        let schema = session_export_schema();
+        let defs = &schema["$defs"];
+        for name in [
+            "SessionExport",
+            "Session",
+            "Folder",
+            "Tag",
+            "MeetingNotes",
+            "TranscriptSegment",
+            "TranscriptWord",
+            "AttachmentExport",
+        ] {
+            assert_eq!(defs[name]["type"], "object", "{} missing", name);
+        }
+
+        let export = &defs["SessionExport"];
+        assert_eq!(
+            export["properties"]["schema_version"]["const"],
+            SCHEMA_VERSION
+        );
+        assert_eq!(
+            export["properties"]["transcript"],
+            json!({ "type": "array", "items": { "$ref": "#/$defs/TranscriptSegment" } })
+        );
+        assert_eq!(
+            defs["Session"]["properties"]["ended_at"],
+            json!({ "anyOf": [{ "type": "integer" }, { "type": "null" }] })
+        );
+        assert!(defs["Session"]["required"]
+            .as_array()
+            .unwrap()
+            .contains(&json!("started_at")));
+
+        // Attachments are exported without their storage path or text
+        let attachment = defs["AttachmentExport"]["properties"].as_object().unwrap();
+        assert!(attachment.contains_key("filename"));
+        assert!(!attachment.contains_key("file_path"));
+        assert!(!attachment.contains_key("extracted_text"));
+        assert!(defs.get("Attachment").is_none());
+    }
+}
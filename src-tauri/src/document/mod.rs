@@ -0,0 +1,492 @@
+mod docx;
+mod font;
+pub mod json;
+mod pdf;
+pub mod template;
+pub mod vault;
+
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// Colour of notes lines the AI added from the transcript ([ai]), as opposed
+/// to the user's own ([noted]), which stay black
+const AI_COLOR: [u8; 3] = [0x6B, 0x72, 0x80];
+/// Shown under a notes section that has [ai] lines
+const AI_LEGEND: &str =
+    "Grey text was added from the transcript by AI. Black text is from the written notes.";
+
+/// Formats notes can be exported to besides Markdown
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum DocumentFormat {
+    Docx,
+    Pdf,
+}
+
+impl DocumentFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            DocumentFormat::Docx => "docx",
+            DocumentFormat::Pdf => "pdf",
+        }
+    }
+}
+
+/// A session laid out for export, independent of the output format
+#[derive(Clone, Debug, Default)]
+pub struct ExportDocument {
+    pub title: String,
+    /// Local start date and time
+    pub date: String,
+    pub tags: Vec<String>,
+    pub notes: Vec<NotesSection>,
+    pub transcript: Vec<TranscriptLine>,
+    pub chat: Vec<ChatThread>,
+}
+
+#[derive(Clone, Debug)]
+pub struct NotesSection {
+    pub heading: String,
+    /// Notes markdown as stored, including the [noted]/[ai] tags
+    pub content: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct TranscriptLine {
+    pub timestamp: String,
+    pub label: String,
+    pub text: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct ChatThread {
+    pub title: String,
+    pub messages: Vec<QaMessage>,
+}
+
+#[derive(Clone, Debug)]
+pub struct QaMessage {
+    pub question: bool,
+    pub content: String,
+}
+
+/// Whether a notes line came from the user's notes or was added by the AI
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Origin {
+    Noted,
+    Ai,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Span {
+    pub text: String,
+    pub bold: bool,
+    pub italic: bool,
+}
+
+impl Span {
+    fn plain(text: impl Into<String>) -> Self {
+        Span {
+            text: text.into(),
+            ..Default::default()
+        }
+    }
+
+    fn bold(text: impl Into<String>) -> Self {
+        Span {
+            text: text.into(),
+            bold: true,
+            ..Default::default()
+        }
+    }
+}
+
+/// One line of formatted text
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Line {
+    pub origin: Option<Origin>,
+    pub spans: Vec<Span>,
+}
+
+impl From<Vec<Span>> for Line {
+    fn from(spans: Vec<Span>) -> Self {
+        Line {
+            origin: None,
+            spans,
+        }
+    }
+}
+
+/// What the DOCX and PDF renderers draw, top to bottom
+#[derive(Clone, Debug, PartialEq)]
+pub enum Block {
+    Title(String),
+    /// Level 1 for the document's sections, 2 and 3 below them
+    Heading {
+        level: usize,
+        line: Line,
+    },
+    Paragraph(Line),
+    /// `number` is set for items of ordered lists
+    ListItem {
+        depth: usize,
+        number: Option<u32>,
+        line: Line,
+    },
+    /// Small print, e.g. the legend of [ai] styling
+    Note(String),
+}
+
+impl Block {
+    fn line(&self) -> Option<&Line> {
+        match self {
+            Block::Heading { line, .. } | Block::Paragraph(line) | Block::ListItem { line, .. } => {
+                Some(line)
+            }
+            Block::Title(_) | Block::Note(_) => None,
+        }
+    }
+}
+
+static TAG_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\*{0,2}\[(?:noted|ai)\]\*{0,2} ").unwrap());
+static BOLD_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\*{4}").unwrap());
+static ORIGIN_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\*{0,2}\[(noted|ai)\]\*{0,2}\s*").unwrap());
+static HEADING_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(#{1,6})\s+(.*)$").unwrap());
+static LIST_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^([ \t]*)(?:[-*+]|(\d{1,9})[.)])\s+(.*)$").unwrap());
+static RULE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\s*(?:-{3,}|\*{3,}|_{3,})\s*$").unwrap());
+
+/// Strip [ai] and [noted] tags (with optional bold wrapping) from notes content
+pub fn strip_tags(content: &str) -> String {
+    content
+        .lines()
+        .map(|line| {
+            let stripped = TAG_RE.replace_all(line, "");
+            BOLD_RE.replace_all(&stripped, "").to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl ExportDocument {
+    pub fn to_markdown(&self) -> String {
+        self.markdown(&self.tags.join(", "))
+    }
+
+    /// Markdown with `tags` as the text of the tags line
+    fn markdown(&self, tags: &str) -> String {
+        let mut md = String::new();
+
+        md.push_str(&format!("# {}\n\n", self.title));
+        md.push_str(&format!("**Date:** {}\n", self.date));
+        if !self.tags.is_empty() {
+            md.push_str(&format!("**Tags:** {}\n", tags));
+        }
+        md.push_str("\n---\n\n");
+
+        for section in &self.notes {
+            md.push_str(&format!("## {}\n\n", section.heading));
+            md.push_str(&strip_tags(&section.content));
+            md.push_str("\n\n");
+        }
+
+        if !self.transcript.is_empty() {
+            md.push_str("## Transcript\n\n");
+            for line in &self.transcript {
+                md.push_str(&format!(
+                    "[{}] [{}]: {}\n\n",
+                    line.timestamp, line.label, line.text
+                ));
+            }
+        }
+
+        if !self.chat.is_empty() {
+            md.push_str("## Q&A\n\n");
+            for thread in &self.chat {
+                md.push_str(&format!("### {}\n\n", thread.title));
+                for message in &thread.messages {
+                    let label = if message.question { "Q" } else { "A" };
+                    md.push_str(&format!("**{}:** {}\n\n", label, message.content));
+                }
+            }
+        }
+
+        md
+    }
+
+    pub fn render(&self, format: DocumentFormat) -> Result<Vec<u8>> {
+        match format {
+            DocumentFormat::Docx => docx::render(self),
+            DocumentFormat::Pdf => pdf::render(self),
+        }
+    }
+
+    /// The document as blocks of formatted text. Notes keep their headings,
+    /// nested lists and bold/italic text, and [noted]/[ai] tags become each
+    /// line's `origin`.
+    pub fn blocks(&self) -> Vec<Block> {
+        let mut blocks = vec![Block::Title(self.title.clone())];
+        blocks.push(Block::Paragraph(
+            vec![Span::bold("Date: "), Span::plain(&self.date)].into(),
+        ));
+        if !self.tags.is_empty() {
+            blocks.push(Block::Paragraph(
+                vec![Span::bold("Tags: "), Span::plain(self.tags.join(", "))].into(),
+            ));
+        }
+
+        for section in &self.notes {
+            blocks.push(heading(1, &section.heading));
+            let notes: Vec<Block> = parse_notes(&section.content)
+                .into_iter()
+                .map(|block| match block {
+                    // Notes headings sit below the section heading
+                    Block::Heading { level, line } => Block::Heading {
+                        level: (level + 1).min(3),
+                        line,
+                    },
+                    block => block,
+                })
+                .collect();
+            if notes
+                .iter()
+                .any(|block| block.line().and_then(|line| line.origin) == Some(Origin::Ai))
+            {
+                blocks.push(Block::Note(AI_LEGEND.to_string()));
+            }
+            blocks.extend(notes);
+        }
+
+        if !self.transcript.is_empty() {
+            blocks.push(heading(1, "Transcript"));
+            for line in &self.transcript {
+                blocks.push(Block::Paragraph(
+                    vec![
+                        Span::plain(format!("[{}] ", line.timestamp)),
+                        Span::bold(format!("{}: ", line.label)),
+                        Span::plain(&line.text),
+                    ]
+                    .into(),
+                ));
+            }
+        }
+
+        if !self.chat.is_empty() {
+            blocks.push(heading(1, "Q&A"));
+            for thread in &self.chat {
+                blocks.push(heading(2, &thread.title));
+                for message in &thread.messages {
+                    let label = if message.question { "Q: " } else { "A: " };
+                    let mut answer = parse_notes(&message.content);
+                    match answer.first_mut() {
+                        Some(Block::Paragraph(line)) => line.spans.insert(0, Span::bold(label)),
+                        _ => answer.insert(0, Block::Paragraph(vec![Span::bold(label)].into())),
+                    }
+                    blocks.extend(answer);
+                }
+            }
+        }
+
+        blocks
+    }
+}
+
+fn heading(level: usize, text: &str) -> Block {
+    Block::Heading {
+        level,
+        line: vec![Span::plain(text)].into(),
+    }
+}
+
+/// Parse notes markdown into headings, list items and paragraphs. Covers
+/// what the notes editor and the enhance prompt produce; other markdown is
+/// kept as text.
+pub fn parse_notes(content: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    for line in content.lines() {
+        let line = line.trim_end();
+        if line.trim().is_empty() || RULE_RE.is_match(line) {
+            continue;
+        }
+
+        if let Some(caps) = HEADING_RE.captures(line) {
+            blocks.push(Block::Heading {
+                level: caps[1].len(),
+                line: parse_line(&caps[2]),
+            });
+        } else if let Some(caps) = LIST_RE.captures(line) {
+            // Nested items are indented by two spaces per level
+            let indent: usize = caps[1].chars().map(|c| if c == '\t' { 2 } else { 1 }).sum();
+            blocks.push(Block::ListItem {
+                depth: indent / 2,
+                number: caps.get(2).and_then(|n| n.as_str().parse().ok()),
+                line: parse_line(&caps[3]),
+            });
+        } else {
+            blocks.push(Block::Paragraph(parse_line(line.trim_start())));
+        }
+    }
+    blocks
+}
+
+fn parse_line(text: &str) -> Line {
+    let (origin, text) = match ORIGIN_RE.captures(text) {
+        Some(caps) => {
+            let origin = if &caps[1] == "ai" {
+                Origin::Ai
+            } else {
+                Origin::Noted
+            };
+            (Some(origin), &text[caps[0].len()..])
+        }
+        None => (None, text),
+    };
+    let text = TAG_RE.replace_all(text, "");
+    let text = BOLD_RE.replace_all(&text, "");
+    Line {
+        origin,
+        spans: parse_spans(&text),
+    }
+}
+
+/// Split `**bold**` and `*italic*` text into spans. A marker only counts if
+/// it is closed later in the line, so a lone `*` stays text.
+fn parse_spans(text: &str) -> Vec<Span> {
+    let chars: Vec<char> = text.chars().collect();
+    let closes = |from: usize, bold: bool| {
+        let rest = chars.get(from..).unwrap_or_default();
+        if bold {
+            rest.windows(2).any(|pair| pair == ['*', '*'])
+        } else {
+            rest.contains(&'*')
+        }
+    };
+
+    let mut spans = Vec::new();
+    let mut current = Span::default();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\\' && chars.get(i + 1).is_some_and(|n| n.is_ascii_punctuation()) {
+            current.text.push(chars[i + 1]);
+            i += 2;
+            continue;
+        }
+        if c == '*' {
+            let bold = chars.get(i + 1) == Some(&'*');
+            let (width, open) = if bold {
+                (2, current.bold)
+            } else {
+                (1, current.italic)
+            };
+            let opens = chars
+                .get(i + width)
+                .is_some_and(|next| !next.is_whitespace())
+                && closes(i + width + 1, bold);
+            if open || opens {
+                if !current.text.is_empty() {
+                    spans.push(current.clone());
+                    current.text.clear();
+                }
+                if bold {
+                    current.bold = !current.bold;
+                } else {
+                    current.italic = !current.italic;
+                }
+                i += width;
+                continue;
+            }
+        }
+        current.text.push(c);
+        i += 1;
+    }
+    if !current.text.is_empty() {
+        spans.push(current);
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_notes_keeps_structure_and_origin() {
+        let notes = "### Budget\n- [noted] Q3 = 100k\n  - **[ai]** CFO confirmed **board signoff March 15**\n\n[ai] 5 * 3 is *roughly* 15\n1. First";
+        let blocks = parse_notes(notes);
+        assert_eq!(blocks.len(), 5);
+        assert_eq!(
+            blocks[0],
+            Block::Heading {
+                level: 3,
+                line: vec![Span::plain("Budget")].into()
+            }
+        );
+        assert_eq!(
+            blocks[2],
+            Block::ListItem {
+                depth: 1,
+                number: None,
+                line: Line {
+                    origin: Some(Origin::Ai),
+                    spans: vec![
+                        Span::plain("CFO confirmed "),
+                        Span::bold("board signoff March 15")
+                    ],
+                },
+            }
+        );
+        assert_eq!(
+            blocks[3],
+            Block::Paragraph(Line {
+                origin: Some(Origin::Ai),
+                spans: vec![
+                    Span::plain("5 * 3 is "),
+                    Span {
+                        text: "roughly".to_string(),
+                        bold: false,
+                        italic: true
+                    },
+                    Span::plain(" 15")
+                ],
+            })
+        );
+        assert!(matches!(
+            blocks[4],
+            Block::ListItem {
+                depth: 0,
+                number: Some(1),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_to_markdown_strips_tags() {
+        let doc = ExportDocument {
+            title: "Planning".to_string(),
+            date: "2025-03-01 10:00".to_string(),
+            tags: vec!["q3".to_string()],
+            notes: vec![NotesSection {
+                heading: "Notes".to_string(),
+                content: "- **[noted]** Budget\n- [ai] Review **April 12**".to_string(),
+            }],
+            transcript: vec![TranscriptLine {
+                timestamp: "00:01:05".to_string(),
+                label: "You".to_string(),
+                text: "Let's start".to_string(),
+            }],
+            chat: Vec::new(),
+        };
+        assert_eq!(
+            doc.to_markdown(),
+            "# Planning\n\n**Date:** 2025-03-01 10:00\n**Tags:** q3\n\n---\n\n\
+             ## Notes\n\n- Budget\n- Review **April 12**\n\n\
+             ## Transcript\n\n[00:01:05] [You]: Let's start\n\n"
+        );
+    }
+}
@@ -0,0 +1,787 @@
+use super::font::UnicodeFont;
+use super::{Block, ExportDocument, Origin, Span, AI_COLOR};
+use anyhow::{Context, Result};
+use lopdf::content::{Content, Operation};
+use lopdf::{dictionary, Dictionary, Document, Object, ObjectId, Stream, StringFormat};
+use std::collections::BTreeSet;
+
+/// A4, in points
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 56.0;
+const LINE_HEIGHT: f32 = 1.35;
+const BODY_SIZE: f32 = 10.5;
+/// Indent per list nesting level, also the room left for the bullet
+const LIST_INDENT: f32 = 14.0;
+const BLACK: [u8; 3] = [0, 0, 0];
+
+/// The standard Helvetica faces every PDF reader has, which need no
+/// embedding but only cover Windows-1252, and a system font embedded for the
+/// characters they lack
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Font {
+    Regular,
+    Bold,
+    Italic,
+    BoldItalic,
+    Unicode,
+}
+
+impl Font {
+    const ALL: [Font; 4] = [Font::Regular, Font::Bold, Font::Italic, Font::BoldItalic];
+
+    fn of(span: &Span) -> Self {
+        match (span.bold, span.italic) {
+            (false, false) => Font::Regular,
+            (true, false) => Font::Bold,
+            (false, true) => Font::Italic,
+            (true, true) => Font::BoldItalic,
+        }
+    }
+
+    fn resource_name(self) -> &'static str {
+        match self {
+            Font::Regular => "F1",
+            Font::Bold => "F2",
+            Font::Italic => "F3",
+            Font::BoldItalic => "F4",
+            Font::Unicode => "F5",
+        }
+    }
+
+    fn base_font(self) -> &'static str {
+        match self {
+            Font::Regular => "Helvetica",
+            Font::Bold => "Helvetica-Bold",
+            Font::Italic => "Helvetica-Oblique",
+            Font::BoldItalic => "Helvetica-BoldOblique",
+            Font::Unicode => unreachable!("the Unicode font is embedded, not standard"),
+        }
+    }
+
+    fn is_bold(self) -> bool {
+        matches!(self, Font::Bold | Font::BoldItalic)
+    }
+}
+
+/// Text of one line, as runs of the same font
+type Fragments = Vec<(Font, String)>;
+
+/// Picks and measures the font each character is set in
+#[derive(Default)]
+struct Fonts {
+    unicode: Option<UnicodeFont>,
+}
+
+impl Fonts {
+    /// Fonts for `blocks`, with a system font for characters outside
+    /// Windows-1252 if there are any. Characters no font has print as `?`.
+    fn for_blocks(blocks: &[Block]) -> Self {
+        let chars: BTreeSet<char> = blocks
+            .iter()
+            .flat_map(|block| match block {
+                Block::Title(text) | Block::Note(text) => vec![text.as_str()],
+                block => block
+                    .line()
+                    .map(|line| line.spans.iter().map(|span| span.text.as_str()).collect())
+                    .unwrap_or_default(),
+            })
+            .flat_map(str::chars)
+            .filter(|&c| !c.is_whitespace() && win_ansi(c).is_none())
+            .collect();
+        if chars.is_empty() {
+            return Fonts::default();
+        }
+
+        // Spaces between words are set in the font of the word before
+        let mut wanted = chars.clone();
+        wanted.insert(' ');
+        let unicode = UnicodeFont::find(&wanted);
+        let missing: String = chars
+            .iter()
+            .filter(|&&c| unicode.as_ref().and_then(|font| font.glyph(c)).is_none())
+            .collect();
+        if !missing.is_empty() {
+            log::warn!("No font to show these characters in the PDF: {}", missing);
+        }
+        Fonts { unicode }
+    }
+
+    /// The font that shows `c`, `font` if it can
+    fn pick(&self, font: Font, c: char) -> Font {
+        let embedded = self.unicode.as_ref().and_then(|unicode| unicode.glyph(c));
+        if win_ansi(c).is_none() && embedded.is_some() {
+            Font::Unicode
+        } else {
+            font
+        }
+    }
+
+    /// Width of `c` in 1/1000 em. The oblique faces share the upright
+    /// metrics; characters outside ASCII get typical widths.
+    fn char_width(&self, font: Font, c: char) -> f32 {
+        if font == Font::Unicode {
+            let glyph = self.unicode.as_ref().and_then(|unicode| unicode.glyph(c));
+            return glyph.map_or(0.0, |glyph| glyph.width);
+        }
+        let bold = font.is_bold();
+        let width = match win_ansi(c).unwrap_or(b'?') {
+            byte @ b' '..=b'~' => {
+                let table = if bold {
+                    &HELVETICA_BOLD_WIDTHS
+                } else {
+                    &HELVETICA_WIDTHS
+                };
+                table[(byte - b' ') as usize]
+            }
+            0x85 | 0x97 => 1000,
+            0x95 => 350,
+            0xA0 => 278,
+            _ if bold => 611,
+            _ => 556,
+        };
+        width as f32
+    }
+
+    fn text_width(&self, font: Font, text: &str, size: f32) -> f32 {
+        text.chars().map(|c| self.char_width(font, c)).sum::<f32>() * size / 1000.0
+    }
+
+    /// `text` as the string operand for `font`: Windows-1252 bytes for the
+    /// standard fonts, big-endian glyph ids for the embedded one
+    fn encode(&self, font: Font, text: &str) -> Object {
+        match &self.unicode {
+            Some(unicode) if font == Font::Unicode => {
+                let ids = text
+                    .chars()
+                    .filter_map(|c| unicode.glyph(c))
+                    .flat_map(|glyph| glyph.id.to_be_bytes())
+                    .collect();
+                Object::String(ids, StringFormat::Hexadecimal)
+            }
+            _ => Object::String(
+                text.chars().map(|c| win_ansi(c).unwrap_or(b'?')).collect(),
+                StringFormat::Literal,
+            ),
+        }
+    }
+}
+
+struct Style {
+    size: f32,
+    color: [u8; 3],
+    indent: f32,
+    space_before: f32,
+    space_after: f32,
+}
+
+/// Render the document as a PDF file
+pub fn render(doc: &ExportDocument) -> Result<Vec<u8>> {
+    let blocks = doc.blocks();
+    let fonts = Fonts::for_blocks(&blocks);
+    let mut layout = Layout::new(&fonts);
+    for block in blocks {
+        match block {
+            Block::Title(title) => layout.paragraph(
+                &[Span {
+                    text: title,
+                    bold: true,
+                    ..Default::default()
+                }],
+                None,
+                &Style {
+                    size: 20.0,
+                    color: BLACK,
+                    indent: 0.0,
+                    space_before: 0.0,
+                    space_after: 8.0,
+                },
+            ),
+            Block::Heading { level, line } => {
+                let size = match level {
+                    1 => 15.0,
+                    2 => 13.0,
+                    _ => 11.5,
+                };
+                let spans: Vec<Span> = line
+                    .spans
+                    .into_iter()
+                    .map(|span| Span { bold: true, ..span })
+                    .collect();
+                // Don't leave a heading alone at the bottom of a page
+                layout.keep_space(size * 2.0 + BODY_SIZE * LINE_HEIGHT * 3.0);
+                layout.paragraph(
+                    &spans,
+                    None,
+                    &Style {
+                        size,
+                        color: BLACK,
+                        indent: 0.0,
+                        space_before: size,
+                        space_after: size * 0.4,
+                    },
+                );
+            }
+            Block::Paragraph(line) => layout.paragraph(
+                &line.spans,
+                None,
+                &Style {
+                    size: BODY_SIZE,
+                    color: origin_color(line.origin),
+                    indent: 0.0,
+                    space_before: 0.0,
+                    space_after: 5.0,
+                },
+            ),
+            Block::ListItem {
+                depth,
+                number,
+                line,
+            } => {
+                let marker = match number {
+                    Some(number) => format!("{}.", number),
+                    None if depth % 2 == 0 => "•".to_string(),
+                    None => "–".to_string(),
+                };
+                layout.paragraph(
+                    &line.spans,
+                    Some(&marker),
+                    &Style {
+                        size: BODY_SIZE,
+                        color: origin_color(line.origin),
+                        indent: LIST_INDENT * (depth as f32 + 1.0),
+                        space_before: 0.0,
+                        space_after: 2.0,
+                    },
+                );
+            }
+            Block::Note(text) => layout.paragraph(
+                &[Span {
+                    text,
+                    italic: true,
+                    ..Default::default()
+                }],
+                None,
+                &Style {
+                    size: 8.5,
+                    color: AI_COLOR,
+                    indent: 0.0,
+                    space_before: 0.0,
+                    space_after: 6.0,
+                },
+            ),
+        }
+    }
+
+    let pages = layout.finish();
+    write_pdf(&doc.title, pages, &fonts)
+}
+
+fn origin_color(origin: Option<Origin>) -> [u8; 3] {
+    match origin {
+        Some(Origin::Ai) => AI_COLOR,
+        _ => BLACK,
+    }
+}
+
+/// Lays text out top to bottom, starting new pages as needed
+struct Layout<'a> {
+    fonts: &'a Fonts,
+    pages: Vec<Vec<Operation>>,
+    /// Top of the free space on the current page
+    y: f32,
+}
+
+impl<'a> Layout<'a> {
+    fn new(fonts: &'a Fonts) -> Self {
+        Layout {
+            fonts,
+            pages: vec![Vec::new()],
+            y: PAGE_HEIGHT - MARGIN,
+        }
+    }
+
+    fn new_page(&mut self) {
+        self.pages.push(Vec::new());
+        self.y = PAGE_HEIGHT - MARGIN;
+    }
+
+    fn at_page_top(&self) -> bool {
+        self.y >= PAGE_HEIGHT - MARGIN
+    }
+
+    /// Start a new page unless `height` still fits on this one
+    fn keep_space(&mut self, height: f32) {
+        if !self.at_page_top() && self.y - height < MARGIN {
+            self.new_page();
+        }
+    }
+
+    /// Wrapped text, with `marker` (a bullet or number) hanging in the indent
+    /// of its first line
+    fn paragraph(&mut self, spans: &[Span], marker: Option<&str>, style: &Style) {
+        let left = MARGIN + style.indent;
+        let lines = wrap(
+            self.fonts,
+            words(self.fonts, spans),
+            style.size,
+            PAGE_WIDTH - MARGIN - left,
+        );
+        if !self.at_page_top() {
+            self.y -= style.space_before;
+        }
+
+        let leading = style.size * LINE_HEIGHT;
+        for (index, line) in lines.iter().enumerate() {
+            if self.y - leading < MARGIN {
+                self.new_page();
+            }
+            self.y -= leading;
+            if index == 0 {
+                if let Some(marker) = marker {
+                    let fragments = vec![(Font::Regular, marker.to_string())];
+                    self.draw(&fragments, left - LIST_INDENT, style.size, style.color);
+                }
+            }
+            self.draw(line, left, style.size, style.color);
+        }
+        self.y -= style.space_after;
+    }
+
+    fn draw(&mut self, fragments: &Fragments, x: f32, size: f32, color: [u8; 3]) {
+        let y = self.y;
+        let page = self.pages.last_mut().expect("layout always has a page");
+        draw_text(self.fonts, page, fragments, x, y, size, color);
+    }
+
+    /// The content of each page, with page numbers added
+    fn finish(mut self) -> Vec<Vec<Operation>> {
+        let count = self.pages.len();
+        for (index, page) in self.pages.iter_mut().enumerate() {
+            let number = format!("{} / {}", index + 1, count);
+            let width = self.fonts.text_width(Font::Regular, &number, 8.0);
+            let fragments = vec![(Font::Regular, number)];
+            let x = (PAGE_WIDTH - width) / 2.0;
+            draw_text(self.fonts, page, &fragments, x, MARGIN / 2.0, 8.0, AI_COLOR);
+        }
+        self.pages
+    }
+}
+
+fn draw_text(
+    fonts: &Fonts,
+    page: &mut Vec<Operation>,
+    fragments: &Fragments,
+    mut x: f32,
+    y: f32,
+    size: f32,
+    color: [u8; 3],
+) {
+    page.push(Operation::new("BT", vec![]));
+    page.push(Operation::new(
+        "rg",
+        color.iter().map(|&c| (c as f32 / 255.0).into()).collect(),
+    ));
+    for (font, text) in fragments {
+        page.push(Operation::new(
+            "Tf",
+            vec![Object::Name(font.resource_name().into()), size.into()],
+        ));
+        page.push(Operation::new(
+            "Tm",
+            vec![1.into(), 0.into(), 0.into(), 1.into(), x.into(), y.into()],
+        ));
+        page.push(Operation::new("Tj", vec![fonts.encode(*font, text)]));
+        x += fonts.text_width(*font, text, size);
+    }
+    page.push(Operation::new("ET", vec![]));
+}
+
+/// Split spans into words, each a list of font runs, at whitespace
+fn words(fonts: &Fonts, spans: &[Span]) -> Vec<Fragments> {
+    let mut words = Vec::new();
+    let mut word: Fragments = Vec::new();
+    for span in spans {
+        for c in span.text.chars() {
+            if c.is_whitespace() {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+                continue;
+            }
+            let font = fonts.pick(Font::of(span), c);
+            match word.last_mut() {
+                Some((last, text)) if *last == font => text.push(c),
+                _ => word.push((font, c.to_string())),
+            }
+        }
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// Greedy line breaking. Words wider than a line are broken anywhere.
+fn wrap(fonts: &Fonts, words: Vec<Fragments>, size: f32, max_width: f32) -> Vec<Fragments> {
+    let space = fonts.text_width(Font::Regular, " ", size);
+    let mut lines = Vec::new();
+    let mut line: Fragments = Vec::new();
+    let mut width = 0.0;
+    for word in words
+        .into_iter()
+        .flat_map(|word| split_word(fonts, word, size, max_width))
+    {
+        let word_width: f32 = word
+            .iter()
+            .map(|(font, text)| fonts.text_width(*font, text, size))
+            .sum();
+        if !line.is_empty() && width + space + word_width > max_width {
+            lines.push(std::mem::take(&mut line));
+            width = 0.0;
+        }
+        if let Some((_, text)) = line.last_mut() {
+            text.push(' ');
+            width += space;
+        }
+        width += word_width;
+        for (font, text) in word {
+            match line.last_mut() {
+                Some((last, line_text)) if *last == font => line_text.push_str(&text),
+                _ => line.push((font, text)),
+            }
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+fn split_word(fonts: &Fonts, word: Fragments, size: f32, max_width: f32) -> Vec<Fragments> {
+    let mut pieces: Vec<Fragments> = vec![Vec::new()];
+    let mut width = 0.0;
+    for (font, text) in word {
+        for c in text.chars() {
+            let c_width = fonts.char_width(font, c) * size / 1000.0;
+            if pieces.last().is_some_and(|piece| !piece.is_empty()) && width + c_width > max_width {
+                pieces.push(Vec::new());
+                width = 0.0;
+            }
+            width += c_width;
+            let piece = pieces.last_mut().unwrap();
+            match piece.last_mut() {
+                Some((last, piece_text)) if *last == font => piece_text.push(c),
+                _ => piece.push((font, c.to_string())),
+            }
+        }
+    }
+    pieces
+}
+
+fn write_pdf(title: &str, pages: Vec<Vec<Operation>>, fonts: &Fonts) -> Result<Vec<u8>> {
+    let mut doc = Document::with_version("1.5");
+    let pages_id = doc.new_object_id();
+
+    let mut font_resources = Dictionary::new();
+    for font in Font::ALL {
+        let font_id = doc.add_object(dictionary! {
+            "Type" => "Font",
+            "Subtype" => "Type1",
+            "BaseFont" => font.base_font(),
+            "Encoding" => "WinAnsiEncoding",
+        });
+        font_resources.set(font.resource_name(), font_id);
+    }
+    if let Some(unicode) = &fonts.unicode {
+        let font_id = add_unicode_font(&mut doc, unicode)?;
+        font_resources.set(Font::Unicode.resource_name(), font_id);
+    }
+    let resources_id = doc.add_object(dictionary! { "Font" => font_resources });
+
+    let count = pages.len() as i64;
+    let mut kids: Vec<Object> = Vec::new();
+    for operations in pages {
+        let content = Content { operations }.encode()?;
+        let content_id = doc.add_object(Stream::new(dictionary! {}, content));
+        let page_id = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "Contents" => content_id,
+        });
+        kids.push(page_id.into());
+    }
+    doc.objects.insert(
+        pages_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => kids,
+            "Count" => count,
+            "Resources" => resources_id,
+            "MediaBox" => vec![0.into(), 0.into(), PAGE_WIDTH.into(), PAGE_HEIGHT.into()],
+        }),
+    );
+
+    // Document title as UTF-16, which unlike the page text has no charset limit
+    let mut title_utf16 = vec![0xFE, 0xFF];
+    title_utf16.extend(title.encode_utf16().flat_map(u16::to_be_bytes));
+    let info_id = doc.add_object(dictionary! {
+        "Title" => Object::String(title_utf16, StringFormat::Hexadecimal),
+    });
+    let catalog_id = doc.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+    });
+    doc.trailer.set("Root", catalog_id);
+    doc.trailer.set("Info", info_id);
+    doc.compress();
+
+    let mut bytes = Vec::new();
+    doc.save_to(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Embed `font` as a composite font addressed by glyph id, with a map back
+/// to the characters so the text can be searched and copied
+fn add_unicode_font(doc: &mut Document, font: &UnicodeFont) -> Result<ObjectId> {
+    let file = font.subset().context("Failed to embed the PDF font")?;
+    // Subset fonts are named with a tag of six capital letters
+    let name = Object::Name(format!("TALKYA+{}", font.name()).into_bytes());
+    let metrics = font.metrics();
+
+    let file_id = doc.add_object(Stream::new(
+        dictionary! { "Length1" => file.len() as i64 },
+        file,
+    ));
+    let descriptor_id = doc.add_object(dictionary! {
+        "Type" => "FontDescriptor",
+        "FontName" => name.clone(),
+        "Flags" => 32,
+        "FontBBox" => metrics.bbox.iter().map(|&v| v.into()).collect::<Vec<Object>>(),
+        "ItalicAngle" => metrics.italic_angle,
+        "Ascent" => metrics.ascent,
+        "Descent" => metrics.descent,
+        "CapHeight" => metrics.cap_height,
+        "StemV" => 80,
+        "FontFile2" => file_id,
+    });
+
+    let mut widths: Vec<Object> = Vec::new();
+    for (_, glyph) in font.glyphs() {
+        widths.push((glyph.id as i64).into());
+        widths.push(vec![glyph.width.into()].into());
+    }
+    let cid_font_id = doc.add_object(dictionary! {
+        "Type" => "Font",
+        "Subtype" => "CIDFontType2",
+        "BaseFont" => name.clone(),
+        "CIDSystemInfo" => dictionary! {
+            "Registry" => Object::string_literal("Adobe"),
+            "Ordering" => Object::string_literal("Identity"),
+            "Supplement" => 0,
+        },
+        "FontDescriptor" => descriptor_id,
+        "W" => widths,
+        "CIDToGIDMap" => "Identity",
+    });
+    let to_unicode_id = doc.add_object(Stream::new(
+        dictionary! {},
+        to_unicode_cmap(font).into_bytes(),
+    ));
+
+    Ok(doc.add_object(dictionary! {
+        "Type" => "Font",
+        "Subtype" => "Type0",
+        "BaseFont" => name,
+        "Encoding" => "Identity-H",
+        "DescendantFonts" => vec![cid_font_id.into()],
+        "ToUnicode" => to_unicode_id,
+    }))
+}
+
+/// CMap from the glyph ids of `font` to the characters they show
+fn to_unicode_cmap(font: &UnicodeFont) -> String {
+    let mut cmap = String::from(
+        "/CIDInit /ProcSet findresource begin\n\
+         12 dict begin\n\
+         begincmap\n\
+         /CIDSystemInfo << /Registry (Adobe) /Ordering (UCS) /Supplement 0 >> def\n\
+         /CMapName /Adobe-Identity-UCS def\n\
+         /CMapType 2 def\n\
+         1 begincodespacerange\n<0000> <FFFF>\nendcodespacerange\n",
+    );
+    let glyphs: Vec<_> = font.glyphs().collect();
+    // A bfchar section holds at most 100 mappings
+    for chunk in glyphs.chunks(100) {
+        cmap.push_str(&format!("{} beginbfchar\n", chunk.len()));
+        for (c, glyph) in chunk {
+            let utf16: String = c
+                .encode_utf16(&mut [0; 2])
+                .iter()
+                .map(|unit| format!("{:04X}", unit))
+                .collect();
+            cmap.push_str(&format!("<{:04X}> <{}>\n", glyph.id, utf16));
+        }
+        cmap.push_str("endbfchar\n");
+    }
+    cmap.push_str(
+        "endcmap\n\
+         CMapName currentdict /CMap defineresource pop\n\
+         end\n\
+         end\n",
+    );
+    cmap
+}
+
+/// The Windows-1252 byte of `c`, the character set of the standard fonts
+fn win_ansi(c: char) -> Option<u8> {
+    let byte = match c {
+        ' '..='~' | '\u{A0}'..='\u{FF}' => c as u8,
+        '€' => 0x80,
+        '‚' => 0x82,
+        'ƒ' => 0x83,
+        '„' => 0x84,
+        '…' => 0x85,
+        '†' => 0x86,
+        '‡' => 0x87,
+        'ˆ' => 0x88,
+        '‰' => 0x89,
+        'Š' => 0x8A,
+        '‹' => 0x8B,
+        'Œ' => 0x8C,
+        'Ž' => 0x8E,
+        '‘' => 0x91,
+        '’' => 0x92,
+        '“' => 0x93,
+        '”' => 0x94,
+        '•' => 0x95,
+        '–' => 0x96,
+        '—' => 0x97,
+        '˜' => 0x98,
+        '™' => 0x99,
+        'š' => 0x9A,
+        '›' => 0x9B,
+        'œ' => 0x9C,
+        'ž' => 0x9E,
+        'Ÿ' => 0x9F,
+        _ => return None,
+    };
+    Some(byte)
+}
+
+/// Advance widths of ' ' to '~' in 1/1000 em, from the Adobe font metrics
+#[rustfmt::skip]
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556,
+    1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722, 778,
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469, 556,
+    333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556,
+    556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+#[rustfmt::skip]
+const HELVETICA_BOLD_WIDTHS: [u16; 95] = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611,
+    975, 722, 722, 722, 722, 667, 611, 778, 722, 278, 556, 722, 611, 833, 722, 778,
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 333, 278, 333, 584, 556,
+    333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556, 278, 889, 611, 611,
+    611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::super::{NotesSection, AI_LEGEND};
+    use super::*;
+
+    #[test]
+    fn test_wrap_breaks_at_spaces_and_keeps_fonts() {
+        let spans = vec![
+            Span {
+                text: "Review on".to_string(),
+                ..Default::default()
+            },
+            Span {
+                text: " April 12".to_string(),
+                bold: true,
+                ..Default::default()
+            },
+        ];
+        // "Review on April" is 72.2pt wide at 10pt
+        let fonts = Fonts::default();
+        let lines = wrap(&fonts, words(&fonts, &spans), 10.0, 75.0);
+        assert_eq!(
+            lines,
+            vec![
+                vec![
+                    (Font::Regular, "Review on ".to_string()),
+                    (Font::Bold, "April".to_string())
+                ],
+                vec![(Font::Bold, "12".to_string())],
+            ]
+        );
+
+        // A word wider than the line is broken
+        let spans = vec![Span {
+            text: "WWWWWW".to_string(),
+            ..Default::default()
+        }];
+        assert_eq!(wrap(&fonts, words(&fonts, &spans), 10.0, 20.0).len(), 3);
+    }
+
+    #[test]
+    fn test_render_styles_headings_bullets_and_ai_lines() {
+        let doc = ExportDocument {
+            title: "Planning".to_string(),
+            date: "2025-03-01 10:00".to_string(),
+            notes: vec![NotesSection {
+                heading: "Notes".to_string(),
+                content: "### Budget\n- [noted] Q3 budget\n  - [ai] Board signoff".to_string(),
+            }],
+            ..Default::default()
+        };
+        let pdf = Document::load_mem(&render(&doc).unwrap()).unwrap();
+        let page = *pdf.get_pages().values().next().unwrap();
+        let content = Content::decode(&pdf.get_page_content(page).unwrap()).unwrap();
+
+        // Each string drawn, with the font, size and colour it was drawn in
+        let mut drawn = Vec::new();
+        let (mut font, mut size, mut color) = (String::new(), 0.0, [0; 3]);
+        for operation in content.operations {
+            let operands = &operation.operands;
+            match operation.operator.as_str() {
+                "Tf" => {
+                    font = operands[0].as_name_str().unwrap().to_string();
+                    size = operands[1].as_float().unwrap();
+                }
+                "rg" => {
+                    color =
+                        [0, 1, 2].map(|i| (operands[i].as_float().unwrap() * 255.0).round() as u8)
+                }
+                "Tj" => {
+                    let text = operands[0]
+                        .as_str()
+                        .unwrap()
+                        .iter()
+                        .map(|&b| b as char)
+                        .collect();
+                    drawn.push((font.clone(), size, color, text));
+                }
+                _ => {}
+            }
+        }
+        let has = |font: &str, size: f32, color: [u8; 3], text: &str| {
+            drawn.contains(&(font.to_string(), size, color, text.to_string()))
+        };
+
+        assert!(has("F2", 20.0, BLACK, "Planning"));
+        assert!(has("F2", 15.0, BLACK, "Notes"));
+        assert!(has("F2", 11.5, BLACK, "Budget"));
+        // Bullets alternate by depth, • and –
+        assert!(has("F1", BODY_SIZE, BLACK, "\u{95}"));
+        assert!(has("F1", BODY_SIZE, BLACK, "Q3 budget"));
+        assert!(has("F1", BODY_SIZE, AI_COLOR, "\u{96}"));
+        assert!(has("F1", BODY_SIZE, AI_COLOR, "Board signoff"));
+        assert!(has("F3", 8.5, AI_COLOR, AI_LEGEND));
+    }
+}
@@ -0,0 +1,150 @@
+use anyhow::Result;
+use minijinja::Environment;
+use serde::Serialize;
+
+/// Everything an export template can use. Templates are Jinja, rendered with
+/// minijinja; `-%}` drops the newline after a block tag. For example:
+///
+/// ```jinja
+/// # {{ session.title }} ({{ session.date }})
+/// {% for item in action_items -%}
+/// - [{{ "x" if item.status == "done" else " " }}] {{ item.text }}{% if item.owner %} ({{ item.owner }}){% endif %}
+/// {% endfor %}
+/// ```
+#[derive(Clone, Debug, Serialize)]
+pub struct TemplateContext {
+    pub session: TemplateSession,
+    /// Tag names
+    pub tags: Vec<String>,
+    /// Folder path from the top level, `/`-separated
+    pub folder: Option<String>,
+    /// The user's notes as Markdown, without [noted]/[ai] markers
+    pub notes: Option<String>,
+    /// Enhanced notes as Markdown, without [noted]/[ai] markers
+    pub enhanced_notes: Option<String>,
+    pub transcript: Vec<TemplateSegment>,
+    pub attachments: Vec<TemplateAttachment>,
+    pub action_items: Vec<TemplateActionItem>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TemplateSession {
+    pub id: String,
+    pub title: String,
+    /// Local start date, `YYYY-MM-DD`
+    pub date: String,
+    /// Local start time, `HH:MM`
+    pub time: String,
+    /// Unix seconds
+    pub started_at: i64,
+    pub ended_at: Option<i64>,
+    pub duration_minutes: Option<i64>,
+    /// Name of the model environment the session used
+    pub environment: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TemplateSegment {
+    /// Offset into the session, `HH:MM:SS`
+    pub timestamp: String,
+    pub start_ms: i64,
+    pub end_ms: i64,
+    /// "mic" or "speaker"
+    pub source: String,
+    /// The diarized speaker's name, "You" for the microphone or "Other" for
+    /// system audio
+    pub speaker: String,
+    pub text: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TemplateAttachment {
+    pub filename: String,
+    pub mime_type: String,
+    /// Bytes
+    pub file_size: i64,
+    /// Text extracted from the document, if any
+    pub text: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TemplateActionItem {
+    pub text: String,
+    pub owner: Option<String>,
+    /// `YYYY-MM-DD`
+    pub due_date: Option<String>,
+    /// "open", "done" or "dismissed"
+    pub status: String,
+}
+
+fn environment() -> Environment<'static> {
+    let mut env = Environment::new();
+    env.set_keep_trailing_newline(true);
+    env
+}
+
+/// Check that a template parses, so errors show up when it is saved rather
+/// than on export
+pub fn check_template(source: &str) -> Result<()> {
+    environment().template_from_str(source)?;
+    Ok(())
+}
+
+pub fn render_template(source: &str, context: &TemplateContext) -> Result<String> {
+    Ok(environment().render_str(source, context)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_minutes_template() {
+        let context = TemplateContext {
+            session: TemplateSession {
+                id: "abc".to_string(),
+                title: "Weekly sync".to_string(),
+                date: "2025-03-01".to_string(),
+                time: "10:00".to_string(),
+                started_at: 0,
+                ended_at: None,
+                duration_minutes: None,
+                environment: None,
+            },
+            tags: vec!["ops".to_string(), "team".to_string()],
+            folder: None,
+            notes: None,
+            enhanced_notes: None,
+            transcript: vec![TemplateSegment {
+                timestamp: "00:00:05".to_string(),
+                start_ms: 5_000,
+                end_ms: 7_000,
+                source: "mic".to_string(),
+                speaker: "Sam".to_string(),
+                text: "Shipping Friday.".to_string(),
+            }],
+            attachments: Vec::new(),
+            action_items: vec![TemplateActionItem {
+                text: "Update the roadmap".to_string(),
+                owner: Some("Alex".to_string()),
+                due_date: None,
+                status: "open".to_string(),
+            }],
+        };
+        let template = "# {{ session.title }}\nDate: {{ session.date }} | {{ tags | join(\", \") }}\n\
+                        {% for item in action_items -%}\n\
+                        - {{ item.text }}{% if item.owner %} ({{ item.owner }}){% endif %}\n\
+                        {% endfor -%}\n\
+                        {% for line in transcript -%}\n\
+                        {{ line.speaker }} @ {{ line.timestamp }}: {{ line.text }}\n\
+                        {% endfor %}";
+        assert_eq!(
+            render_template(template, &context).unwrap(),
+            "# Weekly sync\nDate: 2025-03-01 | ops, team\n- Update the roadmap (Alex)\n\
+             Sam @ 00:00:05: Shipping Friday.\n"
+        );
+
+        assert!(check_template(template).is_ok());
+        assert!(check_template("{% for item in action_items %}").is_err());
+    }
+}
@@ -0,0 +1,717 @@
+use super::ExportDocument;
+use crate::managers::folders::Folder;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Sync state, kept in the vault root. Dotfiles are hidden in Obsidian and Logseq.
+const MANIFEST_FILE: &str = ".talky-sync.json";
+/// How much of each note is read when looking for its frontmatter id
+const FRONTMATTER_READ_LIMIT: u64 = 4096;
+
+/// How tags are written in the body of synced notes
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum TagStyle {
+    /// `#tag`, as Obsidian links tags
+    Hashtags,
+    /// `[[tag]]` page links, as in Logseq
+    Wikilinks,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Type)]
+pub struct VaultSyncResult {
+    pub written: u32,
+    pub unchanged: u32,
+    pub removed: u32,
+    /// Notes edited in the vault since they were synced, which were left as
+    /// they are instead of taking the session's changes. Relative to the vault
+    /// root.
+    pub conflicts: Vec<String>,
+}
+
+/// Frontmatter of a synced note
+#[derive(Clone, Debug)]
+pub struct NoteMetadata {
+    pub id: String,
+    pub title: String,
+    /// Local start time, `YYYY-MM-DDTHH:MM`
+    pub date: String,
+    pub duration_minutes: Option<i64>,
+    pub tags: Vec<String>,
+    /// Folder path, `/`-separated
+    pub folder: Option<String>,
+    pub environment: Option<String>,
+}
+
+/// What was written to the vault by the last sync
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct VaultManifest {
+    /// Unix seconds the last sync started at
+    pub synced_at: i64,
+    /// Export options of the last sync; changing them rewrites every note
+    pub options: String,
+    /// Keyed by session id
+    pub notes: HashMap<String, ManifestEntry>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// Relative to the vault root, `/`-separated
+    pub path: String,
+    /// The session's `updated_at` when the note was written
+    pub updated_at: i64,
+    /// The frontmatter changes with folder, tag and environment names, which
+    /// don't touch the session's `updated_at`
+    pub frontmatter_hash: String,
+    /// Hash of the note as written, to tell whether it was edited in the vault
+    pub content_hash: String,
+}
+
+impl VaultManifest {
+    /// The manifest of the vault at `root`, or an empty one if it has none
+    /// or it can't be read, in which case every note is written again
+    pub fn load(root: &Path) -> Self {
+        let Ok(json) = fs::read_to_string(root.join(MANIFEST_FILE)) else {
+            return Self::default();
+        };
+        serde_json::from_str(&json).unwrap_or_else(|e| {
+            log::warn!("Ignoring unreadable vault sync manifest: {}", e);
+            Self::default()
+        })
+    }
+
+    pub fn save(&self, root: &Path) -> Result<()> {
+        fs::write(
+            root.join(MANIFEST_FILE),
+            serde_json::to_string_pretty(self)?,
+        )?;
+        Ok(())
+    }
+}
+
+/// A session's note, about to be synced
+pub struct VaultNote {
+    pub metadata: NoteMetadata,
+    /// The session's folder path as vault directories
+    pub folder_dirs: Vec<String>,
+    /// File name without `.md`, safe as a path component
+    pub file_name: String,
+    /// The session's `updated_at`
+    pub updated_at: i64,
+}
+
+/// One sync of the vault at `root`, a session at a time. Only notes whose
+/// session changed since the last sync are rewritten, notes the user renamed
+/// or moved are found again by their frontmatter id, and files the user
+/// edited or that weren't written by a sync are never overwritten.
+pub struct VaultSync {
+    root: PathBuf,
+    previous: VaultManifest,
+    manifest: VaultManifest,
+    options_changed: bool,
+    /// Paths of the vault's notes, lowercased, with their session ids
+    taken: HashMap<String, String>,
+    /// Scanned only if a note isn't where the manifest says
+    note_ids: Option<HashMap<String, PathBuf>>,
+    result: VaultSyncResult,
+}
+
+impl VaultSync {
+    /// Start a sync at `synced_at` (Unix seconds). `options` describes the
+    /// export options; changing them rewrites every note.
+    pub fn start(root: &Path, options: String, synced_at: i64) -> Self {
+        let previous = VaultManifest::load(root);
+        let taken = previous
+            .notes
+            .iter()
+            .map(|(session_id, entry)| (entry.path.to_lowercase(), session_id.clone()))
+            .collect();
+        VaultSync {
+            root: root.to_path_buf(),
+            options_changed: previous.options != options,
+            manifest: VaultManifest {
+                synced_at,
+                options,
+                notes: HashMap::new(),
+            },
+            previous,
+            taken,
+            note_ids: None,
+            result: VaultSyncResult::default(),
+        }
+    }
+
+    /// Sync one session's note. `content` renders it, and is only called if
+    /// the session changed since the last sync.
+    pub fn sync_note(
+        &mut self,
+        note: VaultNote,
+        content: impl FnOnce(&NoteMetadata) -> Result<String>,
+    ) {
+        let session_id = note.metadata.id.clone();
+        let entry = self.previous.notes.get(&session_id).cloned();
+        let written_path = entry
+            .as_ref()
+            .map(|entry| self.root.join(&entry.path))
+            .filter(|path| path.is_file());
+        let moved_by_user = written_path.is_none();
+        let current = written_path.or_else(|| {
+            self.note_ids
+                .get_or_insert_with(|| find_note_ids(&self.root))
+                .get(&session_id)
+                .cloned()
+        });
+
+        if let (Some(entry), Some(path)) = (&entry, &current) {
+            // Changes in the second of the last sync may have been missed
+            let unchanged = !self.options_changed
+                && entry.updated_at == note.updated_at
+                && note.updated_at < self.previous.synced_at
+                && entry.frontmatter_hash == content_hash(frontmatter(&note.metadata).as_bytes());
+            if unchanged {
+                let mut entry = entry.clone();
+                entry.path = vault_relative(&self.root, path);
+                self.manifest.notes.insert(session_id, entry);
+                self.result.unchanged += 1;
+                return;
+            }
+        }
+
+        let moved_by_user = moved_by_user && current.is_some();
+        match self.write_note(note, entry.as_ref(), current, moved_by_user, content) {
+            Ok(Outcome::Written(entry)) => {
+                self.result.written += 1;
+                self.manifest.notes.insert(session_id, entry);
+            }
+            Ok(Outcome::Unchanged(entry)) => {
+                self.result.unchanged += 1;
+                self.manifest.notes.insert(session_id, entry);
+            }
+            Ok(Outcome::Conflict(entry)) => {
+                log::info!(
+                    "Keeping vault note edited since the last sync: {}",
+                    entry.path
+                );
+                self.result.conflicts.push(entry.path.clone());
+                self.manifest.notes.insert(session_id, entry);
+            }
+            Err(e) => {
+                log::error!("Failed to sync {} to vault: {:#}", session_id, e);
+                // Keep tracking the old note so it isn't taken for deleted
+                if let Some(entry) = entry {
+                    self.manifest.notes.insert(session_id, entry);
+                }
+            }
+        }
+    }
+
+    /// Write (or move) one session's note. `current` is its file, if it
+    /// exists, and `moved_by_user` whether that is where the user moved or
+    /// renamed it to.
+    fn write_note(
+        &mut self,
+        note: VaultNote,
+        entry: Option<&ManifestEntry>,
+        current: Option<PathBuf>,
+        moved_by_user: bool,
+        content: impl FnOnce(&NoteMetadata) -> Result<String>,
+    ) -> Result<Outcome> {
+        let metadata = &note.metadata;
+        let content = content(metadata)?;
+        let hash = content_hash(content.as_bytes());
+
+        // A note that is neither what the last sync wrote nor what this one
+        // would write was edited in the vault. Its entry is kept as it was,
+        // so it is still seen as edited, and the session as changed, next time.
+        if let Some(path) = &current {
+            let existing = fs::read(path).map(|existing| content_hash(&existing));
+            let edited = existing.is_ok_and(|existing| {
+                existing != hash && entry.is_none_or(|entry| existing != entry.content_hash)
+            });
+            if edited {
+                let relative = vault_relative(&self.root, path);
+                self.taken
+                    .insert(relative.to_lowercase(), metadata.id.clone());
+                let entry = match entry {
+                    Some(entry) => ManifestEntry {
+                        path: relative,
+                        ..entry.clone()
+                    },
+                    None => ManifestEntry {
+                        path: relative,
+                        updated_at: note.updated_at,
+                        frontmatter_hash: String::new(),
+                        content_hash: String::new(),
+                    },
+                };
+                return Ok(Outcome::Conflict(entry));
+            }
+        }
+
+        // Notes the user moved or renamed stay where they are; the others
+        // follow the session's folder and title
+        let path = match current {
+            Some(path) if moved_by_user => path,
+            current => {
+                let target = self.note_path(&note, current.as_deref());
+                if let Some(parent) = target.parent() {
+                    fs::create_dir_all(parent).context("Failed to create directory")?;
+                }
+                if let Some(current) = current.filter(|current| *current != target) {
+                    fs::rename(&current, &target)
+                        .with_context(|| format!("Failed to move {:?}", current))?;
+                    self.taken
+                        .remove(&vault_relative(&self.root, &current).to_lowercase());
+                }
+                target
+            }
+        };
+
+        let relative = vault_relative(&self.root, &path);
+        self.taken
+            .insert(relative.to_lowercase(), metadata.id.clone());
+
+        let unchanged = fs::read(&path)
+            .map(|existing| content_hash(&existing) == hash)
+            .unwrap_or(false);
+        if !unchanged {
+            fs::write(&path, &content).context("Failed to write file")?;
+        }
+
+        let entry = ManifestEntry {
+            path: relative,
+            updated_at: note.updated_at,
+            frontmatter_hash: content_hash(frontmatter(metadata).as_bytes()),
+            content_hash: hash,
+        };
+        Ok(if unchanged {
+            Outcome::Unchanged(entry)
+        } else {
+            Outcome::Written(entry)
+        })
+    }
+
+    /// Where the note goes in the session's folder: its file name, numbered
+    /// if another note or a file the sync didn't write is already there
+    fn note_path(&self, note: &VaultNote, current: Option<&Path>) -> PathBuf {
+        let mut relative_dir = note.folder_dirs.join("/");
+        if !relative_dir.is_empty() {
+            relative_dir.push('/');
+        }
+        let current = current.map(|current| vault_relative(&self.root, current).to_lowercase());
+
+        let mut relative = format!("{}{}.md", relative_dir, note.file_name);
+        let mut counter = 2u32;
+        loop {
+            let key = relative.to_lowercase();
+            let other_note = self
+                .taken
+                .get(&key)
+                .is_some_and(|owner| *owner != note.metadata.id);
+            let other_file = current.as_ref() != Some(&key) && self.root.join(&relative).exists();
+            if !other_note && !other_file {
+                return self.root.join(relative);
+            }
+            relative = format!("{}{} ({}).md", relative_dir, note.file_name, counter);
+            counter += 1;
+        }
+    }
+
+    /// Remove the notes of sessions that weren't synced, as they were deleted
+    /// or moved to the trash, unless they were edited in the vault. Then save
+    /// the manifest.
+    pub fn finish(mut self) -> Result<VaultSyncResult> {
+        for (session_id, entry) in &self.previous.notes {
+            if self.manifest.notes.contains_key(session_id) {
+                continue;
+            }
+            let path = self.root.join(&entry.path);
+            let edited = fs::read(&path)
+                .map(|content| content_hash(&content) != entry.content_hash)
+                .unwrap_or(true);
+            if edited {
+                log::info!("Keeping edited vault note of deleted session: {:?}", path);
+                continue;
+            }
+            match fs::remove_file(&path) {
+                Ok(()) => self.result.removed += 1,
+                Err(e) => log::error!("Failed to remove {:?}: {}", path, e),
+            }
+        }
+
+        self.manifest.save(&self.root)?;
+        Ok(self.result)
+    }
+}
+
+/// What syncing a note did, with the note's new manifest entry
+enum Outcome {
+    Written(ManifestEntry),
+    /// The file already had the note's content
+    Unchanged(ManifestEntry),
+    /// The file was edited in the vault and left alone
+    Conflict(ManifestEntry),
+}
+
+/// `path` relative to `root`, `/`-separated as stored in the manifest
+fn vault_relative(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// The note for a session: YAML frontmatter, then the Markdown export with
+/// tags as links
+pub fn note_markdown(doc: &ExportDocument, metadata: &NoteMetadata, style: TagStyle) -> String {
+    let tags = match style {
+        TagStyle::Hashtags => doc
+            .tags
+            .iter()
+            .map(|tag| format!("#{}", tag_name(tag)))
+            .collect::<Vec<_>>()
+            .join(" "),
+        TagStyle::Wikilinks => doc
+            .tags
+            .iter()
+            .map(|tag| format!("[[{}]]", tag.replace(['[', ']', '|', '#', '^'], "")))
+            .collect::<Vec<_>>()
+            .join(", "),
+    };
+    format!("{}{}", frontmatter(metadata), doc.markdown(&tags))
+}
+
+pub fn frontmatter(metadata: &NoteMetadata) -> String {
+    // JSON strings are valid YAML double-quoted scalars
+    let quote = |value: &str| serde_json::to_string(value).unwrap_or_default();
+
+    let mut yaml = String::from("---\n");
+    yaml.push_str(&format!("id: {}\n", quote(&metadata.id)));
+    yaml.push_str(&format!("title: {}\n", quote(&metadata.title)));
+    yaml.push_str(&format!("date: {}\n", metadata.date));
+    if let Some(minutes) = metadata.duration_minutes {
+        yaml.push_str(&format!("duration: {}\n", minutes));
+    }
+    if !metadata.tags.is_empty() {
+        yaml.push_str("tags:\n");
+        for tag in &metadata.tags {
+            yaml.push_str(&format!("  - {}\n", quote(&tag_name(tag))));
+        }
+    }
+    if let Some(folder) = &metadata.folder {
+        yaml.push_str(&format!("folder: {}\n", quote(folder)));
+    }
+    if let Some(environment) = &metadata.environment {
+        yaml.push_str(&format!("environment: {}\n", quote(environment)));
+    }
+    yaml.push_str("---\n\n");
+    yaml
+}
+
+/// A tag as Obsidian accepts it: no spaces or punctuation besides `-`, `_`
+/// and `/` (for nested tags)
+fn tag_name(tag: &str) -> String {
+    tag.split_whitespace()
+        .collect::<Vec<_>>()
+        .join("-")
+        .chars()
+        .filter(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '/'))
+        .collect()
+}
+
+/// The names along each folder's path from the top level, keyed by folder id
+pub fn folder_paths(folders: &[Folder]) -> HashMap<String, Vec<String>> {
+    let by_id: HashMap<&str, &Folder> = folders.iter().map(|f| (f.id.as_str(), f)).collect();
+    let mut paths = HashMap::new();
+    for folder in folders {
+        let mut names = Vec::new();
+        let mut seen = HashSet::new();
+        let mut current = Some(folder);
+        // A broken parent link ends the path instead of looping
+        while let Some(f) = current.filter(|f| seen.insert(f.id.as_str())) {
+            names.push(f.name.clone());
+            current = f.parent_id.as_deref().and_then(|id| by_id.get(id).copied());
+        }
+        names.reverse();
+        paths.insert(folder.id.clone(), names);
+    }
+    paths
+}
+
+/// Stable 64-bit FNV-1a hash, as hex
+pub fn content_hash(bytes: &[u8]) -> String {
+    let hash = bytes.iter().fold(0xcbf29ce484222325u64, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    format!("{:016x}", hash)
+}
+
+/// The frontmatter `id` of every Markdown note under `root`, so notes the
+/// user renamed or moved are still found. Hidden directories (`.obsidian`,
+/// `.trash`, ...) are skipped.
+pub fn find_note_ids(root: &Path) -> HashMap<String, PathBuf> {
+    let mut ids = HashMap::new();
+    let mut dirs = vec![root.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            if path.is_dir() {
+                dirs.push(path);
+            } else if path.extension().is_some_and(|ext| ext == "md") {
+                if let Some(id) = read_note_id(&path) {
+                    ids.entry(id).or_insert(path);
+                }
+            }
+        }
+    }
+    ids
+}
+
+fn read_note_id(path: &Path) -> Option<String> {
+    let mut head = Vec::new();
+    fs::File::open(path)
+        .ok()?
+        .take(FRONTMATTER_READ_LIMIT)
+        .read_to_end(&mut head)
+        .ok()?;
+    frontmatter_id(&String::from_utf8_lossy(&head))
+}
+
+fn frontmatter_id(text: &str) -> Option<String> {
+    let mut lines = text.lines();
+    if lines.next()?.trim_end() != "---" {
+        return None;
+    }
+    for line in lines.take_while(|line| line.trim_end() != "---") {
+        if let Some(value) = line.strip_prefix("id:") {
+            let value = value.trim();
+            return if value.starts_with('"') {
+                serde_json::from_str(value).ok()
+            } else {
+                Some(value.trim_matches('\'').to_string())
+            };
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_note_markdown_has_frontmatter_and_tag_links() {
+        let doc = ExportDocument {
+            title: "Planning".to_string(),
+            date: "2025-03-01 10:00".to_string(),
+            tags: vec!["Q3 budget".to_string(), "ops".to_string()],
+            ..Default::default()
+        };
+        let metadata = NoteMetadata {
+            id: "abc".to_string(),
+            title: "Planning \"Q3\"".to_string(),
+            date: "2025-03-01T10:00".to_string(),
+            duration_minutes: Some(45),
+            tags: doc.tags.clone(),
+            folder: Some("Work/Clients".to_string()),
+            environment: None,
+        };
+        let note = note_markdown(&doc, &metadata, TagStyle::Hashtags);
+        assert!(note.starts_with(
+            "---\nid: \"abc\"\ntitle: \"Planning \\\"Q3\\\"\"\ndate: 2025-03-01T10:00\nduration: 45\n\
+             tags:\n  - \"Q3-budget\"\n  - \"ops\"\nfolder: \"Work/Clients\"\n---\n\n# Planning\n"
+        ));
+        assert!(note.contains("**Tags:** #Q3-budget #ops\n"));
+        assert!(note_markdown(&doc, &metadata, TagStyle::Wikilinks)
+            .contains("**Tags:** [[Q3 budget]], [[ops]]\n"));
+
+        assert_eq!(frontmatter_id(&note), Some("abc".to_string()));
+        assert_eq!(frontmatter_id("# No frontmatter\nid: abc"), None);
+    }
+
+    #[test]
+    fn test_folder_paths_follow_parents() {
+        let folder = |id: &str, name: &str, parent_id: Option<&str>| Folder {
+            id: id.to_string(),
+            name: name.to_string(),
+            color: None,
+            sort_order: 0,
+            created_at: 0,
+            parent_id: parent_id.map(str::to_string),
+        };
+        let paths = folder_paths(&[
+            folder("a", "Work", None),
+            folder("b", "Clients", Some("a")),
+            folder("c", "Loop", Some("c")),
+        ]);
+        assert_eq!(paths["b"], vec!["Work", "Clients"]);
+        assert_eq!(paths["c"], vec!["Loop"]);
+    }
+
+    fn vault_note(id: &str, title: &str, folder: &[&str], updated_at: i64) -> VaultNote {
+        VaultNote {
+            metadata: NoteMetadata {
+                id: id.to_string(),
+                title: title.to_string(),
+                date: "2025-03-01T10:00".to_string(),
+                duration_minutes: None,
+                tags: Vec::new(),
+                folder: (!folder.is_empty()).then(|| folder.join("/")),
+                environment: None,
+            },
+            folder_dirs: folder.iter().map(|name| name.to_string()).collect(),
+            file_name: format!("2025-03-01 {}", title),
+            updated_at,
+        }
+    }
+
+    /// Sync `notes` with their bodies into `root` at `synced_at`
+    fn sync(root: &Path, synced_at: i64, notes: Vec<(VaultNote, &str)>) -> VaultSyncResult {
+        let mut sync = VaultSync::start(root, "options".to_string(), synced_at);
+        for (note, body) in notes {
+            sync.sync_note(note, |metadata| {
+                Ok(format!("{}{}", frontmatter(metadata), body))
+            });
+        }
+        sync.finish().unwrap()
+    }
+
+    fn read(root: &Path, relative: &str) -> String {
+        fs::read_to_string(root.join(relative)).unwrap()
+    }
+
+    #[test]
+    fn test_vault_sync_rewrites_changed_notes_and_removes_deleted() {
+        let root = std::env::temp_dir().join(format!("talky-vault-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).unwrap();
+
+        let result = sync(
+            &root,
+            100,
+            vec![
+                (vault_note("a", "Planning", &[], 10), "first"),
+                (vault_note("b", "Standup", &["Work"], 10), "standup"),
+            ],
+        );
+        assert_eq!((result.written, result.unchanged), (2, 0));
+        assert!(read(&root, "2025-03-01 Planning.md").ends_with("first"));
+        assert!(read(&root, "Work/2025-03-01 Standup.md").ends_with("standup"));
+
+        let result = sync(
+            &root,
+            200,
+            vec![
+                (vault_note("a", "Planning", &[], 10), "first"),
+                (vault_note("b", "Standup", &["Work"], 10), "standup"),
+            ],
+        );
+        assert_eq!((result.written, result.unchanged), (0, 2));
+
+        // "a" changed and "b" was deleted
+        let result = sync(
+            &root,
+            300,
+            vec![(vault_note("a", "Planning", &[], 250), "second")],
+        );
+        assert_eq!((result.written, result.removed), (1, 1));
+        assert!(read(&root, "2025-03-01 Planning.md").ends_with("second"));
+        assert!(!root.join("Work/2025-03-01 Standup.md").exists());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_vault_sync_follows_moves_and_renames() {
+        let root = std::env::temp_dir().join(format!("talky-vault-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).unwrap();
+
+        sync(
+            &root,
+            100,
+            vec![
+                (vault_note("a", "Planning", &[], 10), "first"),
+                (vault_note("b", "Standup", &[], 10), "standup"),
+            ],
+        );
+
+        // The user moves and renames "a"; the session of "b" is renamed and
+        // moved to a folder
+        fs::create_dir_all(root.join("Archive")).unwrap();
+        fs::rename(
+            root.join("2025-03-01 Planning.md"),
+            root.join("Archive/Q3.md"),
+        )
+        .unwrap();
+        let result = sync(
+            &root,
+            200,
+            vec![
+                (vault_note("a", "Planning", &[], 150), "second"),
+                (vault_note("b", "Daily", &["Work"], 150), "daily"),
+            ],
+        );
+        assert_eq!((result.written, result.removed), (2, 0));
+        assert!(result.conflicts.is_empty());
+        assert!(read(&root, "Archive/Q3.md").ends_with("second"));
+        assert!(!root.join("2025-03-01 Planning.md").exists());
+        assert!(read(&root, "Work/2025-03-01 Daily.md").ends_with("daily"));
+        assert!(!root.join("2025-03-01 Standup.md").exists());
+
+        let manifest = VaultManifest::load(&root);
+        assert_eq!(manifest.notes["a"].path, "Archive/Q3.md");
+        assert_eq!(manifest.notes["b"].path, "Work/2025-03-01 Daily.md");
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_vault_sync_never_overwrites_edited_or_untracked_files() {
+        let root = std::env::temp_dir().join(format!("talky-vault-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).unwrap();
+
+        // A file the sync didn't write keeps its name
+        fs::write(root.join("2025-03-01 Planning.md"), "my own note").unwrap();
+        sync(
+            &root,
+            100,
+            vec![(vault_note("a", "Planning", &[], 10), "first")],
+        );
+        assert_eq!(read(&root, "2025-03-01 Planning.md"), "my own note");
+        assert!(read(&root, "2025-03-01 Planning (2).md").ends_with("first"));
+
+        // The note is edited in the vault, then its session changes
+        let path = root.join("2025-03-01 Planning (2).md");
+        let edited = format!("{}\nMy addition", read(&root, "2025-03-01 Planning (2).md"));
+        fs::write(&path, &edited).unwrap();
+        for synced_at in [200, 300] {
+            let result = sync(
+                &root,
+                synced_at,
+                vec![(vault_note("a", "Planning", &[], 150), "second")],
+            );
+            assert_eq!(result.written, 0);
+            assert_eq!(result.conflicts, vec!["2025-03-01 Planning (2).md"]);
+            assert_eq!(fs::read_to_string(&path).unwrap(), edited);
+        }
+
+        // Nor is it removed when the session is deleted
+        let result = sync(&root, 400, Vec::new());
+        assert_eq!(result.removed, 0);
+        assert_eq!(fs::read_to_string(&path).unwrap(), edited);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,130 @@
+mod tokenizer;
+
+use anyhow::Result;
+use ndarray::{Array2, ArrayView3, Axis, Ix3};
+use ort::session::builder::GraphOptimizationLevel;
+use ort::{session::Session, value::TensorRef};
+use std::fs;
+use std::path::Path;
+use tokenizer::WordPieceTokenizer;
+
+/// Longest input, in tokens, the model is run on. Longer text is cut off.
+const MAX_TOKENS: usize = 256;
+
+/// Sentence-embedding model run through ONNX Runtime.
+///
+/// Expects a BERT-style sentence-transformers export: a directory holding
+/// `model.onnx` and its `vocab.txt`. The model takes `input_ids`,
+/// `attention_mask` and optionally `token_type_ids`, and its first output
+/// holds the token embeddings of shape (batch, tokens, dim).
+pub struct SentenceEmbedder {
+    session: Session,
+    tokenizer: WordPieceTokenizer,
+    takes_token_types: bool,
+}
+
+impl SentenceEmbedder {
+    pub fn new<P: AsRef<Path>>(model_dir: P) -> Result<Self> {
+        let model_dir = model_dir.as_ref();
+        let tokenizer =
+            WordPieceTokenizer::from_vocab(&fs::read_to_string(model_dir.join("vocab.txt"))?)?;
+        let session = Session::builder()?
+            .with_intra_threads(2)?
+            .with_inter_threads(1)?
+            .with_optimization_level(GraphOptimizationLevel::Level3)?
+            .commit_from_file(model_dir.join("model.onnx"))?;
+        let takes_token_types = session
+            .inputs
+            .iter()
+            .any(|input| input.name == "token_type_ids");
+        Ok(Self {
+            session,
+            tokenizer,
+            takes_token_types,
+        })
+    }
+
+    /// Mean-pooled, L2-normalized embeddings, one per text
+    pub fn embed(&mut self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let encoded: Vec<Vec<i64>> = texts
+            .iter()
+            .map(|text| self.tokenizer.encode(text, MAX_TOKENS))
+            .collect();
+        let max_len = encoded.iter().map(Vec::len).max().unwrap_or(0);
+        let mut input_ids = Array2::<i64>::zeros((texts.len(), max_len));
+        let mut attention_mask = Array2::<i64>::zeros((texts.len(), max_len));
+        for (row, ids) in encoded.iter().enumerate() {
+            for (col, &id) in ids.iter().enumerate() {
+                input_ids[[row, col]] = id;
+                attention_mask[[row, col]] = 1;
+            }
+        }
+        let token_type_ids = Array2::<i64>::zeros((texts.len(), max_len));
+
+        let outputs = if self.takes_token_types {
+            self.session.run(ort::inputs![
+                "input_ids" => TensorRef::from_array_view(input_ids.view())?,
+                "attention_mask" => TensorRef::from_array_view(attention_mask.view())?,
+                "token_type_ids" => TensorRef::from_array_view(token_type_ids.view())?,
+            ])?
+        } else {
+            self.session.run(ort::inputs![
+                "input_ids" => TensorRef::from_array_view(input_ids.view())?,
+                "attention_mask" => TensorRef::from_array_view(attention_mask.view())?,
+            ])?
+        };
+        let tokens = outputs[0]
+            .try_extract_array::<f32>()?
+            .into_dimensionality::<Ix3>()?;
+
+        let lengths: Vec<usize> = encoded.iter().map(Vec::len).collect();
+        Ok(mean_pool(tokens.view(), &lengths))
+    }
+}
+
+/// One L2-normalized embedding per row of `tokens` (batch, tokens, dim),
+/// pooled over the first `lengths[row]` tokens so padding doesn't count
+fn mean_pool(tokens: ArrayView3<f32>, lengths: &[usize]) -> Vec<Vec<f32>> {
+    let mut embeddings = Vec::with_capacity(lengths.len());
+    for (row, &length) in lengths.iter().enumerate() {
+        // Normalizing the sum gives the same direction as the mean
+        let mut embedding = vec![0.0f32; tokens.shape()[2]];
+        for token in tokens.index_axis(Axis(0), row).outer_iter().take(length) {
+            embedding
+                .iter_mut()
+                .zip(token.iter())
+                .for_each(|(sum, x)| *sum += x);
+        }
+
+        let norm = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            embedding.iter_mut().for_each(|x| *x /= norm);
+        }
+        embeddings.push(embedding);
+    }
+    embeddings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::Array3;
+
+    #[test]
+    fn test_mean_pool_skips_padding_and_normalizes() {
+        // Two texts of 2 and 1 tokens, padded to 2
+        let tokens =
+            Array3::from_shape_vec((2, 2, 2), vec![3.0, 0.0, 0.0, 4.0, 0.0, 2.0, 100.0, 100.0])
+                .unwrap();
+
+        let embeddings = mean_pool(tokens.view(), &[2, 1]);
+        assert_eq!(embeddings, vec![vec![0.6, 0.8], vec![0.0, 1.0]]);
+
+        let empty = Array3::<f32>::zeros((1, 1, 2));
+        assert_eq!(mean_pool(empty.view(), &[1]), vec![vec![0.0, 0.0]]);
+    }
+}
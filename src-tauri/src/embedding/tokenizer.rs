@@ -0,0 +1,171 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+/// Words longer than this many characters become a single `[UNK]`, as in BERT
+const MAX_WORD_CHARS: usize = 100;
+
+/// Uncased BERT WordPiece tokenizer, driven by the model's `vocab.txt`
+/// (one token per line, the line number being its id).
+pub struct WordPieceTokenizer {
+    vocab: HashMap<String, i64>,
+    cls_id: i64,
+    sep_id: i64,
+    unk_id: i64,
+}
+
+impl WordPieceTokenizer {
+    pub fn from_vocab(vocab_text: &str) -> Result<Self> {
+        let vocab: HashMap<String, i64> = vocab_text
+            .lines()
+            .enumerate()
+            .map(|(id, token)| (token.trim_end().to_string(), id as i64))
+            .collect();
+        let special = |token: &str| {
+            vocab
+                .get(token)
+                .copied()
+                .ok_or_else(|| anyhow::anyhow!("Vocabulary has no {} token", token))
+        };
+        Ok(Self {
+            cls_id: special("[CLS]")?,
+            sep_id: special("[SEP]")?,
+            unk_id: special("[UNK]")?,
+            vocab,
+        })
+    }
+
+    /// Token ids for `text`, wrapped in `[CLS]` … `[SEP]` and cut to `max_len` ids
+    pub fn encode(&self, text: &str, max_len: usize) -> Vec<i64> {
+        let mut ids = vec![self.cls_id];
+        let budget = max_len.saturating_sub(2);
+        for word in basic_tokens(text) {
+            if ids.len() > budget {
+                break;
+            }
+            self.push_word_pieces(&word, &mut ids);
+        }
+        ids.truncate(budget + 1);
+        ids.push(self.sep_id);
+        ids
+    }
+
+    /// Greedy longest-match split of one word into vocabulary pieces
+    fn push_word_pieces(&self, word: &str, ids: &mut Vec<i64>) {
+        let chars: Vec<char> = word.chars().collect();
+        if chars.len() > MAX_WORD_CHARS {
+            ids.push(self.unk_id);
+            return;
+        }
+
+        let mut pieces = Vec::new();
+        let mut start = 0;
+        while start < chars.len() {
+            let mut end = chars.len();
+            let mut piece_id = None;
+            while start < end {
+                let piece: String = chars[start..end].iter().collect();
+                let piece = if start > 0 {
+                    format!("##{}", piece)
+                } else {
+                    piece
+                };
+                if let Some(&id) = self.vocab.get(&piece) {
+                    piece_id = Some(id);
+                    break;
+                }
+                end -= 1;
+            }
+            match piece_id {
+                Some(id) => pieces.push(id),
+                // One unmatched piece makes the whole word unknown
+                None => {
+                    ids.push(self.unk_id);
+                    return;
+                }
+            }
+            start = end;
+        }
+        ids.extend(pieces);
+    }
+}
+
+/// Lowercased, accent-stripped words with punctuation and CJK characters split
+/// out as words of their own
+fn basic_tokens(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    for c in text.nfd().filter(|c| !is_combining_mark(*c)) {
+        if c.is_whitespace() || c.is_control() || c == '\u{FFFD}' {
+            if !current.is_empty() {
+                tokens.push(std::mem::take(&mut current));
+            }
+        } else if is_punctuation(c) || is_cjk(c) {
+            if !current.is_empty() {
+                tokens.push(std::mem::take(&mut current));
+            }
+            tokens.push(c.to_string());
+        } else {
+            current.extend(c.to_lowercase());
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+fn is_punctuation(c: char) -> bool {
+    c.is_ascii_punctuation()
+        || matches!(
+            c,
+            '¡' | '«' | '»' | '¿'
+                | '\u{2010}'..='\u{2027}'
+                | '\u{2030}'..='\u{205E}'
+                | '\u{3001}'..='\u{3003}'
+        )
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(
+        c,
+        '\u{4E00}'..='\u{9FFF}'
+            | '\u{3400}'..='\u{4DBF}'
+            | '\u{F900}'..='\u{FAFF}'
+            | '\u{20000}'..='\u{2A6DF}'
+            | '\u{2F800}'..='\u{2FA1F}'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokenizer() -> WordPieceTokenizer {
+        let vocab = [
+            "[PAD]", "[UNK]", "[CLS]", "[SEP]", "the", "price", "##s", "un", "##want", "##ed", ",",
+            "!", "cafe", "中",
+        ];
+        WordPieceTokenizer::from_vocab(&vocab.join("\n")).unwrap()
+    }
+
+    #[test]
+    fn test_encode_splits_words_and_punctuation() {
+        let t = tokenizer();
+        assert_eq!(
+            t.encode("The Prices, unwanted!", 64),
+            vec![2, 4, 5, 6, 10, 7, 8, 9, 11, 3]
+        );
+        // Accents are stripped, CJK characters stand alone, unknown words are [UNK]
+        assert_eq!(t.encode("Café中xyz", 64), vec![2, 12, 13, 1, 3]);
+    }
+
+    #[test]
+    fn test_encode_truncates_to_max_len() {
+        let t = tokenizer();
+        assert_eq!(t.encode("the the the the the", 4), vec![2, 4, 4, 3]);
+        assert_eq!(t.encode("unwanted unwanted", 4), vec![2, 7, 8, 3]);
+        assert_eq!(t.encode("", 4), vec![2, 3]);
+    }
+}
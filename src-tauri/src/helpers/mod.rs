@@ -1 +1,3 @@
 pub mod clamshell;
+pub mod subtitles;
+pub mod text_diff;
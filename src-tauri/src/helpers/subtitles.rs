@@ -0,0 +1,225 @@
+use crate::audio_toolkit::TimedWord;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// Caption size limits, in line with common subtitling guidelines: two lines
+/// of at most 42 characters, on screen for at most 7 seconds
+const MAX_LINE_CHARS: usize = 42;
+const MAX_CUE_CHARS: usize = 2 * MAX_LINE_CHARS;
+const MAX_CUE_MS: i64 = 7_000;
+/// A sentence ending closes the cue once it holds at least this many characters
+const MIN_SENTENCE_CUE_CHARS: usize = 20;
+/// Cues without a usable duration are shown for this long
+const MIN_CUE_MS: i64 = 1_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum SubtitleFormat {
+    Srt,
+    Vtt,
+}
+
+/// One caption. `label` names the source or speaker of the text.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cue {
+    pub start_ms: i64,
+    pub end_ms: i64,
+    pub label: String,
+    pub text: String,
+}
+
+/// Split the timed words of one transcript segment into caption-sized cues
+pub fn segment_cues(words: &[TimedWord], label: &str) -> Vec<Cue> {
+    let mut cues = Vec::new();
+    let mut current: Vec<&TimedWord> = Vec::new();
+    let mut current_chars = 0;
+    for word in words {
+        let word_chars = word.text.chars().count();
+        if let Some(first) = current.first() {
+            if current_chars + 1 + word_chars > MAX_CUE_CHARS
+                || word.end_ms - first.start_ms > MAX_CUE_MS
+            {
+                cues.push(cue(&current, label));
+                current.clear();
+                current_chars = 0;
+            }
+        }
+
+        current_chars += word_chars + usize::from(!current.is_empty());
+        current.push(word);
+        if word.text.ends_with(['.', '?', '!']) && current_chars >= MIN_SENTENCE_CUE_CHARS {
+            cues.push(cue(&current, label));
+            current.clear();
+            current_chars = 0;
+        }
+    }
+    if !current.is_empty() {
+        cues.push(cue(&current, label));
+    }
+    cues
+}
+
+fn cue(words: &[&TimedWord], label: &str) -> Cue {
+    let start_ms = words.first().map_or(0, |w| w.start_ms);
+    let end_ms = words.last().map_or(0, |w| w.end_ms);
+    Cue {
+        start_ms,
+        end_ms: end_ms.max(start_ms + MIN_CUE_MS),
+        label: label.to_string(),
+        text: words
+            .iter()
+            .map(|w| w.text.as_str())
+            .collect::<Vec<_>>()
+            .join(" "),
+    }
+}
+
+/// Render cues as an SRT or WebVTT file. Cues are labelled where the source
+/// or speaker changes, not on every cue.
+pub fn render_subtitles(cues: &[Cue], format: SubtitleFormat) -> String {
+    let mut out = String::new();
+    if format == SubtitleFormat::Vtt {
+        out.push_str("WEBVTT\n\n");
+    }
+
+    let mut previous_label: Option<&str> = None;
+    for (index, cue) in cues.iter().enumerate() {
+        let labelled = previous_label != Some(cue.label.as_str());
+        previous_label = Some(&cue.label);
+        let text = wrap_lines(&cue.text).join("\n");
+
+        match format {
+            SubtitleFormat::Srt => {
+                out.push_str(&format!(
+                    "{}\n{} --> {}\n",
+                    index + 1,
+                    format_cue_time(cue.start_ms, ','),
+                    format_cue_time(cue.end_ms, ',')
+                ));
+                if labelled {
+                    out.push_str(&format!("{}: ", cue.label));
+                }
+                out.push_str(&text);
+            }
+            SubtitleFormat::Vtt => {
+                out.push_str(&format!(
+                    "{} --> {}\n",
+                    format_cue_time(cue.start_ms, '.'),
+                    format_cue_time(cue.end_ms, '.')
+                ));
+                if labelled {
+                    out.push_str(&format!("<v {}>", escape_vtt(&cue.label)));
+                }
+                out.push_str(&escape_vtt(&text));
+            }
+        }
+        out.push_str("\n\n");
+    }
+    out
+}
+
+/// HH:MM:SS followed by `separator` and milliseconds (`,` for SRT, `.` for WebVTT)
+fn format_cue_time(ms: i64, separator: char) -> String {
+    let ms = ms.max(0);
+    let total_secs = ms / 1000;
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        total_secs / 3600,
+        (total_secs % 3600) / 60,
+        total_secs % 60,
+        separator,
+        ms % 1000
+    )
+}
+
+/// Cue text on one line, or on two lines split as evenly as the words allow
+fn wrap_lines(text: &str) -> Vec<String> {
+    if text.chars().count() <= MAX_LINE_CHARS {
+        return vec![text.to_string()];
+    }
+
+    let half = text.chars().count() / 2;
+    let split = text
+        .char_indices()
+        .filter(|(_, c)| *c == ' ')
+        .map(|(byte, _)| byte)
+        .min_by_key(|&byte| text[..byte].chars().count().abs_diff(half));
+    match split {
+        Some(byte) => vec![text[..byte].to_string(), text[byte + 1..].to_string()],
+        None => vec![text.to_string()],
+    }
+}
+
+fn escape_vtt(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(text: &str, start_ms: i64, ms_per_word: i64) -> Vec<TimedWord> {
+        text.split_whitespace()
+            .enumerate()
+            .map(|(i, word)| TimedWord {
+                text: word.to_string(),
+                start_ms: start_ms + i as i64 * ms_per_word,
+                end_ms: start_ms + (i as i64 + 1) * ms_per_word,
+                estimated: false,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_segment_cues_split_at_sentences_and_duration() {
+        let cues = segment_cues(
+            &words(
+                "Welcome to the quarterly review. Today we cover revenue",
+                0,
+                400,
+            ),
+            "You",
+        );
+        assert_eq!(
+            cues.iter().map(|c| c.text.as_str()).collect::<Vec<_>>(),
+            vec!["Welcome to the quarterly review.", "Today we cover revenue"]
+        );
+        assert_eq!((cues[1].start_ms, cues[1].end_ms), (2_000, 3_600));
+
+        // Slow speech is cut at the maximum cue duration
+        let cues = segment_cues(&words("one two three four five", 0, 2_000), "You");
+        assert_eq!(
+            cues.iter().map(|c| c.text.as_str()).collect::<Vec<_>>(),
+            vec!["one two three", "four five"]
+        );
+    }
+
+    #[test]
+    fn test_render_srt_and_vtt() {
+        let cues = vec![
+            Cue {
+                start_ms: 1_500,
+                end_ms: 3_000,
+                label: "Other".to_string(),
+                text: "Can everyone see my screen & the <chart>?".to_string(),
+            },
+            Cue {
+                start_ms: 3_000,
+                end_ms: 3_723_004,
+                label: "Other".to_string(),
+                text: "This is the plan for next quarter and the one after that".to_string(),
+            },
+        ];
+        assert_eq!(
+            render_subtitles(&cues, SubtitleFormat::Srt),
+            "1\n00:00:01,500 --> 00:00:03,000\nOther: Can everyone see my screen & the <chart>?\n\n\
+             2\n00:00:03,000 --> 01:02:03,004\nThis is the plan for next\nquarter and the one after that\n\n"
+        );
+        assert_eq!(
+            render_subtitles(&cues[..1], SubtitleFormat::Vtt),
+            "WEBVTT\n\n00:00:01.500 --> 00:00:03.000\n<v Other>Can everyone see my screen &amp; the &lt;chart&gt;?\n\n"
+        );
+    }
+}
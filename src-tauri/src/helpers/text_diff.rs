@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// One line of a line-based diff. `kind` is "equal", "added" or "removed".
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Type)]
+pub struct DiffLine {
+    pub kind: String,
+    pub text: String,
+}
+
+/// Line diff between two texts, using the longest common subsequence of lines.
+/// Removed lines are listed before the added lines that replace them.
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // lcs[i][j] = length of the LCS of old[i..] and new[j..]
+    let mut lcs = vec![vec![0u32; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let line = |kind: &str, text: &str| DiffLine {
+        kind: kind.to_string(),
+        text: text.to_string(),
+    };
+
+    let mut diff = Vec::with_capacity(old.len().max(new.len()));
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            diff.push(line("equal", old[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            diff.push(line("removed", old[i]));
+            i += 1;
+        } else {
+            diff.push(line("added", new[j]));
+            j += 1;
+        }
+    }
+    diff.extend(old[i..].iter().map(|text| line("removed", text)));
+    diff.extend(new[j..].iter().map(|text| line("added", text)));
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(diff: &[DiffLine]) -> Vec<(&str, &str)> {
+        diff.iter()
+            .map(|l| (l.kind.as_str(), l.text.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_diff_lines_changed_line() {
+        let diff = diff_lines("# Notes\n- ship v2\n- hire", "# Notes\n- ship v3\n- hire");
+        assert_eq!(
+            kinds(&diff),
+            vec![
+                ("equal", "# Notes"),
+                ("removed", "- ship v2"),
+                ("added", "- ship v3"),
+                ("equal", "- hire"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_lines_from_empty() {
+        let diff = diff_lines("", "a\nb");
+        assert_eq!(kinds(&diff), vec![("added", "a"), ("added", "b")]);
+        assert!(diff_lines("same", "same").iter().all(|l| l.kind == "equal"));
+    }
+}
@@ -4,6 +4,8 @@ mod aec;
 mod apple_intelligence;
 pub mod audio_toolkit;
 mod commands;
+mod document;
+mod embedding;
 mod helpers;
 mod llm_client;
 mod managers;
@@ -14,6 +16,7 @@ mod platform;
 #[cfg(target_os = "macos")]
 mod power_events;
 mod settings;
+mod shortcut;
 mod tray;
 mod tray_i18n;
 mod utils;
@@ -22,6 +25,9 @@ use tauri_specta::{collect_commands, Builder};
 
 use env_filter::Builder as EnvFilterBuilder;
 use managers::audio::AudioRecordingManager;
+use managers::backup::BackupManager;
+use managers::calendar::CalendarManager;
+use managers::embedding::EmbeddingManager;
 use managers::history::HistoryManager;
 use managers::model::ModelManager;
 use managers::session::SessionManager;
@@ -217,8 +223,14 @@ fn initialize_core_logic(app_handle: &AppHandle) {
             .expect("Failed to initialize history manager"),
     );
     let session_manager = Arc::new(
-        SessionManager::new(app_handle, data_dir).expect("Failed to initialize session manager"),
+        SessionManager::new(app_handle, data_dir.clone())
+            .expect("Failed to initialize session manager"),
     );
+    let backup_manager = Arc::new(
+        BackupManager::new(app_handle, data_dir).expect("Failed to initialize backup manager"),
+    );
+    let calendar_manager = Arc::new(CalendarManager::new(app_handle));
+    let embedding_manager = Arc::new(EmbeddingManager::new(app_handle, model_manager.clone()));
 
     // Add managers to Tauri's managed state
     app_handle.manage(recording_manager.clone());
@@ -226,6 +238,33 @@ fn initialize_core_logic(app_handle: &AppHandle) {
     app_handle.manage(transcription_manager.clone());
     app_handle.manage(history_manager.clone());
     app_handle.manage(session_manager.clone());
+    app_handle.manage(backup_manager.clone());
+    app_handle.manage(calendar_manager.clone());
+    app_handle.manage(embedding_manager.clone());
+
+    // Prune archived session audio that has outlived the retention period
+    if let Err(e) = session_manager.cleanup_old_recordings() {
+        log::warn!("Failed to clean up old recordings: {}", e);
+    }
+
+    // Permanently delete sessions that have been in the trash too long and the
+    // scheduled sessions of missed meetings, now and periodically while the app runs
+    if let Err(e) = session_manager.purge_expired_trash() {
+        log::warn!("Failed to purge expired trash: {}", e);
+    }
+    if let Err(e) = session_manager.expire_scheduled_sessions() {
+        log::warn!("Failed to remove past scheduled sessions: {}", e);
+    }
+    session_manager.start_trash_purger();
+
+    // Take scheduled backups in the background
+    backup_manager.start_scheduler();
+
+    // Import calendar files from the watched folder as they change
+    calendar_manager.start_watcher();
+
+    // Embed transcripts and notes for semantic search as they come in
+    embedding_manager.start_indexer();
 
     // Get the current theme to set the appropriate initial icon
     let initial_theme = tray::get_current_theme(app_handle);
@@ -280,6 +319,9 @@ fn initialize_core_logic(app_handle: &AppHandle) {
         let _ = autostart_manager.disable();
     }
 
+    // Global shortcut for bookmarking a recording from any app
+    shortcut::init(app_handle);
+
     // Start power event monitoring (detects system sleep to stop recording gracefully)
     #[cfg(target_os = "macos")]
     power_events::start_monitoring(app_handle.clone());
@@ -340,6 +382,9 @@ pub fn run() {
         commands::settings::add_post_process_prompt,
         commands::settings::update_post_process_prompt,
         commands::settings::delete_post_process_prompt,
+        commands::settings::add_export_template,
+        commands::settings::update_export_template,
+        commands::settings::delete_export_template,
         commands::settings::set_post_process_selected_prompt,
         commands::settings::update_custom_words,
         commands::settings::get_word_suggestions,
@@ -351,6 +396,12 @@ pub fn run() {
         commands::settings::change_skip_mic_on_speaker_energy_setting,
         commands::settings::change_app_language_setting,
         commands::settings::change_update_checks_setting,
+        commands::settings::change_trash_retention_days_setting,
+        commands::settings::change_backup_directory_setting,
+        commands::settings::change_backup_interval_setting,
+        commands::settings::change_backup_keep_count_setting,
+        commands::settings::change_calendar_folder_setting,
+        commands::settings::change_bookmark_shortcut_setting,
         commands::settings::change_copy_as_bullets_setting,
         commands::settings::get_environments,
         commands::settings::create_environment,
@@ -372,10 +423,23 @@ pub fn run() {
         commands::has_custom_data_directory,
         commands::set_data_directory,
         commands::open_user_data_directory,
+        commands::backup::create_backup,
+        commands::backup::list_backups,
+        commands::backup::restore_backup,
+        commands::calendar::import_calendar_file,
+        commands::calendar::sync_calendar_folder,
+        commands::calendar::get_session_calendar_event,
+        commands::embedding::semantic_search,
+        commands::encryption::get_storage_encryption_status,
+        commands::encryption::unlock_storage,
+        commands::encryption::lock_storage,
+        commands::encryption::enable_storage_encryption,
+        commands::encryption::disable_storage_encryption,
         commands::check_apple_intelligence_available,
         commands::check_ollama_available,
         platform::get_platform_capabilities,
         commands::models::get_available_models,
+        commands::models::get_feature_models,
         commands::models::get_model_info,
         commands::models::download_model,
         commands::models::delete_model,
@@ -409,14 +473,37 @@ pub fn run() {
         commands::session::start_session,
         commands::session::start_session_recording,
         commands::session::stop_session_recording,
+        commands::session::import_audio_file,
+        commands::session::cancel_audio_import,
         commands::session::reactivate_session,
         commands::session::end_session,
         commands::session::search_sessions,
+        commands::session::search_full_text,
         commands::session::get_sessions,
         commands::session::get_session,
         commands::session::get_session_transcript,
+        commands::session::update_transcript_segment,
+        commands::session::delete_transcript_segment,
+        commands::session::split_transcript_segment,
+        commands::session::merge_transcript_segments,
+        commands::session::get_transcript_segment_edits,
+        commands::session::revert_transcript_segment_edit,
+        commands::session::get_session_speakers,
+        commands::session::rename_session_speaker,
+        commands::session::retranscribe_session,
+        commands::session::cancel_retranscription,
+        commands::session::get_transcript_revisions,
+        commands::session::get_transcript_revision_segments,
+        commands::session::apply_transcript_revision,
+        commands::session::delete_transcript_revision,
         commands::session::get_active_session,
         commands::session::delete_session,
+        commands::session::get_trashed_sessions,
+        commands::session::restore_session,
+        commands::session::purge_session,
+        commands::session::empty_trash,
+        commands::session::export_session_bundle,
+        commands::session::import_session_bundle,
         commands::session::update_session_title,
         commands::session::update_session_environment,
         commands::session::get_meeting_notes,
@@ -424,17 +511,44 @@ pub fn run() {
         commands::session::save_user_notes,
         commands::session::save_enhanced_notes,
         commands::session::get_user_notes,
+        commands::session::get_meeting_notes_revisions,
+        commands::session::get_meeting_notes_revision,
+        commands::session::restore_meeting_notes_revision,
+        commands::session::diff_meeting_notes_revisions,
         commands::session::generate_session_summary,
         commands::session::generate_session_summary_stream,
         commands::session::get_session_summary,
         commands::session::flush_pending_audio,
+        // Action item commands
+        commands::session::extract_action_items,
+        commands::session::get_session_action_items,
+        commands::session::query_action_items,
+        commands::session::create_action_item,
+        commands::session::update_action_item,
+        commands::session::set_action_item_status,
+        commands::session::delete_action_item,
+        // Bookmark commands
+        commands::session::create_bookmark,
+        commands::session::update_bookmark,
+        commands::session::delete_bookmark,
+        commands::session::get_session_bookmarks,
+        commands::session::get_session_timeline,
         // Folder commands
         commands::session::create_folder,
         commands::session::update_folder,
         commands::session::delete_folder,
         commands::session::get_folders,
+        commands::session::move_folder,
         commands::session::move_session_to_folder,
         commands::session::get_sessions_by_folder,
+        commands::session::get_sessions_in_folder_tree,
+        // Smart folder commands
+        commands::session::create_smart_folder,
+        commands::session::update_smart_folder,
+        commands::session::delete_smart_folder,
+        commands::session::get_smart_folders,
+        commands::session::get_smart_folder_sessions,
+        commands::session::preview_smart_folder,
         // Tag commands
         commands::session::create_tag,
         commands::session::update_tag,
@@ -445,6 +559,17 @@ pub fn run() {
         commands::session::get_session_tags,
         commands::session::set_session_tags,
         commands::session::get_sessions_by_tag,
+        // Audio recording commands
+        commands::session::get_audio_recordings,
+        // Chat commands
+        commands::session::create_chat_thread,
+        commands::session::get_chat_threads,
+        commands::session::rename_chat_thread,
+        commands::session::delete_chat_thread,
+        commands::session::add_chat_message,
+        commands::session::get_chat_messages,
+        commands::session::retrieve_session_passages,
+        commands::session::ask_across_sessions,
         // Attachment commands
         commands::session::add_attachment,
         commands::session::get_attachments,
@@ -455,6 +580,12 @@ pub fn run() {
         // Export commands
         commands::export::export_note_as_markdown,
         commands::export::export_all_notes_as_markdown,
+        commands::export::export_note_as_document,
+        commands::export::export_all_notes_as_documents,
+        commands::export::export_note_as_json,
+        commands::export::export_all_notes_as_ndjson,
+        commands::export::sync_notes_to_vault,
+        commands::export::export_session_subtitles,
     ]);
 
     #[cfg(debug_assertions)] // <- Only export on non-release builds
@@ -611,8 +742,11 @@ pub fn run() {
                 hide_pill_window(app_handle);
             }
 
-            // Suppress unused variable warning on non-macOS
-            #[cfg(not(target_os = "macos"))]
-            let _ = (app_handle, event);
+            // Decrypted attachment copies don't outlive the app
+            if let tauri::RunEvent::Exit = event {
+                app_handle
+                    .state::<Arc<SessionManager>>()
+                    .remove_plaintext_copies();
+            }
         });
 }
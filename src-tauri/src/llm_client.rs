@@ -79,6 +79,8 @@ impl ChatMessage {
 struct ChatCompletionRequest {
     model: String,
     messages: Vec<ChatMessage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -149,6 +151,38 @@ pub async fn send_chat_completion(
     api_key: &str,
     model: &str,
     messages: Vec<ChatMessage>,
+) -> Result<Option<String>, String> {
+    send_completion(base_url, api_key, model, messages, None).await
+}
+
+/// Chat completion constrained to a JSON schema via `response_format`.
+/// Providers that ignore the field still get the request, so the prompt should
+/// ask for the same JSON and callers should parse the reply leniently.
+pub async fn send_structured_completion(
+    base_url: &str,
+    api_key: &str,
+    model: &str,
+    messages: Vec<ChatMessage>,
+    schema_name: &str,
+    schema: serde_json::Value,
+) -> Result<Option<String>, String> {
+    let response_format = serde_json::json!({
+        "type": "json_schema",
+        "json_schema": {
+            "name": schema_name,
+            "strict": true,
+            "schema": schema,
+        },
+    });
+    send_completion(base_url, api_key, model, messages, Some(response_format)).await
+}
+
+async fn send_completion(
+    base_url: &str,
+    api_key: &str,
+    model: &str,
+    messages: Vec<ChatMessage>,
+    response_format: Option<serde_json::Value>,
 ) -> Result<Option<String>, String> {
     let base_url = base_url.trim_end_matches('/');
     let url = format!("{}/chat/completions", base_url);
@@ -160,6 +194,7 @@ pub async fn send_chat_completion(
     let request_body = ChatCompletionRequest {
         model: model.to_string(),
         messages,
+        response_format,
     };
 
     let response = client
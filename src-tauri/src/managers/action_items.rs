@@ -0,0 +1,495 @@
+use crate::managers::bundle::BundleActionItem;
+use crate::managers::session::SessionManager;
+use anyhow::Result;
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashSet;
+use tauri::Emitter;
+use uuid::Uuid;
+
+/// Action item states; anything else is rejected
+pub const ACTION_ITEM_STATUSES: [&str; 3] = ["open", "done", "dismissed"];
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct ActionItem {
+    pub id: String,
+    pub session_id: String,
+    pub session_title: String,
+    pub text: String,
+    pub owner: Option<String>,
+    /// YYYY-MM-DD
+    pub due_date: Option<String>,
+    pub status: String,
+    /// Transcript segment the item was taken from, if it still exists
+    pub source_segment_id: Option<i64>,
+    pub edited: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// Filters for listing action items across sessions; unset fields match everything.
+/// Due dates are YYYY-MM-DD and inclusive; items without a due date are left out
+/// when either bound is set.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct ActionItemFilter {
+    pub session_id: Option<String>,
+    pub status: Option<String>,
+    /// Case-insensitive substring of the owner
+    pub owner: Option<String>,
+    pub due_from: Option<String>,
+    pub due_to: Option<String>,
+}
+
+/// One action item as returned by the extraction prompt
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct ExtractedActionItem {
+    pub text: String,
+    #[serde(default)]
+    pub owner: Option<String>,
+    #[serde(default)]
+    pub due_date: Option<String>,
+    #[serde(default)]
+    pub source_segment_id: Option<i64>,
+}
+
+impl SessionManager {
+    /// Replace a session's extracted action items with a fresh extraction. Items the
+    /// user has edited, closed or added by hand are kept, and new items repeating
+    /// one of them are dropped.
+    pub fn replace_extracted_action_items(
+        &self,
+        session_id: &str,
+        items: Vec<ExtractedActionItem>,
+    ) -> Result<Vec<ActionItem>> {
+        let now = Utc::now().timestamp();
+        let mut conn = self.get_connection()?;
+        let tx = conn.transaction()?;
+
+        tx.execute(
+            "DELETE FROM action_items WHERE session_id = ?1 AND status = 'open' AND edited = 0",
+            params![session_id],
+        )?;
+        let kept: HashSet<String> = {
+            let mut stmt = tx.prepare("SELECT text FROM action_items WHERE session_id = ?1")?;
+            let rows = stmt.query_map(params![session_id], |row| row.get::<_, String>("text"))?;
+            let mut kept = HashSet::new();
+            for row in rows {
+                kept.insert(normalize_action_item_text(&row?));
+            }
+            kept
+        };
+
+        // The model may cite segments that don't belong to this session
+        let segment_ids: HashSet<i64> = {
+            let mut stmt =
+                tx.prepare("SELECT id FROM transcript_segments WHERE session_id = ?1")?;
+            let rows = stmt.query_map(params![session_id], |row| row.get::<_, i64>("id"))?;
+            let mut ids = HashSet::new();
+            for row in rows {
+                ids.insert(row?);
+            }
+            ids
+        };
+
+        for item in items {
+            if kept.contains(&normalize_action_item_text(&item.text)) {
+                continue;
+            }
+            let source_segment_id = item.source_segment_id.filter(|id| segment_ids.contains(id));
+            tx.execute(
+                "INSERT INTO action_items (id, session_id, text, owner, due_date, status, source_segment_id, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, 'open', ?6, ?7, ?7)",
+                params![
+                    Uuid::new_v4().to_string(),
+                    session_id,
+                    item.text,
+                    item.owner,
+                    item.due_date,
+                    source_segment_id,
+                    now
+                ],
+            )?;
+        }
+        tx.commit()?;
+
+        let items = self.get_session_action_items(session_id)?;
+        let _ = self.app_handle.emit("action-items-updated", session_id);
+        Ok(items)
+    }
+
+    pub fn get_session_action_items(&self, session_id: &str) -> Result<Vec<ActionItem>> {
+        self.query_action_items(&ActionItemFilter {
+            session_id: Some(session_id.to_string()),
+            ..Default::default()
+        })
+    }
+
+    /// Action items across all sessions outside the trash, soonest due first
+    pub fn query_action_items(&self, filter: &ActionItemFilter) -> Result<Vec<ActionItem>> {
+        let owner = filter
+            .owner
+            .as_deref()
+            .map(str::trim)
+            .filter(|o| !o.is_empty())
+            .map(|o| format!("%{}%", o));
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT a.id, a.session_id, s.title AS session_title, a.text, a.owner, a.due_date, a.status,
+                    a.source_segment_id, a.edited, a.created_at, a.updated_at
+             FROM action_items a
+             JOIN sessions s ON s.id = a.session_id
+             WHERE s.status != 'deleted'
+               AND (?1 IS NULL OR a.session_id = ?1)
+               AND (?2 IS NULL OR a.status = ?2)
+               AND (?3 IS NULL OR a.owner LIKE ?3)
+               AND (?4 IS NULL OR a.due_date >= ?4)
+               AND (?5 IS NULL OR a.due_date <= ?5)
+             ORDER BY a.due_date IS NULL, a.due_date ASC, s.started_at DESC, a.created_at ASC, a.rowid ASC",
+        )?;
+
+        let rows = stmt.query_map(
+            params![
+                filter.session_id,
+                filter.status,
+                owner,
+                filter.due_from,
+                filter.due_to
+            ],
+            map_action_item,
+        )?;
+
+        let mut items = Vec::new();
+        for row in rows {
+            items.push(row?);
+        }
+        Ok(items)
+    }
+
+    fn get_action_item(&self, item_id: &str) -> Result<ActionItem> {
+        let conn = self.get_connection()?;
+        conn.query_row(
+            "SELECT a.id, a.session_id, s.title AS session_title, a.text, a.owner, a.due_date, a.status,
+                    a.source_segment_id, a.edited, a.created_at, a.updated_at
+             FROM action_items a
+             JOIN sessions s ON s.id = a.session_id
+             WHERE a.id = ?1",
+            params![item_id],
+            map_action_item,
+        )
+        .optional()?
+        .ok_or_else(|| anyhow::anyhow!("Action item not found: {}", item_id))
+    }
+
+    /// Add an action item by hand. It counts as edited, so re-extraction keeps it.
+    pub fn create_action_item(
+        &self,
+        session_id: &str,
+        text: &str,
+        owner: Option<String>,
+        due_date: Option<String>,
+    ) -> Result<ActionItem> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err(anyhow::anyhow!("Action item text is empty"));
+        }
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now().timestamp();
+        let conn = self.get_connection()?;
+        conn.execute(
+            "INSERT INTO action_items (id, session_id, text, owner, due_date, status, edited, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, 'open', 1, ?6, ?6)",
+            params![
+                id,
+                session_id,
+                text,
+                owner,
+                parse_due_date(due_date.as_deref())?,
+                now
+            ],
+        )?;
+        self.get_action_item(&id)
+    }
+
+    /// Update an action item's text, owner, due date and status. Notes are not touched.
+    pub fn update_action_item(
+        &self,
+        item_id: &str,
+        text: &str,
+        owner: Option<String>,
+        due_date: Option<String>,
+        status: &str,
+    ) -> Result<ActionItem> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err(anyhow::anyhow!("Action item text is empty"));
+        }
+        check_action_item_status(status)?;
+        let conn = self.get_connection()?;
+        let updated = conn.execute(
+            "UPDATE action_items SET text = ?1, owner = ?2, due_date = ?3, status = ?4, edited = 1, updated_at = ?5
+             WHERE id = ?6",
+            params![
+                text,
+                owner,
+                parse_due_date(due_date.as_deref())?,
+                status,
+                Utc::now().timestamp(),
+                item_id
+            ],
+        )?;
+        if updated == 0 {
+            return Err(anyhow::anyhow!("Action item not found: {}", item_id));
+        }
+        self.get_action_item(item_id)
+    }
+
+    pub fn set_action_item_status(&self, item_id: &str, status: &str) -> Result<ActionItem> {
+        check_action_item_status(status)?;
+        let conn = self.get_connection()?;
+        let updated = conn.execute(
+            "UPDATE action_items SET status = ?1, edited = 1, updated_at = ?2 WHERE id = ?3",
+            params![status, Utc::now().timestamp(), item_id],
+        )?;
+        if updated == 0 {
+            return Err(anyhow::anyhow!("Action item not found: {}", item_id));
+        }
+        self.get_action_item(item_id)
+    }
+
+    pub fn delete_action_item(&self, item_id: &str) -> Result<()> {
+        let conn = self.get_connection()?;
+        conn.execute("DELETE FROM action_items WHERE id = ?1", params![item_id])?;
+        Ok(())
+    }
+}
+
+/// A session's action items for a bundle. Source segments are stored as their
+/// position in `segment_ids`, the session's transcript in bundle order.
+pub(crate) fn bundle_action_items(
+    conn: &Connection,
+    session_id: &str,
+    segment_ids: &[i64],
+) -> Result<Vec<BundleActionItem>> {
+    let mut stmt = conn.prepare(
+        "SELECT text, owner, due_date, status, source_segment_id, edited, created_at, updated_at
+         FROM action_items WHERE session_id = ?1
+         ORDER BY created_at, rowid",
+    )?;
+    let rows = stmt.query_map(params![session_id], |row| {
+        let source_segment_id: Option<i64> = row.get("source_segment_id")?;
+        Ok(BundleActionItem {
+            text: row.get("text")?,
+            owner: row.get("owner")?,
+            due_date: row.get("due_date")?,
+            status: row.get("status")?,
+            source_segment: source_segment_id
+                .and_then(|id| segment_ids.iter().position(|&s| s == id)),
+            edited: row.get("edited")?,
+            created_at: row.get("created_at")?,
+            updated_at: row.get("updated_at")?,
+        })
+    })?;
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row?);
+    }
+    Ok(items)
+}
+
+/// Add a bundle's action items to an imported session whose transcript
+/// segments got `segment_ids`, in bundle order
+pub(crate) fn insert_bundle_action_items(
+    conn: &Connection,
+    session_id: &str,
+    items: &[BundleActionItem],
+    segment_ids: &[i64],
+) -> Result<()> {
+    for item in items {
+        check_action_item_status(&item.status)?;
+        conn.execute(
+            "INSERT INTO action_items (id, session_id, text, owner, due_date, status, source_segment_id, edited, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                Uuid::new_v4().to_string(),
+                session_id,
+                item.text,
+                item.owner,
+                parse_due_date(item.due_date.as_deref())?,
+                item.status,
+                item.source_segment.and_then(|i| segment_ids.get(i)),
+                item.edited,
+                item.created_at,
+                item.updated_at
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+fn map_action_item(row: &rusqlite::Row) -> rusqlite::Result<ActionItem> {
+    Ok(ActionItem {
+        id: row.get("id")?,
+        session_id: row.get("session_id")?,
+        session_title: row.get("session_title")?,
+        text: row.get("text")?,
+        owner: row.get("owner")?,
+        due_date: row.get("due_date")?,
+        status: row.get("status")?,
+        source_segment_id: row.get("source_segment_id")?,
+        edited: row.get("edited")?,
+        created_at: row.get("created_at")?,
+        updated_at: row.get("updated_at")?,
+    })
+}
+
+fn check_action_item_status(status: &str) -> Result<()> {
+    if !ACTION_ITEM_STATUSES.contains(&status) {
+        return Err(anyhow::anyhow!("Unknown action item status: {}", status));
+    }
+    Ok(())
+}
+
+/// Validate a YYYY-MM-DD due date; blank means no due date
+fn parse_due_date(due_date: Option<&str>) -> Result<Option<String>> {
+    match due_date.map(str::trim).filter(|d| !d.is_empty()) {
+        Some(date) => chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map(|d| Some(d.format("%Y-%m-%d").to_string()))
+            .map_err(|_| anyhow::anyhow!("Due date must be YYYY-MM-DD: {}", date)),
+        None => Ok(None),
+    }
+}
+
+fn normalize_action_item_text(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Parse the extraction model's reply: `{"action_items": [...]}`, possibly
+/// wrapped in a code fence by models that ignore the response format. Items
+/// without text are dropped and unparseable due dates cleared.
+pub fn parse_extracted_action_items(reply: &str) -> Result<Vec<ExtractedActionItem>> {
+    #[derive(Deserialize)]
+    struct Reply {
+        action_items: Vec<ExtractedActionItem>,
+    }
+
+    let start = reply.find('{');
+    let end = reply.rfind('}');
+    let json = match (start, end) {
+        (Some(start), Some(end)) if start < end => &reply[start..=end],
+        _ => return Err(anyhow::anyhow!("No JSON object in the model's reply")),
+    };
+    let reply: Reply = serde_json::from_str(json)
+        .map_err(|e| anyhow::anyhow!("Model returned malformed action items: {}", e))?;
+
+    Ok(reply
+        .action_items
+        .into_iter()
+        .filter_map(|mut item| {
+            item.text = item.text.trim().to_string();
+            if item.text.is_empty() {
+                return None;
+            }
+            item.owner = item
+                .owner
+                .map(|o| o.trim().to_string())
+                .filter(|o| !o.is_empty());
+            item.due_date = parse_due_date(item.due_date.as_deref()).ok().flatten();
+            Some(item)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::managers::session::test_connection;
+
+    #[test]
+    fn test_bundle_action_items_round_trip() {
+        let conn = test_connection();
+        conn.execute_batch(
+            "INSERT INTO sessions (id, title, started_at) VALUES ('call', 'Call', 0), ('copy', 'Copy', 0);
+             INSERT INTO transcript_segments (id, session_id, text, source, start_ms, end_ms, created_at) VALUES
+                (7, 'call', 'Hello', 'mic', 0, 1000, 0),
+                (9, 'call', 'I will send the deck', 'mic', 1000, 2000, 0),
+                (20, 'copy', 'Hello', 'mic', 0, 1000, 0),
+                (21, 'copy', 'I will send the deck', 'mic', 1000, 2000, 0);
+             INSERT INTO action_items (id, session_id, text, owner, due_date, status, source_segment_id, edited, created_at, updated_at) VALUES
+                ('deck', 'call', 'Send the deck', 'Sam', '2026-03-02', 'done', 9, 1, 1, 5),
+                ('room', 'call', 'Book a room', NULL, NULL, 'open', NULL, 0, 2, 2);",
+        )
+        .unwrap();
+
+        let items = bundle_action_items(&conn, "call", &[7, 9]).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].source_segment, Some(1));
+        assert_eq!(items[1].source_segment, None);
+
+        insert_bundle_action_items(&conn, "copy", &items, &[20, 21]).unwrap();
+        let mut stmt = conn
+            .prepare(
+                "SELECT text, status, source_segment_id, edited FROM action_items
+                 WHERE session_id = 'copy' ORDER BY created_at",
+            )
+            .unwrap();
+        let imported: Vec<(String, String, Option<i64>, bool)> = stmt
+            .query_map([], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+            })
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert_eq!(
+            imported,
+            vec![
+                (
+                    "Send the deck".to_string(),
+                    "done".to_string(),
+                    Some(21),
+                    true
+                ),
+                ("Book a room".to_string(), "open".to_string(), None, false),
+            ]
+        );
+        let (owner, due_date, updated_at): (String, String, i64) = conn
+            .query_row(
+                "SELECT owner, due_date, updated_at FROM action_items
+                 WHERE session_id = 'copy' AND text = 'Send the deck'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(
+            (owner.as_str(), due_date.as_str(), updated_at),
+            ("Sam", "2026-03-02", 5)
+        );
+
+        let mut bad = bundle_action_items(&conn, "call", &[7, 9]).unwrap();
+        bad[0].status = "someday".to_string();
+        assert!(insert_bundle_action_items(&conn, "copy", &bad, &[20, 21]).is_err());
+    }
+
+    #[test]
+    fn test_parse_extracted_action_items() {
+        let reply = "```json\n{\"action_items\": [
+            {\"text\": \" Send the budget \", \"owner\": \"Sam\", \"due_date\": \"2026-03-02\", \"source_segment_id\": 12},
+            {\"text\": \"Book a room\", \"owner\": \" \", \"due_date\": \"next Friday\"},
+            {\"text\": \"  \"}
+        ]}\n```";
+        let items = parse_extracted_action_items(reply).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].text, "Send the budget");
+        assert_eq!(items[0].due_date.as_deref(), Some("2026-03-02"));
+        assert_eq!(items[0].source_segment_id, Some(12));
+        assert_eq!(items[1].owner, None);
+        assert_eq!(items[1].due_date, None);
+
+        assert!(parse_extracted_action_items("No action items.").is_err());
+    }
+}
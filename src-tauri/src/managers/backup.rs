@@ -0,0 +1,1004 @@
+use crate::managers::bundle::unpack_regular;
+use crate::managers::encryption::{self, open_database, KeyFile, StorageKey, KEY_FILE_NAME};
+use crate::managers::history::MIGRATIONS as HISTORY_MIGRATIONS;
+use crate::managers::session::{SessionManager, SESSION_MIGRATIONS};
+use crate::settings::get_settings;
+use crate::utils::MutexExt;
+use anyhow::Result;
+use chrono::{Local, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use log::{debug, error, info, warn};
+use rusqlite::backup::{Backup, StepResult};
+use rusqlite::{params, Connection, OpenFlags};
+use rusqlite_migration::{Migrations, M};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, Weak};
+use std::thread;
+use std::time::{Duration, SystemTime};
+use tar::{Archive, Builder, Header};
+use tauri::{AppHandle, Emitter, Manager};
+use uuid::Uuid;
+
+/// Backups are gzipped tars holding `manifest.json`, a snapshot of each database
+/// and the attachment and recording trees, named so they sort by creation time.
+/// Backups of encrypted storage also carry `encryption.json`.
+const BACKUP_FORMAT: &str = "talky-backup";
+const BACKUP_VERSION: u32 = 1;
+const BACKUP_PREFIX: &str = "talky-backup-";
+const BACKUP_SUFFIX: &str = ".tar.gz";
+const MANIFEST_NAME: &str = "manifest.json";
+/// Databases live in the user data directory (which may be customised)...
+const DATABASES: [&str; 2] = ["sessions.db", "history.db"];
+/// ...while attachment and recording files always live in the app data directory
+const FILE_DIRS: [&str; 2] = ["attachments", "recordings"];
+/// How often the scheduler checks whether a backup is due
+const SCHEDULE_POLL: Duration = Duration::from_secs(60);
+/// Attempts at copying a database while another connection holds a lock on it
+const BUSY_RETRIES: u32 = 25;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BackupManifest {
+    format: String,
+    version: u32,
+    created_at: i64,
+    app_version: String,
+    databases: Vec<BackupDatabase>,
+    /// sessions.db, attachments and recordings are stored encrypted, as they
+    /// were on disk, next to the key file holding the salt and KDF parameters
+    #[serde(default)]
+    encrypted: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BackupDatabase {
+    name: String,
+    schema_version: i64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct BackupInfo {
+    pub file_name: String,
+    pub file_path: String,
+    pub size_bytes: i64,
+    pub created_at: i64,
+}
+
+pub struct BackupManager {
+    app_handle: AppHandle,
+    dirs: DataDirs,
+    /// Held while a backup or restore runs so the two never overlap
+    busy: Mutex<()>,
+}
+
+/// Where the live data that gets backed up and restored lives
+struct DataDirs {
+    /// Where sessions.db and history.db live
+    db_dir: PathBuf,
+    /// App data directory holding the attachment and recording trees
+    files_dir: PathBuf,
+}
+
+impl BackupManager {
+    /// Creates a new BackupManager.
+    /// If `data_dir` is Some, databases are read from there, matching the
+    /// session and history managers. Otherwise, uses the default app data directory.
+    pub fn new(app_handle: &AppHandle, data_dir: Option<PathBuf>) -> Result<Self> {
+        let files_dir = app_handle.path().app_data_dir()?;
+        let db_dir = data_dir.unwrap_or_else(|| files_dir.clone());
+
+        Ok(Self {
+            app_handle: app_handle.clone(),
+            dirs: DataDirs { db_dir, files_dir },
+            busy: Mutex::new(()),
+        })
+    }
+
+    /// Start the thread that takes scheduled backups according to the
+    /// `backup_interval` and `backup_directory` settings. It stops once the
+    /// manager is dropped.
+    pub fn start_scheduler(self: &Arc<Self>) {
+        let manager: Weak<Self> = Arc::downgrade(self);
+        thread::spawn(move || {
+            loop {
+                thread::sleep(SCHEDULE_POLL);
+                let Some(manager) = manager.upgrade() else {
+                    break;
+                };
+                if let Err(e) = manager.run_scheduled_backup() {
+                    error!("Scheduled backup failed: {}", e);
+                }
+            }
+            debug!("Backup scheduler shutting down");
+        });
+    }
+
+    fn run_scheduled_backup(&self) -> Result<()> {
+        let settings = get_settings(&self.app_handle);
+        let (Some(interval), Some(dir)) = (
+            settings.backup_interval.to_seconds(),
+            settings.backup_directory.map(PathBuf::from),
+        ) else {
+            return Ok(());
+        };
+
+        // Nothing can be copied until the user unlocks encrypted storage
+        let session_manager = self.app_handle.state::<Arc<SessionManager>>();
+        if session_manager.get_storage_encryption_status().locked {
+            return Ok(());
+        }
+
+        let now = Utc::now().timestamp();
+        let last = self
+            .list_backups(&dir)?
+            .first()
+            .map(|b| b.created_at)
+            .unwrap_or(0);
+        if now - last < interval as i64 {
+            return Ok(());
+        }
+
+        info!("Taking scheduled backup into {:?}", dir);
+        let backup = self.create_backup(&dir)?;
+        self.rotate_backups(&dir, settings.backup_keep_count as usize)?;
+        let _ = self.app_handle.emit("backup-created", &backup);
+        Ok(())
+    }
+
+    /// Write a timestamped backup of both databases, attachments and archived
+    /// audio into `dest_dir`. Databases are copied with SQLite's online backup
+    /// API, so this is safe while the app keeps writing. With encrypted storage
+    /// the backup stays encrypted under the same passphrase and includes the
+    /// key file, so it can be restored on another machine.
+    pub fn create_backup(&self, dest_dir: &Path) -> Result<BackupInfo> {
+        let _busy = self.busy.lock_or_recover();
+        let key = self.database_key()?;
+        let app_version = self.app_handle.package_info().version.to_string();
+        self.dirs
+            .create_backup(dest_dir, key.as_deref(), &app_version)
+    }
+
+    /// Key for sessions.db, None when storage isn't encrypted
+    fn database_key(&self) -> Result<Option<Arc<StorageKey>>> {
+        self.app_handle
+            .state::<Arc<SessionManager>>()
+            .database_key()
+    }
+
+    /// List the backups in `dir`, newest first
+    pub fn list_backups(&self, dir: &Path) -> Result<Vec<BackupInfo>> {
+        list_backups(dir)
+    }
+
+    /// Delete all but the newest `keep` backups in `dir` (0 keeps everything)
+    pub fn rotate_backups(&self, dir: &Path, keep: usize) -> Result<usize> {
+        rotate_backups(dir, keep)
+    }
+
+    /// Restore a backup over the current data. The archive is unpacked and checked
+    /// first: a known manifest, databases that pass `integrity_check` and a schema
+    /// this build can migrate. Staged data is converted to the current storage
+    /// layout, so an encrypted backup can be restored into plaintext storage or
+    /// storage with another passphrase when `passphrase` unlocks it. Only then are
+    /// the databases copied into the live files with the backup API and the
+    /// attachment and recording trees swapped.
+    ///
+    /// Callers must make sure nothing is recording or transcribing.
+    pub fn restore_backup(&self, archive_path: &Path, passphrase: Option<&str>) -> Result<()> {
+        let _busy = self.busy.lock_or_recover();
+        let key = self.database_key()?;
+        self.dirs
+            .restore_backup(archive_path, key.as_ref(), passphrase)?;
+
+        info!("Backup {:?} restored", archive_path);
+        let _ = self.app_handle.emit("backup-restored", ());
+        Ok(())
+    }
+}
+
+impl DataDirs {
+    fn create_backup(
+        &self,
+        dest_dir: &Path,
+        key: Option<&StorageKey>,
+        app_version: &str,
+    ) -> Result<BackupInfo> {
+        fs::create_dir_all(dest_dir)?;
+
+        let staging = std::env::temp_dir().join(format!("talky-backup-{}", Uuid::new_v4()));
+        fs::create_dir_all(&staging)?;
+        let file_name = format!(
+            "{}{}{}",
+            BACKUP_PREFIX,
+            Local::now().format("%Y%m%d-%H%M%S"),
+            BACKUP_SUFFIX
+        );
+        let dest = dest_dir.join(&file_name);
+        // Written under a temporary name so a half-written archive is never listed
+        let partial = dest_dir.join(format!("{}.partial", file_name));
+
+        let result = self.write_backup(&staging, &partial, key, app_version);
+        let _ = fs::remove_dir_all(&staging);
+        if let Err(e) = result.and_then(|_| Ok(fs::rename(&partial, &dest)?)) {
+            let _ = fs::remove_file(&partial);
+            return Err(e);
+        }
+
+        info!("Backup written to {:?}", dest);
+        backup_info(&dest)
+    }
+
+    fn write_backup(
+        &self,
+        staging: &Path,
+        archive_path: &Path,
+        key: Option<&StorageKey>,
+        app_version: &str,
+    ) -> Result<()> {
+        let mut databases = Vec::new();
+        for name in DATABASES {
+            let source = self.db_dir.join(name);
+            if !source.exists() {
+                continue;
+            }
+            let key = database_key_for(name, key);
+            let src = open_database(&source, OpenFlags::SQLITE_OPEN_READ_ONLY, key)?;
+            let mut dst = open_database(&staging.join(name), OpenFlags::default(), key)?;
+            copy_database(&src, &mut dst)?;
+            // Fold the snapshot back into a single file before archiving it
+            dst.pragma_update(None, "journal_mode", "DELETE")?;
+            databases.push(BackupDatabase {
+                name: name.to_string(),
+                schema_version: schema_version(&dst)?,
+            });
+        }
+
+        let manifest = BackupManifest {
+            format: BACKUP_FORMAT.to_string(),
+            version: BACKUP_VERSION,
+            created_at: Utc::now().timestamp(),
+            app_version: app_version.to_string(),
+            databases,
+            encrypted: key.is_some(),
+        };
+
+        let encoder = GzEncoder::new(File::create(archive_path)?, Compression::default());
+        let mut builder = Builder::new(encoder);
+
+        let manifest_json = serde_json::to_vec_pretty(&manifest)?;
+        let mut header = Header::new_gnu();
+        header.set_size(manifest_json.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(manifest.created_at.max(0) as u64);
+        header.set_cksum();
+        builder.append_data(&mut header, MANIFEST_NAME, manifest_json.as_slice())?;
+
+        for database in &manifest.databases {
+            builder.append_path_with_name(staging.join(&database.name), &database.name)?;
+        }
+        if manifest.encrypted {
+            builder.append_path_with_name(self.db_dir.join(KEY_FILE_NAME), KEY_FILE_NAME)?;
+        }
+        for dir in FILE_DIRS {
+            append_tree(&mut builder, &self.files_dir.join(dir), Path::new(dir))?;
+        }
+
+        builder.into_inner()?.finish()?;
+        Ok(())
+    }
+
+    fn restore_backup(
+        &self,
+        archive_path: &Path,
+        key: Option<&Arc<StorageKey>>,
+        passphrase: Option<&str>,
+    ) -> Result<()> {
+        // Unpack next to the live trees so swapping them in is a rename
+        let staging = self.files_dir.join(format!(".restore-{}", Uuid::new_v4()));
+        fs::create_dir_all(&staging)?;
+        let result = self
+            .validate_backup(archive_path, &staging, key, passphrase)
+            .and_then(|_| self.swap_in(&staging, key.map(Arc::as_ref)));
+        if let Err(e) = fs::remove_dir_all(&staging) {
+            warn!("Failed to clean up {:?}: {}", staging, e);
+        }
+        result
+    }
+
+    fn validate_backup(
+        &self,
+        archive_path: &Path,
+        staging: &Path,
+        key: Option<&Arc<StorageKey>>,
+        passphrase: Option<&str>,
+    ) -> Result<()> {
+        let mut archive = Archive::new(GzDecoder::new(File::open(archive_path)?));
+        unpack_regular(&mut archive, staging)
+            .map_err(|e| anyhow::anyhow!("Not a valid backup archive: {}", e))?;
+
+        let manifest = fs::read_to_string(staging.join(MANIFEST_NAME))
+            .map_err(|_| anyhow::anyhow!("Backup is missing {}", MANIFEST_NAME))?;
+        let manifest = parse_manifest(&manifest)?;
+        if !manifest.databases.iter().any(|d| d.name == "sessions.db") {
+            return Err(anyhow::anyhow!("Backup does not contain sessions.db"));
+        }
+        let backup_key = backup_key(&manifest, staging, key, passphrase)?;
+
+        for database in &manifest.databases {
+            let migrations = match database.name.as_str() {
+                "sessions.db" => SESSION_MIGRATIONS,
+                "history.db" => HISTORY_MIGRATIONS,
+                other => return Err(anyhow::anyhow!("Unexpected database in backup: {}", other)),
+            };
+            let path = staging.join(&database.name);
+            let key = database_key_for(&database.name, backup_key.as_deref());
+            if key.is_some_and(|key| !opens_with(&path, key)) {
+                return Err(anyhow::anyhow!(
+                    "{} in the backup can't be opened with its key",
+                    database.name
+                ));
+            }
+            let mut conn = open_database(&path, OpenFlags::default(), key)?;
+            check_database(&conn, &database.name, migrations)?;
+            // Bring older backups up to this build's schema before they go live
+            Migrations::new(migrations.to_vec()).to_latest(&mut conn)?;
+            if database.name == "sessions.db" {
+                self.relocate_attachments(&conn)?;
+            }
+        }
+
+        // Bring sessions.db and the files into the layout of the live storage
+        let same_key = match (&backup_key, key) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        };
+        if !same_key {
+            debug!("Converting backup to the current storage encryption");
+            encryption::convert_database(
+                &staging.join("sessions.db"),
+                backup_key.as_deref(),
+                key.map(Arc::as_ref),
+            )?;
+            for dir in FILE_DIRS {
+                convert_tree(
+                    &staging.join(dir),
+                    backup_key.as_deref(),
+                    key.map(Arc::as_ref),
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Attachment rows store absolute paths; point them at this machine's app data
+    fn relocate_attachments(&self, conn: &Connection) -> Result<()> {
+        let attachments_dir = self.files_dir.join("attachments");
+        let mut stmt = conn.prepare("SELECT id, session_id, file_path FROM session_attachments")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>("id")?,
+                row.get::<_, String>("session_id")?,
+                row.get::<_, String>("file_path")?,
+            ))
+        })?;
+        let mut attachments = Vec::new();
+        for row in rows {
+            attachments.push(row?);
+        }
+
+        for (id, session_id, file_path) in attachments {
+            let Some(file_name) = Path::new(&file_path).file_name() else {
+                continue;
+            };
+            let relocated = attachments_dir.join(&session_id).join(file_name);
+            conn.execute(
+                "UPDATE session_attachments SET file_path = ?1 WHERE id = ?2",
+                params![relocated.to_string_lossy().to_string(), id],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Put the staged databases and trees in place of the live ones. The live
+    /// data is kept under `staging/previous` until everything is in, and put
+    /// back if any step fails, so a failed restore leaves the data as it was.
+    fn swap_in(&self, staging: &Path, key: Option<&StorageKey>) -> Result<()> {
+        let previous = staging.join("previous");
+        fs::create_dir_all(&previous)?;
+
+        let databases: Vec<&str> = DATABASES
+            .into_iter()
+            .filter(|name| staging.join(name).exists())
+            .collect();
+        for name in &databases {
+            let live = self.db_dir.join(name);
+            if live.exists() {
+                let key = database_key_for(name, key);
+                copy_database_file(&live, &previous.join(name), key)?;
+            }
+        }
+
+        let mut swapped_dirs = Vec::new();
+        let result = self.swap_in_steps(staging, &databases, key, &mut swapped_dirs);
+        if let Err(e) = result {
+            warn!("Restore failed, rolling back: {}", e);
+            for dir in swapped_dirs.iter().rev() {
+                if let Err(e) = self.unswap_tree(staging, dir) {
+                    error!("Failed to roll back {}: {}", dir, e);
+                }
+            }
+            for name in &databases {
+                let snapshot = previous.join(name);
+                if !snapshot.exists() {
+                    continue;
+                }
+                let key = database_key_for(name, key);
+                if let Err(e) = copy_database_file(&snapshot, &self.db_dir.join(name), key) {
+                    error!("Failed to roll back {}: {}", name, e);
+                }
+            }
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Swap in the trees first, since renames are cheap to undo, then the databases
+    fn swap_in_steps(
+        &self,
+        staging: &Path,
+        databases: &[&str],
+        key: Option<&StorageKey>,
+        swapped_dirs: &mut Vec<&'static str>,
+    ) -> Result<()> {
+        let previous = staging.join("previous");
+        for dir in FILE_DIRS {
+            let live = self.files_dir.join(dir);
+            if live.exists() {
+                fs::rename(&live, previous.join(dir))?;
+            }
+            swapped_dirs.push(dir);
+            let staged = staging.join(dir);
+            if staged.exists() {
+                fs::rename(&staged, &live)?;
+            } else {
+                fs::create_dir_all(&live)?;
+            }
+        }
+
+        for name in databases {
+            let key = database_key_for(name, key);
+            copy_database_file(&staging.join(name), &self.db_dir.join(name), key)?;
+            debug!("Restored {}", name);
+        }
+        Ok(())
+    }
+
+    /// Move a restored tree back out and the previous one back in
+    fn unswap_tree(&self, staging: &Path, dir: &str) -> Result<()> {
+        let live = self.files_dir.join(dir);
+        let previous = staging.join("previous").join(dir);
+        if live.exists() {
+            fs::rename(&live, staging.join(dir))?;
+        }
+        if previous.exists() {
+            fs::rename(&previous, &live)?;
+        }
+        Ok(())
+    }
+}
+
+/// List the backups in `dir`, newest first
+fn list_backups(dir: &Path) -> Result<Vec<BackupInfo>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut backups = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let is_backup = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(is_backup_file_name);
+        if is_backup && path.is_file() {
+            backups.push(backup_info(&path)?);
+        }
+    }
+    backups.sort_by(|a, b| b.file_name.cmp(&a.file_name));
+    Ok(backups)
+}
+
+/// Delete all but the newest `keep` backups in `dir` (0 keeps everything)
+fn rotate_backups(dir: &Path, keep: usize) -> Result<usize> {
+    let names = list_backups(dir)?
+        .into_iter()
+        .map(|b| b.file_name)
+        .collect();
+    let expired = expired_backups(names, keep);
+    for name in &expired {
+        if let Err(e) = fs::remove_file(dir.join(name)) {
+            warn!("Failed to delete old backup {}: {}", name, e);
+        }
+    }
+    if !expired.is_empty() {
+        debug!("Rotated out {} old backups", expired.len());
+    }
+    Ok(expired.len())
+}
+
+/// Only sessions.db is encrypted; history.db holds no meeting content
+fn database_key_for<'a>(name: &str, key: Option<&'a StorageKey>) -> Option<&'a StorageKey> {
+    key.filter(|_| name == "sessions.db")
+}
+
+/// Key the backup's sessions.db and files are encrypted with, None when
+/// it is plaintext. A backup of this storage opens with the current key; any
+/// other needs the passphrase it was taken under, checked against the key file
+/// stored in the archive.
+fn backup_key(
+    manifest: &BackupManifest,
+    staging: &Path,
+    current: Option<&Arc<StorageKey>>,
+    passphrase: Option<&str>,
+) -> Result<Option<Arc<StorageKey>>> {
+    if !manifest.encrypted {
+        return Ok(None);
+    }
+    if let Some(current) = current {
+        if opens_with(&staging.join("sessions.db"), current) {
+            return Ok(Some(current.clone()));
+        }
+    }
+    let key_file = KeyFile::load(staging)?.ok_or_else(|| {
+        anyhow::anyhow!(
+            "This encrypted backup has no {} and can only be restored into the storage it came from",
+            KEY_FILE_NAME
+        )
+    })?;
+    let passphrase = passphrase
+        .ok_or_else(|| anyhow::anyhow!("Enter the passphrase this backup was encrypted with"))?;
+    Ok(Some(Arc::new(key_file.unlock(passphrase)?)))
+}
+
+/// Whether `key` opens the database at `path`
+fn opens_with(path: &Path, key: &StorageKey) -> bool {
+    open_database(path, OpenFlags::SQLITE_OPEN_READ_ONLY, Some(key))
+        .and_then(|conn| {
+            conn.query_row("SELECT COUNT(*) FROM sqlite_master", [], |_| Ok(()))?;
+            Ok(())
+        })
+        .is_ok()
+}
+
+/// Re-encrypt every file under `dir` from one key to another (None = plaintext)
+fn convert_tree(dir: &Path, from: Option<&StorageKey>, to: Option<&StorageKey>) -> Result<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            convert_tree(&path, from, to)?;
+            continue;
+        }
+        if let Some(from) = from {
+            encryption::decrypt_file_in_place(&path, from)?;
+        }
+        if let Some(to) = to {
+            encryption::encrypt_file_in_place(&path, to)?;
+        }
+    }
+    Ok(())
+}
+
+fn copy_database_file(src: &Path, dst: &Path, key: Option<&StorageKey>) -> Result<()> {
+    let src = open_database(src, OpenFlags::SQLITE_OPEN_READ_ONLY, key)?;
+    let mut dst = open_database(dst, OpenFlags::default(), key)?;
+    copy_database(&src, &mut dst)
+}
+
+/// Copy a whole database in one step, so the copy reflects a single read
+/// transaction. An incremental backup restarts every time the app writes.
+fn copy_database(src: &Connection, dst: &mut Connection) -> Result<()> {
+    let backup = Backup::new(src, dst)?;
+    for _ in 0..BUSY_RETRIES {
+        match backup.step(-1)? {
+            StepResult::Done => return Ok(()),
+            _ => thread::sleep(Duration::from_millis(200)),
+        }
+    }
+    Err(anyhow::anyhow!("Database stayed locked, try again later"))
+}
+
+fn schema_version(conn: &Connection) -> Result<i64> {
+    Ok(conn.pragma_query_value(None, "user_version", |row| row.get(0))?)
+}
+
+fn check_database(conn: &Connection, name: &str, migrations: &[M]) -> Result<()> {
+    let integrity: String = conn.query_row("PRAGMA integrity_check", [], |row| row.get(0))?;
+    if integrity != "ok" {
+        return Err(anyhow::anyhow!(
+            "{} in the backup is damaged: {}",
+            name,
+            integrity
+        ));
+    }
+    let version = schema_version(conn)?;
+    if version > migrations.len() as i64 {
+        return Err(anyhow::anyhow!(
+            "{} in the backup was made by a newer version of Talky",
+            name
+        ));
+    }
+    Ok(())
+}
+
+/// Append every file under `dir` to the archive below `prefix`.
+/// Files are read up to the size seen when their header was written, so audio
+/// still being recorded can't grow past its entry.
+fn append_tree<W: std::io::Write>(
+    builder: &mut Builder<W>,
+    dir: &Path,
+    prefix: &Path,
+) -> Result<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let name = prefix.join(entry.file_name());
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            append_tree(builder, &path, &name)?;
+        } else if file_type.is_file() {
+            let file = File::open(&path)?;
+            let metadata = file.metadata()?;
+            let mut header = Header::new_gnu();
+            header.set_metadata(&metadata);
+            header.set_cksum();
+            builder.append_data(&mut header, &name, file.take(metadata.len()))?;
+        }
+    }
+    Ok(())
+}
+
+fn parse_manifest(json: &str) -> Result<BackupManifest> {
+    let value: serde_json::Value = serde_json::from_str(json)?;
+    if value.get("format").and_then(|f| f.as_str()) != Some(BACKUP_FORMAT) {
+        return Err(anyhow::anyhow!("Not a Talky backup"));
+    }
+    let version = value.get("version").and_then(|v| v.as_u64()).unwrap_or(0);
+    if version == 0 || version > BACKUP_VERSION as u64 {
+        return Err(anyhow::anyhow!(
+            "Backup format version {} is not supported by this version of Talky",
+            version
+        ));
+    }
+    Ok(serde_json::from_value(value)?)
+}
+
+fn is_backup_file_name(name: &str) -> bool {
+    name.starts_with(BACKUP_PREFIX) && name.ends_with(BACKUP_SUFFIX)
+}
+
+fn backup_info(path: &Path) -> Result<BackupInfo> {
+    let metadata = fs::metadata(path)?;
+    let created_at = metadata
+        .modified()
+        .unwrap_or(SystemTime::UNIX_EPOCH)
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64;
+    Ok(BackupInfo {
+        file_name: path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        file_path: path.to_string_lossy().to_string(),
+        size_bytes: metadata.len() as i64,
+        created_at,
+    })
+}
+
+/// Backup names embed their timestamp, so the newest sort last
+fn expired_backups(mut names: Vec<String>, keep: usize) -> Vec<String> {
+    if keep == 0 || names.len() <= keep {
+        return Vec::new();
+    }
+    names.sort();
+    names.truncate(names.len() - keep);
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_dirs() -> DataDirs {
+        let root = std::env::temp_dir().join(format!("talky-backup-test-{}", Uuid::new_v4()));
+        let dirs = DataDirs {
+            db_dir: root.join("data"),
+            files_dir: root.join("app"),
+        };
+        fs::create_dir_all(&dirs.db_dir).unwrap();
+        fs::create_dir_all(&dirs.files_dir).unwrap();
+        dirs
+    }
+
+    fn remove_dirs(dirs: &DataDirs) {
+        let _ = fs::remove_dir_all(dirs.db_dir.parent().unwrap());
+    }
+
+    /// A session with an attachment and a recording
+    fn seed(dirs: &DataDirs) {
+        let mut conn = Connection::open(dirs.db_dir.join("sessions.db")).unwrap();
+        Migrations::new(SESSION_MIGRATIONS.to_vec())
+            .to_latest(&mut conn)
+            .unwrap();
+        let attachment = dirs
+            .files_dir
+            .join("attachments")
+            .join("call")
+            .join("agenda.txt");
+        fs::create_dir_all(attachment.parent().unwrap()).unwrap();
+        fs::write(&attachment, "agenda").unwrap();
+        conn.execute(
+            "INSERT INTO sessions (id, title, started_at) VALUES ('call', 'Call', 0)",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO session_attachments (id, session_id, filename, file_path, mime_type, file_size, created_at)
+             VALUES ('agenda', 'call', 'agenda.txt', ?1, 'text/plain', 6, 0)",
+            params![attachment.to_string_lossy().to_string()],
+        )
+        .unwrap();
+        fs::create_dir_all(dirs.files_dir.join("recordings")).unwrap();
+        fs::write(dirs.files_dir.join("recordings").join("call.wav"), "audio").unwrap();
+    }
+
+    fn session_titles(dirs: &DataDirs) -> Vec<String> {
+        let conn = Connection::open(dirs.db_dir.join("sessions.db")).unwrap();
+        let mut stmt = conn
+            .prepare("SELECT title FROM sessions ORDER BY id")
+            .unwrap();
+        let titles = stmt
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        titles
+    }
+
+    fn attachment_path(dirs: &DataDirs) -> String {
+        let conn = Connection::open(dirs.db_dir.join("sessions.db")).unwrap();
+        conn.query_row(
+            "SELECT file_path FROM session_attachments WHERE id = 'agenda'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap()
+    }
+
+    fn file_names(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    fn write_archive(path: &Path, append: impl FnOnce(&mut Builder<GzEncoder<File>>)) {
+        let mut builder = Builder::new(GzEncoder::new(
+            File::create(path).unwrap(),
+            Compression::default(),
+        ));
+        let manifest = serde_json::to_vec(&BackupManifest {
+            format: BACKUP_FORMAT.to_string(),
+            version: BACKUP_VERSION,
+            created_at: 0,
+            app_version: "0.1.0".to_string(),
+            databases: vec![BackupDatabase {
+                name: "sessions.db".to_string(),
+                schema_version: SESSION_MIGRATIONS.len() as i64,
+            }],
+            encrypted: false,
+        })
+        .unwrap();
+        let mut header = Header::new_gnu();
+        header.set_size(manifest.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, MANIFEST_NAME, manifest.as_slice())
+            .unwrap();
+        append(&mut builder);
+        builder.into_inner().unwrap().finish().unwrap();
+    }
+
+    #[test]
+    fn test_backup_round_trip() {
+        let dirs = test_dirs();
+        seed(&dirs);
+        let backups_dir = dirs.files_dir.parent().unwrap().join("backups");
+        let backup = dirs.create_backup(&backups_dir, None, "0.1.0").unwrap();
+        assert!(is_backup_file_name(&backup.file_name));
+        assert_eq!(file_names(&backups_dir), vec![backup.file_name.clone()]);
+
+        // Change everything the backup holds, then restore over it
+        let conn = Connection::open(dirs.db_dir.join("sessions.db")).unwrap();
+        conn.execute_batch(
+            "UPDATE sessions SET title = 'Renamed';
+             INSERT INTO sessions (id, title, started_at) VALUES ('later', 'Later', 1);",
+        )
+        .unwrap();
+        drop(conn);
+        fs::write(attachment_path(&dirs), "changed").unwrap();
+        fs::write(dirs.files_dir.join("recordings").join("later.wav"), "audio").unwrap();
+
+        let archive = PathBuf::from(&backup.file_path);
+        dirs.restore_backup(&archive, None, None).unwrap();
+        assert_eq!(session_titles(&dirs), vec!["Call"]);
+        assert_eq!(
+            fs::read_to_string(attachment_path(&dirs)).unwrap(),
+            "agenda"
+        );
+        assert_eq!(
+            file_names(&dirs.files_dir.join("recordings")),
+            vec!["call.wav"]
+        );
+        assert_eq!(
+            file_names(&dirs.files_dir),
+            vec!["attachments", "recordings"]
+        );
+
+        // On another machine attachment rows point at its own app data
+        let other = test_dirs();
+        other.restore_backup(&archive, None, None).unwrap();
+        assert_eq!(session_titles(&other), vec!["Call"]);
+        let path = attachment_path(&other);
+        assert!(Path::new(&path).starts_with(&other.files_dir));
+        assert_eq!(fs::read_to_string(path).unwrap(), "agenda");
+        assert_eq!(
+            fs::read_to_string(other.files_dir.join("recordings").join("call.wav")).unwrap(),
+            "audio"
+        );
+
+        remove_dirs(&dirs);
+        remove_dirs(&other);
+    }
+
+    #[test]
+    fn test_rotate_backups_keeps_newest_at_limit() {
+        let dirs = test_dirs();
+        let dir = dirs.files_dir.join("backups");
+        fs::create_dir_all(&dir).unwrap();
+        for name in [
+            "talky-backup-20260101-090000.tar.gz",
+            "talky-backup-20260102-090000.tar.gz",
+            "talky-backup-20260103-090000.tar.gz",
+            "talky-backup-20260104-090000.tar.gz.partial",
+            "notes.txt",
+        ] {
+            fs::write(dir.join(name), "").unwrap();
+        }
+
+        assert_eq!(rotate_backups(&dir, 3).unwrap(), 0);
+        assert_eq!(rotate_backups(&dir, 2).unwrap(), 1);
+        let kept: Vec<String> = list_backups(&dir)
+            .unwrap()
+            .into_iter()
+            .map(|b| b.file_name)
+            .collect();
+        assert_eq!(
+            kept,
+            vec![
+                "talky-backup-20260103-090000.tar.gz",
+                "talky-backup-20260102-090000.tar.gz",
+            ]
+        );
+        // Only finished backups are counted or deleted
+        assert!(dir
+            .join("talky-backup-20260104-090000.tar.gz.partial")
+            .exists());
+        assert!(dir.join("notes.txt").exists());
+        assert_eq!(rotate_backups(&dir, 2).unwrap(), 0);
+
+        remove_dirs(&dirs);
+    }
+
+    #[test]
+    fn test_restore_rejects_bad_archives_and_keeps_live_data() {
+        let dirs = test_dirs();
+        seed(&dirs);
+        let archives = dirs.files_dir.parent().unwrap().join("archives");
+        fs::create_dir_all(&archives).unwrap();
+
+        let garbage = archives.join("garbage.tar.gz");
+        fs::write(&garbage, "not a backup").unwrap();
+
+        let linked = archives.join("linked.tar.gz");
+        write_archive(&linked, |builder| {
+            let mut header = Header::new_gnu();
+            header.set_entry_type(tar::EntryType::Symlink);
+            header.set_size(0);
+            header.set_mode(0o644);
+            builder
+                .append_link(&mut header, "attachments/call/agenda.txt", "/etc/passwd")
+                .unwrap();
+        });
+
+        let damaged = archives.join("damaged.tar.gz");
+        write_archive(&damaged, |builder| {
+            let data = vec![0xAB; 8192];
+            let mut header = Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, "sessions.db", data.as_slice())
+                .unwrap();
+        });
+
+        for archive in [&garbage, &linked, &damaged] {
+            assert!(
+                dirs.restore_backup(archive, None, None).is_err(),
+                "{:?} was restored",
+                archive
+            );
+            assert_eq!(session_titles(&dirs), vec!["Call"]);
+            assert_eq!(
+                fs::read_to_string(attachment_path(&dirs)).unwrap(),
+                "agenda"
+            );
+            assert_eq!(
+                file_names(&dirs.files_dir.join("recordings")),
+                vec!["call.wav"]
+            );
+            assert_eq!(
+                file_names(&dirs.files_dir),
+                vec!["attachments", "recordings"]
+            );
+        }
+
+        remove_dirs(&dirs);
+    }
+
+    #[test]
+    fn test_expired_backups_keeps_newest() {
+        let names = vec![
+            "talky-backup-20260103-090000.tar.gz".to_string(),
+            "talky-backup-20260101-090000.tar.gz".to_string(),
+            "talky-backup-20260102-090000.tar.gz".to_string(),
+        ];
+        assert_eq!(
+            expired_backups(names.clone(), 2),
+            vec!["talky-backup-20260101-090000.tar.gz".to_string()]
+        );
+        assert!(expired_backups(names.clone(), 3).is_empty());
+        assert!(expired_backups(names, 0).is_empty());
+    }
+
+    #[test]
+    fn test_parse_manifest_rejects_unknown_formats() {
+        assert!(parse_manifest(r#"{"format":"talky-session","version":1}"#).is_err());
+        assert!(parse_manifest(
+            r#"{"format":"talky-backup","version":99,"created_at":0,"app_version":"","databases":[]}"#
+        )
+        .is_err());
+        let manifest = parse_manifest(
+            r#"{"format":"talky-backup","version":1,"created_at":0,"app_version":"0.1.0",
+                "databases":[{"name":"sessions.db","schema_version":36}]}"#,
+        )
+        .unwrap();
+        assert_eq!(manifest.databases[0].schema_version, 36);
+    }
+}
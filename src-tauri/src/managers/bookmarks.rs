@@ -0,0 +1,227 @@
+use crate::managers::bundle::BundleBookmark;
+use crate::managers::session::{SessionManager, TranscriptSegment};
+use anyhow::Result;
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::Emitter;
+use uuid::Uuid;
+
+/// A moment of a session the user marked as important. `offset_ms` is on the
+/// session timeline, like a transcript segment's `start_ms`.
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct Bookmark {
+    pub id: String,
+    pub session_id: String,
+    pub offset_ms: i64,
+    pub label: Option<String>,
+    pub color: Option<String>,
+    pub created_at: i64,
+}
+
+/// A session's transcript with its bookmarks, both in timeline order
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct SessionTimeline {
+    pub segments: Vec<TranscriptSegment>,
+    pub bookmarks: Vec<Bookmark>,
+}
+
+impl SessionManager {
+    /// Bookmark a moment of a session. Without `offset_ms` the bookmark is put
+    /// at the current position of the recording, which must be running.
+    pub fn create_bookmark(
+        &self,
+        session_id: &str,
+        offset_ms: Option<i64>,
+        label: Option<String>,
+        color: Option<String>,
+    ) -> Result<Bookmark> {
+        let offset_ms = match offset_ms {
+            Some(offset_ms) => offset_ms.max(0),
+            None => self
+                .current_session_time_ms(session_id)
+                .ok_or_else(|| anyhow::anyhow!("Session is not being recorded"))?,
+        };
+        let id = Uuid::new_v4().to_string();
+        let conn = self.get_connection()?;
+        conn.execute(
+            "INSERT INTO bookmarks (id, session_id, offset_ms, label, color, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                id,
+                session_id,
+                offset_ms,
+                bookmark_label(label),
+                color,
+                Utc::now().timestamp()
+            ],
+        )?;
+        let bookmark = self.get_bookmark(&id)?;
+
+        let _ = self.app_handle.emit("bookmark-added", &bookmark);
+        Ok(bookmark)
+    }
+
+    fn get_bookmark(&self, bookmark_id: &str) -> Result<Bookmark> {
+        let conn = self.get_connection()?;
+        conn.query_row(
+            "SELECT id, session_id, offset_ms, label, color, created_at FROM bookmarks WHERE id = ?1",
+            params![bookmark_id],
+            map_bookmark,
+        )
+        .optional()?
+        .ok_or_else(|| anyhow::anyhow!("Bookmark not found: {}", bookmark_id))
+    }
+
+    pub fn update_bookmark(
+        &self,
+        bookmark_id: &str,
+        label: Option<String>,
+        color: Option<String>,
+    ) -> Result<Bookmark> {
+        let conn = self.get_connection()?;
+        let updated = conn.execute(
+            "UPDATE bookmarks SET label = ?1, color = ?2 WHERE id = ?3",
+            params![bookmark_label(label), color, bookmark_id],
+        )?;
+        if updated == 0 {
+            return Err(anyhow::anyhow!("Bookmark not found: {}", bookmark_id));
+        }
+        self.get_bookmark(bookmark_id)
+    }
+
+    pub fn delete_bookmark(&self, bookmark_id: &str) -> Result<()> {
+        let conn = self.get_connection()?;
+        conn.execute("DELETE FROM bookmarks WHERE id = ?1", params![bookmark_id])?;
+        Ok(())
+    }
+
+    pub fn get_session_bookmarks(&self, session_id: &str) -> Result<Vec<Bookmark>> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare_cached(
+            "SELECT id, session_id, offset_ms, label, color, created_at FROM bookmarks
+             WHERE session_id = ?1
+             ORDER BY offset_ms, created_at",
+        )?;
+        let rows = stmt.query_map(params![session_id], map_bookmark)?;
+        let mut bookmarks = Vec::new();
+        for row in rows {
+            bookmarks.push(row?);
+        }
+        Ok(bookmarks)
+    }
+
+    pub fn get_session_timeline(&self, session_id: &str) -> Result<SessionTimeline> {
+        Ok(SessionTimeline {
+            segments: self.get_session_transcript(session_id)?,
+            bookmarks: self.get_session_bookmarks(session_id)?,
+        })
+    }
+}
+
+/// A session's bookmarks for a bundle, in timeline order
+pub(crate) fn bundle_bookmarks(conn: &Connection, session_id: &str) -> Result<Vec<BundleBookmark>> {
+    let mut stmt = conn.prepare(
+        "SELECT offset_ms, label, color, created_at FROM bookmarks
+         WHERE session_id = ?1
+         ORDER BY offset_ms, created_at",
+    )?;
+    let rows = stmt.query_map(params![session_id], |row| {
+        Ok(BundleBookmark {
+            offset_ms: row.get("offset_ms")?,
+            label: row.get("label")?,
+            color: row.get("color")?,
+            created_at: row.get("created_at")?,
+        })
+    })?;
+    let mut bookmarks = Vec::new();
+    for row in rows {
+        bookmarks.push(row?);
+    }
+    Ok(bookmarks)
+}
+
+pub(crate) fn insert_bundle_bookmarks(
+    conn: &Connection,
+    session_id: &str,
+    bookmarks: &[BundleBookmark],
+) -> Result<()> {
+    for bookmark in bookmarks {
+        conn.execute(
+            "INSERT INTO bookmarks (id, session_id, offset_ms, label, color, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                Uuid::new_v4().to_string(),
+                session_id,
+                bookmark.offset_ms.max(0),
+                bookmark_label(bookmark.label.clone()),
+                bookmark.color,
+                bookmark.created_at
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+fn map_bookmark(row: &rusqlite::Row) -> rusqlite::Result<Bookmark> {
+    Ok(Bookmark {
+        id: row.get("id")?,
+        session_id: row.get("session_id")?,
+        offset_ms: row.get("offset_ms")?,
+        label: row.get("label")?,
+        color: row.get("color")?,
+        created_at: row.get("created_at")?,
+    })
+}
+
+/// Trimmed bookmark label; blank labels are stored as none
+fn bookmark_label(label: Option<String>) -> Option<String> {
+    label
+        .map(|label| label.trim().to_string())
+        .filter(|label| !label.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::managers::session::test_connection;
+
+    #[test]
+    fn test_bundle_bookmarks_round_trip() {
+        let conn = test_connection();
+        conn.execute_batch(
+            "INSERT INTO sessions (id, title, started_at) VALUES ('call', 'Call', 0), ('copy', 'Copy', 0);
+             INSERT INTO bookmarks (id, session_id, offset_ms, label, color, created_at) VALUES
+                ('late', 'call', 90000, NULL, NULL, 1),
+                ('early', 'call', 1500, 'Decision', '#f59e0b', 2);",
+        )
+        .unwrap();
+
+        let bookmarks = bundle_bookmarks(&conn, "call").unwrap();
+        insert_bundle_bookmarks(&conn, "copy", &bookmarks).unwrap();
+
+        let mut stmt = conn
+            .prepare(
+                "SELECT offset_ms, label, color FROM bookmarks
+                 WHERE session_id = 'copy' ORDER BY offset_ms",
+            )
+            .unwrap();
+        let imported: Vec<(i64, Option<String>, Option<String>)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert_eq!(
+            imported,
+            vec![
+                (
+                    1500,
+                    Some("Decision".to_string()),
+                    Some("#f59e0b".to_string())
+                ),
+                (90000, None, None),
+            ]
+        );
+    }
+}
@@ -0,0 +1,325 @@
+use anyhow::Result;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use tar::{Archive, Builder, Header};
+
+/// `.talky` session bundles: a gzipped tar with `session.json` at the root and
+/// the session's attachment files and archived audio next to it. IDs are not
+/// part of the format; everything is re-keyed when a bundle is imported.
+pub const BUNDLE_FORMAT: &str = "talky-session";
+/// Bump when the layout of `SessionBundle` changes in a way older builds can't read.
+/// Version 2 added action items and bookmarks; version 1 bundles import without them.
+pub const BUNDLE_VERSION: u32 = 2;
+pub const BUNDLE_EXTENSION: &str = "talky";
+const MANIFEST_NAME: &str = "session.json";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SessionBundle {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: i64,
+    pub session: BundleSession,
+    pub folder: Option<BundleFolder>,
+    #[serde(default)]
+    pub tags: Vec<BundleTag>,
+    #[serde(default)]
+    pub segments: Vec<BundleSegment>,
+    #[serde(default)]
+    pub speakers: Vec<BundleSpeaker>,
+    pub notes: Option<BundleNotes>,
+    #[serde(default)]
+    pub attachments: Vec<BundleAttachment>,
+    #[serde(default)]
+    pub recordings: Vec<BundleRecording>,
+    #[serde(default)]
+    pub action_items: Vec<BundleActionItem>,
+    #[serde(default)]
+    pub bookmarks: Vec<BundleBookmark>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BundleSession {
+    pub title: String,
+    pub started_at: i64,
+    pub ended_at: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BundleFolder {
+    pub name: String,
+    pub color: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BundleTag {
+    pub name: String,
+    pub color: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BundleSegment {
+    pub text: String,
+    pub source: String,
+    pub start_ms: i64,
+    pub end_ms: i64,
+    pub created_at: i64,
+    pub speaker: Option<String>,
+    #[serde(default)]
+    pub words: Vec<BundleWord>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BundleWord {
+    pub text: String,
+    pub start_ms: i64,
+    pub end_ms: i64,
+    /// Spread over the segment rather than timed by the engine
+    #[serde(default)]
+    pub estimated: bool,
+}
+
+/// Diarized speaker with its voice centroid, so recording can continue
+/// into an imported session with consistent labels
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BundleSpeaker {
+    pub label: String,
+    pub name: Option<String>,
+    pub centroid: Vec<f32>,
+    pub segment_count: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BundleNotes {
+    pub summary: Option<String>,
+    pub action_items: Option<String>,
+    pub decisions: Option<String>,
+    pub user_notes: Option<String>,
+    pub enhanced_notes: Option<String>,
+    pub enhanced_notes_edited: bool,
+}
+
+/// `path` is the file's location inside the bundle
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BundleAttachment {
+    pub filename: String,
+    pub mime_type: String,
+    pub extracted_text: Option<String>,
+    pub created_at: i64,
+    pub path: String,
+}
+
+/// `path` is the file's location inside the bundle
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BundleRecording {
+    pub channel: String,
+    pub offset_ms: i64,
+    pub duration_ms: i64,
+    pub created_at: i64,
+    pub path: String,
+}
+
+/// `source_segment` is the position of the item's source in `segments`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BundleActionItem {
+    pub text: String,
+    pub owner: Option<String>,
+    pub due_date: Option<String>,
+    pub status: String,
+    pub source_segment: Option<usize>,
+    pub edited: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BundleBookmark {
+    pub offset_ms: i64,
+    pub label: Option<String>,
+    pub color: Option<String>,
+    pub created_at: i64,
+}
+
+/// Write a bundle to `dest`. `files` maps paths inside the bundle to files on disk.
+pub fn write_bundle(
+    dest: &Path,
+    bundle: &SessionBundle,
+    files: &[(String, PathBuf)],
+) -> Result<()> {
+    let encoder = GzEncoder::new(File::create(dest)?, Compression::default());
+    let mut builder = Builder::new(encoder);
+
+    let manifest = serde_json::to_vec_pretty(bundle)?;
+    let mut header = Header::new_gnu();
+    header.set_size(manifest.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(bundle.exported_at.max(0) as u64);
+    header.set_cksum();
+    builder.append_data(&mut header, MANIFEST_NAME, manifest.as_slice())?;
+
+    for (name, source) in files {
+        builder.append_path_with_name(source, name)?;
+    }
+
+    builder.into_inner()?.finish()?;
+    Ok(())
+}
+
+/// Unpack a bundle into `dir` and read its manifest
+pub fn unpack_bundle(src: &Path, dir: &Path) -> Result<SessionBundle> {
+    let mut archive = Archive::new(GzDecoder::new(File::open(src)?));
+    unpack_regular(&mut archive, dir)
+        .map_err(|e| anyhow::anyhow!("Not a valid session bundle: {}", e))?;
+
+    let manifest = fs::read_to_string(dir.join(MANIFEST_NAME))
+        .map_err(|_| anyhow::anyhow!("Session bundle is missing {}", MANIFEST_NAME))?;
+    parse_manifest(&manifest)
+}
+
+/// Unpack an archive that may only hold regular files and directories.
+/// `Archive::unpack` would recreate symlinks and hardlinks, which later
+/// entries or readers could follow out of `dir`, so any other entry type
+/// rejects the whole archive.
+pub fn unpack_regular<R: Read>(archive: &mut Archive<R>, dir: &Path) -> Result<()> {
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let entry_type = entry.header().entry_type();
+        if !entry_type.is_file() && !entry_type.is_dir() {
+            return Err(anyhow::anyhow!(
+                "unsupported entry {} ({:?})",
+                entry.path()?.display(),
+                entry_type
+            ));
+        }
+        // `unpack_in` skips entries that would land outside `dir`
+        entry.unpack_in(dir)?;
+    }
+    Ok(())
+}
+
+fn parse_manifest(json: &str) -> Result<SessionBundle> {
+    let value: serde_json::Value = serde_json::from_str(json)?;
+    if value.get("format").and_then(|f| f.as_str()) != Some(BUNDLE_FORMAT) {
+        return Err(anyhow::anyhow!("Not a Talky session bundle"));
+    }
+    let version = value.get("version").and_then(|v| v.as_u64()).unwrap_or(0);
+    if version == 0 || version > BUNDLE_VERSION as u64 {
+        return Err(anyhow::anyhow!(
+            "Session bundle version {} is not supported by this version of Talky",
+            version
+        ));
+    }
+    Ok(serde_json::from_value(value)?)
+}
+
+/// Resolve a path from the manifest inside the unpacked bundle, refusing
+/// anything that points outside of it
+pub fn bundle_file(dir: &Path, path: &str) -> Result<PathBuf> {
+    let relative = Path::new(path);
+    if !relative
+        .components()
+        .all(|c| matches!(c, Component::Normal(_)))
+    {
+        return Err(anyhow::anyhow!("Invalid path in session bundle: {}", path));
+    }
+    let full = dir.join(relative);
+    // Don't follow links, even though `unpack_regular` never creates any
+    match fs::symlink_metadata(&full) {
+        Ok(metadata) if metadata.file_type().is_file() => Ok(full),
+        _ => Err(anyhow::anyhow!("Session bundle is missing {}", path)),
+    }
+}
+
+/// Reduce a stored file name to its last component, so a name containing
+/// separators can't steer where a file lands inside the bundle or app data
+pub fn sanitize_bundle_name(name: &str) -> String {
+    let name = name.rsplit(['/', '\\']).next().unwrap_or_default();
+    match name {
+        "" | "." | ".." => "file".to_string(),
+        name => name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_manifest_checks_format_and_version() {
+        let manifest = |format: &str, version: u32| {
+            format!(
+                r#"{{"format":"{}","version":{},"exported_at":0,
+                    "session":{{"title":"Standup","started_at":0,"ended_at":null}},
+                    "folder":null,"notes":null}}"#,
+                format, version
+            )
+        };
+
+        let bundle = parse_manifest(&manifest(BUNDLE_FORMAT, BUNDLE_VERSION)).unwrap();
+        assert_eq!(bundle.session.title, "Standup");
+        assert!(bundle.segments.is_empty());
+
+        // Version 1 bundles predate action items and bookmarks
+        let bundle = parse_manifest(&manifest(BUNDLE_FORMAT, 1)).unwrap();
+        assert!(bundle.action_items.is_empty());
+        assert!(bundle.bookmarks.is_empty());
+
+        assert!(parse_manifest(&manifest("something-else", 1)).is_err());
+        assert!(parse_manifest(&manifest(BUNDLE_FORMAT, BUNDLE_VERSION + 1)).is_err());
+    }
+
+    #[test]
+    fn test_bundle_file_rejects_escaping_paths() {
+        let dir = std::env::temp_dir();
+        assert!(bundle_file(&dir, "../etc/passwd").is_err());
+        assert!(bundle_file(&dir, "/etc/passwd").is_err());
+    }
+
+    #[test]
+    fn test_unpack_regular_rejects_links() {
+        let dir = std::env::temp_dir().join(format!("talky-bundle-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let archive = |entry_type: tar::EntryType| {
+            let mut builder = Builder::new(Vec::new());
+            let mut header = Header::new_gnu();
+            header.set_entry_type(entry_type);
+            header.set_size(0);
+            header.set_mode(0o644);
+            if entry_type.is_file() {
+                builder
+                    .append_data(&mut header, "notes.txt", &[][..])
+                    .unwrap();
+            } else {
+                builder
+                    .append_link(&mut header, "notes.txt", "/etc/passwd")
+                    .unwrap();
+            }
+            builder.into_inner().unwrap()
+        };
+
+        for entry_type in [tar::EntryType::Symlink, tar::EntryType::Link] {
+            let bytes = archive(entry_type);
+            assert!(unpack_regular(&mut Archive::new(bytes.as_slice()), &dir).is_err());
+            assert!(fs::symlink_metadata(dir.join("notes.txt")).is_err());
+        }
+
+        let bytes = archive(tar::EntryType::Regular);
+        unpack_regular(&mut Archive::new(bytes.as_slice()), &dir).unwrap();
+        assert!(bundle_file(&dir, "notes.txt").is_ok());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_sanitize_bundle_name() {
+        assert_eq!(sanitize_bundle_name("notes.pdf"), "notes.pdf");
+        assert_eq!(sanitize_bundle_name("../../notes.pdf"), "notes.pdf");
+        assert_eq!(sanitize_bundle_name("C:\\temp\\notes.pdf"), "notes.pdf");
+        assert_eq!(sanitize_bundle_name(".."), "file");
+    }
+}
@@ -0,0 +1,1341 @@
+use crate::managers::session::{CalendarImportSummary, SessionManager};
+use crate::settings::get_settings;
+use crate::utils::MutexExt;
+use anyhow::Result;
+use chrono::{Datelike, Days, Local, NaiveDate, NaiveDateTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use log::{debug, error, info, warn};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, Weak};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Events are imported from a day ago to this many days ahead, so a long
+/// calendar history doesn't flood the session list with scheduled sessions
+const IMPORT_DAYS_BEHIND: i64 = 1;
+const IMPORT_DAYS_AHEAD: i64 = 30;
+/// How often the watcher checks the calendar folder for changed files
+const WATCH_POLL: Duration = Duration::from_secs(60);
+/// Unchanged files are still re-read this often, so recurring events keep
+/// filling the moving import window
+const RESYNC_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// Upper bound on the periods walked when expanding one recurrence rule
+const MAX_RECURRENCE_STEPS: u64 = 100_000;
+
+/// One occurrence of a calendar event
+#[derive(Clone, Debug, PartialEq)]
+pub struct CalendarEvent {
+    pub uid: String,
+    /// Empty for one-off events, the original start time of an occurrence of a
+    /// recurring one, so moved occurrences keep their identity
+    pub instance: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub location: Option<String>,
+    pub starts_at: i64,
+    pub ends_at: i64,
+    pub attendees: Vec<CalendarAttendee>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Type)]
+pub struct CalendarAttendee {
+    pub name: Option<String>,
+    pub email: Option<String>,
+}
+
+pub struct CalendarManager {
+    app_handle: AppHandle,
+    /// Modification time and last import of each file in the watched folder
+    synced: Mutex<HashMap<PathBuf, (SystemTime, Instant)>>,
+}
+
+impl CalendarManager {
+    pub fn new(app_handle: &AppHandle) -> Self {
+        Self {
+            app_handle: app_handle.clone(),
+            synced: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Start the thread that imports .ics files from the `calendar_folder`
+    /// setting as they change. It stops once the manager is dropped.
+    pub fn start_watcher(self: &Arc<Self>) {
+        let manager: Weak<Self> = Arc::downgrade(self);
+        thread::spawn(move || {
+            loop {
+                let Some(manager) = manager.upgrade() else {
+                    break;
+                };
+                if let Err(e) = manager.sync_folder(false) {
+                    error!("Calendar folder sync failed: {}", e);
+                }
+                drop(manager);
+                thread::sleep(WATCH_POLL);
+            }
+            debug!("Calendar watcher shutting down");
+        });
+    }
+
+    /// Import the events of one .ics file
+    pub fn import_file(&self, path: &Path) -> Result<CalendarImportSummary> {
+        let summary = self.import_path(path)?;
+        let _ = self.app_handle.emit("calendar-synced", &summary);
+        Ok(summary)
+    }
+
+    /// Import the .ics files in the calendar folder. Unless `force` is set, only
+    /// files that changed or haven't been read for a while are imported. Events
+    /// of files removed from the folder are removed too.
+    pub fn sync_folder(&self, force: bool) -> Result<CalendarImportSummary> {
+        let Some(dir) = get_settings(&self.app_handle)
+            .calendar_folder
+            .map(PathBuf::from)
+        else {
+            return Ok(CalendarImportSummary::default());
+        };
+
+        // Nothing can be written until the user unlocks encrypted storage
+        let session_manager = self.app_handle.state::<Arc<SessionManager>>();
+        if session_manager.get_storage_encryption_status().locked {
+            return Ok(CalendarImportSummary::default());
+        }
+
+        let mut summary = CalendarImportSummary::default();
+        let mut present = HashSet::new();
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if !path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("ics"))
+            {
+                continue;
+            }
+            present.insert(source_name(&path));
+
+            let modified = fs::metadata(&path)?.modified()?;
+            let due = force
+                || self
+                    .synced
+                    .lock_or_recover()
+                    .get(&path)
+                    .is_none_or(|(m, at)| *m != modified || at.elapsed() >= RESYNC_INTERVAL);
+            if !due {
+                continue;
+            }
+
+            match self.import_path(&path) {
+                Ok(file_summary) => {
+                    summary.merge(&file_summary);
+                    self.synced
+                        .lock_or_recover()
+                        .insert(path, (modified, Instant::now()));
+                }
+                Err(e) => warn!("Failed to import calendar {:?}: {}", path, e),
+            }
+        }
+
+        for source in session_manager.get_calendar_sources()? {
+            let path = PathBuf::from(&source);
+            if path.parent() == Some(dir.as_path()) && !present.contains(&source) {
+                info!("Calendar {} left the folder, removing its events", source);
+                let removed = session_manager.sync_calendar_events(
+                    &source,
+                    &[],
+                    import_window(Utc::now().timestamp()),
+                    None,
+                )?;
+                summary.merge(&removed);
+                self.synced.lock_or_recover().remove(&path);
+            }
+        }
+
+        if summary.has_changes() {
+            let _ = self.app_handle.emit("calendar-synced", &summary);
+        }
+        Ok(summary)
+    }
+
+    fn import_path(&self, path: &Path) -> Result<CalendarImportSummary> {
+        let text = fs::read_to_string(path)?;
+        let window = import_window(Utc::now().timestamp());
+        let events = parse_ics(&text, window)?;
+        debug!("Calendar {:?}: {} events in window", path, events.len());
+
+        let settings = get_settings(&self.app_handle);
+        let summary = self
+            .app_handle
+            .state::<Arc<SessionManager>>()
+            .sync_calendar_events(
+                &source_name(path),
+                &events,
+                window,
+                settings.default_environment_id.as_deref(),
+            )?;
+        if summary.has_changes() {
+            info!(
+                "Imported calendar {:?}: {} created, {} updated, {} removed",
+                path, summary.created, summary.updated, summary.removed
+            );
+        }
+        Ok(summary)
+    }
+}
+
+fn source_name(path: &Path) -> String {
+    path.to_string_lossy().to_string()
+}
+
+/// The span of event start times that are imported, as unix seconds
+fn import_window(now: i64) -> (i64, i64) {
+    const DAY: i64 = 24 * 60 * 60;
+    (
+        now - IMPORT_DAYS_BEHIND * DAY,
+        now + IMPORT_DAYS_AHEAD * DAY,
+    )
+}
+
+/// Split attendee names into the words the transcriber corrects towards, since
+/// custom words are matched one word at a time
+pub fn name_vocabulary(names: &[String]) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+    for name in names {
+        for word in name.split_whitespace() {
+            let word = word.trim_matches(|c: char| !c.is_alphanumeric());
+            if word.chars().count() < 2 || !word.chars().any(char::is_alphabetic) {
+                continue;
+            }
+            if !words.iter().any(|w| w == word) {
+                words.push(word.to_string());
+            }
+        }
+    }
+    words
+}
+
+// ==================== iCalendar Parsing ====================
+
+/// Parse the VEVENTs of an iCalendar file into the occurrences overlapping
+/// `window` (unix seconds). Recurring events are expanded for DAILY, WEEKLY
+/// and by-date MONTHLY rules; other rules only yield their first occurrence.
+/// Cancelled and all-day events are left out.
+pub fn parse_ics(text: &str, window: (i64, i64)) -> Result<Vec<CalendarEvent>> {
+    let lines = unfold_lines(text);
+    if !lines
+        .iter()
+        .any(|l| l.trim().eq_ignore_ascii_case("BEGIN:VCALENDAR"))
+    {
+        return Err(anyhow::anyhow!("Not an iCalendar file"));
+    }
+
+    let zone_aliases = timezone_aliases(&lines);
+    let mut raw_events = Vec::new();
+    let mut current: Option<RawEvent> = None;
+    // Components nested in the current VEVENT, such as VALARM
+    let mut depth = 0u32;
+    for line in &lines {
+        let Some(prop) = parse_property(line) else {
+            continue;
+        };
+        let value = prop.value.trim().to_ascii_uppercase();
+        match prop.name.as_str() {
+            "BEGIN" if current.is_none() && value == "VEVENT" => {
+                current = Some(RawEvent::default());
+            }
+            "BEGIN" if current.is_some() => depth += 1,
+            "END" if current.is_some() && depth == 0 => raw_events.extend(current.take()),
+            "END" if current.is_some() => depth -= 1,
+            _ if depth == 0 => {
+                if let Some(event) = current.as_mut() {
+                    event.apply(&prop, &zone_aliases);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let in_window = |start: i64, length: i64| start + length > window.0 && start < window.1;
+    let (overrides, masters): (Vec<RawEvent>, Vec<RawEvent>) = raw_events
+        .into_iter()
+        .partition(|e| e.recurrence_id.is_some());
+
+    let mut events = Vec::new();
+    for raw in &masters {
+        let (Some(uid), Some(start)) = (raw.uid.as_deref(), raw.start) else {
+            continue;
+        };
+        if raw.cancelled || start.date_only {
+            continue;
+        }
+        let Some(start_ts) = start.timestamp() else {
+            continue;
+        };
+        let length = raw.length(start_ts);
+
+        let Some(rule) = raw.rrule.as_deref() else {
+            if in_window(start_ts, length) {
+                events.push(raw.occurrence(uid, String::new(), start_ts, length));
+            }
+            continue;
+        };
+        let starts = match Recurrence::parse(rule) {
+            Some(recurrence) => recurrence.occurrences(&start, window.1),
+            None => {
+                debug!("Unsupported recurrence rule {}, importing once", rule);
+                vec![start_ts]
+            }
+        };
+        for occurrence in starts {
+            if !raw.exdates.contains(&occurrence) && in_window(occurrence, length) {
+                events.push(raw.occurrence(uid, occurrence.to_string(), occurrence, length));
+            }
+        }
+    }
+
+    // Moved or cancelled occurrences of recurring events replace the expanded ones
+    for raw in &overrides {
+        let (Some(uid), Some(original)) = (raw.uid.as_deref(), raw.recurrence_id) else {
+            continue;
+        };
+        let instance = original.to_string();
+        events.retain(|e: &CalendarEvent| !(e.uid == uid && e.instance == instance));
+        if raw.cancelled {
+            continue;
+        }
+        let Some(start_ts) = raw.start.and_then(|s| s.timestamp()) else {
+            continue;
+        };
+        let length = raw.length(start_ts);
+        if in_window(start_ts, length) {
+            events.push(raw.occurrence(uid, instance, start_ts, length));
+        }
+    }
+
+    events.sort_by_key(|e| e.starts_at);
+    Ok(events)
+}
+
+#[derive(Default)]
+struct RawEvent {
+    uid: Option<String>,
+    recurrence_id: Option<i64>,
+    title: Option<String>,
+    description: Option<String>,
+    location: Option<String>,
+    start: Option<EventTime>,
+    end: Option<EventTime>,
+    duration: Option<i64>,
+    rrule: Option<String>,
+    exdates: Vec<i64>,
+    cancelled: bool,
+    attendees: Vec<CalendarAttendee>,
+}
+
+impl RawEvent {
+    fn apply(&mut self, prop: &Property, zone_aliases: &HashMap<String, String>) {
+        let tzid = prop
+            .param("TZID")
+            .map(|id| zone_aliases.get(id).map(String::as_str).unwrap_or(id));
+        match prop.name.as_str() {
+            "UID" => self.uid = Some(prop.value.trim().to_string()),
+            "SUMMARY" => self.title = non_empty(unescape_text(&prop.value)),
+            "DESCRIPTION" => self.description = non_empty(unescape_text(&prop.value)),
+            "LOCATION" => self.location = non_empty(unescape_text(&prop.value)),
+            "DTSTART" => self.start = parse_time(&prop.value, tzid),
+            "DTEND" => self.end = parse_time(&prop.value, tzid),
+            "DURATION" => self.duration = parse_duration(&prop.value),
+            "RRULE" => self.rrule = Some(prop.value.trim().to_string()),
+            "RECURRENCE-ID" => {
+                self.recurrence_id = parse_time(&prop.value, tzid).and_then(|t| t.timestamp())
+            }
+            "EXDATE" => self.exdates.extend(
+                prop.value
+                    .split(',')
+                    .filter_map(|v| parse_time(v, tzid).and_then(|t| t.timestamp())),
+            ),
+            "STATUS" => self.cancelled = prop.value.trim().eq_ignore_ascii_case("CANCELLED"),
+            "ATTENDEE" | "ORGANIZER" => {
+                if let Some(attendee) = parse_attendee(prop) {
+                    let known = self.attendees.iter().any(|a| {
+                        a == &attendee || (attendee.email.is_some() && a.email == attendee.email)
+                    });
+                    if !known {
+                        self.attendees.push(attendee);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Length in seconds, from DTEND or DURATION. Events with neither take no time.
+    fn length(&self, start_ts: i64) -> i64 {
+        self.end
+            .and_then(|e| e.timestamp())
+            .map(|end| end - start_ts)
+            .or(self.duration)
+            .filter(|length| *length >= 0)
+            .unwrap_or(0)
+    }
+
+    fn occurrence(
+        &self,
+        uid: &str,
+        instance: String,
+        starts_at: i64,
+        length: i64,
+    ) -> CalendarEvent {
+        CalendarEvent {
+            uid: uid.to_string(),
+            instance,
+            title: self
+                .title
+                .clone()
+                .unwrap_or_else(|| "Untitled event".to_string()),
+            description: self.description.clone(),
+            location: self.location.clone(),
+            starts_at,
+            ends_at: starts_at + length,
+            attendees: self.attendees.clone(),
+        }
+    }
+}
+
+struct Property {
+    name: String,
+    params: Vec<(String, String)>,
+    value: String,
+}
+
+impl Property {
+    fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Join folded lines, which continue on the next line after a space or tab
+fn unfold_lines(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        if let Some(continuation) = line.strip_prefix([' ', '\t']) {
+            if let Some(last) = lines.last_mut() {
+                last.push_str(continuation);
+                continue;
+            }
+        }
+        lines.push(line.to_string());
+    }
+    lines
+}
+
+/// Split `NAME;PARAM=value;PARAM="quoted:value":value` into its parts
+fn parse_property(line: &str) -> Option<Property> {
+    let mut in_quotes = false;
+    let (split, _) = line.char_indices().find(|&(_, c)| {
+        if c == '"' {
+            in_quotes = !in_quotes;
+        }
+        c == ':' && !in_quotes
+    })?;
+    let (head, value) = (&line[..split], &line[split + 1..]);
+
+    let mut parts = split_unquoted(head, ';').into_iter();
+    let name = parts.next()?.trim().to_ascii_uppercase();
+    let params = parts
+        .filter_map(|p| p.split_once('='))
+        .map(|(n, v)| {
+            (
+                n.trim().to_ascii_uppercase(),
+                v.trim_matches('"').to_string(),
+            )
+        })
+        .collect();
+    Some(Property {
+        name,
+        params,
+        value: value.to_string(),
+    })
+}
+
+fn split_unquoted(s: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut in_quotes = false;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        if c == '"' {
+            in_quotes = !in_quotes;
+        } else if c == separator && !in_quotes {
+            parts.push(&s[start..i]);
+            start = i + c.len_utf8();
+        }
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+fn unescape_text(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+    out
+}
+
+fn non_empty(value: String) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+fn parse_attendee(prop: &Property) -> Option<CalendarAttendee> {
+    // Meeting rooms and equipment are booked as attendees too
+    if prop
+        .param("CUTYPE")
+        .is_some_and(|t| t.eq_ignore_ascii_case("ROOM") || t.eq_ignore_ascii_case("RESOURCE"))
+    {
+        return None;
+    }
+
+    let value = prop.value.trim();
+    let email = value
+        .get(..7)
+        .filter(|scheme| scheme.eq_ignore_ascii_case("mailto:"))
+        .map(|_| value[7..].trim().to_string())
+        .filter(|email| !email.is_empty());
+    // Some clients put the address in CN when there is no display name
+    let name = prop
+        .param("CN")
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty() && !n.contains('@'));
+
+    (name.is_some() || email.is_some()).then_some(CalendarAttendee { name, email })
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Zone {
+    Utc,
+    Named(Tz),
+    /// No zone given: the wall time is in the user's local time zone
+    Floating,
+}
+
+impl Zone {
+    fn from_tzid(tzid: Option<&str>) -> Self {
+        match tzid {
+            Some(id) => parse_tzid(id).map(Zone::Named).unwrap_or_else(|| {
+                debug!("Unknown time zone {}, using local time", id);
+                Zone::Floating
+            }),
+            None => Zone::Floating,
+        }
+    }
+
+    fn timestamp(self, local: NaiveDateTime) -> Option<i64> {
+        match self {
+            Zone::Utc => Some(local.and_utc().timestamp()),
+            Zone::Named(tz) => resolve_local(&tz, local),
+            Zone::Floating => resolve_local(&Local, local),
+        }
+    }
+}
+
+fn resolve_local<T: TimeZone>(tz: &T, local: NaiveDateTime) -> Option<i64> {
+    // A wall time skipped by a DST change happens an hour later
+    tz.from_local_datetime(&local)
+        .earliest()
+        .or_else(|| {
+            tz.from_local_datetime(&(local + chrono::Duration::hours(1)))
+                .earliest()
+        })
+        .map(|t| t.timestamp())
+}
+
+/// Custom TZIDs whose VTIMEZONE names the Olson zone it describes in an
+/// `X-LIC-LOCATION` property, as Lightning and Apple Calendar write them
+fn timezone_aliases(lines: &[String]) -> HashMap<String, String> {
+    let mut aliases = HashMap::new();
+    let mut in_timezone = false;
+    let mut tzid: Option<String> = None;
+    for line in lines {
+        let Some(prop) = parse_property(line) else {
+            continue;
+        };
+        let value = prop.value.trim();
+        match prop.name.as_str() {
+            "BEGIN" if value.eq_ignore_ascii_case("VTIMEZONE") => {
+                in_timezone = true;
+                tzid = None;
+            }
+            "END" if value.eq_ignore_ascii_case("VTIMEZONE") => in_timezone = false,
+            "TZID" if in_timezone => tzid = Some(value.to_string()),
+            "X-LIC-LOCATION" if in_timezone => {
+                if let Some(id) = tzid.clone() {
+                    aliases.insert(id, value.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    aliases
+}
+
+fn parse_tzid(id: &str) -> Option<Tz> {
+    let id = id.trim_matches('"');
+    id.parse::<Tz>()
+        .ok()
+        .or_else(|| {
+            // Some exporters prefix Olson names, e.g. /mozilla.org/20050126_1/America/New_York
+            let mut parts = id.rsplit('/');
+            let city = parts.next()?;
+            let region = parts.next()?;
+            format!("{}/{}", region, city).parse().ok()
+        })
+        .or_else(|| {
+            // Outlook and Exchange use Windows zone names such as "Pacific Standard Time"
+            WINDOWS_ZONES
+                .iter()
+                .find(|(windows, _)| windows.eq_ignore_ascii_case(id))
+                .and_then(|(_, olson)| olson.parse().ok())
+        })
+}
+
+/// Windows time zone names and the Olson zone CLDR's windowsZones.xml maps
+/// each to for territory 001
+const WINDOWS_ZONES: &[(&str, &str)] = &[
+    ("Dateline Standard Time", "Etc/GMT+12"),
+    ("UTC-11", "Etc/GMT+11"),
+    ("Aleutian Standard Time", "America/Adak"),
+    ("Hawaiian Standard Time", "Pacific/Honolulu"),
+    ("Marquesas Standard Time", "Pacific/Marquesas"),
+    ("Alaskan Standard Time", "America/Anchorage"),
+    ("UTC-09", "Etc/GMT+9"),
+    ("Pacific Standard Time (Mexico)", "America/Tijuana"),
+    ("UTC-08", "Etc/GMT+8"),
+    ("Pacific Standard Time", "America/Los_Angeles"),
+    ("US Mountain Standard Time", "America/Phoenix"),
+    ("Mountain Standard Time (Mexico)", "America/Mazatlan"),
+    ("Mountain Standard Time", "America/Denver"),
+    ("Yukon Standard Time", "America/Whitehorse"),
+    ("Central America Standard Time", "America/Guatemala"),
+    ("Central Standard Time", "America/Chicago"),
+    ("Easter Island Standard Time", "Pacific/Easter"),
+    ("Central Standard Time (Mexico)", "America/Mexico_City"),
+    ("Canada Central Standard Time", "America/Regina"),
+    ("SA Pacific Standard Time", "America/Bogota"),
+    ("Eastern Standard Time (Mexico)", "America/Cancun"),
+    ("Eastern Standard Time", "America/New_York"),
+    ("Haiti Standard Time", "America/Port-au-Prince"),
+    ("Cuba Standard Time", "America/Havana"),
+    ("US Eastern Standard Time", "America/Indiana/Indianapolis"),
+    ("Turks And Caicos Standard Time", "America/Grand_Turk"),
+    ("Paraguay Standard Time", "America/Asuncion"),
+    ("Atlantic Standard Time", "America/Halifax"),
+    ("Venezuela Standard Time", "America/Caracas"),
+    ("Central Brazilian Standard Time", "America/Cuiaba"),
+    ("SA Western Standard Time", "America/La_Paz"),
+    ("Pacific SA Standard Time", "America/Santiago"),
+    ("Newfoundland Standard Time", "America/St_Johns"),
+    ("Tocantins Standard Time", "America/Araguaina"),
+    ("E. South America Standard Time", "America/Sao_Paulo"),
+    ("SA Eastern Standard Time", "America/Cayenne"),
+    ("Argentina Standard Time", "America/Argentina/Buenos_Aires"),
+    ("Greenland Standard Time", "America/Nuuk"),
+    ("Montevideo Standard Time", "America/Montevideo"),
+    ("Magallanes Standard Time", "America/Punta_Arenas"),
+    ("Saint Pierre Standard Time", "America/Miquelon"),
+    ("Bahia Standard Time", "America/Bahia"),
+    ("UTC-02", "Etc/GMT+2"),
+    ("Mid-Atlantic Standard Time", "Etc/GMT+2"),
+    ("Azores Standard Time", "Atlantic/Azores"),
+    ("Cape Verde Standard Time", "Atlantic/Cape_Verde"),
+    ("UTC", "Etc/UTC"),
+    ("GMT Standard Time", "Europe/London"),
+    ("Greenwich Standard Time", "Atlantic/Reykjavik"),
+    ("Sao Tome Standard Time", "Africa/Sao_Tome"),
+    ("Morocco Standard Time", "Africa/Casablanca"),
+    ("W. Europe Standard Time", "Europe/Berlin"),
+    ("Central Europe Standard Time", "Europe/Budapest"),
+    ("Romance Standard Time", "Europe/Paris"),
+    ("Central European Standard Time", "Europe/Warsaw"),
+    ("W. Central Africa Standard Time", "Africa/Lagos"),
+    ("Jordan Standard Time", "Asia/Amman"),
+    ("GTB Standard Time", "Europe/Bucharest"),
+    ("Middle East Standard Time", "Asia/Beirut"),
+    ("Egypt Standard Time", "Africa/Cairo"),
+    ("E. Europe Standard Time", "Europe/Chisinau"),
+    ("Syria Standard Time", "Asia/Damascus"),
+    ("West Bank Standard Time", "Asia/Hebron"),
+    ("South Africa Standard Time", "Africa/Johannesburg"),
+    ("FLE Standard Time", "Europe/Kyiv"),
+    ("Israel Standard Time", "Asia/Jerusalem"),
+    ("South Sudan Standard Time", "Africa/Juba"),
+    ("Kaliningrad Standard Time", "Europe/Kaliningrad"),
+    ("Sudan Standard Time", "Africa/Khartoum"),
+    ("Libya Standard Time", "Africa/Tripoli"),
+    ("Namibia Standard Time", "Africa/Windhoek"),
+    ("Arabic Standard Time", "Asia/Baghdad"),
+    ("Turkey Standard Time", "Europe/Istanbul"),
+    ("Arab Standard Time", "Asia/Riyadh"),
+    ("Belarus Standard Time", "Europe/Minsk"),
+    ("Russian Standard Time", "Europe/Moscow"),
+    ("E. Africa Standard Time", "Africa/Nairobi"),
+    ("Volgograd Standard Time", "Europe/Volgograd"),
+    ("Iran Standard Time", "Asia/Tehran"),
+    ("Arabian Standard Time", "Asia/Dubai"),
+    ("Astrakhan Standard Time", "Europe/Astrakhan"),
+    ("Azerbaijan Standard Time", "Asia/Baku"),
+    ("Russia Time Zone 3", "Europe/Samara"),
+    ("Mauritius Standard Time", "Indian/Mauritius"),
+    ("Saratov Standard Time", "Europe/Saratov"),
+    ("Georgian Standard Time", "Asia/Tbilisi"),
+    ("Caucasus Standard Time", "Asia/Yerevan"),
+    ("Afghanistan Standard Time", "Asia/Kabul"),
+    ("West Asia Standard Time", "Asia/Tashkent"),
+    ("Qyzylorda Standard Time", "Asia/Qyzylorda"),
+    ("Ekaterinburg Standard Time", "Asia/Yekaterinburg"),
+    ("Pakistan Standard Time", "Asia/Karachi"),
+    ("India Standard Time", "Asia/Kolkata"),
+    ("Sri Lanka Standard Time", "Asia/Colombo"),
+    ("Nepal Standard Time", "Asia/Kathmandu"),
+    ("Central Asia Standard Time", "Asia/Bishkek"),
+    ("Bangladesh Standard Time", "Asia/Dhaka"),
+    ("Omsk Standard Time", "Asia/Omsk"),
+    ("Myanmar Standard Time", "Asia/Yangon"),
+    ("SE Asia Standard Time", "Asia/Bangkok"),
+    ("Altai Standard Time", "Asia/Barnaul"),
+    ("W. Mongolia Standard Time", "Asia/Hovd"),
+    ("North Asia Standard Time", "Asia/Krasnoyarsk"),
+    ("N. Central Asia Standard Time", "Asia/Novosibirsk"),
+    ("Tomsk Standard Time", "Asia/Tomsk"),
+    ("China Standard Time", "Asia/Shanghai"),
+    ("North Asia East Standard Time", "Asia/Irkutsk"),
+    ("Singapore Standard Time", "Asia/Singapore"),
+    ("W. Australia Standard Time", "Australia/Perth"),
+    ("Taipei Standard Time", "Asia/Taipei"),
+    ("Ulaanbaatar Standard Time", "Asia/Ulaanbaatar"),
+    ("Aus Central W. Standard Time", "Australia/Eucla"),
+    ("Transbaikal Standard Time", "Asia/Chita"),
+    ("Tokyo Standard Time", "Asia/Tokyo"),
+    ("North Korea Standard Time", "Asia/Pyongyang"),
+    ("Korea Standard Time", "Asia/Seoul"),
+    ("Yakutsk Standard Time", "Asia/Yakutsk"),
+    ("Cen. Australia Standard Time", "Australia/Adelaide"),
+    ("AUS Central Standard Time", "Australia/Darwin"),
+    ("E. Australia Standard Time", "Australia/Brisbane"),
+    ("AUS Eastern Standard Time", "Australia/Sydney"),
+    ("West Pacific Standard Time", "Pacific/Port_Moresby"),
+    ("Tasmania Standard Time", "Australia/Hobart"),
+    ("Vladivostok Standard Time", "Asia/Vladivostok"),
+    ("Lord Howe Standard Time", "Australia/Lord_Howe"),
+    ("Bougainville Standard Time", "Pacific/Bougainville"),
+    ("Russia Time Zone 10", "Asia/Srednekolymsk"),
+    ("Magadan Standard Time", "Asia/Magadan"),
+    ("Norfolk Standard Time", "Pacific/Norfolk"),
+    ("Sakhalin Standard Time", "Asia/Sakhalin"),
+    ("Central Pacific Standard Time", "Pacific/Guadalcanal"),
+    ("Russia Time Zone 11", "Asia/Kamchatka"),
+    ("New Zealand Standard Time", "Pacific/Auckland"),
+    ("UTC+12", "Etc/GMT-12"),
+    ("Fiji Standard Time", "Pacific/Fiji"),
+    ("Chatham Islands Standard Time", "Pacific/Chatham"),
+    ("UTC+13", "Etc/GMT-13"),
+    ("Tonga Standard Time", "Pacific/Tongatapu"),
+    ("Samoa Standard Time", "Pacific/Apia"),
+    ("Line Islands Standard Time", "Pacific/Kiritimati"),
+];
+
+#[derive(Clone, Copy, Debug)]
+struct EventTime {
+    local: NaiveDateTime,
+    zone: Zone,
+    date_only: bool,
+}
+
+impl EventTime {
+    fn timestamp(&self) -> Option<i64> {
+        self.zone.timestamp(self.local)
+    }
+}
+
+/// Parse a DATE-TIME (UTC, zoned by `tzid` or floating) or DATE value
+fn parse_time(value: &str, tzid: Option<&str>) -> Option<EventTime> {
+    let value = value.trim();
+    if value.len() == 8 {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+        return Some(EventTime {
+            local: date.and_hms_opt(0, 0, 0)?,
+            zone: Zone::Floating,
+            date_only: true,
+        });
+    }
+
+    let (local, zone) = match value.strip_suffix(['Z', 'z']) {
+        Some(utc) => (utc, Zone::Utc),
+        None => (value, Zone::from_tzid(tzid)),
+    };
+    Some(EventTime {
+        local: NaiveDateTime::parse_from_str(local, "%Y%m%dT%H%M%S").ok()?,
+        zone,
+        date_only: false,
+    })
+}
+
+/// Parse a DURATION value such as `PT1H30M` or `P1D` into seconds
+fn parse_duration(value: &str) -> Option<i64> {
+    let value = value.trim();
+    let (sign, rest) = match value.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let rest = rest.strip_prefix(['P', 'p'])?;
+
+    let mut seconds = 0i64;
+    let mut number = String::new();
+    for c in rest.chars() {
+        let unit = match c.to_ascii_uppercase() {
+            '0'..='9' => {
+                number.push(c);
+                continue;
+            }
+            'T' => continue,
+            'W' => 7 * 24 * 60 * 60,
+            'D' => 24 * 60 * 60,
+            'H' => 60 * 60,
+            'M' => 60,
+            'S' => 1,
+            _ => return None,
+        };
+        seconds += number.parse::<i64>().ok()? * unit;
+        number.clear();
+    }
+    Some(sign * seconds)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+#[derive(Debug)]
+struct Recurrence {
+    frequency: Frequency,
+    interval: u64,
+    count: Option<usize>,
+    until: Option<i64>,
+    /// Weekdays of a weekly rule, sorted from Monday
+    by_day: Vec<Weekday>,
+}
+
+impl Recurrence {
+    /// Parse an RRULE value, or None for parts this importer doesn't expand
+    fn parse(rule: &str) -> Option<Self> {
+        let mut frequency = None;
+        let mut interval = 1;
+        let mut count = None;
+        let mut until = None;
+        let mut by_day = Vec::new();
+
+        for part in rule.split(';').filter(|p| !p.is_empty()) {
+            let (key, value) = part.split_once('=')?;
+            match key.to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    frequency = Some(match value.to_ascii_uppercase().as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        _ => return None,
+                    })
+                }
+                "INTERVAL" => interval = value.parse().ok().filter(|i| *i > 0)?,
+                "COUNT" => count = Some(value.parse().ok()?),
+                "UNTIL" => {
+                    let time = parse_time(value, None)?;
+                    // A date-only UNTIL includes that whole day
+                    let end_of_day = if time.date_only { 24 * 60 * 60 - 1 } else { 0 };
+                    until = Some(time.timestamp()? + end_of_day);
+                }
+                "BYDAY" => {
+                    for day in value.split(',') {
+                        by_day.push(parse_weekday(day)?);
+                    }
+                }
+                "WKST" => {}
+                _ => return None,
+            }
+        }
+
+        let frequency = frequency?;
+        // BYDAY is only understood for weekly rules
+        if !by_day.is_empty() && frequency != Frequency::Weekly {
+            return None;
+        }
+        by_day.sort_by_key(|d: &Weekday| d.num_days_from_monday());
+        by_day.dedup();
+        Some(Self {
+            frequency,
+            interval,
+            count,
+            until,
+            by_day,
+        })
+    }
+
+    /// Start times of the occurrences from `start` until `window_end`. COUNT is
+    /// counted from the first occurrence, whether or not it's in the window.
+    fn occurrences(&self, start: &EventTime, window_end: i64) -> Vec<i64> {
+        let first = start.local.date();
+        let time = start.local.time();
+        let week_start = first - Days::new(first.weekday().num_days_from_monday() as u64);
+
+        let mut out = Vec::new();
+        let mut emitted = 0;
+        for step in 0..MAX_RECURRENCE_STEPS {
+            let period = step * self.interval;
+            let dates: Vec<NaiveDate> = match self.frequency {
+                Frequency::Daily => first
+                    .checked_add_days(Days::new(period))
+                    .into_iter()
+                    .collect(),
+                Frequency::Weekly if self.by_day.is_empty() => first
+                    .checked_add_days(Days::new(period * 7))
+                    .into_iter()
+                    .collect(),
+                Frequency::Weekly => {
+                    let Some(week) = week_start.checked_add_days(Days::new(period * 7)) else {
+                        break;
+                    };
+                    self.by_day
+                        .iter()
+                        .map(|d| week + Days::new(d.num_days_from_monday() as u64))
+                        .collect()
+                }
+                Frequency::Monthly => add_months(first, period).into_iter().collect(),
+            };
+
+            for date in dates {
+                if date < first {
+                    continue;
+                }
+                let Some(ts) = start.zone.timestamp(date.and_time(time)) else {
+                    continue;
+                };
+                if ts >= window_end
+                    || self.until.is_some_and(|until| ts > until)
+                    || self.count.is_some_and(|count| emitted >= count)
+                {
+                    return out;
+                }
+                emitted += 1;
+                out.push(ts);
+            }
+        }
+        out
+    }
+}
+
+fn parse_weekday(day: &str) -> Option<Weekday> {
+    // Ordinals such as 2TU (second Tuesday) only apply to monthly rules
+    Some(match day.trim().to_ascii_uppercase().as_str() {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return None,
+    })
+}
+
+/// The same day of the month `months` later; None when that month is too short
+fn add_months(date: NaiveDate, months: u64) -> Option<NaiveDate> {
+    let total = date.year() as i64 * 12 + date.month0() as i64 + i64::try_from(months).ok()?;
+    let year = i32::try_from(total.div_euclid(12)).ok()?;
+    NaiveDate::from_ymd_opt(year, total.rem_euclid(12) as u32 + 1, date.day())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(s: &str) -> i64 {
+        NaiveDateTime::parse_from_str(s, "%Y%m%dT%H%M%S")
+            .unwrap()
+            .and_utc()
+            .timestamp()
+    }
+
+    #[test]
+    fn test_parse_ics_event_with_attendees() {
+        let ics = "BEGIN:VCALENDAR\r\n\
+BEGIN:VEVENT\r\n\
+UID:abc@example.com\r\n\
+SUMMARY:Design review\\, round 2\r\n\
+DESCRIPTION:Agenda:\\n- mockups\r\n\
+DTSTART;TZID=Europe/Berlin:20250310T100000\r\n\
+DTEND;TZID=Europe/Berlin:20250310T110000\r\n\
+ORGANIZER;CN=\"Müller, Anna\":mailto:anna@example.com\r\n\
+ATTENDEE;CN=Anna Müller;PARTSTAT=ACCEPTED:mailto:anna@example.com\r\n\
+ATTENDEE;CN=Bob\r\n  Smith:MAILTO:bob@example.com\r\n\
+ATTENDEE;CUTYPE=ROOM;CN=Room 4:mailto:room4@example.com\r\n\
+BEGIN:VALARM\r\n\
+DESCRIPTION:Reminder\r\n\
+END:VALARM\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+UID:cancelled@example.com\r\n\
+STATUS:CANCELLED\r\n\
+DTSTART:20250310T120000Z\r\n\
+END:VEVENT\r\n\
+END:VCALENDAR\r\n";
+        let window = (utc("20250301T000000"), utc("20250401T000000"));
+        let events = parse_ics(ics, window).unwrap();
+
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert_eq!(event.title, "Design review, round 2");
+        assert_eq!(event.description.as_deref(), Some("Agenda:\n- mockups"));
+        assert_eq!(event.instance, "");
+        // 10:00 CET
+        assert_eq!(event.starts_at, utc("20250310T090000"));
+        assert_eq!(event.ends_at - event.starts_at, 3600);
+        let names: Vec<_> = event.attendees.iter().map(|a| a.name.as_deref()).collect();
+        assert_eq!(names, vec![Some("Müller, Anna"), Some("Bob Smith")]);
+        assert_eq!(event.attendees[1].email.as_deref(), Some("bob@example.com"));
+
+        assert!(parse_ics("not a calendar", window).is_err());
+    }
+
+    #[test]
+    fn test_parse_ics_expands_weekly_recurrence() {
+        let ics = "BEGIN:VCALENDAR
+BEGIN:VEVENT
+UID:standup
+SUMMARY:Standup
+DTSTART:20250303T090000Z
+DURATION:PT15M
+RRULE:FREQ=WEEKLY;BYDAY=MO,WE;COUNT=5
+EXDATE:20250305T090000Z
+END:VEVENT
+BEGIN:VEVENT
+UID:standup
+RECURRENCE-ID:20250310T090000Z
+SUMMARY:Standup (moved)
+DTSTART:20250310T100000Z
+DTEND:20250310T101500Z
+END:VEVENT
+END:VCALENDAR";
+        let window = (utc("20250304T000000"), utc("20250401T000000"));
+        let events = parse_ics(ics, window).unwrap();
+
+        // Mar 3 is before the window and Mar 5 is excluded; COUNT stops after Mar 17
+        let starts: Vec<_> = events
+            .iter()
+            .map(|e| (e.starts_at, e.title.as_str()))
+            .collect();
+        assert_eq!(
+            starts,
+            vec![
+                (utc("20250310T100000"), "Standup (moved)"),
+                (utc("20250312T090000"), "Standup"),
+                (utc("20250317T090000"), "Standup"),
+            ]
+        );
+        assert_eq!(events[0].instance, utc("20250310T090000").to_string());
+        assert_eq!(events[1].ends_at - events[1].starts_at, 15 * 60);
+    }
+
+    #[test]
+    fn test_parse_ics_resolves_windows_and_custom_tzids() {
+        let ics = "BEGIN:VCALENDAR
+BEGIN:VEVENT
+UID:outlook
+DTSTART;TZID=Pacific Standard Time:20250715T090000
+DTEND;TZID=Pacific Standard Time:20250715T093000
+END:VEVENT
+BEGIN:VEVENT
+UID:lightning
+DTSTART;TZID=/custom/Tokyo:20250715T090000
+END:VEVENT
+BEGIN:VTIMEZONE
+TZID:/custom/Tokyo
+X-LIC-LOCATION:Asia/Tokyo
+END:VTIMEZONE
+END:VCALENDAR";
+        let window = (utc("20250701T000000"), utc("20250801T000000"));
+        let events = parse_ics(ics, window).unwrap();
+
+        let starts: Vec<_> = events
+            .iter()
+            .map(|e| (e.uid.as_str(), e.starts_at))
+            .collect();
+        // 09:00 PDT and 09:00 JST
+        assert_eq!(
+            starts,
+            vec![
+                ("lightning", utc("20250715T000000")),
+                ("outlook", utc("20250715T160000")),
+            ]
+        );
+        assert!(WINDOWS_ZONES
+            .iter()
+            .all(|(_, olson)| olson.parse::<Tz>().is_ok()));
+    }
+
+    /// (uid, start) of each occurrence `parse_ics` finds in `window`
+    fn occurrences(ics: &str, window: (&str, &str)) -> Vec<(String, i64)> {
+        parse_ics(ics, (utc(window.0), utc(window.1)))
+            .unwrap()
+            .into_iter()
+            .map(|e| (e.uid, e.starts_at))
+            .collect()
+    }
+
+    fn starts_of(events: &[(String, i64)], uid: &str) -> Vec<i64> {
+        events
+            .iter()
+            .filter(|(u, _)| u == uid)
+            .map(|(_, start)| *start)
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_ics_recurrence_count_and_until() {
+        let ics = "BEGIN:VCALENDAR
+BEGIN:VEVENT
+UID:every-other-day
+DTSTART:20250301T090000Z
+RRULE:FREQ=DAILY;INTERVAL=2;COUNT=3
+END:VEVENT
+BEGIN:VEVENT
+UID:until-time
+DTSTART:20250301T090000Z
+RRULE:FREQ=DAILY;UNTIL=20250303T090000Z
+END:VEVENT
+BEGIN:VEVENT
+UID:until-date
+DTSTART:20250301T230000Z
+RRULE:FREQ=DAILY;UNTIL=20250302
+END:VEVENT
+BEGIN:VEVENT
+UID:month-end
+DTSTART:20250131T090000Z
+RRULE:FREQ=MONTHLY;COUNT=3
+END:VEVENT
+BEGIN:VEVENT
+UID:open-ended
+DTSTART:20250301T090000Z
+RRULE:FREQ=WEEKLY
+END:VEVENT
+END:VCALENDAR";
+        let events = occurrences(ics, ("20250101T000000", "20250601T000000"));
+
+        assert_eq!(
+            starts_of(&events, "every-other-day"),
+            vec![
+                utc("20250301T090000"),
+                utc("20250303T090000"),
+                utc("20250305T090000"),
+            ]
+        );
+        // UNTIL includes an occurrence starting exactly then, and a date-only
+        // UNTIL its whole day
+        assert_eq!(
+            starts_of(&events, "until-time"),
+            vec![
+                utc("20250301T090000"),
+                utc("20250302T090000"),
+                utc("20250303T090000"),
+            ]
+        );
+        assert_eq!(
+            starts_of(&events, "until-date"),
+            vec![utc("20250301T230000"), utc("20250302T230000")]
+        );
+        // Months without a 31st are skipped and don't count
+        assert_eq!(
+            starts_of(&events, "month-end"),
+            vec![
+                utc("20250131T090000"),
+                utc("20250331T090000"),
+                utc("20250531T090000"),
+            ]
+        );
+        // Rules without an end stop at the end of the window
+        let open_ended = starts_of(&events, "open-ended");
+        assert_eq!(open_ended.len(), 14);
+        assert_eq!(open_ended.last(), Some(&utc("20250531T090000")));
+    }
+
+    #[test]
+    fn test_parse_ics_weekly_byday_and_unsupported_rules() {
+        let ics = "BEGIN:VCALENDAR
+BEGIN:VEVENT
+UID:fortnightly
+DTSTART:20250306T090000Z
+RRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=FR,TU,FR;WKST=MO
+END:VEVENT
+BEGIN:VEVENT
+UID:second-tuesday
+DTSTART:20250311T090000Z
+RRULE:FREQ=MONTHLY;BYDAY=2TU
+END:VEVENT
+BEGIN:VEVENT
+UID:monthly-byday
+DTSTART:20250303T090000Z
+RRULE:FREQ=MONTHLY;BYDAY=MO
+END:VEVENT
+BEGIN:VEVENT
+UID:yearly
+DTSTART:20250304T090000Z
+RRULE:FREQ=YEARLY
+END:VEVENT
+END:VCALENDAR";
+        let events = occurrences(ics, ("20250301T000000", "20250401T000000"));
+
+        // The Tuesday before the Thursday start isn't an occurrence, and the
+        // week of Mar 10 is skipped
+        assert_eq!(
+            starts_of(&events, "fortnightly"),
+            vec![
+                utc("20250307T090000"),
+                utc("20250318T090000"),
+                utc("20250321T090000"),
+            ]
+        );
+        // Rules the importer doesn't expand keep their first occurrence
+        for uid in ["second-tuesday", "monthly-byday", "yearly"] {
+            assert_eq!(starts_of(&events, uid).len(), 1, "{}", uid);
+        }
+    }
+
+    #[test]
+    fn test_parse_ics_exdates_and_cancelled_occurrences() {
+        let ics = "BEGIN:VCALENDAR
+BEGIN:VEVENT
+UID:weekly
+DTSTART;TZID=Europe/Berlin:20250324T100000
+DTEND;TZID=Europe/Berlin:20250324T110000
+RRULE:FREQ=WEEKLY
+EXDATE;TZID=Europe/Berlin:20250407T100000,20250414T100000
+EXDATE:20250428T080000Z
+END:VEVENT
+BEGIN:VEVENT
+UID:weekly
+RECURRENCE-ID;TZID=Europe/Berlin:20250505T100000
+STATUS:CANCELLED
+DTSTART;TZID=Europe/Berlin:20250505T100000
+END:VEVENT
+END:VCALENDAR";
+        let events = parse_ics(ics, (utc("20250320T000000"), utc("20250513T000000"))).unwrap();
+
+        // 10:00 CET, then 10:00 CEST after the switch on Mar 30
+        let starts: Vec<_> = events.iter().map(|e| e.starts_at).collect();
+        assert_eq!(
+            starts,
+            vec![
+                utc("20250324T090000"),
+                utc("20250331T080000"),
+                utc("20250421T080000"),
+                utc("20250512T080000"),
+            ]
+        );
+        assert!(events.iter().all(|e| e.ends_at - e.starts_at == 3600));
+    }
+
+    #[test]
+    fn test_parse_ics_skips_all_day_events() {
+        let ics = "BEGIN:VCALENDAR
+BEGIN:VEVENT
+UID:holiday
+SUMMARY:Public holiday
+DTSTART;VALUE=DATE:20250310
+DTEND;VALUE=DATE:20250311
+END:VEVENT
+BEGIN:VEVENT
+UID:weekly-all-day
+DTSTART;VALUE=DATE:20250311
+RRULE:FREQ=WEEKLY;COUNT=4
+END:VEVENT
+BEGIN:VEVENT
+UID:meeting
+DTSTART:20250310T090000Z
+END:VEVENT
+END:VCALENDAR";
+        let events = occurrences(ics, ("20250301T000000", "20250401T000000"));
+
+        assert_eq!(
+            events,
+            vec![("meeting".to_string(), utc("20250310T090000"))]
+        );
+    }
+
+    #[test]
+    fn test_parse_tzid_maps_windows_names() {
+        let tz = |id: &str| parse_tzid(id).map(|tz| tz.name());
+        assert_eq!(tz("W. Europe Standard Time"), Some("Europe/Berlin"));
+        assert_eq!(tz("eastern standard time"), Some("America/New_York"));
+        assert_eq!(tz("\"India Standard Time\""), Some("Asia/Kolkata"));
+        // Names that extend another are matched whole
+        assert_eq!(
+            tz("Pacific Standard Time (Mexico)"),
+            Some("America/Tijuana")
+        );
+        assert_eq!(tz("UTC-08"), Some("Etc/GMT+8"));
+        assert_eq!(
+            tz("/mozilla.org/20050126_1/America/New_York"),
+            Some("America/New_York")
+        );
+        assert_eq!(tz("Mars Standard Time"), None);
+
+        let mut names: Vec<_> = WINDOWS_ZONES
+            .iter()
+            .map(|(windows, _)| windows.to_ascii_lowercase())
+            .collect();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), WINDOWS_ZONES.len());
+
+        // Wall times follow the zone's daylight saving rules
+        let ics = "BEGIN:VCALENDAR
+BEGIN:VEVENT
+UID:winter
+DTSTART;TZID=W. Europe Standard Time:20250115T100000
+END:VEVENT
+BEGIN:VEVENT
+UID:summer
+DTSTART;TZID=W. Europe Standard Time:20250715T100000
+END:VEVENT
+END:VCALENDAR";
+        assert_eq!(
+            occurrences(ics, ("20250101T000000", "20260101T000000")),
+            vec![
+                ("winter".to_string(), utc("20250115T090000")),
+                ("summer".to_string(), utc("20250715T080000")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_name_vocabulary() {
+        let names = vec!["Anna Müller".to_string(), "O'Brien, J.".to_string()];
+        assert_eq!(name_vocabulary(&names), vec!["Anna", "Müller", "O'Brien"]);
+    }
+}
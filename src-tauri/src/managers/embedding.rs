@@ -0,0 +1,634 @@
+use crate::embedding::SentenceEmbedder;
+use crate::managers::model::{ModelManager, EMBEDDING_MODEL_ID};
+use crate::managers::session::{blob_to_embedding, embedding_to_blob, SessionManager};
+use crate::utils::MutexExt;
+use anyhow::Result;
+use chrono::Utc;
+use log::{debug, error, info};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashSet};
+use std::sync::{Arc, Condvar, Mutex, Weak};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+/// Texts embedded per model run
+const BATCH_SIZE: usize = 16;
+/// How often the indexer looks for unembedded text without being notified,
+/// e.g. after transcript edits or once the model has been downloaded
+const INDEX_POLL: Duration = Duration::from_secs(60);
+
+/// Notes are embedded in paragraphs of at most about this many characters,
+/// roughly what fits in the embedding model's input
+const NOTES_PARAGRAPH_CHARS: usize = 1_000;
+
+/// A transcript segment or notes paragraph close in meaning to a semantic
+/// search query. `kind` is "transcript" or "notes".
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct SemanticHit {
+    pub session_id: String,
+    pub session_title: String,
+    pub kind: String,
+    pub segment_id: Option<i64>,
+    pub source: Option<String>,
+    pub start_ms: Option<i64>,
+    pub text: String,
+    /// Cosine similarity to the query, higher is more relevant
+    pub score: f32,
+}
+
+/// A session's notes whose paragraphs changed since they were last embedded
+pub struct PendingNotes {
+    pub session_id: String,
+    pub updated_at: i64,
+    pub paragraphs: Vec<String>,
+    /// Paragraphs that already have an embedding
+    pub embedded: HashSet<String>,
+}
+
+/// Keeps the semantic search index of transcripts and notes up to date and
+/// answers queries against it. Embeddings are computed on-device with the
+/// model from `ModelManager`; nothing leaves the machine.
+pub struct EmbeddingManager {
+    app_handle: AppHandle,
+    model_manager: Arc<ModelManager>,
+    /// Loaded on first use
+    embedder: Mutex<Option<SentenceEmbedder>>,
+    /// Set by `notify` to wake the indexer before its next poll
+    pending: Mutex<bool>,
+    wake: Condvar,
+}
+
+impl EmbeddingManager {
+    pub fn new(app_handle: &AppHandle, model_manager: Arc<ModelManager>) -> Self {
+        Self {
+            app_handle: app_handle.clone(),
+            model_manager,
+            embedder: Mutex::new(None),
+            pending: Mutex::new(false),
+            wake: Condvar::new(),
+        }
+    }
+
+    /// Start the thread that embeds new segments and notes in the background.
+    /// It stops once the manager is dropped.
+    pub fn start_indexer(self: &Arc<Self>) {
+        let manager: Weak<Self> = Arc::downgrade(self);
+        thread::spawn(move || {
+            loop {
+                let Some(manager) = manager.upgrade() else {
+                    break;
+                };
+                if let Err(e) = manager.index_pending() {
+                    error!("Semantic indexing failed: {}", e);
+                }
+
+                let pending = manager.pending.lock_or_recover();
+                let (mut pending, _) = manager
+                    .wake
+                    .wait_timeout_while(pending, INDEX_POLL, |pending| !*pending)
+                    .unwrap_or_else(|e| e.into_inner());
+                *pending = false;
+            }
+            debug!("Semantic indexer shutting down");
+        });
+    }
+
+    /// Wake the indexer, there is new text to embed
+    pub fn notify(&self) {
+        *self.pending.lock_or_recover() = true;
+        self.wake.notify_one();
+    }
+
+    /// Embed every transcript segment and notes paragraph that has no current
+    /// embedding. Does nothing until the model is downloaded and storage is
+    /// unlocked. Returns the number of texts embedded.
+    pub fn index_pending(&self) -> Result<usize> {
+        let session_manager = self.app_handle.state::<Arc<SessionManager>>();
+        if session_manager.get_storage_encryption_status().locked || !self.is_model_available() {
+            return Ok(0);
+        }
+
+        let embedded =
+            session_manager.index_embeddings(EMBEDDING_MODEL_ID, |texts| self.embed(texts))?;
+        if embedded > 0 {
+            info!("Semantic index: embedded {} texts", embedded);
+        }
+        Ok(embedded)
+    }
+
+    /// The `limit` transcript segments and notes paragraphs closest in meaning
+    /// to `query`
+    pub fn search(&self, query: &str, limit: usize) -> Result<Vec<SemanticHit>> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+        if !self.is_model_available() {
+            return Err(anyhow::anyhow!(
+                "Download the semantic search model to search by meaning"
+            ));
+        }
+
+        let mut vectors = self.embed(&[query])?;
+        let query_vector = vectors.pop().unwrap_or_default();
+        self.app_handle
+            .state::<Arc<SessionManager>>()
+            .semantic_search(EMBEDDING_MODEL_ID, &query_vector, limit)
+    }
+
+    fn is_model_available(&self) -> bool {
+        let available = self
+            .model_manager
+            .get_model_path(EMBEDDING_MODEL_ID)
+            .is_ok();
+        if !available {
+            // The model may have been deleted; don't keep it in memory
+            *self.embedder.lock_or_recover() = None;
+        }
+        available
+    }
+
+    fn embed(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        let mut guard = self.embedder.lock_or_recover();
+        let mut embedder = match guard.take() {
+            Some(embedder) => embedder,
+            None => {
+                let model_dir = self.model_manager.get_model_path(EMBEDDING_MODEL_ID)?;
+                info!("Loading sentence-embedding model from {:?}", model_dir);
+                SentenceEmbedder::new(&model_dir)?
+            }
+        };
+        let vectors = embedder.embed(texts);
+        *guard = Some(embedder);
+        vectors
+    }
+}
+
+impl SessionManager {
+    /// Let the embedding indexer know there is new text to embed
+    pub(crate) fn notify_embedding_indexer(&self) {
+        if let Some(embedding_manager) = self.app_handle.try_state::<Arc<EmbeddingManager>>() {
+            embedding_manager.notify();
+        }
+    }
+
+    /// Embed with `embed` every transcript segment and notes paragraph that
+    /// has no current embedding of `model_id`. Returns the number of texts
+    /// embedded.
+    pub fn index_embeddings(
+        &self,
+        model_id: &str,
+        embed: impl FnMut(&[&str]) -> Result<Vec<Vec<f32>>>,
+    ) -> Result<usize> {
+        let mut conn = self.get_connection()?;
+        index_embeddings(&mut conn, model_id, embed)
+    }
+
+    /// The `limit` transcript segments and notes paragraphs closest to an
+    /// already embedded query, across all sessions not in the trash
+    pub fn semantic_search(
+        &self,
+        model_id: &str,
+        query: &[f32],
+        limit: usize,
+    ) -> Result<Vec<SemanticHit>> {
+        let conn = self.get_connection()?;
+        semantic_search(&conn, model_id, query, limit)
+    }
+}
+
+fn index_embeddings(
+    conn: &mut Connection,
+    model_id: &str,
+    mut embed: impl FnMut(&[&str]) -> Result<Vec<Vec<f32>>>,
+) -> Result<usize> {
+    purge_stale_embeddings(conn, model_id)?;
+
+    let mut embedded = 0;
+    loop {
+        let segments = unembedded_segments(conn, BATCH_SIZE)?;
+        if segments.is_empty() {
+            break;
+        }
+        let texts: Vec<&str> = segments.iter().map(|(_, text)| text.as_str()).collect();
+        let vectors = embed(&texts)?;
+        store_segment_embeddings(conn, model_id, &segments, &vectors)?;
+        embedded += segments.len();
+    }
+
+    for notes in unembedded_notes(conn)? {
+        let added: Vec<&str> = notes
+            .paragraphs
+            .iter()
+            .filter(|paragraph| !notes.embedded.contains(*paragraph))
+            .map(String::as_str)
+            .collect();
+        let mut vectors = Vec::with_capacity(added.len());
+        for batch in added.chunks(BATCH_SIZE) {
+            vectors.extend(embed(batch)?);
+        }
+        let added: Vec<(&str, Vec<f32>)> = added.into_iter().zip(vectors).collect();
+        store_note_embeddings(conn, model_id, &notes, &added)?;
+        embedded += added.len();
+    }
+    Ok(embedded)
+}
+
+/// Drop embeddings made by any other model than `model_id`, so everything is
+/// embedded again after a model change
+fn purge_stale_embeddings(conn: &mut Connection, model_id: &str) -> Result<()> {
+    let tx = conn.transaction()?;
+    let purged = tx.execute(
+        "DELETE FROM text_embeddings WHERE model_id != ?1",
+        params![model_id],
+    )?;
+    if purged > 0 {
+        tx.execute("UPDATE meeting_notes SET embedded_at = NULL", [])?;
+        info!("Purged {} embeddings of other models", purged);
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Segments whose current text has no embedding, newest first so a running
+/// meeting is searchable before older backlog
+fn unembedded_segments(conn: &Connection, limit: usize) -> Result<Vec<(i64, String)>> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT t.id, t.text FROM transcript_segments t
+         LEFT JOIN text_embeddings e ON e.segment_id = t.id
+         WHERE e.id IS NULL OR e.text != t.text
+         ORDER BY t.id DESC
+         LIMIT ?1",
+    )?;
+    let rows = stmt.query_map(params![limit as i64], |row| {
+        Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+    })?;
+    let mut segments = Vec::new();
+    for row in rows {
+        segments.push(row?);
+    }
+    Ok(segments)
+}
+
+/// Store the embeddings of segments as returned by `unembedded_segments`.
+/// Segments deleted in the meantime are skipped.
+fn store_segment_embeddings(
+    conn: &mut Connection,
+    model_id: &str,
+    segments: &[(i64, String)],
+    vectors: &[Vec<f32>],
+) -> Result<()> {
+    let now = Utc::now().timestamp();
+    let tx = conn.transaction()?;
+    {
+        let mut stmt = tx.prepare_cached(
+            "INSERT INTO text_embeddings (session_id, kind, segment_id, text, model_id, vector, created_at)
+             SELECT session_id, 'transcript', id, ?2, ?3, ?4, ?5 FROM transcript_segments WHERE id = ?1
+             ON CONFLICT(segment_id) DO UPDATE SET
+                text = excluded.text,
+                model_id = excluded.model_id,
+                vector = excluded.vector,
+                created_at = excluded.created_at",
+        )?;
+        for ((segment_id, text), vector) in segments.iter().zip(vectors) {
+            stmt.execute(params![
+                segment_id,
+                text,
+                model_id,
+                embedding_to_blob(vector),
+                now
+            ])?;
+        }
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Notes saved since their paragraphs were last embedded
+fn unembedded_notes(conn: &Connection) -> Result<Vec<PendingNotes>> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT session_id, updated_at, user_notes, enhanced_notes FROM meeting_notes
+         WHERE embedded_at IS NULL OR embedded_at != updated_at",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, i64>(1)?,
+            row.get::<_, Option<String>>(2)?,
+            row.get::<_, Option<String>>(3)?,
+        ))
+    })?;
+    let mut notes = Vec::new();
+    for row in rows {
+        let (session_id, updated_at, user_notes, enhanced_notes) = row?;
+        let mut paragraphs = note_paragraphs(user_notes.as_deref().unwrap_or(""));
+        for paragraph in note_paragraphs(enhanced_notes.as_deref().unwrap_or("")) {
+            if !paragraphs.contains(&paragraph) {
+                paragraphs.push(paragraph);
+            }
+        }
+        notes.push(PendingNotes {
+            session_id,
+            updated_at,
+            paragraphs,
+            embedded: HashSet::new(),
+        });
+    }
+
+    let mut stmt = conn.prepare_cached(
+        "SELECT text FROM text_embeddings WHERE session_id = ?1 AND kind = 'notes'",
+    )?;
+    for pending in &mut notes {
+        let rows = stmt.query_map(params![pending.session_id], |row| row.get::<_, String>(0))?;
+        for row in rows {
+            pending.embedded.insert(row?);
+        }
+    }
+    Ok(notes)
+}
+
+/// Bring a session's notes embeddings in line with `notes.paragraphs`:
+/// paragraphs no longer in the notes are dropped and `added` ones stored
+fn store_note_embeddings(
+    conn: &mut Connection,
+    model_id: &str,
+    notes: &PendingNotes,
+    added: &[(&str, Vec<f32>)],
+) -> Result<()> {
+    let now = Utc::now().timestamp();
+    let tx = conn.transaction()?;
+    {
+        let mut delete = tx.prepare_cached(
+            "DELETE FROM text_embeddings WHERE session_id = ?1 AND kind = 'notes' AND text = ?2",
+        )?;
+        for text in &notes.embedded {
+            if !notes.paragraphs.contains(text) {
+                delete.execute(params![notes.session_id, text])?;
+            }
+        }
+
+        let mut insert = tx.prepare_cached(
+            "INSERT INTO text_embeddings (session_id, kind, text, model_id, vector, created_at)
+             VALUES (?1, 'notes', ?2, ?3, ?4, ?5)",
+        )?;
+        for (text, vector) in added {
+            insert.execute(params![
+                notes.session_id,
+                text,
+                model_id,
+                embedding_to_blob(vector),
+                now
+            ])?;
+        }
+    }
+    tx.execute(
+        "UPDATE meeting_notes SET embedded_at = ?1 WHERE session_id = ?2",
+        params![notes.updated_at, notes.session_id],
+    )?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// Scans every stored vector but only keeps the `limit` best hits in a heap,
+/// so memory stays flat however large the index gets
+fn semantic_search(
+    conn: &Connection,
+    model_id: &str,
+    query: &[f32],
+    limit: usize,
+) -> Result<Vec<SemanticHit>> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT e.session_id, s.title, e.kind, e.segment_id, t.source, t.start_ms, e.text, e.vector
+         FROM text_embeddings e
+         JOIN sessions s ON s.id = e.session_id
+         LEFT JOIN transcript_segments t ON t.id = e.segment_id
+         WHERE e.model_id = ?1 AND s.status != 'deleted'",
+    )?;
+    let mut best = BinaryHeap::with_capacity(limit + 1);
+    let mut rows = stmt.query(params![model_id])?;
+    while let Some(row) = rows.next()? {
+        let vector = blob_to_embedding(&row.get::<_, Vec<u8>>(7)?);
+        // Both sides are L2-normalized
+        let score: f32 = vector.iter().zip(query).map(|(a, b)| a * b).sum();
+        if best.len() == limit
+            && best
+                .peek()
+                .is_none_or(|worst: &WorstFirst| worst.0.score >= score)
+        {
+            continue;
+        }
+        best.push(WorstFirst(SemanticHit {
+            session_id: row.get(0)?,
+            session_title: row.get(1)?,
+            kind: row.get(2)?,
+            segment_id: row.get(3)?,
+            source: row.get(4)?,
+            start_ms: row.get(5)?,
+            text: row.get(6)?,
+            score,
+        }));
+        if best.len() > limit {
+            best.pop();
+        }
+    }
+
+    let mut hits: Vec<SemanticHit> = best.into_iter().map(|hit| hit.0).collect();
+    hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+    Ok(hits)
+}
+
+/// Orders hits so the least similar one is at the top of a `BinaryHeap`
+struct WorstFirst(SemanticHit);
+
+impl PartialEq for WorstFirst {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for WorstFirst {}
+
+impl PartialOrd for WorstFirst {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for WorstFirst {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.score.total_cmp(&self.0.score)
+    }
+}
+
+/// Notes split into the paragraphs that are embedded for semantic search.
+/// Paragraphs end at blank lines, or at a line end once they reach
+/// `NOTES_PARAGRAPH_CHARS`. Repeated paragraphs and ones without any
+/// letters or digits (rules, empty bullets) are left out.
+fn note_paragraphs(text: &str) -> Vec<String> {
+    let mut paragraphs: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut flush = |current: &mut String| {
+        let paragraph = std::mem::take(current);
+        if paragraph.chars().any(char::is_alphanumeric) && !paragraphs.contains(&paragraph) {
+            paragraphs.push(paragraph);
+        }
+    };
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || current.len() + line.len() > NOTES_PARAGRAPH_CHARS {
+            flush(&mut current);
+        }
+        if !line.is_empty() {
+            if !current.is_empty() {
+                current.push('\n');
+            }
+            current.push_str(line);
+        }
+    }
+    flush(&mut current);
+    paragraphs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::managers::session::test_connection;
+
+    /// Embeds a text by which of a few topics it mentions
+    fn stub_embed(texts: &[&str]) -> Vec<Vec<f32>> {
+        texts
+            .iter()
+            .map(|text| {
+                let text = text.to_lowercase();
+                let vector: Vec<f32> = ["pricing", "hiring", "roadmap"]
+                    .iter()
+                    .map(|topic| text.matches(topic).count() as f32)
+                    .collect();
+                let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt().max(1.0);
+                vector.iter().map(|v| v / norm).collect()
+            })
+            .collect()
+    }
+
+    /// Index with `stub_embed`, returning the texts that were embedded
+    fn index(conn: &mut Connection, model_id: &str) -> Vec<String> {
+        let mut embedded = Vec::new();
+        let count = index_embeddings(conn, model_id, |texts| {
+            embedded.extend(texts.iter().map(|text| text.to_string()));
+            Ok(stub_embed(texts))
+        })
+        .unwrap();
+        assert_eq!(count, embedded.len());
+        embedded.sort();
+        embedded
+    }
+
+    fn embedded_notes(conn: &Connection) -> Vec<String> {
+        let mut stmt = conn
+            .prepare("SELECT text FROM text_embeddings WHERE kind = 'notes' ORDER BY text")
+            .unwrap();
+        let rows = stmt.query_map([], |row| row.get(0)).unwrap();
+        rows.collect::<rusqlite::Result<_>>().unwrap()
+    }
+
+    #[test]
+    fn test_index_embeddings_only_embeds_changes() {
+        let mut conn = test_connection();
+        conn.execute_batch(
+            "INSERT INTO sessions (id, title, started_at) VALUES ('call', 'Call', 0);
+             INSERT INTO transcript_segments (id, session_id, text, start_ms, end_ms, created_at) VALUES
+                (1, 'call', 'pricing first', 0, 1000, 0),
+                (2, 'call', 'hiring next', 1000, 2000, 0);
+             INSERT INTO meeting_notes (session_id, user_notes, created_at, updated_at)
+                VALUES ('call', 'Roadmap\n\nPricing', 0, 1);",
+        )
+        .unwrap();
+
+        assert_eq!(
+            index(&mut conn, "model"),
+            vec!["Pricing", "Roadmap", "hiring next", "pricing first"]
+        );
+        assert!(index(&mut conn, "model").is_empty());
+
+        conn.execute_batch(
+            "UPDATE transcript_segments SET text = 'pricing, corrected' WHERE id = 1;
+             DELETE FROM transcript_segments WHERE id = 2;",
+        )
+        .unwrap();
+        assert_eq!(index(&mut conn, "model"), vec!["pricing, corrected"]);
+        let segment_embeddings: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM text_embeddings WHERE kind = 'transcript'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(segment_embeddings, 1);
+
+        conn.execute(
+            "UPDATE meeting_notes SET user_notes = 'Roadmap\n\nHiring', updated_at = 2",
+            [],
+        )
+        .unwrap();
+        assert_eq!(index(&mut conn, "model"), vec!["Hiring"]);
+        assert_eq!(embedded_notes(&conn), vec!["Hiring", "Roadmap"]);
+
+        // A different model embeds everything again
+        assert_eq!(
+            index(&mut conn, "other-model"),
+            vec!["Hiring", "Roadmap", "pricing, corrected"]
+        );
+    }
+
+    #[test]
+    fn test_semantic_search_ranks_by_similarity() {
+        let mut conn = test_connection();
+        conn.execute_batch(
+            "INSERT INTO sessions (id, title, started_at) VALUES ('call', 'Call', 0);
+             INSERT INTO sessions (id, title, started_at, status) VALUES ('old', 'Old', 0, 'deleted');
+             INSERT INTO transcript_segments (session_id, text, start_ms, end_ms, created_at) VALUES
+                ('call', 'pricing and hiring', 0, 1000, 0),
+                ('call', 'pricing pricing pricing', 1000, 2000, 0),
+                ('call', 'the roadmap', 2000, 3000, 0),
+                ('old', 'pricing', 0, 1000, 0);",
+        )
+        .unwrap();
+        index(&mut conn, "model");
+        let query = stub_embed(&["pricing"]).remove(0);
+
+        let hits = semantic_search(&conn, "model", &query, 2).unwrap();
+        let texts: Vec<_> = hits.iter().map(|hit| hit.text.as_str()).collect();
+        assert_eq!(texts, vec!["pricing pricing pricing", "pricing and hiring"]);
+        assert!(hits[0].score > hits[1].score);
+        assert_eq!(hits[0].start_ms, Some(1000));
+
+        let hits = semantic_search(&conn, "model", &query, 10).unwrap();
+        assert_eq!(hits.len(), 3);
+        assert_eq!(hits[2].text, "the roadmap");
+
+        assert!(semantic_search(&conn, "model", &query, 0)
+            .unwrap()
+            .is_empty());
+        assert!(semantic_search(&conn, "other-model", &query, 10)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_note_paragraphs() {
+        let notes = "## Pricing\n- $49 per seat\n- annual only\n\n---\n\n## Pricing\n- $49 per seat\n- annual only\n\nShip in May";
+        assert_eq!(
+            note_paragraphs(notes),
+            vec!["## Pricing\n- $49 per seat\n- annual only", "Ship in May"]
+        );
+
+        let long = format!("{}\n{}", "a".repeat(600), "b".repeat(600));
+        assert_eq!(
+            note_paragraphs(&long),
+            vec!["a".repeat(600), "b".repeat(600)]
+        );
+    }
+}
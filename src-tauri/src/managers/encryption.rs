@@ -0,0 +1,394 @@
+use anyhow::Result;
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use rusqlite::{params, Connection, OpenFlags};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use zeroize::Zeroizing;
+
+/// Opt-in encryption at rest. sessions.db is encrypted by SQLCipher and every
+/// attachment and recording file with XChaCha20-Poly1305. Both keys are derived
+/// from the user's passphrase with Argon2id; the salt and KDF parameters live in
+/// `encryption.json` next to sessions.db, whose presence means encryption is on.
+pub const KEY_FILE_NAME: &str = "encryption.json";
+const KEY_FILE_VERSION: u32 = 1;
+/// Encrypted files start with this marker, then a 24-byte nonce
+const FILE_MAGIC: &[u8] = b"TALKYENC\x01";
+const NONCE_LEN: usize = 24;
+const SALT_LEN: usize = 16;
+/// Encrypted into the key file so a wrong passphrase is caught before it
+/// touches the database
+const VERIFIER: &[u8] = b"talky-storage-key";
+// Argon2id with 64 MiB and 3 passes, OWASP's baseline recommendation
+const ARGON2_M_COST: u32 = 64 * 1024;
+const ARGON2_T_COST: u32 = 3;
+const ARGON2_P_COST: u32 = 1;
+
+/// A conversion between the plaintext and encrypted layouts that has started but
+/// not finished. Recorded in the key file so unlocking after a crash can resume it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageMigration {
+    Encrypt,
+    Decrypt,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KeyFile {
+    version: u32,
+    kdf: String,
+    salt: String,
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+    verifier: String,
+    #[serde(default)]
+    pub pending: Option<StorageMigration>,
+}
+
+impl KeyFile {
+    fn path(dir: &Path) -> PathBuf {
+        dir.join(KEY_FILE_NAME)
+    }
+
+    pub fn exists(dir: &Path) -> bool {
+        Self::path(dir).exists()
+    }
+
+    pub fn load(dir: &Path) -> Result<Option<Self>> {
+        let path = Self::path(dir);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let key_file: Self = serde_json::from_str(&fs::read_to_string(path)?)?;
+        if key_file.version > KEY_FILE_VERSION || key_file.kdf != "argon2id" {
+            return Err(anyhow::anyhow!(
+                "{} was written by a newer version of Talky",
+                KEY_FILE_NAME
+            ));
+        }
+        Ok(Some(key_file))
+    }
+
+    /// Write the key file, replacing any previous one in a single rename
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        let path = Self::path(dir);
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_vec_pretty(self)?)?;
+        fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+
+    pub fn remove(dir: &Path) -> Result<()> {
+        fs::remove_file(Self::path(dir))?;
+        Ok(())
+    }
+
+    /// Derive a key from a new passphrase with a fresh salt
+    pub fn create(passphrase: &str) -> Result<(Self, StorageKey)> {
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let key = derive_key(
+            passphrase,
+            &salt,
+            ARGON2_M_COST,
+            ARGON2_T_COST,
+            ARGON2_P_COST,
+        )?;
+        let key_file = Self {
+            version: KEY_FILE_VERSION,
+            kdf: "argon2id".to_string(),
+            salt: to_hex(&salt),
+            m_cost: ARGON2_M_COST,
+            t_cost: ARGON2_T_COST,
+            p_cost: ARGON2_P_COST,
+            verifier: to_hex(&key.encrypt(VERIFIER)?),
+            pending: None,
+        };
+        Ok((key_file, key))
+    }
+
+    /// Derive the key for `passphrase`, failing if it isn't the right one
+    pub fn unlock(&self, passphrase: &str) -> Result<StorageKey> {
+        let salt = from_hex(&self.salt)?;
+        let key = derive_key(passphrase, &salt, self.m_cost, self.t_cost, self.p_cost)?;
+        let verified = key
+            .decrypt(&from_hex(&self.verifier)?)
+            .is_ok_and(|v| v == VERIFIER);
+        if !verified {
+            return Err(anyhow::anyhow!("Wrong passphrase"));
+        }
+        Ok(key)
+    }
+}
+
+pub struct StorageKey {
+    db_key: Zeroizing<[u8; 32]>,
+    file_key: Zeroizing<[u8; 32]>,
+}
+
+impl StorageKey {
+    /// The database key as a raw-key literal for SQLCipher's `PRAGMA key`
+    pub fn sqlcipher_key(&self) -> Zeroizing<String> {
+        Zeroizing::new(format!("x'{}'", to_hex(self.db_key.as_slice())))
+    }
+
+    fn cipher(&self) -> XChaCha20Poly1305 {
+        XChaCha20Poly1305::new(self.file_key.as_slice().into())
+    }
+
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher()
+            .encrypt(&nonce, plaintext)
+            .map_err(|_| anyhow::anyhow!("Encryption failed"))?;
+
+        let mut out = Vec::with_capacity(FILE_MAGIC.len() + NONCE_LEN + ciphertext.len());
+        out.extend_from_slice(FILE_MAGIC);
+        out.extend_from_slice(&nonce);
+        out.extend_from_slice(&ciphertext);
+        Ok(out)
+    }
+
+    pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let body = data
+            .strip_prefix(FILE_MAGIC)
+            .filter(|b| b.len() >= NONCE_LEN)
+            .ok_or_else(|| anyhow::anyhow!("Not an encrypted file"))?;
+        let (nonce, ciphertext) = body.split_at(NONCE_LEN);
+        self.cipher()
+            .decrypt(XNonce::from_slice(nonce), ciphertext)
+            .map_err(|_| {
+                anyhow::anyhow!("Decryption failed, the file is damaged or the key is wrong")
+            })
+    }
+}
+
+fn derive_key(
+    passphrase: &str,
+    salt: &[u8],
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+) -> Result<StorageKey> {
+    let params = Params::new(m_cost, t_cost, p_cost, Some(64))
+        .map_err(|e| anyhow::anyhow!("Invalid key derivation parameters: {}", e))?;
+    let mut output = Zeroizing::new([0u8; 64]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, output.as_mut_slice())
+        .map_err(|e| anyhow::anyhow!("Key derivation failed: {}", e))?;
+
+    // First half keys the database, second half the attachment files
+    let mut db_key = Zeroizing::new([0u8; 32]);
+    let mut file_key = Zeroizing::new([0u8; 32]);
+    db_key.copy_from_slice(&output[..32]);
+    file_key.copy_from_slice(&output[32..]);
+    Ok(StorageKey { db_key, file_key })
+}
+
+pub fn is_encrypted_data(data: &[u8]) -> bool {
+    data.starts_with(FILE_MAGIC)
+}
+
+/// Whether the file at `path` is encrypted, reading only its header
+pub fn is_encrypted_file(path: &Path) -> Result<bool> {
+    let mut header = Vec::with_capacity(FILE_MAGIC.len());
+    fs::File::open(path)?
+        .take(FILE_MAGIC.len() as u64)
+        .read_to_end(&mut header)?;
+    Ok(is_encrypted_data(&header))
+}
+
+/// Read a file, decrypting it if it is encrypted
+pub fn read_file(path: &Path, key: Option<&StorageKey>) -> Result<Vec<u8>> {
+    let data = fs::read(path)?;
+    if !is_encrypted_data(&data) {
+        return Ok(data);
+    }
+    key.ok_or_else(|| anyhow::anyhow!("Storage is locked"))?
+        .decrypt(&data)
+}
+
+/// Encrypt a file in place. Returns false if it was already encrypted.
+pub fn encrypt_file_in_place(path: &Path, key: &StorageKey) -> Result<bool> {
+    let data = fs::read(path)?;
+    if is_encrypted_data(&data) {
+        return Ok(false);
+    }
+    replace_file(path, &key.encrypt(&data)?)?;
+    Ok(true)
+}
+
+/// Decrypt a file in place. Returns false if it was not encrypted.
+pub fn decrypt_file_in_place(path: &Path, key: &StorageKey) -> Result<bool> {
+    let data = fs::read(path)?;
+    if !is_encrypted_data(&data) {
+        return Ok(false);
+    }
+    replace_file(path, &key.decrypt(&data)?)?;
+    Ok(true)
+}
+
+fn replace_file(path: &Path, contents: &[u8]) -> Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    fs::write(&tmp, contents)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Whether this build links SQLCipher (the `storage-encryption` feature).
+/// Without it sessions.db can't be encrypted or opened once it is.
+pub fn is_supported() -> bool {
+    cfg!(feature = "storage-encryption")
+}
+
+pub fn ensure_supported() -> Result<()> {
+    if !is_supported() {
+        return Err(anyhow::anyhow!(
+            "This build of Talky doesn't support storage encryption"
+        ));
+    }
+    Ok(())
+}
+
+/// Open a database outside a connection pool, keyed when it is encrypted
+pub fn open_database(
+    path: &Path,
+    flags: OpenFlags,
+    key: Option<&StorageKey>,
+) -> Result<Connection> {
+    if key.is_some() {
+        ensure_supported()?;
+    }
+    let conn = Connection::open_with_flags(path, flags)?;
+    if let Some(key) = key {
+        conn.pragma_update(None, "key", key.sqlcipher_key().as_str())?;
+    }
+    Ok(conn)
+}
+
+/// Rewrite a database with a different key (None = plaintext) using
+/// SQLCipher's `sqlcipher_export`, then swap it in. Nothing else may have it open.
+pub fn convert_database(
+    path: &Path,
+    from: Option<&StorageKey>,
+    to: Option<&StorageKey>,
+) -> Result<()> {
+    let mut converted = path.as_os_str().to_owned();
+    converted.push(".converting");
+    let converted = PathBuf::from(converted);
+    let _ = fs::remove_file(&converted);
+
+    {
+        let conn = open_database(path, OpenFlags::default(), from)?;
+        // Fold the WAL into the main file so nothing is left behind to replay
+        // against the converted database
+        conn.pragma_update(None, "journal_mode", "DELETE")?;
+
+        let to_key = to.map(|k| k.sqlcipher_key());
+        conn.execute(
+            "ATTACH DATABASE ?1 AS converted KEY ?2",
+            params![
+                converted.to_string_lossy().to_string(),
+                to_key.as_ref().map(|k| k.as_str()).unwrap_or("")
+            ],
+        )?;
+        conn.query_row("SELECT sqlcipher_export('converted')", [], |_| Ok(()))?;
+        // sqlcipher_export copies schema and rows but not the migration version
+        let version: i64 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+        conn.execute_batch(&format!("PRAGMA converted.user_version = {};", version))?;
+        conn.execute("DETACH DATABASE converted", [])?;
+    }
+
+    fs::rename(&converted, path)?;
+    for suffix in ["-wal", "-shm"] {
+        let mut sidecar = path.as_os_str().to_owned();
+        sidecar.push(suffix);
+        let _ = fs::remove_file(PathBuf::from(sidecar));
+    }
+    Ok(())
+}
+
+/// Whether a database file is an unencrypted SQLite database
+pub fn is_plaintext_database(path: &Path) -> bool {
+    let mut header = [0u8; 16];
+    fs::File::open(path)
+        .and_then(|mut f| f.read_exact(&mut header))
+        .is_ok_and(|_| &header == b"SQLite format 3\0")
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(hex: &str) -> Result<Vec<u8>> {
+    if !hex.is_ascii() || !hex.len().is_multiple_of(2) {
+        return Err(anyhow::anyhow!("Invalid hex string"));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| Ok(u8::from_str_radix(&hex[i..i + 2], 16)?))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Cheap parameters so the tests don't spend seconds in Argon2
+    fn test_key(passphrase: &str) -> StorageKey {
+        derive_key(passphrase, b"0123456789abcdef", 8, 1, 1).unwrap()
+    }
+
+    #[test]
+    fn test_encrypt_roundtrip() {
+        let key = test_key("correct horse");
+        let data = key.encrypt(b"meeting notes").unwrap();
+        assert!(is_encrypted_data(&data));
+        assert_eq!(key.decrypt(&data).unwrap(), b"meeting notes");
+
+        assert!(test_key("battery staple").decrypt(&data).is_err());
+        assert!(key.decrypt(b"meeting notes").is_err());
+    }
+
+    #[test]
+    fn test_derived_keys_are_distinct_and_stable() {
+        let key = test_key("correct horse");
+        assert_ne!(*key.db_key, *key.file_key);
+        assert_eq!(*test_key("correct horse").db_key, *key.db_key);
+        assert!(key.sqlcipher_key().starts_with("x'"));
+        assert_eq!(key.sqlcipher_key().len(), 67);
+    }
+
+    #[test]
+    fn test_file_encryption_in_place() {
+        let key = test_key("correct horse");
+        let path =
+            std::env::temp_dir().join(format!("talky-encryption-test-{}", uuid::Uuid::new_v4()));
+        fs::write(&path, b"RIFF").unwrap();
+        assert!(!is_encrypted_file(&path).unwrap());
+
+        assert!(encrypt_file_in_place(&path, &key).unwrap());
+        assert!(is_encrypted_file(&path).unwrap());
+        assert!(!encrypt_file_in_place(&path, &key).unwrap());
+        assert_eq!(read_file(&path, Some(&key)).unwrap(), b"RIFF");
+        assert!(read_file(&path, None).is_err());
+
+        assert!(decrypt_file_in_place(&path, &key).unwrap());
+        assert_eq!(fs::read(&path).unwrap(), b"RIFF");
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_hex_roundtrip() {
+        assert_eq!(from_hex(&to_hex(&[0, 15, 255])).unwrap(), vec![0, 15, 255]);
+        assert!(from_hex("abc").is_err());
+    }
+}
@@ -0,0 +1,547 @@
+use crate::managers::session::{build_fts_query, Session, SessionManager};
+use anyhow::Result;
+use chrono::Utc;
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct Folder {
+    pub id: String,
+    pub name: String,
+    pub color: Option<String>,
+    pub sort_order: i32,
+    pub created_at: i64,
+    /// None for top-level folders
+    pub parent_id: Option<String>,
+}
+
+/// Filters a smart folder applies; every field that is set must match.
+/// Dates are unix seconds on `started_at`, `date_to` exclusive.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct SmartFolderQuery {
+    /// Sessions must carry all of these tags
+    pub tag_ids: Vec<String>,
+    pub date_from: Option<i64>,
+    pub date_to: Option<i64>,
+    pub environment_id: Option<String>,
+    /// Matched against titles, notes, transcripts and attachment text like `search_sessions`
+    pub text: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct SmartFolder {
+    pub id: String,
+    pub name: String,
+    pub color: Option<String>,
+    pub query: SmartFolderQuery,
+    pub sort_order: i32,
+    pub created_at: i64,
+}
+
+impl SessionManager {
+    pub fn create_folder(
+        &self,
+        name: String,
+        color: Option<String>,
+        parent_id: Option<String>,
+    ) -> Result<Folder> {
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now().timestamp();
+        let conn = self.get_connection()?;
+
+        if let Some(parent_id) = &parent_id {
+            let exists = conn
+                .query_row(
+                    "SELECT 1 FROM folders WHERE id = ?1",
+                    params![parent_id],
+                    |_| Ok(()),
+                )
+                .optional()?
+                .is_some();
+            if !exists {
+                return Err(anyhow::anyhow!("Folder not found: {}", parent_id));
+            }
+        }
+
+        // Get max sort_order
+        let max_order: i32 = conn
+            .query_row(
+                "SELECT COALESCE(MAX(sort_order), 0) FROM folders",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap_or(0);
+
+        conn.execute(
+            "INSERT INTO folders (id, name, color, sort_order, created_at, parent_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![id, name, color, max_order + 1, now, parent_id],
+        )?;
+
+        Ok(Folder {
+            id,
+            name,
+            color,
+            sort_order: max_order + 1,
+            created_at: now,
+            parent_id,
+        })
+    }
+
+    pub fn update_folder(
+        &self,
+        folder_id: &str,
+        name: String,
+        color: Option<String>,
+    ) -> Result<()> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "UPDATE folders SET name = ?1, color = ?2 WHERE id = ?3",
+            params![name, color, folder_id],
+        )?;
+        Ok(())
+    }
+
+    /// Delete a folder, moving its sessions and subfolders up to its parent
+    /// (unfiled and top-level for a top-level folder)
+    pub fn delete_folder(&self, folder_id: &str) -> Result<()> {
+        let mut conn = self.get_connection()?;
+        let tx = conn.transaction()?;
+        let parent_id: Option<String> = tx
+            .query_row(
+                "SELECT parent_id FROM folders WHERE id = ?1",
+                params![folder_id],
+                |row| row.get("parent_id"),
+            )
+            .optional()?
+            .flatten();
+        tx.execute(
+            "UPDATE sessions SET folder_id = ?1 WHERE folder_id = ?2",
+            params![parent_id, folder_id],
+        )?;
+        tx.execute(
+            "UPDATE folders SET parent_id = ?1 WHERE parent_id = ?2",
+            params![parent_id, folder_id],
+        )?;
+        tx.execute("DELETE FROM folders WHERE id = ?1", params![folder_id])?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// All folders, flat. Build the tree from `parent_id`.
+    pub fn get_folders(&self) -> Result<Vec<Folder>> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, name, color, sort_order, created_at, parent_id FROM folders ORDER BY sort_order ASC",
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            Ok(Folder {
+                id: row.get("id")?,
+                name: row.get("name")?,
+                color: row.get("color")?,
+                sort_order: row.get("sort_order")?,
+                created_at: row.get("created_at")?,
+                parent_id: row.get("parent_id")?,
+            })
+        })?;
+
+        let mut folders = Vec::new();
+        for row in rows {
+            folders.push(row?);
+        }
+        Ok(folders)
+    }
+
+    /// Move a folder under `parent_id` (None for top level). Refuses to move a
+    /// folder into itself or one of its own subfolders.
+    pub fn move_folder(&self, folder_id: &str, parent_id: Option<String>) -> Result<()> {
+        let folders = self.get_folders()?;
+        if !folders.iter().any(|f| f.id == folder_id) {
+            return Err(anyhow::anyhow!("Folder not found: {}", folder_id));
+        }
+        if let Some(parent_id) = &parent_id {
+            if !folders.iter().any(|f| &f.id == parent_id) {
+                return Err(anyhow::anyhow!("Folder not found: {}", parent_id));
+            }
+            if folder_move_creates_cycle(&folders, folder_id, parent_id) {
+                return Err(anyhow::anyhow!(
+                    "A folder can't be moved into itself or one of its subfolders"
+                ));
+            }
+        }
+
+        let conn = self.get_connection()?;
+        conn.execute(
+            "UPDATE folders SET parent_id = ?1 WHERE id = ?2",
+            params![parent_id, folder_id],
+        )?;
+        Ok(())
+    }
+
+    pub fn move_session_to_folder(
+        &self,
+        session_id: &str,
+        folder_id: Option<String>,
+    ) -> Result<()> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "UPDATE sessions SET folder_id = ?1 WHERE id = ?2",
+            params![folder_id, session_id],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_sessions_by_folder(&self, folder_id: Option<String>) -> Result<Vec<Session>> {
+        let conn = self.get_connection()?;
+
+        let query = if folder_id.is_some() {
+            "SELECT id, title, started_at, ended_at, status, folder_id, environment_id, deleted_at
+             FROM sessions WHERE folder_id = ?1 AND status != 'deleted' ORDER BY started_at DESC"
+        } else {
+            "SELECT id, title, started_at, ended_at, status, folder_id, environment_id, deleted_at
+             FROM sessions WHERE folder_id IS NULL AND status != 'deleted' ORDER BY started_at DESC"
+        };
+
+        let mut stmt = conn.prepare(query)?;
+        let mut sessions = Vec::new();
+
+        let map_row = |row: &rusqlite::Row| -> rusqlite::Result<Session> {
+            Ok(Session {
+                id: row.get("id")?,
+                title: row.get("title")?,
+                started_at: row.get("started_at")?,
+                ended_at: row.get("ended_at")?,
+                status: row.get("status")?,
+                folder_id: row.get("folder_id")?,
+                environment_id: row.get("environment_id")?,
+                deleted_at: row.get("deleted_at")?,
+            })
+        };
+
+        if let Some(fid) = &folder_id {
+            let rows = stmt.query_map(params![fid], map_row)?;
+            for row in rows {
+                sessions.push(row?);
+            }
+        } else {
+            let rows = stmt.query_map([], map_row)?;
+            for row in rows {
+                sessions.push(row?);
+            }
+        }
+
+        Ok(sessions)
+    }
+
+    /// Sessions in a folder and all of its subfolders
+    pub fn get_sessions_in_folder_tree(&self, folder_id: &str) -> Result<Vec<Session>> {
+        let conn = self.get_connection()?;
+        // UNION (not UNION ALL) stops the walk if the tree ever contains a cycle
+        let mut stmt = conn.prepare(
+            "WITH RECURSIVE tree(id) AS (
+                SELECT ?1
+                UNION
+                SELECT f.id FROM folders f JOIN tree t ON f.parent_id = t.id
+             )
+             SELECT id, title, started_at, ended_at, status, folder_id, environment_id, deleted_at
+             FROM sessions
+             WHERE folder_id IN (SELECT id FROM tree) AND status != 'deleted'
+             ORDER BY started_at DESC",
+        )?;
+
+        let rows = stmt.query_map(params![folder_id], |row| {
+            Ok(Session {
+                id: row.get("id")?,
+                title: row.get("title")?,
+                started_at: row.get("started_at")?,
+                ended_at: row.get("ended_at")?,
+                status: row.get("status")?,
+                folder_id: row.get("folder_id")?,
+                environment_id: row.get("environment_id")?,
+                deleted_at: row.get("deleted_at")?,
+            })
+        })?;
+
+        let mut sessions = Vec::new();
+        for row in rows {
+            sessions.push(row?);
+        }
+        Ok(sessions)
+    }
+
+    // ==================== Smart Folders ====================
+
+    pub fn create_smart_folder(
+        &self,
+        name: String,
+        color: Option<String>,
+        query: SmartFolderQuery,
+    ) -> Result<SmartFolder> {
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now().timestamp();
+        let conn = self.get_connection()?;
+
+        let max_order: i32 = conn
+            .query_row(
+                "SELECT COALESCE(MAX(sort_order), 0) FROM smart_folders",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap_or(0);
+
+        conn.execute(
+            "INSERT INTO smart_folders (id, name, color, query, sort_order, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![id, name, color, serde_json::to_string(&query)?, max_order + 1, now],
+        )?;
+
+        Ok(SmartFolder {
+            id,
+            name,
+            color,
+            query,
+            sort_order: max_order + 1,
+            created_at: now,
+        })
+    }
+
+    pub fn update_smart_folder(
+        &self,
+        smart_folder_id: &str,
+        name: String,
+        color: Option<String>,
+        query: SmartFolderQuery,
+    ) -> Result<()> {
+        let conn = self.get_connection()?;
+        let updated = conn.execute(
+            "UPDATE smart_folders SET name = ?1, color = ?2, query = ?3 WHERE id = ?4",
+            params![name, color, serde_json::to_string(&query)?, smart_folder_id],
+        )?;
+        if updated == 0 {
+            return Err(anyhow::anyhow!(
+                "Smart folder not found: {}",
+                smart_folder_id
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn delete_smart_folder(&self, smart_folder_id: &str) -> Result<()> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "DELETE FROM smart_folders WHERE id = ?1",
+            params![smart_folder_id],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_smart_folders(&self) -> Result<Vec<SmartFolder>> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, name, color, query, sort_order, created_at FROM smart_folders ORDER BY sort_order ASC",
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            let query: String = row.get("query")?;
+            Ok(SmartFolder {
+                id: row.get("id")?,
+                name: row.get("name")?,
+                color: row.get("color")?,
+                // A query this build can't read shows everything rather than failing the list
+                query: serde_json::from_str(&query).unwrap_or_default(),
+                sort_order: row.get("sort_order")?,
+                created_at: row.get("created_at")?,
+            })
+        })?;
+
+        let mut smart_folders = Vec::new();
+        for row in rows {
+            smart_folders.push(row?);
+        }
+        Ok(smart_folders)
+    }
+
+    pub fn get_smart_folder_sessions(&self, smart_folder_id: &str) -> Result<Vec<Session>> {
+        let query = self
+            .get_smart_folders()?
+            .into_iter()
+            .find(|f| f.id == smart_folder_id)
+            .map(|f| f.query)
+            .ok_or_else(|| anyhow::anyhow!("Smart folder not found: {}", smart_folder_id))?;
+        self.evaluate_smart_folder_query(&query)
+    }
+
+    /// Sessions matching a smart folder query, newest first. Also used to preview
+    /// a query before it is saved.
+    pub fn evaluate_smart_folder_query(&self, query: &SmartFolderQuery) -> Result<Vec<Session>> {
+        let (filter, values) = smart_folder_filter(query);
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT s.id, s.title, s.started_at, s.ended_at, s.status, s.folder_id, s.environment_id, s.deleted_at
+             FROM sessions s
+             WHERE s.status != 'deleted'{}
+             ORDER BY s.started_at DESC",
+            filter
+        ))?;
+
+        let rows = stmt.query_map(rusqlite::params_from_iter(values), |row| {
+            Ok(Session {
+                id: row.get("id")?,
+                title: row.get("title")?,
+                started_at: row.get("started_at")?,
+                ended_at: row.get("ended_at")?,
+                status: row.get("status")?,
+                folder_id: row.get("folder_id")?,
+                environment_id: row.get("environment_id")?,
+                deleted_at: row.get("deleted_at")?,
+            })
+        })?;
+
+        let mut sessions = Vec::new();
+        for row in rows {
+            sessions.push(row?);
+        }
+        Ok(sessions)
+    }
+}
+
+/// Whether making `parent_id` the parent of `folder_id` would put the folder
+/// inside itself, i.e. `parent_id` is the folder or one of its descendants
+fn folder_move_creates_cycle(folders: &[Folder], folder_id: &str, parent_id: &str) -> bool {
+    let parents: std::collections::HashMap<&str, Option<&str>> = folders
+        .iter()
+        .map(|f| (f.id.as_str(), f.parent_id.as_deref()))
+        .collect();
+    let mut current = Some(parent_id);
+    let mut steps = 0;
+    while let Some(id) = current {
+        if id == folder_id {
+            return true;
+        }
+        // An existing cycle would loop forever; treat it as one
+        steps += 1;
+        if steps > parents.len() {
+            return true;
+        }
+        current = parents.get(id).copied().flatten();
+    }
+    false
+}
+
+/// SQL conditions (each prefixed with AND, against `sessions s`) and their
+/// positional parameters for a smart folder query
+pub(crate) fn smart_folder_filter(
+    query: &SmartFolderQuery,
+) -> (String, Vec<rusqlite::types::Value>) {
+    use rusqlite::types::Value;
+
+    let mut filter = String::new();
+    let mut values: Vec<Value> = Vec::new();
+    fn bind(values: &mut Vec<Value>, value: Value) -> usize {
+        values.push(value);
+        values.len()
+    }
+
+    for tag_id in &query.tag_ids {
+        let n = bind(&mut values, Value::Text(tag_id.clone()));
+        filter.push_str(&format!(
+            " AND EXISTS (SELECT 1 FROM session_tags st WHERE st.session_id = s.id AND st.tag_id = ?{})",
+            n
+        ));
+    }
+    if let Some(from) = query.date_from {
+        let n = bind(&mut values, Value::Integer(from));
+        filter.push_str(&format!(" AND s.started_at >= ?{}", n));
+    }
+    if let Some(to) = query.date_to {
+        let n = bind(&mut values, Value::Integer(to));
+        filter.push_str(&format!(" AND s.started_at < ?{}", n));
+    }
+    if let Some(environment_id) = &query.environment_id {
+        let n = bind(&mut values, Value::Text(environment_id.clone()));
+        filter.push_str(&format!(" AND s.environment_id = ?{}", n));
+    }
+    if let Some(text) = query
+        .text
+        .as_deref()
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+    {
+        let like = bind(&mut values, Value::Text(format!("%{}%", text)));
+        // An empty phrase never matches, so queries of only operators fall back to LIKE
+        let fts = bind(
+            &mut values,
+            Value::Text(build_fts_query(text).unwrap_or_else(|| "\"\"".to_string())),
+        );
+        filter.push_str(&format!(
+            " AND (s.title LIKE ?{like}
+                OR s.id IN (
+                    SELECT session_id FROM meeting_notes
+                    WHERE user_notes LIKE ?{like} OR enhanced_notes LIKE ?{like}
+                )
+                OR s.id IN (
+                    SELECT ts.session_id FROM transcript_fts
+                    JOIN transcript_segments ts ON ts.id = transcript_fts.rowid
+                    WHERE transcript_fts MATCH ?{fts}
+                )
+                OR s.id IN (
+                    SELECT session_id FROM attachments_fts WHERE attachments_fts MATCH ?{fts}
+                ))"
+        ));
+    }
+
+    (filter, values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn folder(id: &str, parent_id: Option<&str>) -> Folder {
+        Folder {
+            id: id.to_string(),
+            name: id.to_string(),
+            color: None,
+            sort_order: 0,
+            created_at: 0,
+            parent_id: parent_id.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_folder_move_creates_cycle() {
+        // client -> project -> series, plus an unrelated top-level folder
+        let folders = vec![
+            folder("client", None),
+            folder("project", Some("client")),
+            folder("series", Some("project")),
+            folder("other", None),
+        ];
+        assert!(folder_move_creates_cycle(&folders, "client", "client"));
+        assert!(folder_move_creates_cycle(&folders, "client", "series"));
+        assert!(!folder_move_creates_cycle(&folders, "series", "client"));
+        assert!(!folder_move_creates_cycle(&folders, "client", "other"));
+    }
+
+    #[test]
+    fn test_smart_folder_filter_binds_each_condition() {
+        let (filter, values) = smart_folder_filter(&SmartFolderQuery::default());
+        assert!(filter.is_empty());
+        assert!(values.is_empty());
+
+        let (filter, values) = smart_folder_filter(&SmartFolderQuery {
+            tag_ids: vec!["a".to_string(), "b".to_string()],
+            date_from: Some(100),
+            environment_id: Some("env".to_string()),
+            text: Some("  ".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(values.len(), 4);
+        assert!(filter.contains("st.tag_id = ?2"));
+        assert!(filter.contains("s.started_at >= ?3"));
+        assert!(filter.contains("s.environment_id = ?4"));
+        assert!(!filter.contains("LIKE"));
+    }
+}
@@ -1,5 +1,14 @@
+pub mod action_items;
 pub mod audio;
+pub mod backup;
+pub mod bookmarks;
+pub mod bundle;
+pub mod calendar;
+pub mod embedding;
+pub mod encryption;
+pub mod folders;
 pub mod history;
 pub mod model;
+pub mod retrieval;
 pub mod session;
 pub mod transcription;
@@ -10,16 +10,40 @@ use std::collections::HashMap;
 use std::fs;
 use std::fs::File;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 use tar::Archive;
 use tauri::{AppHandle, Emitter, Manager};
 
+/// Model behind semantic search, see `EmbeddingManager`
+pub const EMBEDDING_MODEL_ID: &str = "all-minilm-l6-v2";
+
+/// Model behind mic-channel diarization, see `SpeakerEmbedder`
+pub const SPEAKER_MODEL_ID: &str = "wespeaker-resnet34";
+
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub enum EngineType {
     Whisper,
     Parakeet,
     Moonshine,
+    /// Sentence embeddings for semantic search, not a transcription engine
+    Embedding,
+    /// Voice embeddings for diarization, not a transcription engine
+    SpeakerEmbedding,
+}
+
+impl EngineType {
+    pub fn is_transcription(&self) -> bool {
+        !matches!(self, EngineType::Embedding | EngineType::SpeakerEmbedding)
+    }
+
+    /// Files a directory-based model of this engine can't be loaded without
+    pub fn required_files(&self) -> &'static [&'static str] {
+        match self {
+            EngineType::Embedding => &["model.onnx", "vocab.txt"],
+            _ => &[],
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
@@ -119,6 +143,48 @@ impl ModelManager {
             },
         );
 
+        // Sentence-embedding model for semantic search (directory with
+        // model.onnx and vocab.txt), fastembed's ONNX export of all-MiniLM-L6-v2
+        available_models.insert(
+            EMBEDDING_MODEL_ID.to_string(),
+            ModelInfo {
+                id: EMBEDDING_MODEL_ID.to_string(),
+                name: "MiniLM L6".to_string(),
+                description: "Semantic search over transcripts and notes.".to_string(),
+                filename: "all-minilm-l6-v2".to_string(), // Directory name
+                url: Some("https://storage.googleapis.com/qdrant-fastembed/sentence-transformers-all-MiniLM-L6-v2.tar.gz".to_string()),
+                size_mb: 86,
+                is_downloaded: false,
+                is_downloading: false,
+                partial_size: 0,
+                is_directory: true,
+                engine_type: EngineType::Embedding,
+                accuracy_score: 0.0,
+                speed_score: 0.0,
+            },
+        );
+
+        // Speaker-embedding model for diarizing the mic channel (WeSpeaker
+        // ResNet34 export, fbank features in, 256-dim embedding out)
+        available_models.insert(
+            SPEAKER_MODEL_ID.to_string(),
+            ModelInfo {
+                id: SPEAKER_MODEL_ID.to_string(),
+                name: "WeSpeaker ResNet34".to_string(),
+                description: "Tells apart speakers sharing the microphone.".to_string(),
+                filename: "wespeaker_en_voxceleb_resnet34.onnx".to_string(),
+                url: Some("https://github.com/k2-fsa/sherpa-onnx/releases/download/speaker-recongition-models/wespeaker_en_voxceleb_resnet34.onnx".to_string()),
+                size_mb: 26,
+                is_downloaded: false,
+                is_downloading: false,
+                partial_size: 0,
+                is_directory: false,
+                engine_type: EngineType::SpeakerEmbedding,
+                accuracy_score: 0.0,
+                speed_score: 0.0,
+            },
+        );
+
         let manager = Self {
             app_handle: app_handle.clone(),
             models_dir,
@@ -137,9 +203,26 @@ impl ModelManager {
         Ok(manager)
     }
 
+    /// Transcription models
     pub fn get_available_models(&self) -> Vec<ModelInfo> {
         let models = self.available_models.lock_or_recover();
-        models.values().cloned().collect()
+        models
+            .values()
+            .filter(|model| model.engine_type.is_transcription())
+            .cloned()
+            .collect()
+    }
+
+    /// Models behind optional features (semantic search, diarization)
+    pub fn get_feature_models(&self) -> Vec<ModelInfo> {
+        let models = self.available_models.lock_or_recover();
+        let mut feature_models: Vec<ModelInfo> = models
+            .values()
+            .filter(|model| !model.engine_type.is_transcription())
+            .cloned()
+            .collect();
+        feature_models.sort_by(|a, b| a.name.cmp(&b.name));
+        feature_models
     }
 
     pub fn get_model_info(&self, model_id: &str) -> Option<ModelInfo> {
@@ -237,7 +320,10 @@ impl ModelManager {
         if settings.selected_model.is_empty() {
             // Find the first available (downloaded) model
             let models = self.available_models.lock_or_recover();
-            if let Some(available_model) = models.values().find(|model| model.is_downloaded) {
+            if let Some(available_model) = models
+                .values()
+                .find(|model| model.is_downloaded && model.engine_type.is_transcription())
+            {
                 info!(
                     "Auto-selecting model: {} ({})",
                     available_model.id, available_model.name
@@ -491,14 +577,22 @@ impl ModelManager {
             })?;
 
             // Find the actual extracted directory (archive might have a nested structure)
-            let extracted_dirs: Vec<_> = fs::read_dir(&temp_extract_dir)?
-                .filter_map(|entry| entry.ok())
-                .filter(|entry| entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false))
-                .collect();
+            let source_dir = extracted_model_dir(&temp_extract_dir)?;
+            if let Err(e) = check_model_files(&source_dir, &model_info.engine_type) {
+                let _ = fs::remove_dir_all(&temp_extract_dir);
+                let _ = fs::remove_file(&partial_path);
+                let _ = self.app_handle.emit(
+                    "model-extraction-failed",
+                    &serde_json::json!({
+                        "model_id": model_id,
+                        "error": e.to_string()
+                    }),
+                );
+                return Err(e);
+            }
 
-            if extracted_dirs.len() == 1 {
+            if source_dir != temp_extract_dir {
                 // Single directory extracted, move it to the final location
-                let source_dir = extracted_dirs[0].path();
                 if final_model_dir.exists() {
                     fs::remove_dir_all(&final_model_dir)?;
                 }
@@ -628,7 +722,10 @@ impl ModelManager {
 
         if model_info.is_directory {
             // For directory-based models, ensure the directory exists and is complete
-            if model_path.exists() && model_path.is_dir() && !partial_path.exists() {
+            if model_path.is_dir()
+                && !partial_path.exists()
+                && check_model_files(&model_path, &model_info.engine_type).is_ok()
+            {
                 Ok(model_path)
             } else {
                 Err(anyhow::anyhow!(
@@ -679,3 +776,90 @@ impl ModelManager {
         Ok(())
     }
 }
+
+/// The directory an archive was extracted to, or its single top-level
+/// directory when the archive nests everything in one
+fn extracted_model_dir(extract_dir: &Path) -> Result<PathBuf> {
+    let extracted_dirs: Vec<_> = fs::read_dir(extract_dir)?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false))
+        .collect();
+    if extracted_dirs.len() == 1 {
+        Ok(extracted_dirs[0].path())
+    } else {
+        Ok(extract_dir.to_path_buf())
+    }
+}
+
+/// Fail unless `model_dir` has every file the engine needs, so an archive
+/// with an unexpected layout isn't taken for a downloaded model
+fn check_model_files(model_dir: &Path, engine_type: &EngineType) -> Result<()> {
+    let missing: Vec<_> = engine_type
+        .required_files()
+        .iter()
+        .filter(|file| !model_dir.join(file).is_file())
+        .copied()
+        .collect();
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!(
+            "Model archive is missing {}",
+            missing.join(", ")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+
+    /// A .tar.gz of `files`, as model archives are downloaded
+    fn archive(files: &[&str]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::fast()));
+        for file in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(4);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, file, &b"data"[..])
+                .unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    fn unpack(files: &[&str]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("talky-model-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let data = archive(files);
+        Archive::new(GzDecoder::new(data.as_slice()))
+            .unpack(&dir)
+            .unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_embedding_archive_needs_model_and_vocab() {
+        // fastembed nests the model in a single directory
+        let dir = unpack(&[
+            "fast-all-MiniLM-L6-v2/model.onnx",
+            "fast-all-MiniLM-L6-v2/vocab.txt",
+            "fast-all-MiniLM-L6-v2/config.json",
+        ]);
+        let model_dir = extracted_model_dir(&dir).unwrap();
+        assert_eq!(model_dir, dir.join("fast-all-MiniLM-L6-v2"));
+        assert!(check_model_files(&model_dir, &EngineType::Embedding).is_ok());
+        fs::remove_dir_all(&dir).unwrap();
+
+        let dir = unpack(&["model.onnx", "tokenizer.json"]);
+        let model_dir = extracted_model_dir(&dir).unwrap();
+        assert_eq!(model_dir, dir);
+        let error = check_model_files(&model_dir, &EngineType::Embedding).unwrap_err();
+        assert_eq!(error.to_string(), "Model archive is missing vocab.txt");
+        assert!(check_model_files(&model_dir, &EngineType::Parakeet).is_ok());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
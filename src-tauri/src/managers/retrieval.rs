@@ -0,0 +1,438 @@
+use crate::managers::folders::{smart_folder_filter, SmartFolderQuery};
+use crate::managers::session::{interleave, SessionManager};
+use anyhow::Result;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashSet;
+
+/// Transcript and notes hits retrieved for a cross-session question, and how
+/// much of the transcript around each segment hit is included with it
+const RETRIEVAL_SEGMENT_HITS: u32 = 30;
+const RETRIEVAL_NOTES_HITS: u32 = 8;
+const RETRIEVAL_CONTEXT_MS: i64 = 15_000;
+/// Sessions whose notes are used when nothing matches the question's keywords
+const RETRIEVAL_FALLBACK_SESSIONS: u32 = 5;
+/// Passage text kept for one question, taken in relevance order, so the
+/// prompt fits small context windows; whole notes are cut shorter
+const RETRIEVAL_MAX_CHARS: usize = 60_000;
+const RETRIEVAL_MAX_NOTES_CHARS: usize = 4_000;
+
+/// The sessions a cross-session question is asked over; unset fields match everything.
+/// Dates are unix seconds on `started_at`, `date_to` exclusive.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct SessionScope {
+    /// Sessions in this folder or any folder below it
+    pub folder_id: Option<String>,
+    /// Sessions must carry all of these tags
+    pub tag_ids: Vec<String>,
+    pub date_from: Option<i64>,
+    pub date_to: Option<i64>,
+}
+
+/// A transcript segment or notes excerpt retrieved for a cross-session question.
+/// `kind` is "transcript" or "notes"; segment fields are only set for transcripts.
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct RetrievedPassage {
+    pub session_id: String,
+    pub session_title: String,
+    pub session_started_at: i64,
+    pub kind: String,
+    pub segment_id: Option<i64>,
+    pub source: Option<String>,
+    pub speaker: Option<String>,
+    pub start_ms: Option<i64>,
+    pub text: String,
+}
+
+/// Where an answer to a cross-session question came from. `label` is the
+/// `[n]` marker used in the answer text.
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct AnswerCitation {
+    pub label: u32,
+    pub session_id: String,
+    pub session_title: String,
+    /// Unset when citing the session's notes
+    pub segment_id: Option<i64>,
+    pub start_ms: Option<i64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct CrossSessionAnswer {
+    pub answer: String,
+    pub citations: Vec<AnswerCitation>,
+}
+
+impl SessionManager {
+    /// Select the transcript segments and notes across sessions in `scope` that
+    /// best match `question`, ordered by session and time. Segment hits come with
+    /// the transcript around them. Passages are kept by relevance until
+    /// `RETRIEVAL_MAX_CHARS` is used up. When no keyword matches, the notes of the
+    /// most recent sessions in scope are used instead.
+    pub fn retrieve_passages(
+        &self,
+        question: &str,
+        scope: &SessionScope,
+    ) -> Result<Vec<RetrievedPassage>> {
+        let conn = self.get_connection()?;
+        retrieve_passages(&conn, question, scope, RETRIEVAL_MAX_CHARS)
+    }
+}
+
+fn retrieve_passages(
+    conn: &Connection,
+    question: &str,
+    scope: &SessionScope,
+    max_chars: usize,
+) -> Result<Vec<RetrievedPassage>> {
+    let (filter, values) = session_scope_filter(scope);
+    let mut passages = Vec::new();
+
+    if let Some(fts_query) = build_question_fts_query(question) {
+        let fts = values.len() + 1;
+        let limit = values.len() + 2;
+
+        let hits = {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT ts.session_id, ts.start_ms
+                 FROM transcript_fts
+                 JOIN transcript_segments ts ON ts.id = transcript_fts.rowid
+                 JOIN sessions s ON s.id = ts.session_id
+                 WHERE transcript_fts MATCH ?{fts} AND s.status != 'deleted'{filter}
+                 ORDER BY bm25(transcript_fts)
+                 LIMIT ?{limit}"
+            ))?;
+            let mut params = values.clone();
+            params.push(fts_query.clone().into());
+            params.push(RETRIEVAL_SEGMENT_HITS.into());
+            let rows = stmt.query_map(rusqlite::params_from_iter(params), |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+            })?;
+            let mut hits = Vec::new();
+            for row in rows {
+                hits.push(row?);
+            }
+            hits
+        };
+
+        // Each hit brings the segments around it, so short replies keep their context
+        let mut seen = HashSet::new();
+        let mut context_stmt = conn.prepare_cached(
+            "SELECT ts.id, ts.session_id, s.title, s.started_at, ts.source, ts.speaker, ts.start_ms, ts.text
+             FROM transcript_segments ts
+             JOIN sessions s ON s.id = ts.session_id
+             WHERE ts.session_id = ?1 AND ts.start_ms BETWEEN ?2 AND ?3
+             ORDER BY ts.start_ms",
+        )?;
+        let mut segment_hits = Vec::new();
+        for (session_id, start_ms) in hits {
+            let rows = context_stmt.query_map(
+                params![
+                    session_id,
+                    start_ms - RETRIEVAL_CONTEXT_MS,
+                    start_ms + RETRIEVAL_CONTEXT_MS
+                ],
+                |row| {
+                    Ok(RetrievedPassage {
+                        session_id: row.get("session_id")?,
+                        session_title: row.get("title")?,
+                        session_started_at: row.get("started_at")?,
+                        kind: "transcript".to_string(),
+                        segment_id: Some(row.get("id")?),
+                        source: row.get("source")?,
+                        speaker: row.get("speaker")?,
+                        start_ms: row.get("start_ms")?,
+                        text: row.get("text")?,
+                    })
+                },
+            )?;
+            let mut context = Vec::new();
+            for row in rows {
+                let passage = row?;
+                if seen.insert(passage.segment_id) {
+                    context.push(passage);
+                }
+            }
+            if !context.is_empty() {
+                segment_hits.push(context);
+            }
+        }
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT s.id, s.title, s.started_at,
+                    snippet(notes_fts, -1, '', '', '…', 64) AS text
+             FROM notes_fts
+             JOIN meeting_notes mn ON mn.id = notes_fts.rowid
+             JOIN sessions s ON s.id = mn.session_id
+             WHERE notes_fts MATCH ?{fts} AND s.status != 'deleted'{filter}
+             ORDER BY bm25(notes_fts)
+             LIMIT ?{limit}"
+        ))?;
+        let mut params = values.clone();
+        params.push(fts_query.into());
+        params.push(RETRIEVAL_NOTES_HITS.into());
+        let rows = stmt.query_map(rusqlite::params_from_iter(params), map_notes_passage)?;
+        let mut notes_hits = Vec::new();
+        for row in rows {
+            notes_hits.push(vec![row?]);
+        }
+
+        // bm25 scores of the two indexes aren't comparable, so their hits take
+        // turns; a segment hit stays together with its context
+        passages = interleave(vec![segment_hits, notes_hits], usize::MAX)
+            .into_iter()
+            .flatten()
+            .collect();
+    }
+
+    if passages.is_empty() {
+        let limit = values.len() + 1;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT s.id, s.title, s.started_at,
+                    COALESCE(NULLIF(mn.enhanced_notes, ''), mn.user_notes) AS text
+             FROM sessions s
+             JOIN meeting_notes mn ON mn.session_id = s.id
+             WHERE s.status != 'deleted'
+               AND COALESCE(NULLIF(mn.enhanced_notes, ''), mn.user_notes, '') != ''{filter}
+             ORDER BY s.started_at DESC
+             LIMIT ?{limit}"
+        ))?;
+        let mut params = values;
+        params.push(RETRIEVAL_FALLBACK_SESSIONS.into());
+        let rows = stmt.query_map(rusqlite::params_from_iter(params), map_notes_passage)?;
+        for row in rows {
+            passages.push(row?);
+        }
+    }
+
+    let mut passages = take_passage_budget(passages, max_chars);
+    passages.sort_by(|a, b| {
+        (
+            a.session_started_at,
+            &a.session_id,
+            a.start_ms.is_some(),
+            a.start_ms,
+        )
+            .cmp(&(
+                b.session_started_at,
+                &b.session_id,
+                b.start_ms.is_some(),
+                b.start_ms,
+            ))
+    });
+    Ok(passages)
+}
+
+fn map_notes_passage(row: &rusqlite::Row) -> rusqlite::Result<RetrievedPassage> {
+    Ok(RetrievedPassage {
+        session_id: row.get("id")?,
+        session_title: row.get("title")?,
+        session_started_at: row.get("started_at")?,
+        kind: "notes".to_string(),
+        segment_id: None,
+        source: None,
+        speaker: None,
+        start_ms: None,
+        text: row.get("text")?,
+    })
+}
+
+/// Keep passages, most relevant first, until `max_chars` of text is used up.
+/// Notes are cut to `RETRIEVAL_MAX_NOTES_CHARS` first.
+fn take_passage_budget(passages: Vec<RetrievedPassage>, max_chars: usize) -> Vec<RetrievedPassage> {
+    let mut used = 0;
+    let mut kept = Vec::new();
+    for mut passage in passages {
+        if used >= max_chars {
+            break;
+        }
+        if passage.start_ms.is_none() {
+            passage.text = passage
+                .text
+                .chars()
+                .take(RETRIEVAL_MAX_NOTES_CHARS)
+                .collect();
+        }
+        used += passage.text.len();
+        kept.push(passage);
+    }
+    kept
+}
+
+/// Like `smart_folder_filter`, for the scope of a cross-session question
+fn session_scope_filter(scope: &SessionScope) -> (String, Vec<rusqlite::types::Value>) {
+    let (mut filter, mut values) = smart_folder_filter(&SmartFolderQuery {
+        tag_ids: scope.tag_ids.clone(),
+        date_from: scope.date_from,
+        date_to: scope.date_to,
+        ..Default::default()
+    });
+    if let Some(folder_id) = &scope.folder_id {
+        values.push(rusqlite::types::Value::Text(folder_id.clone()));
+        filter.push_str(&format!(
+            " AND s.folder_id IN (
+                WITH RECURSIVE tree(id) AS (
+                    SELECT ?{}
+                    UNION
+                    SELECT f.id FROM folders f JOIN tree t ON f.parent_id = t.id
+                )
+                SELECT id FROM tree
+            )",
+            values.len()
+        ));
+    }
+    (filter, values)
+}
+
+/// Words too common to help find where a question was discussed
+const QUESTION_STOPWORDS: &[&str] = &[
+    "about", "after", "again", "all", "also", "and", "any", "are", "been", "before", "but",
+    "calls", "can", "could", "did", "does", "for", "from", "had", "has", "have", "how", "into",
+    "last", "meeting", "meetings", "more", "our", "over", "said", "should", "some", "than", "that",
+    "the", "their", "them", "then", "there", "these", "they", "this", "those", "was", "were",
+    "what", "when", "where", "which", "while", "who", "why", "will", "with", "would", "you",
+    "your",
+];
+
+/// Turn a natural-language question into an FTS5 query matching any of its
+/// keywords. Common suffixes are dropped and every term is a prefix match, so
+/// "pricing" also finds "price" and "priced". Returns None without keywords.
+fn build_question_fts_query(question: &str) -> Option<String> {
+    let mut terms: Vec<String> = Vec::new();
+    for word in question.split(|c: char| !c.is_alphanumeric()) {
+        let word = word.to_lowercase();
+        if word.chars().count() < 3 || QUESTION_STOPWORDS.contains(&word.as_str()) {
+            continue;
+        }
+        let stem = ["ing", "ed", "es", "s"]
+            .iter()
+            .find_map(|suffix| {
+                word.strip_suffix(suffix)
+                    .filter(|stem| stem.chars().count() >= 4)
+            })
+            .unwrap_or(&word);
+        let term = format!("\"{}\"*", stem);
+        if !terms.contains(&term) {
+            terms.push(term);
+        }
+    }
+
+    if terms.is_empty() {
+        return None;
+    }
+    Some(terms.join(" OR "))
+}
+
+/// The `[n]` citation labels used in an answer, in order of first use.
+/// Groups such as `[1, 3]` are accepted too.
+pub fn cited_labels(answer: &str) -> Vec<u32> {
+    let mut labels = Vec::new();
+    for group in answer.split('[').skip(1) {
+        let Some((inner, _)) = group.split_once(']') else {
+            continue;
+        };
+        let parsed: Option<Vec<u32>> = inner
+            .split(',')
+            .map(|label| label.trim().parse().ok())
+            .collect();
+        for label in parsed.unwrap_or_default() {
+            if !labels.contains(&label) {
+                labels.push(label);
+            }
+        }
+    }
+    labels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::managers::session::test_connection;
+
+    #[test]
+    fn test_build_question_fts_query() {
+        assert_eq!(
+            build_question_fts_query("What did we decide about pricing across the calls?"),
+            Some("\"decide\"* OR \"pric\"* OR \"acros\"*".to_string())
+        );
+        assert_eq!(
+            build_question_fts_query("what did they say?"),
+            Some("\"say\"*".to_string())
+        );
+        assert_eq!(build_question_fts_query("who? what"), None);
+    }
+
+    #[test]
+    fn test_cited_labels() {
+        assert_eq!(
+            cited_labels("Price is $49 [2]. Annual gets a discount [3, 1][2]; see [notes] [7"),
+            vec![2, 3, 1]
+        );
+    }
+
+    #[test]
+    fn test_take_passage_budget_keeps_most_relevant() {
+        let passage = |session_id: &str, start_ms: Option<i64>, text: &str| RetrievedPassage {
+            session_id: session_id.to_string(),
+            session_title: String::new(),
+            session_started_at: 0,
+            kind: String::new(),
+            segment_id: None,
+            source: None,
+            speaker: None,
+            start_ms,
+            text: text.to_string(),
+        };
+        let notes = "n".repeat(RETRIEVAL_MAX_NOTES_CHARS + 10);
+        let passages = vec![
+            passage("best", Some(0), "0123456789"),
+            passage("notes", None, &notes),
+            passage("dropped", Some(0), "0123456789"),
+        ];
+
+        let kept = take_passage_budget(passages, 12);
+        let ids: Vec<_> = kept.iter().map(|p| p.session_id.as_str()).collect();
+        assert_eq!(ids, vec!["best", "notes"]);
+        assert_eq!(kept[1].text.len(), RETRIEVAL_MAX_NOTES_CHARS);
+    }
+
+    #[test]
+    fn test_retrieve_passages_takes_turns_between_transcripts_and_notes() {
+        let conn = test_connection();
+        conn.execute_batch(
+            "INSERT INTO sessions (id, title, started_at) VALUES ('call', 'Call', 100), ('review', 'Review', 200);
+             INSERT INTO transcript_segments (session_id, text, start_ms, end_ms, created_at) VALUES
+                ('call', 'pricing pricing pricing', 0, 1000, 0),
+                ('call', 'pricing pricing again', 60000, 61000, 0),
+                ('call', 'more pricing pricing', 120000, 121000, 0);
+             INSERT INTO meeting_notes (session_id, user_notes, created_at, updated_at)
+                VALUES ('review', 'We went over the roadmap, hiring and then pricing', 0, 0);",
+        )
+        .unwrap();
+        let all = SessionScope::default();
+
+        // The weaker notes hit still gets the second slot of the budget
+        let passages = retrieve_passages(&conn, "What about pricing?", &all, 30).unwrap();
+        let found: Vec<_> = passages
+            .iter()
+            .map(|p| (p.session_id.as_str(), p.start_ms))
+            .collect();
+        assert_eq!(found, vec![("call", Some(0)), ("review", None)]);
+
+        let passages = retrieve_passages(&conn, "What about pricing?", &all, 1_000).unwrap();
+        assert_eq!(passages.len(), 4);
+
+        let later = SessionScope {
+            date_from: Some(150),
+            ..Default::default()
+        };
+        let passages = retrieve_passages(&conn, "What about pricing?", &later, 1_000).unwrap();
+        assert!(passages.iter().all(|p| p.session_id == "review"));
+
+        // Nothing matches, so the most recent notes are used
+        let passages = retrieve_passages(&conn, "Any budget?", &all, 1_000).unwrap();
+        assert_eq!(passages.len(), 1);
+        assert_eq!(passages[0].kind, "notes");
+    }
+}
@@ -1,20 +1,36 @@
+use crate::audio_toolkit::diarization::{match_speaker, update_centroid};
+use crate::audio_toolkit::{align_word_timings, spread_words, TimedWord, WavStreamWriter};
+use crate::helpers::text_diff::{diff_lines, DiffLine};
+use crate::managers::action_items::{bundle_action_items, insert_bundle_action_items};
+use crate::managers::bookmarks::{bundle_bookmarks, insert_bundle_bookmarks};
+use crate::managers::bundle::{
+    bundle_file, sanitize_bundle_name, unpack_bundle, write_bundle, BundleAttachment, BundleFolder,
+    BundleNotes, BundleRecording, BundleSegment, BundleSession, BundleSpeaker, BundleTag,
+    BundleWord, SessionBundle, BUNDLE_FORMAT, BUNDLE_VERSION,
+};
+use crate::managers::calendar::{CalendarAttendee, CalendarEvent};
+use crate::managers::encryption::{self, KeyFile, StorageKey, StorageMigration};
 use crate::utils::MutexExt;
 use anyhow::Result;
 use chrono::Utc;
 use log::{debug, info, warn};
-use rusqlite::{params, Connection, OptionalExtension};
+use r2d2::{Pool, PooledConnection};
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension};
 use rusqlite_migration::{Migrations, M};
 use serde::{Deserialize, Serialize};
 use specta::Type;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, Mutex};
-use std::thread::JoinHandle;
+use std::sync::{Arc, Mutex, Weak};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
 use tauri::{AppHandle, Emitter, Manager};
 use uuid::Uuid;
 
-static SESSION_MIGRATIONS: &[M] = &[
+pub(crate) static SESSION_MIGRATIONS: &[M] = &[
     M::up(
         "CREATE TABLE IF NOT EXISTS sessions (
             id TEXT PRIMARY KEY,
@@ -112,8 +128,380 @@ static SESSION_MIGRATIONS: &[M] = &[
     ),
     // Migration 13: Index for faster attachment queries by session
     M::up("CREATE INDEX IF NOT EXISTS idx_attachments_session ON session_attachments(session_id);"),
+    // Migration 14: Full-text index over transcript segments, kept in sync by triggers
+    M::up(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS transcript_fts USING fts5(
+            text,
+            content='transcript_segments',
+            content_rowid='id',
+            tokenize='porter unicode61'
+        );
+        CREATE TRIGGER IF NOT EXISTS transcript_fts_ai AFTER INSERT ON transcript_segments BEGIN
+            INSERT INTO transcript_fts(rowid, text) VALUES (new.id, new.text);
+        END;
+        CREATE TRIGGER IF NOT EXISTS transcript_fts_ad AFTER DELETE ON transcript_segments BEGIN
+            INSERT INTO transcript_fts(transcript_fts, rowid, text) VALUES ('delete', old.id, old.text);
+        END;
+        CREATE TRIGGER IF NOT EXISTS transcript_fts_au AFTER UPDATE OF text ON transcript_segments BEGIN
+            INSERT INTO transcript_fts(transcript_fts, rowid, text) VALUES ('delete', old.id, old.text);
+            INSERT INTO transcript_fts(rowid, text) VALUES (new.id, new.text);
+        END;
+        INSERT INTO transcript_fts(transcript_fts) VALUES ('rebuild');",
+    ),
+    // Migration 15: Full-text index over meeting notes
+    M::up(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS notes_fts USING fts5(
+            user_notes,
+            enhanced_notes,
+            content='meeting_notes',
+            content_rowid='id',
+            tokenize='porter unicode61'
+        );
+        CREATE TRIGGER IF NOT EXISTS notes_fts_ai AFTER INSERT ON meeting_notes BEGIN
+            INSERT INTO notes_fts(rowid, user_notes, enhanced_notes)
+            VALUES (new.id, new.user_notes, new.enhanced_notes);
+        END;
+        CREATE TRIGGER IF NOT EXISTS notes_fts_ad AFTER DELETE ON meeting_notes BEGIN
+            INSERT INTO notes_fts(notes_fts, rowid, user_notes, enhanced_notes)
+            VALUES ('delete', old.id, old.user_notes, old.enhanced_notes);
+        END;
+        CREATE TRIGGER IF NOT EXISTS notes_fts_au AFTER UPDATE OF user_notes, enhanced_notes ON meeting_notes BEGIN
+            INSERT INTO notes_fts(notes_fts, rowid, user_notes, enhanced_notes)
+            VALUES ('delete', old.id, old.user_notes, old.enhanced_notes);
+            INSERT INTO notes_fts(rowid, user_notes, enhanced_notes)
+            VALUES (new.id, new.user_notes, new.enhanced_notes);
+        END;
+        INSERT INTO notes_fts(notes_fts) VALUES ('rebuild');",
+    ),
+    // Migration 16: Full-text index over attachment text. session_attachments has a TEXT
+    // primary key (no stable rowid), so the index keeps its own copy keyed by attachment id.
+    M::up(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS attachments_fts USING fts5(
+            attachment_id UNINDEXED,
+            session_id UNINDEXED,
+            filename,
+            extracted_text,
+            tokenize='porter unicode61'
+        );
+        CREATE TRIGGER IF NOT EXISTS attachments_fts_ai AFTER INSERT ON session_attachments BEGIN
+            INSERT INTO attachments_fts(attachment_id, session_id, filename, extracted_text)
+            VALUES (new.id, new.session_id, new.filename, new.extracted_text);
+        END;
+        CREATE TRIGGER IF NOT EXISTS attachments_fts_ad AFTER DELETE ON session_attachments BEGIN
+            DELETE FROM attachments_fts WHERE attachment_id = old.id;
+        END;
+        CREATE TRIGGER IF NOT EXISTS attachments_fts_au AFTER UPDATE OF filename, extracted_text ON session_attachments BEGIN
+            DELETE FROM attachments_fts WHERE attachment_id = old.id;
+            INSERT INTO attachments_fts(attachment_id, session_id, filename, extracted_text)
+            VALUES (new.id, new.session_id, new.filename, new.extracted_text);
+        END;
+        INSERT INTO attachments_fts(attachment_id, session_id, filename, extracted_text)
+        SELECT id, session_id, filename, extracted_text FROM session_attachments;",
+    ),
+    // Migration 17: Create chat_threads table for per-session chat conversations
+    M::up(
+        "CREATE TABLE IF NOT EXISTS chat_threads (
+            id TEXT PRIMARY KEY,
+            session_id TEXT NOT NULL,
+            title TEXT NOT NULL,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL,
+            FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+        );",
+    ),
+    // Migration 18: Create chat_messages table
+    M::up(
+        "CREATE TABLE IF NOT EXISTS chat_messages (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            thread_id TEXT NOT NULL,
+            role TEXT NOT NULL,
+            content TEXT NOT NULL,
+            created_at INTEGER NOT NULL,
+            FOREIGN KEY (thread_id) REFERENCES chat_threads(id) ON DELETE CASCADE
+        );",
+    ),
+    // Migration 19: Indexes for chat lookups by session and thread
+    M::up(
+        "CREATE INDEX IF NOT EXISTS idx_chat_threads_session ON chat_threads(session_id);
+        CREATE INDEX IF NOT EXISTS idx_chat_messages_thread ON chat_messages(thread_id);",
+    ),
+    // Migration 20: Track where each archived recording pass sits on the session timeline
+    M::up("ALTER TABLE audio_recordings ADD COLUMN offset_ms INTEGER NOT NULL DEFAULT 0;"),
+    // Migration 21: Track archived recording length
+    M::up("ALTER TABLE audio_recordings ADD COLUMN duration_ms INTEGER NOT NULL DEFAULT 0;"),
+    // Migration 22: Index for faster recording queries by session
+    M::up("CREATE INDEX IF NOT EXISTS idx_audio_recordings_session ON audio_recordings(session_id);"),
+    // Migration 23: Word-level timings for transcript segments
+    M::up(
+        "CREATE TABLE IF NOT EXISTS transcript_words (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            segment_id INTEGER NOT NULL,
+            text TEXT NOT NULL,
+            start_ms INTEGER NOT NULL,
+            end_ms INTEGER NOT NULL,
+            estimated INTEGER NOT NULL DEFAULT 0,
+            FOREIGN KEY (segment_id) REFERENCES transcript_segments(id) ON DELETE CASCADE
+        );",
+    ),
+    // Migration 24: Index for loading words by segment
+    M::up("CREATE INDEX IF NOT EXISTS idx_transcript_words_segment ON transcript_words(segment_id);"),
+    // Migration 25: Diarized speaker label for mic segments
+    M::up("ALTER TABLE transcript_segments ADD COLUMN speaker TEXT;"),
+    // Migration 26: Per-session speakers with their voice centroid and display name
+    M::up(
+        "CREATE TABLE IF NOT EXISTS session_speakers (
+            session_id TEXT NOT NULL,
+            label TEXT NOT NULL,
+            name TEXT,
+            centroid BLOB NOT NULL,
+            segment_count INTEGER NOT NULL DEFAULT 0,
+            created_at INTEGER NOT NULL,
+            PRIMARY KEY (session_id, label),
+            FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+        );",
+    ),
+    // Migration 27: Alternate transcripts (re-transcriptions, replaced transcripts)
+    M::up(
+        "CREATE TABLE IF NOT EXISTS transcript_revisions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            session_id TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            model_id TEXT,
+            created_at INTEGER NOT NULL,
+            FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+        );",
+    ),
+    // Migration 28: Segments belonging to a transcript revision, words kept as JSON,
+    // and the speakers it found, merged into session_speakers when it is applied
+    M::up(
+        "CREATE TABLE IF NOT EXISTS transcript_revision_segments (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            revision_id INTEGER NOT NULL,
+            text TEXT NOT NULL,
+            source TEXT NOT NULL,
+            start_ms INTEGER NOT NULL,
+            end_ms INTEGER NOT NULL,
+            speaker TEXT,
+            words TEXT NOT NULL DEFAULT '[]',
+            FOREIGN KEY (revision_id) REFERENCES transcript_revisions(id) ON DELETE CASCADE
+        );
+        CREATE TABLE IF NOT EXISTS transcript_revision_speakers (
+            revision_id INTEGER NOT NULL,
+            label TEXT NOT NULL,
+            centroid BLOB NOT NULL,
+            segment_count INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY (revision_id, label),
+            FOREIGN KEY (revision_id) REFERENCES transcript_revisions(id) ON DELETE CASCADE
+        );",
+    ),
+    // Migration 29: Indexes for revision lookups
+    M::up(
+        "CREATE INDEX IF NOT EXISTS idx_transcript_revisions_session ON transcript_revisions(session_id);
+         CREATE INDEX IF NOT EXISTS idx_transcript_revision_segments_revision ON transcript_revision_segments(revision_id);",
+    ),
+    // Migration 30: History of manual transcript edits. Each row keeps the affected
+    // segments as they were before the edit (JSON) and the ids the edit left behind.
+    // `revision_id` is set once the edited transcript is replaced by a revision,
+    // to the revision that kept it; no foreign key, so the history outlives it.
+    M::up(
+        "CREATE TABLE IF NOT EXISTS transcript_segment_edits (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            session_id TEXT NOT NULL,
+            action TEXT NOT NULL,
+            segments_before TEXT NOT NULL,
+            segment_ids_after TEXT NOT NULL DEFAULT '[]',
+            created_at INTEGER NOT NULL,
+            revision_id INTEGER,
+            FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+        );",
+    ),
+    // Migration 31: Index for edit history by session
+    M::up("CREATE INDEX IF NOT EXISTS idx_transcript_segment_edits_session ON transcript_segment_edits(session_id);"),
+    // Migration 32: Snapshots of user notes and enhanced notes, taken on save
+    M::up(
+        "CREATE TABLE IF NOT EXISTS meeting_notes_revisions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            session_id TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            user_notes TEXT,
+            enhanced_notes TEXT,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL,
+            FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+        );",
+    ),
+    // Migration 33: Index for notes history by session
+    M::up("CREATE INDEX IF NOT EXISTS idx_meeting_notes_revisions_session ON meeting_notes_revisions(session_id);"),
+    // Migration 34: When a session was moved to the trash (status 'deleted')
+    M::up("ALTER TABLE sessions ADD COLUMN deleted_at INTEGER;"),
+    // Migration 35: Indexes for transcript lookups by session and tag filtering
+    M::up(
+        "CREATE INDEX IF NOT EXISTS idx_transcript_segments_session ON transcript_segments(session_id, start_ms);
+         CREATE INDEX IF NOT EXISTS idx_session_tags_tag ON session_tags(tag_id);
+         CREATE INDEX IF NOT EXISTS idx_sessions_status ON sessions(status, started_at);",
+    ),
+    // Migration 36: Nested folders. Deleting a folder moves its children up a level
+    // in `delete_folder`; the FK only guards against dangling parents.
+    M::up(
+        "ALTER TABLE folders ADD COLUMN parent_id TEXT REFERENCES folders(id) ON DELETE SET NULL;
+         CREATE INDEX IF NOT EXISTS idx_folders_parent ON folders(parent_id);",
+    ),
+    // Migration 37: Smart folders, a saved query (JSON) evaluated on every listing
+    M::up(
+        "CREATE TABLE IF NOT EXISTS smart_folders (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            color TEXT,
+            query TEXT NOT NULL,
+            sort_order INTEGER NOT NULL DEFAULT 0,
+            created_at INTEGER NOT NULL
+        );",
+    ),
+    // Migration 38: Action items, extracted by the LLM or added by hand. `edited`
+    // keeps an item through re-extraction once the user has touched it.
+    M::up(
+        "CREATE TABLE IF NOT EXISTS action_items (
+            id TEXT PRIMARY KEY,
+            session_id TEXT NOT NULL,
+            text TEXT NOT NULL,
+            owner TEXT,
+            due_date TEXT,
+            status TEXT NOT NULL DEFAULT 'open',
+            source_segment_id INTEGER,
+            edited INTEGER NOT NULL DEFAULT 0,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL,
+            FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE,
+            FOREIGN KEY (source_segment_id) REFERENCES transcript_segments(id) ON DELETE SET NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_action_items_session ON action_items(session_id);
+        CREATE INDEX IF NOT EXISTS idx_action_items_status ON action_items(status, due_date);",
+    ),
+    // Migration 39: Events imported from .ics calendars, one row per occurrence.
+    // Each new event gets a session with status 'scheduled' until recording starts.
+    M::up(
+        "CREATE TABLE IF NOT EXISTS calendar_events (
+            id TEXT PRIMARY KEY,
+            source TEXT NOT NULL,
+            uid TEXT NOT NULL,
+            instance TEXT NOT NULL DEFAULT '',
+            session_id TEXT,
+            title TEXT NOT NULL,
+            description TEXT,
+            location TEXT,
+            starts_at INTEGER NOT NULL,
+            ends_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL,
+            UNIQUE (uid, instance),
+            FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE SET NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_calendar_events_session ON calendar_events(session_id);
+        CREATE INDEX IF NOT EXISTS idx_calendar_events_starts ON calendar_events(starts_at);
+        CREATE TABLE IF NOT EXISTS calendar_attendees (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            event_id TEXT NOT NULL,
+            name TEXT,
+            email TEXT,
+            FOREIGN KEY (event_id) REFERENCES calendar_events(id) ON DELETE CASCADE
+        );
+        CREATE INDEX IF NOT EXISTS idx_calendar_attendees_event ON calendar_attendees(event_id);",
+    ),
+    // Migration 40: Sentence embeddings of transcript segments and notes paragraphs
+    // for semantic search. `text` is what was embedded, so edited segments get
+    // embedded again; `embedded_at` is the notes' `updated_at` when last embedded.
+    M::up(
+        "CREATE TABLE IF NOT EXISTS text_embeddings (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            session_id TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            segment_id INTEGER UNIQUE,
+            text TEXT NOT NULL,
+            model_id TEXT NOT NULL,
+            vector BLOB NOT NULL,
+            created_at INTEGER NOT NULL,
+            FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE,
+            FOREIGN KEY (segment_id) REFERENCES transcript_segments(id) ON DELETE CASCADE
+        );
+        CREATE INDEX IF NOT EXISTS idx_text_embeddings_session ON text_embeddings(session_id, kind);
+        ALTER TABLE meeting_notes ADD COLUMN embedded_at INTEGER;",
+    ),
+    // Migration 41: Moments marked during (or after) recording, on the same
+    // timeline as transcript_segments.start_ms
+    M::up(
+        "CREATE TABLE IF NOT EXISTS bookmarks (
+            id TEXT PRIMARY KEY,
+            session_id TEXT NOT NULL,
+            offset_ms INTEGER NOT NULL,
+            label TEXT,
+            color TEXT,
+            created_at INTEGER NOT NULL,
+            FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+        );
+        CREATE INDEX IF NOT EXISTS idx_bookmarks_session ON bookmarks(session_id, offset_ms);",
+    ),
+    // Migration 42: When anything exported about a session last changed (its
+    // details, notes, transcript or chat), for incremental vault sync. Kept up
+    // to date by triggers so no write path can miss it.
+    M::up(
+        "ALTER TABLE sessions ADD COLUMN updated_at INTEGER;
+        UPDATE sessions SET updated_at = COALESCE(ended_at, started_at);
+        CREATE TRIGGER IF NOT EXISTS sessions_touch_ai AFTER INSERT ON sessions BEGIN
+            UPDATE sessions SET updated_at = CAST(strftime('%s', 'now') AS INTEGER) WHERE id = NEW.id;
+        END;
+        CREATE TRIGGER IF NOT EXISTS sessions_touch_au AFTER UPDATE OF title, started_at, ended_at, status, folder_id, environment_id ON sessions BEGIN
+            UPDATE sessions SET updated_at = CAST(strftime('%s', 'now') AS INTEGER) WHERE id = NEW.id;
+        END;
+        CREATE TRIGGER IF NOT EXISTS notes_touch_ai AFTER INSERT ON meeting_notes BEGIN
+            UPDATE sessions SET updated_at = CAST(strftime('%s', 'now') AS INTEGER) WHERE id = NEW.session_id;
+        END;
+        CREATE TRIGGER IF NOT EXISTS notes_touch_au AFTER UPDATE OF user_notes, enhanced_notes ON meeting_notes BEGIN
+            UPDATE sessions SET updated_at = CAST(strftime('%s', 'now') AS INTEGER) WHERE id = NEW.session_id;
+        END;
+        CREATE TRIGGER IF NOT EXISTS segments_touch_ai AFTER INSERT ON transcript_segments BEGIN
+            UPDATE sessions SET updated_at = CAST(strftime('%s', 'now') AS INTEGER) WHERE id = NEW.session_id;
+        END;
+        CREATE TRIGGER IF NOT EXISTS segments_touch_au AFTER UPDATE OF text, source ON transcript_segments BEGIN
+            UPDATE sessions SET updated_at = CAST(strftime('%s', 'now') AS INTEGER) WHERE id = NEW.session_id;
+        END;
+        CREATE TRIGGER IF NOT EXISTS segments_touch_ad AFTER DELETE ON transcript_segments BEGIN
+            UPDATE sessions SET updated_at = CAST(strftime('%s', 'now') AS INTEGER) WHERE id = OLD.session_id;
+        END;
+        CREATE TRIGGER IF NOT EXISTS chat_threads_touch_ai AFTER INSERT ON chat_threads BEGIN
+            UPDATE sessions SET updated_at = CAST(strftime('%s', 'now') AS INTEGER) WHERE id = NEW.session_id;
+        END;
+        CREATE TRIGGER IF NOT EXISTS chat_threads_touch_au AFTER UPDATE ON chat_threads BEGIN
+            UPDATE sessions SET updated_at = CAST(strftime('%s', 'now') AS INTEGER) WHERE id = NEW.session_id;
+        END;
+        CREATE TRIGGER IF NOT EXISTS chat_threads_touch_ad AFTER DELETE ON chat_threads BEGIN
+            UPDATE sessions SET updated_at = CAST(strftime('%s', 'now') AS INTEGER) WHERE id = OLD.session_id;
+        END;",
+    ),
 ];
 
+/// Upper bound on open connections to sessions.db. Recording, the UI and
+/// background jobs each hold one at most briefly.
+const DB_POOL_SIZE: u32 = 8;
+/// Statements kept prepared per connection (rusqlite's default is 16)
+const DB_STATEMENT_CACHE_CAPACITY: usize = 64;
+
+/// Autosaves within this many seconds of the latest "edit" snapshot update that
+/// snapshot instead of adding a new one, so typing doesn't create a revision
+/// per keystroke.
+const NOTES_REVISION_COALESCE_SECS: i64 = 120;
+
+/// How often sessions past the trash retention are looked for while the app runs
+const TRASH_PURGE_POLL: Duration = Duration::from_secs(60 * 60);
+
+const MIN_PASSPHRASE_LEN: usize = 8;
+
+/// A new note started this long before a scheduled meeting, or while it is
+/// running, continues the meeting's scheduled session
+const SCHEDULED_SESSION_ATTACH_SECS: i64 = 15 * 60;
+
+/// Scheduled sessions of meetings that ended this long ago without being
+/// recorded or written in are removed
+const SCHEDULED_SESSION_EXPIRY_SECS: i64 = 60 * 60;
+
 #[derive(Clone, Debug, Serialize, Deserialize, Type)]
 pub struct Session {
     pub id: String,
@@ -123,15 +511,40 @@ pub struct Session {
     pub status: String,
     pub folder_id: Option<String>,
     pub environment_id: Option<String>,
+    /// Set while the session is in the trash
+    pub deleted_at: Option<i64>,
 }
 
+/// The calendar event a session was scheduled from
 #[derive(Clone, Debug, Serialize, Deserialize, Type)]
-pub struct Folder {
+pub struct SessionCalendarEvent {
     pub id: String,
-    pub name: String,
-    pub color: Option<String>,
-    pub sort_order: i32,
-    pub created_at: i64,
+    pub title: String,
+    pub description: Option<String>,
+    pub location: Option<String>,
+    pub starts_at: i64,
+    pub ends_at: i64,
+    pub attendees: Vec<CalendarAttendee>,
+}
+
+/// What a calendar import changed
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Type)]
+pub struct CalendarImportSummary {
+    pub created: u32,
+    pub updated: u32,
+    pub removed: u32,
+}
+
+impl CalendarImportSummary {
+    pub fn merge(&mut self, other: &Self) {
+        self.created += other.created;
+        self.updated += other.updated;
+        self.removed += other.removed;
+    }
+
+    pub fn has_changes(&self) -> bool {
+        self.created + self.updated + self.removed > 0
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Type)]
@@ -153,6 +566,40 @@ pub struct Attachment {
     pub created_at: i64,
 }
 
+/// One archived audio file for a recording pass. Mic and speaker are stored
+/// as separate 16 kHz mono WAV files; `offset_ms` is where the pass starts on
+/// the session timeline (same clock as transcript segment start_ms).
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct AudioRecording {
+    pub id: i64,
+    pub session_id: String,
+    pub file_name: String,
+    pub file_path: String,
+    pub channel: String,
+    pub offset_ms: i64,
+    pub duration_ms: i64,
+    pub created_at: i64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct ChatThread {
+    pub id: String,
+    pub session_id: String,
+    pub title: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct ChatThreadMessage {
+    pub id: i64,
+    pub thread_id: String,
+    /// "user" or "assistant"
+    pub role: String,
+    pub content: String,
+    pub created_at: i64,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, Type)]
 pub struct SessionWithTags {
     pub session: Session,
@@ -168,6 +615,71 @@ pub struct TranscriptSegment {
     pub start_ms: i64,
     pub end_ms: i64,
     pub created_at: i64,
+    /// Diarized speaker label (mic segments only), see `session_speakers`
+    pub speaker: Option<String>,
+    /// Word timings on the session timeline. Only loaded by `get_session_transcript`
+    #[serde(default)]
+    pub words: Vec<TranscriptWord>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct TranscriptWord {
+    pub id: i64,
+    pub segment_id: i64,
+    pub text: String,
+    pub start_ms: i64,
+    pub end_ms: i64,
+    /// Spread over the segment rather than timed by the engine, see `TimedWord`
+    #[serde(default)]
+    pub estimated: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct SessionSpeaker {
+    pub session_id: String,
+    pub label: String,
+    pub name: Option<String>,
+    pub segment_count: i64,
+}
+
+/// An alternate transcript for a session. `kind` is "retranscription" for output
+/// of `retranscribe_session`, or "replaced" for a transcript that was swapped
+/// out when another revision was applied.
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct TranscriptRevision {
+    pub id: i64,
+    pub session_id: String,
+    pub kind: String,
+    pub model_id: Option<String>,
+    pub segment_count: i64,
+    pub created_at: i64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct TranscriptRevisionSegment {
+    pub id: i64,
+    pub revision_id: i64,
+    pub text: String,
+    pub source: String,
+    pub start_ms: i64,
+    pub end_ms: i64,
+    pub speaker: Option<String>,
+}
+
+/// One manual edit of a session's transcript. `action` is "update", "delete",
+/// "split" or "merge". `segments_before` holds the touched segments exactly as
+/// they were, so walking the history back recovers the engine's output.
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct TranscriptSegmentEdit {
+    pub id: i64,
+    pub session_id: String,
+    pub action: String,
+    pub segments_before: Vec<TranscriptSegment>,
+    pub segment_ids_after: Vec<i64>,
+    pub created_at: i64,
+    /// The "replaced" revision that kept the edited transcript when another
+    /// revision was applied. Such edits can no longer be reverted.
+    pub revision_id: Option<i64>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Type)]
@@ -184,12 +696,67 @@ pub struct MeetingNotes {
     pub updated_at: i64,
 }
 
+/// A snapshot of a session's user notes and enhanced notes. `kind` is "edit"
+/// for user saves (coalesced while typing), "enhance" for AI output, "restore"
+/// when an older revision was restored, or "initial" for notes that existed
+/// before history was kept.
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct MeetingNotesRevision {
+    pub id: i64,
+    pub session_id: String,
+    pub kind: String,
+    pub user_notes: Option<String>,
+    pub enhanced_notes: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct MeetingNotesDiff {
+    pub user_notes: Vec<DiffLine>,
+    pub enhanced_notes: Vec<DiffLine>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct StorageEncryptionStatus {
+    /// Whether this build can encrypt storage at all
+    pub supported: bool,
+    pub enabled: bool,
+    /// Nothing can be read until `unlock_storage` succeeds
+    pub locked: bool,
+}
+
+/// A single ranked full-text search result.
+/// `kind` is "transcript", "notes" or "attachment"; segment fields are only set
+/// for transcript hits and `attachment_id` only for attachment hits.
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct SearchHit {
+    pub session_id: String,
+    pub session_title: String,
+    pub kind: String,
+    pub segment_id: Option<i64>,
+    pub source: Option<String>,
+    pub start_ms: Option<i64>,
+    pub attachment_id: Option<String>,
+    /// Matched text with hits wrapped in `<mark>` tags
+    pub snippet: String,
+    /// bm25 score, lower is more relevant. Only comparable between hits of
+    /// the same kind.
+    pub score: f64,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, Type)]
 pub struct TranscriptSegmentEvent {
     pub session_id: String,
     pub segment: TranscriptSegment,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct TranscriptSegmentDeletedEvent {
+    pub session_id: String,
+    pub segment_id: i64,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, Type)]
 pub struct SessionAmplitudeEvent {
     pub session_id: String,
@@ -197,9 +764,52 @@ pub struct SessionAmplitudeEvent {
     pub speaker: u16,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct AudioImportProgressEvent {
+    pub session_id: String,
+    pub processed_ms: i64,
+    pub total_ms: i64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct TranscriptSpeakerEvent {
+    pub session_id: String,
+    pub segment_id: i64,
+    pub speaker: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct RetranscriptionProgressEvent {
+    pub session_id: String,
+    pub processed_ms: i64,
+    pub total_ms: i64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct RetranscriptionCompleteEvent {
+    pub session_id: String,
+    pub revision_id: Option<i64>,
+    pub segment_count: u32,
+    pub cancelled: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct AudioImportCompleteEvent {
+    pub session_id: String,
+    pub segment_count: u32,
+    pub cancelled: bool,
+    pub error: Option<String>,
+}
+
 pub struct SessionManager {
-    app_handle: AppHandle,
+    pub(crate) app_handle: AppHandle,
     db_path: PathBuf,
+    /// Pooled connections to sessions.db, each configured by `configure_connection`.
+    /// None while encrypted storage is locked or being converted.
+    pool: Mutex<Option<Pool<SqliteConnectionManager>>>,
+    /// Key for sessions.db and attachment files when encryption at rest is on
+    storage_key: Mutex<Option<Arc<StorageKey>>>,
     active_session: Arc<Mutex<Option<String>>>,
     session_start_time: Arc<Mutex<Option<std::time::Instant>>>,
     /// Shared buffer where the speaker capture task accumulates samples
@@ -208,6 +818,16 @@ pub struct SessionManager {
     speaker_shutdown: Arc<AtomicBool>,
     /// Handle to the speaker capture thread for proper cleanup
     speaker_thread_handle: Arc<Mutex<Option<JoinHandle<()>>>>,
+    /// Set while an audio import or re-transcription is running (only one at a time)
+    offline_job_running: Arc<AtomicBool>,
+    /// Signal the running audio import or re-transcription to stop
+    offline_job_cancel: Arc<AtomicBool>,
+    /// Set while a re-transcription has its model loaded in the shared
+    /// TranscriptionManager, so recording can't start on the wrong model
+    engine_borrowed: Mutex<bool>,
+    /// When the current recording pass started, and where on the session
+    /// timeline it started
+    recording_clock: Mutex<Option<(std::time::Instant, i64)>>,
 }
 
 impl SessionManager {
@@ -228,20 +848,33 @@ impl SessionManager {
         let manager = Self {
             app_handle: app_handle.clone(),
             db_path,
+            pool: Mutex::new(None),
+            storage_key: Mutex::new(None),
             active_session: Arc::new(Mutex::new(None)),
             session_start_time: Arc::new(Mutex::new(None)),
             speaker_buffer: Arc::new(Mutex::new(Vec::new())),
             speaker_shutdown: Arc::new(AtomicBool::new(false)),
             speaker_thread_handle: Arc::new(Mutex::new(None)),
+            offline_job_running: Arc::new(AtomicBool::new(false)),
+            offline_job_cancel: Arc::new(AtomicBool::new(false)),
+            engine_borrowed: Mutex::new(false),
+            recording_clock: Mutex::new(None),
         };
 
-        manager.init_database()?;
+        // Plaintext copies left behind if the app didn't exit cleanly
+        manager.remove_plaintext_copies();
+
+        if KeyFile::exists(&db_dir) {
+            info!("Session storage is encrypted, waiting for the passphrase");
+        } else {
+            manager.open_storage(None)?;
+        }
 
         Ok(manager)
     }
 
-    fn init_database(&self) -> Result<()> {
-        let mut conn = Connection::open(&self.db_path)?;
+    fn init_database(&self, key: Option<&StorageKey>) -> Result<()> {
+        let mut conn = encryption::open_database(&self.db_path, OpenFlags::default(), key)?;
 
         // Enable WAL mode for better crash recovery - writes go to a log file first,
         // so the main database file stays intact if we crash mid-write
@@ -257,8 +890,41 @@ impl SessionManager {
         Ok(())
     }
 
-    fn get_connection(&self) -> Result<Connection> {
-        Ok(Connection::open(&self.db_path)?)
+    /// Check out a pooled connection. Foreign keys are enforced and prepared
+    /// statements are cached per connection, so `prepare_cached` pays off on
+    /// hot paths.
+    pub(crate) fn get_connection(&self) -> Result<PooledConnection<SqliteConnectionManager>> {
+        let pool = self
+            .pool
+            .lock_or_recover()
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("Session storage is locked"))?;
+        Ok(pool.get()?)
+    }
+
+    /// Migrate sessions.db and open the connection pool, keyed when encrypted
+    fn open_storage(&self, key: Option<Arc<StorageKey>>) -> Result<()> {
+        self.init_database(key.as_deref())?;
+
+        let sqlcipher_key = key.as_ref().map(|k| k.sqlcipher_key());
+        let manager = SqliteConnectionManager::file(&self.db_path).with_init(move |conn| {
+            // The key has to be set before anything else touches the database
+            if let Some(sqlcipher_key) = &sqlcipher_key {
+                conn.pragma_update(None, "key", sqlcipher_key.as_str())?;
+            }
+            configure_connection(conn)
+        });
+        let pool = Pool::builder().max_size(DB_POOL_SIZE).build(manager)?;
+
+        *self.pool.lock_or_recover() = Some(pool);
+        *self.storage_key.lock_or_recover() = key;
+        Ok(())
+    }
+
+    /// Drop the connection pool so sessions.db can be replaced on disk
+    fn close_storage(&self) {
+        *self.pool.lock_or_recover() = None;
+        *self.storage_key.lock_or_recover() = None;
     }
 
     pub fn start_session(
@@ -266,8 +932,16 @@ impl SessionManager {
         title: Option<String>,
         default_environment_id: Option<String>,
     ) -> Result<Session> {
-        let id = Uuid::new_v4().to_string();
         let now = Utc::now().timestamp();
+
+        // An untitled note around a meeting's start records into its scheduled session
+        if title.is_none() {
+            if let Some(session_id) = self.find_scheduled_session_near(now)? {
+                return self.activate_scheduled_session(&session_id, now);
+            }
+        }
+
+        let id = Uuid::new_v4().to_string();
         let title = title.unwrap_or_else(|| "New Note".to_string());
 
         let env_id = default_environment_id.as_deref();
@@ -289,6 +963,7 @@ impl SessionManager {
             status: "active".to_string(),
             folder_id: None,
             environment_id: default_environment_id,
+            deleted_at: None,
         };
 
         let _ = self.app_handle.emit("session-started", &session);
@@ -297,15 +972,45 @@ impl SessionManager {
         Ok(session)
     }
 
-    pub fn end_session(&self) -> Result<Option<Session>> {
-        let session_id = {
-            let mut active = self.active_session.lock_or_recover();
-            active.take()
-        };
-
-        let Some(session_id) = session_id else {
-            return Ok(None);
-        };
+    /// Create a completed session for transcribing an existing audio file.
+    /// Unlike `start_session`, this does not touch the active session.
+    pub fn create_imported_session(
+        &self,
+        title: String,
+        default_environment_id: Option<String>,
+    ) -> Result<Session> {
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now().timestamp();
+
+        let conn = self.get_connection()?;
+        conn.execute(
+            "INSERT INTO sessions (id, title, started_at, ended_at, status, environment_id) VALUES (?1, ?2, ?3, ?3, 'completed', ?4)",
+            params![id, title, now, default_environment_id],
+        )?;
+
+        info!("Imported session created: {}", id);
+
+        Ok(Session {
+            id,
+            title,
+            started_at: now,
+            ended_at: Some(now),
+            status: "completed".to_string(),
+            folder_id: None,
+            environment_id: default_environment_id,
+            deleted_at: None,
+        })
+    }
+
+    pub fn end_session(&self) -> Result<Option<Session>> {
+        let session_id = {
+            let mut active = self.active_session.lock_or_recover();
+            active.take()
+        };
+
+        let Some(session_id) = session_id else {
+            return Ok(None);
+        };
 
         *self.session_start_time.lock_or_recover() = None;
 
@@ -336,16 +1041,56 @@ impl SessionManager {
         source: &str,
         start_ms: i64,
         end_ms: i64,
+    ) -> Result<TranscriptSegment> {
+        self.add_segment_with_words(session_id, text, source, start_ms, end_ms, &[])
+    }
+
+    /// Add a transcript segment along with its word timings.
+    /// Word timings must already be on the session timeline.
+    pub fn add_segment_with_words(
+        &self,
+        session_id: &str,
+        text: String,
+        source: &str,
+        start_ms: i64,
+        end_ms: i64,
+        words: &[TimedWord],
     ) -> Result<TranscriptSegment> {
         let now = Utc::now().timestamp();
-        let conn = self.get_connection()?;
+        let mut conn = self.get_connection()?;
+        let tx = conn.transaction()?;
 
-        conn.execute(
+        tx.prepare_cached(
             "INSERT INTO transcript_segments (session_id, text, source, start_ms, end_ms, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-            params![session_id, text, source, start_ms, end_ms, now],
-        )?;
+        )?
+        .execute(params![session_id, text, source, start_ms, end_ms, now])?;
 
-        let id = conn.last_insert_rowid();
+        let id = tx.last_insert_rowid();
+
+        let mut segment_words = Vec::with_capacity(words.len());
+        {
+            let mut stmt = tx.prepare_cached(
+                "INSERT INTO transcript_words (segment_id, text, start_ms, end_ms, estimated) VALUES (?1, ?2, ?3, ?4, ?5)",
+            )?;
+            for word in words {
+                stmt.execute(params![
+                    id,
+                    word.text,
+                    word.start_ms,
+                    word.end_ms,
+                    word.estimated
+                ])?;
+                segment_words.push(TranscriptWord {
+                    id: tx.last_insert_rowid(),
+                    segment_id: id,
+                    text: word.text.clone(),
+                    start_ms: word.start_ms,
+                    end_ms: word.end_ms,
+                    estimated: word.estimated,
+                });
+            }
+        }
+        tx.commit()?;
 
         let segment = TranscriptSegment {
             id,
@@ -355,6 +1100,8 @@ impl SessionManager {
             start_ms,
             end_ms,
             created_at: now,
+            speaker: None,
+            words: segment_words,
         };
 
         let _ = self.app_handle.emit(
@@ -364,6 +1111,7 @@ impl SessionManager {
                 segment: segment.clone(),
             },
         );
+        self.notify_embedding_indexer();
 
         Ok(segment)
     }
@@ -371,17 +1119,28 @@ impl SessionManager {
     pub fn search_sessions(&self, query: &str) -> Result<Vec<Session>> {
         let conn = self.get_connection()?;
         let pattern = format!("%{}%", query);
+        // An empty phrase never matches, so blank queries fall back to the LIKE filters
+        let fts_query = build_fts_query(query).unwrap_or_else(|| "\"\"".to_string());
         let mut stmt = conn.prepare(
-            "SELECT DISTINCT s.id, s.title, s.started_at, s.ended_at, s.status, s.folder_id, s.environment_id
+            "SELECT DISTINCT s.id, s.title, s.started_at, s.ended_at, s.status, s.folder_id, s.environment_id, s.deleted_at
              FROM sessions s
              LEFT JOIN meeting_notes mn ON mn.session_id = s.id
-             WHERE s.title LIKE ?1
+             WHERE s.status != 'deleted'
+               AND (s.title LIKE ?1
                 OR mn.user_notes LIKE ?1
                 OR mn.enhanced_notes LIKE ?1
+                OR s.id IN (
+                    SELECT ts.session_id FROM transcript_fts
+                    JOIN transcript_segments ts ON ts.id = transcript_fts.rowid
+                    WHERE transcript_fts MATCH ?2
+                )
+                OR s.id IN (
+                    SELECT session_id FROM attachments_fts WHERE attachments_fts MATCH ?2
+                ))
              ORDER BY s.started_at DESC",
         )?;
 
-        let rows = stmt.query_map(params![pattern], |row| {
+        let rows = stmt.query_map(params![pattern, fts_query], |row| {
             Ok(Session {
                 id: row.get("id")?,
                 title: row.get("title")?,
@@ -390,6 +1149,7 @@ impl SessionManager {
                 status: row.get("status")?,
                 folder_id: row.get("folder_id")?,
                 environment_id: row.get("environment_id")?,
+                deleted_at: row.get("deleted_at")?,
             })
         })?;
 
@@ -400,10 +1160,19 @@ impl SessionManager {
         Ok(sessions)
     }
 
+    /// Full-text search across transcripts, meeting notes and attachment text.
+    ///
+    /// Each index is ranked on its own and the hits are interleaved, so the
+    /// best matching segment, note and document come first whatever their kind.
+    pub fn search_full_text(&self, query: &str, limit: u32) -> Result<Vec<SearchHit>> {
+        let conn = self.get_connection()?;
+        search_full_text(&conn, query, limit)
+    }
+
     pub fn get_sessions(&self) -> Result<Vec<Session>> {
         let conn = self.get_connection()?;
         let mut stmt = conn.prepare(
-            "SELECT id, title, started_at, ended_at, status, folder_id, environment_id FROM sessions ORDER BY started_at DESC",
+            "SELECT id, title, started_at, ended_at, status, folder_id, environment_id, deleted_at FROM sessions WHERE status != 'deleted' ORDER BY started_at DESC",
         )?;
 
         let rows = stmt.query_map([], |row| {
@@ -415,6 +1184,7 @@ impl SessionManager {
                 status: row.get("status")?,
                 folder_id: row.get("folder_id")?,
                 environment_id: row.get("environment_id")?,
+                deleted_at: row.get("deleted_at")?,
             })
         })?;
 
@@ -425,11 +1195,29 @@ impl SessionManager {
         Ok(sessions)
     }
 
+    /// When each session outside the trash last changed in a way that shows
+    /// in exports, in Unix seconds
+    pub fn get_session_updated_at(&self) -> Result<HashMap<String, i64>> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, COALESCE(updated_at, started_at) FROM sessions WHERE status != 'deleted'",
+        )?;
+
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+
+        let mut updated_at = HashMap::new();
+        for row in rows {
+            let (session_id, at) = row?;
+            updated_at.insert(session_id, at);
+        }
+        Ok(updated_at)
+    }
+
     pub fn get_session(&self, session_id: &str) -> Result<Option<Session>> {
         let conn = self.get_connection()?;
         let session = conn
             .query_row(
-                "SELECT id, title, started_at, ended_at, status, folder_id, environment_id FROM sessions WHERE id = ?1",
+                "SELECT id, title, started_at, ended_at, status, folder_id, environment_id, deleted_at FROM sessions WHERE id = ?1",
                 params![session_id],
                 |row| {
                     Ok(Session {
@@ -440,6 +1228,7 @@ impl SessionManager {
                         status: row.get("status")?,
                         folder_id: row.get("folder_id")?,
                         environment_id: row.get("environment_id")?,
+                        deleted_at: row.get("deleted_at")?,
                     })
                 },
             )
@@ -450,7 +1239,7 @@ impl SessionManager {
     pub fn get_session_transcript(&self, session_id: &str) -> Result<Vec<TranscriptSegment>> {
         let conn = self.get_connection()?;
         let mut stmt = conn.prepare(
-            "SELECT id, session_id, text, source, start_ms, end_ms, created_at FROM transcript_segments WHERE session_id = ?1 ORDER BY start_ms ASC",
+            "SELECT id, session_id, text, source, start_ms, end_ms, created_at, speaker FROM transcript_segments WHERE session_id = ?1 ORDER BY start_ms ASC",
         )?;
 
         let rows = stmt.query_map(params![session_id], |row| {
@@ -462,6 +1251,8 @@ impl SessionManager {
                 start_ms: row.get("start_ms")?,
                 end_ms: row.get("end_ms")?,
                 created_at: row.get("created_at")?,
+                speaker: row.get("speaker")?,
+                words: Vec::new(),
             })
         })?;
 
@@ -469,6 +1260,40 @@ impl SessionManager {
         for row in rows {
             segments.push(row?);
         }
+
+        let mut stmt = conn.prepare(
+            "SELECT w.id, w.segment_id, w.text, w.start_ms, w.end_ms, w.estimated
+             FROM transcript_words w
+             JOIN transcript_segments t ON t.id = w.segment_id
+             WHERE t.session_id = ?1
+             ORDER BY w.segment_id, w.id",
+        )?;
+        let rows = stmt.query_map(params![session_id], |row| {
+            Ok(TranscriptWord {
+                id: row.get("id")?,
+                segment_id: row.get("segment_id")?,
+                text: row.get("text")?,
+                start_ms: row.get("start_ms")?,
+                end_ms: row.get("end_ms")?,
+                estimated: row.get("estimated")?,
+            })
+        })?;
+
+        let mut words_by_segment: std::collections::HashMap<i64, Vec<TranscriptWord>> =
+            std::collections::HashMap::new();
+        for row in rows {
+            let word = row?;
+            words_by_segment
+                .entry(word.segment_id)
+                .or_default()
+                .push(word);
+        }
+        for segment in &mut segments {
+            if let Some(words) = words_by_segment.remove(&segment.id) {
+                segment.words = words;
+            }
+        }
+
         Ok(segments)
     }
 
@@ -491,8 +1316,8 @@ impl SessionManager {
         since_ms: i64,
     ) -> Result<Vec<TranscriptSegment>> {
         let conn = self.get_connection()?;
-        let mut stmt = conn.prepare(
-            "SELECT id, session_id, text, source, start_ms, end_ms, created_at
+        let mut stmt = conn.prepare_cached(
+            "SELECT id, session_id, text, source, start_ms, end_ms, created_at, speaker
              FROM transcript_segments
              WHERE session_id = ?1 AND source = ?2 AND end_ms >= ?3
              ORDER BY start_ms DESC
@@ -508,6 +1333,8 @@ impl SessionManager {
                 start_ms: row.get("start_ms")?,
                 end_ms: row.get("end_ms")?,
                 created_at: row.get("created_at")?,
+                speaker: row.get("speaker")?,
+                words: Vec::new(),
             })
         })?;
 
@@ -518,21 +1345,225 @@ impl SessionManager {
         Ok(segments)
     }
 
+    /// Move a session to the trash. Nothing is removed until it is purged,
+    /// either by `delete_session` or by `purge_expired_trash`.
+    pub fn trash_session(&self, session_id: &str) -> Result<()> {
+        if self.get_active_session_id().as_deref() == Some(session_id) {
+            return Err(anyhow::anyhow!(
+                "Cannot delete a session that is still active"
+            ));
+        }
+
+        let conn = self.get_connection()?;
+        let updated = conn.execute(
+            "UPDATE sessions SET status = 'deleted', deleted_at = ?1 WHERE id = ?2 AND status != 'deleted'",
+            params![Utc::now().timestamp(), session_id],
+        )?;
+        if updated == 0 {
+            return Err(anyhow::anyhow!("Session not found: {}", session_id));
+        }
+
+        // The session disappears from every list, same as a hard delete
+        let _ = self.app_handle.emit("session-deleted", session_id);
+        info!("Session moved to trash: {}", session_id);
+        Ok(())
+    }
+
+    /// Sessions in the trash, most recently deleted first
+    pub fn get_trashed_sessions(&self) -> Result<Vec<Session>> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, title, started_at, ended_at, status, folder_id, environment_id, deleted_at
+             FROM sessions WHERE status = 'deleted' ORDER BY deleted_at DESC",
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            Ok(Session {
+                id: row.get("id")?,
+                title: row.get("title")?,
+                started_at: row.get("started_at")?,
+                ended_at: row.get("ended_at")?,
+                status: row.get("status")?,
+                folder_id: row.get("folder_id")?,
+                environment_id: row.get("environment_id")?,
+                deleted_at: row.get("deleted_at")?,
+            })
+        })?;
+
+        let mut sessions = Vec::new();
+        for row in rows {
+            sessions.push(row?);
+        }
+        Ok(sessions)
+    }
+
+    pub fn restore_session(&self, session_id: &str) -> Result<Session> {
+        let conn = self.get_connection()?;
+        // Only ended sessions can be trashed, so they come back as completed
+        let updated = conn.execute(
+            "UPDATE sessions SET status = 'completed', deleted_at = NULL WHERE id = ?1 AND status = 'deleted'",
+            params![session_id],
+        )?;
+        if updated == 0 {
+            return Err(anyhow::anyhow!(
+                "Session is not in the trash: {}",
+                session_id
+            ));
+        }
+
+        let session = self
+            .get_session(session_id)?
+            .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
+        let _ = self.app_handle.emit("session-restored", &session);
+        info!("Session restored from trash: {}", session_id);
+        Ok(session)
+    }
+
+    /// Permanently delete every session in the trash
+    pub fn empty_trash(&self) -> Result<usize> {
+        let ids = self.trashed_session_ids(None)?;
+        for id in &ids {
+            self.delete_session(id)?;
+        }
+        Ok(ids.len())
+    }
+
+    /// Permanently delete sessions that have been in the trash for longer than
+    /// the trash retention setting. A retention of 0 days keeps them forever.
+    pub fn purge_expired_trash(&self) -> Result<()> {
+        let retention_days = crate::settings::get_settings(&self.app_handle).trash_retention_days;
+        if retention_days == 0 {
+            return Ok(());
+        }
+
+        let cutoff = Utc::now().timestamp() - retention_days as i64 * 24 * 60 * 60;
+        let ids = self.trashed_session_ids(Some(cutoff))?;
+        for id in &ids {
+            self.delete_session(id)?;
+        }
+
+        if !ids.is_empty() {
+            info!("Purged {} sessions from the trash", ids.len());
+        }
+        Ok(())
+    }
+
+    /// Permanently delete the scheduled sessions of past meetings that were
+    /// never recorded or written in, so missed meetings don't pile up in the
+    /// session list
+    pub fn expire_scheduled_sessions(&self) -> Result<()> {
+        let cutoff = Utc::now().timestamp() - SCHEDULED_SESSION_EXPIRY_SECS;
+        let conn = self.get_connection()?;
+        let ids = expired_scheduled_session_ids(&conn, cutoff)?;
+        drop(conn);
+        for id in &ids {
+            self.delete_session(id)?;
+        }
+
+        if !ids.is_empty() {
+            info!("Removed {} scheduled sessions of past meetings", ids.len());
+        }
+        Ok(())
+    }
+
+    /// Start the thread that purges expired trash and past scheduled sessions
+    /// every `TRASH_PURGE_POLL`, so sessions don't outlive the retention period
+    /// while the app stays open. It stops once the manager is dropped.
+    pub fn start_trash_purger(self: &Arc<Self>) {
+        let manager: Weak<Self> = Arc::downgrade(self);
+        thread::spawn(move || {
+            loop {
+                thread::sleep(TRASH_PURGE_POLL);
+                let Some(manager) = manager.upgrade() else {
+                    break;
+                };
+                // Encrypted storage can't be read until it is unlocked
+                if manager.get_storage_encryption_status().locked {
+                    continue;
+                }
+                if let Err(e) = manager.purge_expired_trash() {
+                    warn!("Failed to purge expired trash: {}", e);
+                }
+                if let Err(e) = manager.expire_scheduled_sessions() {
+                    warn!("Failed to remove past scheduled sessions: {}", e);
+                }
+            }
+            debug!("Trash purger shutting down");
+        });
+    }
+
+    fn trashed_session_ids(&self, deleted_before: Option<i64>) -> Result<Vec<String>> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT id FROM sessions WHERE status = 'deleted' AND (?1 IS NULL OR deleted_at < ?1)",
+        )?;
+        let rows = stmt.query_map(params![deleted_before], |row| row.get::<_, String>(0))?;
+
+        let mut ids = Vec::new();
+        for row in rows {
+            ids.push(row?);
+        }
+        Ok(ids)
+    }
+
+    /// Permanently delete a session with its transcript, notes, attachments
+    /// and archived audio.
     pub fn delete_session(&self, session_id: &str) -> Result<()> {
         // Clean up attachments (files + db records)
         self.delete_session_attachments(session_id)?;
+        // Clean up archived audio (files + db records)
+        self.delete_session_recordings(session_id)?;
 
         let conn = self.get_connection()?;
 
-        // Clean up any legacy audio_recordings entries
         conn.execute(
-            "DELETE FROM audio_recordings WHERE session_id = ?1",
+            "DELETE FROM chat_messages WHERE thread_id IN (SELECT id FROM chat_threads WHERE session_id = ?1)",
+            params![session_id],
+        )?;
+        conn.execute(
+            "DELETE FROM chat_threads WHERE session_id = ?1",
+            params![session_id],
+        )?;
+        conn.execute(
+            "DELETE FROM action_items WHERE session_id = ?1",
             params![session_id],
         )?;
         conn.execute(
             "DELETE FROM meeting_notes WHERE session_id = ?1",
             params![session_id],
         )?;
+        conn.execute(
+            "DELETE FROM meeting_notes_revisions WHERE session_id = ?1",
+            params![session_id],
+        )?;
+        conn.execute(
+            "DELETE FROM transcript_revision_segments WHERE revision_id IN (SELECT id FROM transcript_revisions WHERE session_id = ?1)",
+            params![session_id],
+        )?;
+        conn.execute(
+            "DELETE FROM transcript_revisions WHERE session_id = ?1",
+            params![session_id],
+        )?;
+        conn.execute(
+            "DELETE FROM session_speakers WHERE session_id = ?1",
+            params![session_id],
+        )?;
+        conn.execute(
+            "DELETE FROM transcript_segment_edits WHERE session_id = ?1",
+            params![session_id],
+        )?;
+        conn.execute(
+            "DELETE FROM text_embeddings WHERE session_id = ?1",
+            params![session_id],
+        )?;
+        conn.execute(
+            "DELETE FROM bookmarks WHERE session_id = ?1",
+            params![session_id],
+        )?;
+        conn.execute(
+            "DELETE FROM transcript_words WHERE segment_id IN (SELECT id FROM transcript_segments WHERE session_id = ?1)",
+            params![session_id],
+        )?;
         conn.execute(
             "DELETE FROM transcript_segments WHERE session_id = ?1",
             params![session_id],
@@ -577,12 +1608,25 @@ impl SessionManager {
         enhanced_notes_edited: Option<bool>,
     ) -> Result<()> {
         let now = Utc::now().timestamp();
-        let conn = self.get_connection()?;
+        let mut conn = self.get_connection()?;
+        let tx = conn.transaction()?;
 
         // Convert bool to i32 for SQLite
         let edited_int = enhanced_notes_edited.map(|b| if b { 1i32 } else { 0i32 });
 
-        conn.execute(
+        // Fresh AI output is never coalesced with the user's edits
+        let revision_kind = if enhanced_notes.is_some() && enhanced_notes_edited == Some(false) {
+            Some("enhance")
+        } else if user_notes.is_some() || enhanced_notes.is_some() {
+            Some("edit")
+        } else {
+            None
+        };
+        if revision_kind.is_some() {
+            snapshot_initial_notes(&tx, session_id)?;
+        }
+
+        tx.execute(
             "INSERT INTO meeting_notes (session_id, summary, action_items, decisions, user_notes, enhanced_notes, enhanced_notes_edited, created_at, updated_at)
              VALUES (?1, ?2, ?3, ?4, ?5, ?6, COALESCE(?7, 0), ?8, ?8)
              ON CONFLICT(session_id) DO UPDATE SET
@@ -596,9 +1640,117 @@ impl SessionManager {
             params![session_id, summary, action_items, decisions, user_notes, enhanced_notes, edited_int, now],
         )?;
 
+        if let Some(kind) = revision_kind {
+            snapshot_meeting_notes(&tx, session_id, kind, now)?;
+        }
+        tx.commit()?;
+
+        if revision_kind.is_some() {
+            self.notify_embedding_indexer();
+        }
         Ok(())
     }
 
+    // ==================== Notes History ====================
+
+    /// Notes snapshots for a session, newest first
+    pub fn get_meeting_notes_revisions(
+        &self,
+        session_id: &str,
+    ) -> Result<Vec<MeetingNotesRevision>> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, session_id, kind, user_notes, enhanced_notes, created_at, updated_at
+             FROM meeting_notes_revisions
+             WHERE session_id = ?1
+             ORDER BY id DESC",
+        )?;
+
+        let rows = stmt.query_map(params![session_id], map_notes_revision)?;
+
+        let mut revisions = Vec::new();
+        for row in rows {
+            revisions.push(row?);
+        }
+        Ok(revisions)
+    }
+
+    pub fn get_meeting_notes_revision(
+        &self,
+        revision_id: i64,
+    ) -> Result<Option<MeetingNotesRevision>> {
+        let conn = self.get_connection()?;
+        let revision = conn
+            .query_row(
+                "SELECT id, session_id, kind, user_notes, enhanced_notes, created_at, updated_at
+                 FROM meeting_notes_revisions WHERE id = ?1",
+                params![revision_id],
+                map_notes_revision,
+            )
+            .optional()?;
+        Ok(revision)
+    }
+
+    /// Put a revision's user notes and enhanced notes back. The restored state
+    /// is snapshotted too, so the notes it replaced stay in the history.
+    pub fn restore_meeting_notes_revision(&self, revision_id: i64) -> Result<MeetingNotes> {
+        let revision = self
+            .get_meeting_notes_revision(revision_id)?
+            .ok_or_else(|| anyhow::anyhow!("Notes revision not found: {}", revision_id))?;
+
+        let now = Utc::now().timestamp();
+        let mut conn = self.get_connection()?;
+        let tx = conn.transaction()?;
+        tx.execute(
+            "INSERT INTO meeting_notes (session_id, user_notes, enhanced_notes, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?4)
+             ON CONFLICT(session_id) DO UPDATE SET
+                user_notes = ?2,
+                enhanced_notes = ?3,
+                updated_at = ?4",
+            params![
+                revision.session_id,
+                revision.user_notes,
+                revision.enhanced_notes,
+                now
+            ],
+        )?;
+        snapshot_meeting_notes(&tx, &revision.session_id, "restore", now)?;
+        tx.commit()?;
+
+        info!(
+            "Restored notes revision {} for session {}",
+            revision_id, revision.session_id
+        );
+        self.get_meeting_notes(&revision.session_id)?
+            .ok_or_else(|| anyhow::anyhow!("Notes not found for session {}", revision.session_id))
+    }
+
+    /// Line diff of user notes and enhanced notes from one revision to another
+    pub fn diff_meeting_notes_revisions(
+        &self,
+        from_revision_id: i64,
+        to_revision_id: i64,
+    ) -> Result<MeetingNotesDiff> {
+        let from = self
+            .get_meeting_notes_revision(from_revision_id)?
+            .ok_or_else(|| anyhow::anyhow!("Notes revision not found: {}", from_revision_id))?;
+        let to = self
+            .get_meeting_notes_revision(to_revision_id)?
+            .ok_or_else(|| anyhow::anyhow!("Notes revision not found: {}", to_revision_id))?;
+
+        Ok(MeetingNotesDiff {
+            user_notes: diff_lines(
+                from.user_notes.as_deref().unwrap_or_default(),
+                to.user_notes.as_deref().unwrap_or_default(),
+            ),
+            enhanced_notes: diff_lines(
+                from.enhanced_notes.as_deref().unwrap_or_default(),
+                to.enhanced_notes.as_deref().unwrap_or_default(),
+            ),
+        })
+    }
+
     pub fn get_meeting_notes(&self, session_id: &str) -> Result<Option<MeetingNotes>> {
         let conn = self.get_connection()?;
         let notes = conn
@@ -671,6 +1823,61 @@ impl SessionManager {
         }
     }
 
+    /// Mark an offline transcription job (audio import or re-transcription) as
+    /// running. Returns false if one is already in progress.
+    pub fn begin_offline_job(&self) -> bool {
+        let started = self
+            .offline_job_running
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .is_ok();
+        if started {
+            self.offline_job_cancel.store(false, Ordering::Release);
+        }
+        started
+    }
+
+    pub fn finish_offline_job(&self) {
+        self.offline_job_running.store(false, Ordering::Release);
+    }
+
+    pub fn cancel_offline_job(&self) {
+        self.offline_job_cancel.store(true, Ordering::Release);
+    }
+
+    pub fn is_offline_job_cancelled(&self) -> bool {
+        self.offline_job_cancel.load(Ordering::Acquire)
+    }
+
+    /// Borrow the transcription engine for a re-transcription, unless it is
+    /// already borrowed or `in_use` says recording needs it. Checked and set
+    /// under one lock, so recording can't start in between.
+    pub fn try_borrow_engine(&self, in_use: impl FnOnce() -> bool) -> bool {
+        let mut borrowed = self.engine_borrowed.lock_or_recover();
+        if *borrowed || in_use() {
+            return false;
+        }
+        *borrowed = true;
+        true
+    }
+
+    pub fn return_engine(&self) {
+        *self.engine_borrowed.lock_or_recover() = false;
+    }
+
+    pub fn is_engine_borrowed(&self) -> bool {
+        *self.engine_borrowed.lock_or_recover()
+    }
+
+    /// Run `start` unless the engine is borrowed, holding the borrow lock so
+    /// a re-transcription can't take the engine while recording starts
+    pub fn unless_engine_borrowed<T>(&self, start: impl FnOnce() -> T) -> Option<T> {
+        let borrowed = self.engine_borrowed.lock_or_recover();
+        if *borrowed {
+            return None;
+        }
+        Some(start())
+    }
+
     /// Get the time offset for a new recording pass by finding the max end_ms in existing segments.
     /// This is simpler than tracking in-memory state and survives app restarts.
     pub fn get_session_time_offset(&self, session_id: &str) -> i64 {
@@ -679,23 +1886,56 @@ impl SessionManager {
             Err(_) => return 0,
         };
 
-        conn.query_row(
+        conn.prepare_cached(
             "SELECT COALESCE(MAX(end_ms), 0) FROM transcript_segments WHERE session_id = ?1",
-            params![session_id],
-            |row| row.get(0),
         )
+        .and_then(|mut stmt| stmt.query_row(params![session_id], |row| row.get(0)))
         .unwrap_or(0)
     }
 
-    pub fn reactivate_session(&self, session_id: &str) -> Result<Session> {
-        // End any currently active session
-        self.end_session()?;
+    /// Called by the transcription loop when a recording pass starts
+    pub fn start_recording_clock(&self, started: std::time::Instant, time_offset_ms: i64) {
+        *self.recording_clock.lock_or_recover() = Some((started, time_offset_ms));
+    }
 
-        // Reactivate the target session
-        let conn = self.get_connection()?;
-        conn.execute(
-            "UPDATE sessions SET status = 'active', ended_at = NULL WHERE id = ?1",
-            params![session_id],
+    /// Called by the transcription loop when its recording pass ends. A pass
+    /// that has already been replaced by a newer one leaves the clock alone.
+    pub fn stop_recording_clock(&self, started: std::time::Instant) {
+        let mut clock = self.recording_clock.lock_or_recover();
+        if clock.is_some_and(|(s, _)| s == started) {
+            *clock = None;
+        }
+    }
+
+    /// Where the recording of `session_id` currently is on the session
+    /// timeline, or None if it isn't being recorded
+    pub fn current_session_time_ms(&self, session_id: &str) -> Option<i64> {
+        if self.get_active_session_id().as_deref() != Some(session_id) {
+            return None;
+        }
+        self.recording_clock
+            .lock_or_recover()
+            .map(|(started, offset_ms)| started.elapsed().as_millis() as i64 + offset_ms)
+    }
+
+    pub fn reactivate_session(&self, session_id: &str) -> Result<Session> {
+        if self
+            .get_session(session_id)?
+            .is_some_and(|s| s.status == "deleted")
+        {
+            return Err(anyhow::anyhow!(
+                "Restore the session from the trash before recording into it"
+            ));
+        }
+
+        // End any currently active session
+        self.end_session()?;
+
+        // Reactivate the target session
+        let conn = self.get_connection()?;
+        conn.execute(
+            "UPDATE sessions SET status = 'active', ended_at = NULL WHERE id = ?1",
+            params![session_id],
         )?;
 
         *self.active_session.lock_or_recover() = Some(session_id.to_string());
@@ -711,228 +1951,944 @@ impl SessionManager {
         Ok(session)
     }
 
-    // ==================== Folder CRUD ====================
+    // ==================== Calendar Events ====================
 
-    pub fn create_folder(&self, name: String, color: Option<String>) -> Result<Folder> {
-        let id = Uuid::new_v4().to_string();
+    /// Bring the events imported from `source` in line with `events`, its
+    /// occurrences starting inside `window`. New events get a scheduled session;
+    /// changed ones update it until recording starts. Events cancelled or dropped
+    /// from the source are removed, with their session if it was never recorded
+    /// or written in.
+    pub fn sync_calendar_events(
+        &self,
+        source: &str,
+        events: &[CalendarEvent],
+        window: (i64, i64),
+        default_environment_id: Option<&str>,
+    ) -> Result<CalendarImportSummary> {
         let now = Utc::now().timestamp();
-        let conn = self.get_connection()?;
+        let mut summary = CalendarImportSummary::default();
+        let mut kept = HashSet::new();
+        let mut unused_sessions = Vec::new();
+
+        let mut conn = self.get_connection()?;
+        let tx = conn.transaction()?;
+        for event in events {
+            let existing = tx
+                .query_row(
+                    "SELECT id, session_id, title, description, location, starts_at, ends_at
+                     FROM calendar_events WHERE uid = ?1 AND instance = ?2",
+                    params![event.uid, event.instance],
+                    |row| {
+                        let unchanged = row.get::<_, String>("title")? == event.title
+                            && row.get::<_, Option<String>>("description")? == event.description
+                            && row.get::<_, Option<String>>("location")? == event.location
+                            && row.get::<_, i64>("starts_at")? == event.starts_at
+                            && row.get::<_, i64>("ends_at")? == event.ends_at;
+                        Ok((
+                            row.get::<_, String>("id")?,
+                            row.get::<_, Option<String>>("session_id")?,
+                            unchanged,
+                        ))
+                    },
+                )
+                .optional()?;
+
+            let event_id = match existing {
+                Some((event_id, session_id, unchanged)) => {
+                    tx.execute(
+                        "UPDATE calendar_events SET source = ?1, title = ?2, description = ?3, location = ?4,
+                            starts_at = ?5, ends_at = ?6, updated_at = ?7
+                         WHERE id = ?8",
+                        params![
+                            source,
+                            event.title,
+                            event.description,
+                            event.location,
+                            event.starts_at,
+                            event.ends_at,
+                            now,
+                            event_id
+                        ],
+                    )?;
+                    // Sessions the user deleted stay deleted
+                    if let Some(session_id) = session_id {
+                        tx.execute(
+                            "UPDATE sessions SET title = ?1, started_at = ?2 WHERE id = ?3 AND status = 'scheduled'",
+                            params![event.title, event.starts_at, session_id],
+                        )?;
+                    }
+                    if !unchanged {
+                        summary.updated += 1;
+                    }
+                    event_id
+                }
+                None => {
+                    let session_id = Uuid::new_v4().to_string();
+                    tx.execute(
+                        "INSERT INTO sessions (id, title, started_at, status, environment_id) VALUES (?1, ?2, ?3, 'scheduled', ?4)",
+                        params![session_id, event.title, event.starts_at, default_environment_id],
+                    )?;
+                    let event_id = Uuid::new_v4().to_string();
+                    tx.execute(
+                        "INSERT INTO calendar_events (id, source, uid, instance, session_id, title, description, location, starts_at, ends_at, updated_at)
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                        params![
+                            event_id,
+                            source,
+                            event.uid,
+                            event.instance,
+                            session_id,
+                            event.title,
+                            event.description,
+                            event.location,
+                            event.starts_at,
+                            event.ends_at,
+                            now
+                        ],
+                    )?;
+                    summary.created += 1;
+                    event_id
+                }
+            };
 
-        // Get max sort_order
-        let max_order: i32 = conn
-            .query_row(
-                "SELECT COALESCE(MAX(sort_order), 0) FROM folders",
-                [],
-                |row| row.get(0),
-            )
-            .unwrap_or(0);
+            tx.execute(
+                "DELETE FROM calendar_attendees WHERE event_id = ?1",
+                params![event_id],
+            )?;
+            for attendee in &event.attendees {
+                tx.execute(
+                    "INSERT INTO calendar_attendees (event_id, name, email) VALUES (?1, ?2, ?3)",
+                    params![event_id, attendee.name, attendee.email],
+                )?;
+            }
+            kept.insert(event_id);
+        }
 
-        conn.execute(
-            "INSERT INTO folders (id, name, color, sort_order, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
-            params![id, name, color, max_order + 1, now],
-        )?;
+        // Events outside the window are left alone, the source no longer lists them
+        let mut stale = Vec::new();
+        {
+            let mut stmt = tx.prepare(
+                "SELECT id, session_id FROM calendar_events
+                 WHERE source = ?1 AND starts_at >= ?2 AND starts_at < ?3",
+            )?;
+            let rows = stmt.query_map(params![source, window.0, window.1], |row| {
+                Ok((
+                    row.get::<_, String>("id")?,
+                    row.get::<_, Option<String>>("session_id")?,
+                ))
+            })?;
+            for row in rows {
+                stale.push(row?);
+            }
+        }
+        for (event_id, session_id) in stale {
+            if kept.contains(&event_id) {
+                continue;
+            }
+            tx.execute(
+                "DELETE FROM calendar_events WHERE id = ?1",
+                params![event_id],
+            )?;
+            summary.removed += 1;
+
+            let Some(session_id) = session_id else {
+                continue;
+            };
+            let unused: bool = tx.query_row(
+                "SELECT EXISTS (
+                    SELECT 1 FROM sessions s WHERE s.id = ?1 AND s.status = 'scheduled'
+                      AND NOT EXISTS (SELECT 1 FROM meeting_notes n WHERE n.session_id = s.id AND COALESCE(n.user_notes, '') != '')
+                      AND NOT EXISTS (SELECT 1 FROM session_attachments a WHERE a.session_id = s.id)
+                )",
+                params![session_id],
+                |row| row.get(0),
+            )?;
+            if unused {
+                unused_sessions.push(session_id);
+            }
+        }
+        tx.commit()?;
+        drop(conn);
 
-        Ok(Folder {
-            id,
-            name,
-            color,
-            sort_order: max_order + 1,
-            created_at: now,
-        })
+        for session_id in unused_sessions {
+            self.delete_session(&session_id)?;
+        }
+        Ok(summary)
     }
 
-    pub fn update_folder(
-        &self,
-        folder_id: &str,
-        name: String,
-        color: Option<String>,
-    ) -> Result<()> {
+    /// Files events have been imported from
+    pub fn get_calendar_sources(&self) -> Result<Vec<String>> {
         let conn = self.get_connection()?;
-        conn.execute(
-            "UPDATE folders SET name = ?1, color = ?2 WHERE id = ?3",
-            params![name, color, folder_id],
-        )?;
-        Ok(())
-    }
+        let mut stmt = conn.prepare("SELECT DISTINCT source FROM calendar_events")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
 
-    pub fn delete_folder(&self, folder_id: &str) -> Result<()> {
-        let conn = self.get_connection()?;
-        // Move sessions in this folder to unfiled (folder_id = NULL)
-        conn.execute(
-            "UPDATE sessions SET folder_id = NULL WHERE folder_id = ?1",
-            params![folder_id],
-        )?;
-        conn.execute("DELETE FROM folders WHERE id = ?1", params![folder_id])?;
-        Ok(())
+        let mut sources = Vec::new();
+        for row in rows {
+            sources.push(row?);
+        }
+        Ok(sources)
     }
 
-    pub fn get_folders(&self) -> Result<Vec<Folder>> {
+    pub fn get_session_calendar_event(
+        &self,
+        session_id: &str,
+    ) -> Result<Option<SessionCalendarEvent>> {
         let conn = self.get_connection()?;
+        let event = conn
+            .query_row(
+                "SELECT id, title, description, location, starts_at, ends_at
+                 FROM calendar_events WHERE session_id = ?1
+                 ORDER BY starts_at LIMIT 1",
+                params![session_id],
+                |row| {
+                    Ok(SessionCalendarEvent {
+                        id: row.get("id")?,
+                        title: row.get("title")?,
+                        description: row.get("description")?,
+                        location: row.get("location")?,
+                        starts_at: row.get("starts_at")?,
+                        ends_at: row.get("ends_at")?,
+                        attendees: Vec::new(),
+                    })
+                },
+            )
+            .optional()?;
+        let Some(mut event) = event else {
+            return Ok(None);
+        };
+
         let mut stmt = conn.prepare(
-            "SELECT id, name, color, sort_order, created_at FROM folders ORDER BY sort_order ASC",
+            "SELECT name, email FROM calendar_attendees WHERE event_id = ?1 ORDER BY id",
         )?;
-
-        let rows = stmt.query_map([], |row| {
-            Ok(Folder {
-                id: row.get("id")?,
+        let rows = stmt.query_map(params![event.id], |row| {
+            Ok(CalendarAttendee {
                 name: row.get("name")?,
-                color: row.get("color")?,
-                sort_order: row.get("sort_order")?,
-                created_at: row.get("created_at")?,
+                email: row.get("email")?,
             })
         })?;
-
-        let mut folders = Vec::new();
         for row in rows {
-            folders.push(row?);
+            event.attendees.push(row?);
         }
-        Ok(folders)
+        Ok(Some(event))
     }
 
-    pub fn move_session_to_folder(
-        &self,
-        session_id: &str,
-        folder_id: Option<String>,
-    ) -> Result<()> {
+    /// Display names of the attendees of the session's calendar event, for
+    /// vocabulary correction in transcription and the enhance prompt
+    pub fn get_session_attendee_names(&self, session_id: &str) -> Result<Vec<String>> {
         let conn = self.get_connection()?;
-        conn.execute(
-            "UPDATE sessions SET folder_id = ?1 WHERE id = ?2",
-            params![folder_id, session_id],
+        let mut stmt = conn.prepare_cached(
+            "SELECT a.name FROM calendar_attendees a
+             JOIN calendar_events e ON e.id = a.event_id
+             WHERE e.session_id = ?1 AND a.name IS NOT NULL
+             ORDER BY a.id",
         )?;
-        Ok(())
+        let rows = stmt.query_map(params![session_id], |row| row.get::<_, String>(0))?;
+
+        let mut names: Vec<String> = Vec::new();
+        for name in rows {
+            let name = name?;
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        Ok(names)
     }
 
-    pub fn get_sessions_by_folder(&self, folder_id: Option<String>) -> Result<Vec<Session>> {
+    /// The scheduled session of the meeting closest to `now` that starts within
+    /// `SCHEDULED_SESSION_ATTACH_SECS` or is still running
+    fn find_scheduled_session_near(&self, now: i64) -> Result<Option<String>> {
         let conn = self.get_connection()?;
+        let session_id = conn
+            .query_row(
+                "SELECT e.session_id FROM calendar_events e
+                 JOIN sessions s ON s.id = e.session_id
+                 WHERE s.status = 'scheduled'
+                   AND e.starts_at - ?2 <= ?1
+                   AND ?1 < MAX(e.ends_at, e.starts_at + ?2)
+                 ORDER BY ABS(e.starts_at - ?1)
+                 LIMIT 1",
+                params![now, SCHEDULED_SESSION_ATTACH_SECS],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(session_id)
+    }
 
-        let query = if folder_id.is_some() {
-            "SELECT id, title, started_at, ended_at, status, folder_id, environment_id
-             FROM sessions WHERE folder_id = ?1 ORDER BY started_at DESC"
-        } else {
-            "SELECT id, title, started_at, ended_at, status, folder_id, environment_id
-             FROM sessions WHERE folder_id IS NULL ORDER BY started_at DESC"
-        };
+    fn activate_scheduled_session(&self, session_id: &str, now: i64) -> Result<Session> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "UPDATE sessions SET status = 'active', started_at = ?1 WHERE id = ?2",
+            params![now, session_id],
+        )?;
 
-        let mut stmt = conn.prepare(query)?;
-        let mut sessions = Vec::new();
+        *self.active_session.lock_or_recover() = Some(session_id.to_string());
+        *self.session_start_time.lock_or_recover() = Some(std::time::Instant::now());
 
-        let map_row = |row: &rusqlite::Row| -> rusqlite::Result<Session> {
-            Ok(Session {
-                id: row.get("id")?,
-                title: row.get("title")?,
-                started_at: row.get("started_at")?,
-                ended_at: row.get("ended_at")?,
-                status: row.get("status")?,
-                folder_id: row.get("folder_id")?,
-                environment_id: row.get("environment_id")?,
-            })
-        };
+        let session = self
+            .get_session(session_id)?
+            .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
 
-        if let Some(fid) = &folder_id {
-            let rows = stmt.query_map(params![fid], map_row)?;
-            for row in rows {
-                sessions.push(row?);
-            }
+        let _ = self.app_handle.emit("session-started", &session);
+        info!("Scheduled session started: {}", session_id);
+
+        Ok(session)
+    }
+
+    // ==================== Transcript Editing ====================
+
+    /// Change a segment's text and/or source. Word timings are carried over to
+    /// the new text where the words still match.
+    pub fn update_segment(
+        &self,
+        segment_id: i64,
+        text: Option<&str>,
+        source: Option<&str>,
+    ) -> Result<TranscriptSegment> {
+        let mut conn = self.get_connection()?;
+        let tx = conn.transaction()?;
+
+        let before = load_segment(&tx, segment_id)?
+            .ok_or_else(|| anyhow::anyhow!("Transcript segment not found: {}", segment_id))?;
+
+        let text = text.map(str::trim).unwrap_or(&before.text).to_string();
+        if text.is_empty() {
+            return Err(anyhow::anyhow!(
+                "Segment text cannot be empty, delete the segment instead"
+            ));
+        }
+        let source = source.unwrap_or(&before.source).to_string();
+        if source != "mic" && source != "speaker" {
+            return Err(anyhow::anyhow!("Unknown segment source: {}", source));
+        }
+        // Diarized speakers only exist on the mic channel
+        let speaker = if source == "mic" {
+            before.speaker.clone()
         } else {
-            let rows = stmt.query_map([], map_row)?;
-            for row in rows {
-                sessions.push(row?);
-            }
+            None
+        };
+
+        tx.execute(
+            "UPDATE transcript_segments SET text = ?1, source = ?2, speaker = ?3 WHERE id = ?4",
+            params![text, source, speaker, segment_id],
+        )?;
+        if text != before.text && !before.words.is_empty() {
+            let words = align_word_timings(&text, &timed_words(&before.words));
+            replace_segment_words(&tx, segment_id, &words)?;
         }
+        record_segment_edit(
+            &tx,
+            &before.session_id,
+            "update",
+            std::slice::from_ref(&before),
+            &[segment_id],
+        )?;
+        tx.commit()?;
 
-        Ok(sessions)
+        let segment = load_segment(&conn, segment_id)?
+            .ok_or_else(|| anyhow::anyhow!("Transcript segment not found: {}", segment_id))?;
+        self.emit_segment_updated(&segment);
+        Ok(segment)
     }
 
-    // ==================== Tag CRUD ====================
+    pub fn delete_segment(&self, segment_id: i64) -> Result<()> {
+        let mut conn = self.get_connection()?;
+        let tx = conn.transaction()?;
 
-    pub fn create_tag(&self, name: String, color: Option<String>) -> Result<Tag> {
-        let id = Uuid::new_v4().to_string();
-        let conn = self.get_connection()?;
+        let before = load_segment(&tx, segment_id)?
+            .ok_or_else(|| anyhow::anyhow!("Transcript segment not found: {}", segment_id))?;
 
-        conn.execute(
-            "INSERT INTO tags (id, name, color) VALUES (?1, ?2, ?3)",
-            params![id, name, color],
+        tx.execute(
+            "DELETE FROM transcript_words WHERE segment_id = ?1",
+            params![segment_id],
         )?;
+        tx.execute(
+            "DELETE FROM transcript_segments WHERE id = ?1",
+            params![segment_id],
+        )?;
+        record_segment_edit(
+            &tx,
+            &before.session_id,
+            "delete",
+            std::slice::from_ref(&before),
+            &[],
+        )?;
+        tx.commit()?;
 
-        Ok(Tag { id, name, color })
+        self.emit_segment_deleted(&before.session_id, segment_id);
+        Ok(())
     }
 
-    pub fn update_tag(&self, tag_id: &str, name: String, color: Option<String>) -> Result<()> {
-        let conn = self.get_connection()?;
-        conn.execute(
-            "UPDATE tags SET name = ?1, color = ?2 WHERE id = ?3",
-            params![name, color, tag_id],
+    /// Split a segment in two before the word at `word_index`. The first half
+    /// keeps the segment's id; the second half is a new segment.
+    pub fn split_segment(
+        &self,
+        segment_id: i64,
+        word_index: usize,
+    ) -> Result<(TranscriptSegment, TranscriptSegment)> {
+        let mut conn = self.get_connection()?;
+        let tx = conn.transaction()?;
+
+        let before = load_segment(&tx, segment_id)?
+            .ok_or_else(|| anyhow::anyhow!("Transcript segment not found: {}", segment_id))?;
+
+        let split = split_at_word(
+            &before.text,
+            &timed_words(&before.words),
+            before.start_ms,
+            before.end_ms,
+            word_index,
+        )
+        .ok_or_else(|| anyhow::anyhow!("Cannot split segment at word {}", word_index))?;
+
+        tx.execute(
+            "UPDATE transcript_segments SET text = ?1, end_ms = ?2 WHERE id = ?3",
+            params![split.first_text, split.split_ms, segment_id],
         )?;
-        Ok(())
+        tx.execute(
+            "INSERT INTO transcript_segments (session_id, text, source, start_ms, end_ms, created_at, speaker)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                before.session_id,
+                split.second_text,
+                before.source,
+                split.split_ms,
+                before.end_ms,
+                before.created_at,
+                before.speaker
+            ],
+        )?;
+        let new_id = tx.last_insert_rowid();
+        if !before.words.is_empty() {
+            replace_segment_words(&tx, segment_id, &split.first_words)?;
+            replace_segment_words(&tx, new_id, &split.second_words)?;
+        }
+        record_segment_edit(
+            &tx,
+            &before.session_id,
+            "split",
+            std::slice::from_ref(&before),
+            &[segment_id, new_id],
+        )?;
+        tx.commit()?;
+
+        let first = load_segment(&conn, segment_id)?
+            .ok_or_else(|| anyhow::anyhow!("Transcript segment not found: {}", segment_id))?;
+        let second = load_segment(&conn, new_id)?
+            .ok_or_else(|| anyhow::anyhow!("Transcript segment not found: {}", new_id))?;
+        self.emit_segment_updated(&first);
+        self.emit_segment_added(&second);
+        Ok((first, second))
     }
 
-    pub fn delete_tag(&self, tag_id: &str) -> Result<()> {
+    /// Merge segments of the same session and source into the earliest one.
+    /// Texts and word timings are joined in time order; the others are deleted.
+    pub fn merge_segments(&self, segment_ids: &[i64]) -> Result<TranscriptSegment> {
+        if segment_ids.len() < 2 {
+            return Err(anyhow::anyhow!("At least two segments are needed to merge"));
+        }
+
+        let mut conn = self.get_connection()?;
+        let tx = conn.transaction()?;
+
+        let mut before = Vec::with_capacity(segment_ids.len());
+        for &id in segment_ids {
+            let segment = load_segment(&tx, id)?
+                .ok_or_else(|| anyhow::anyhow!("Transcript segment not found: {}", id))?;
+            before.push(segment);
+        }
+        before.sort_by_key(|s| (s.start_ms, s.id));
+        before.dedup_by_key(|s| s.id);
+
+        if before.len() < 2 {
+            return Err(anyhow::anyhow!("At least two segments are needed to merge"));
+        }
+        let keep = &before[0];
+        if before.iter().any(|s| s.session_id != keep.session_id) {
+            return Err(anyhow::anyhow!(
+                "Segments from different sessions cannot be merged"
+            ));
+        }
+        if before.iter().any(|s| s.source != keep.source) {
+            return Err(anyhow::anyhow!(
+                "Segments from different sources cannot be merged"
+            ));
+        }
+
+        let text = before
+            .iter()
+            .map(|s| s.text.trim())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let end_ms = before.iter().map(|s| s.end_ms).max().unwrap_or(keep.end_ms);
+        let speaker = before.iter().find_map(|s| s.speaker.clone());
+        let mut words: Vec<TimedWord> = before.iter().flat_map(|s| timed_words(&s.words)).collect();
+        words.sort_by_key(|w| w.start_ms);
+
+        tx.execute(
+            "UPDATE transcript_segments SET text = ?1, end_ms = ?2, speaker = ?3 WHERE id = ?4",
+            params![text, end_ms, speaker, keep.id],
+        )?;
+        replace_segment_words(&tx, keep.id, &words)?;
+        for segment in &before[1..] {
+            tx.execute(
+                "DELETE FROM transcript_words WHERE segment_id = ?1",
+                params![segment.id],
+            )?;
+            tx.execute(
+                "DELETE FROM transcript_segments WHERE id = ?1",
+                params![segment.id],
+            )?;
+        }
+        record_segment_edit(&tx, &keep.session_id, "merge", &before, &[keep.id])?;
+        tx.commit()?;
+
+        let merged = load_segment(&conn, keep.id)?
+            .ok_or_else(|| anyhow::anyhow!("Transcript segment not found: {}", keep.id))?;
+        for segment in &before[1..] {
+            self.emit_segment_deleted(&segment.session_id, segment.id);
+        }
+        self.emit_segment_updated(&merged);
+        Ok(merged)
+    }
+
+    /// Edit history for a session, newest first, including edits of
+    /// transcripts since replaced by a revision
+    pub fn get_segment_edits(&self, session_id: &str) -> Result<Vec<TranscriptSegmentEdit>> {
         let conn = self.get_connection()?;
-        // session_tags will cascade delete
-        conn.execute("DELETE FROM tags WHERE id = ?1", params![tag_id])?;
+        segment_edits(&conn, session_id)
+    }
+
+    /// Undo an edit, putting the segments back exactly as they were (same ids).
+    /// Only the session's most recent edit can be reverted, so reverting
+    /// repeatedly walks the transcript back to the engine's original output.
+    pub fn revert_segment_edit(&self, edit_id: i64) -> Result<()> {
+        let mut conn = self.get_connection()?;
+        let (session_id, before, after_ids) = revert_segment_edit(&mut conn, edit_id)?;
+
+        for id in after_ids
+            .iter()
+            .filter(|id| !before.iter().any(|s| s.id == **id))
+        {
+            self.emit_segment_deleted(&session_id, *id);
+        }
+        for segment in &before {
+            let Some(segment) = load_segment(&conn, segment.id)? else {
+                continue;
+            };
+            if after_ids.contains(&segment.id) {
+                self.emit_segment_updated(&segment);
+            } else {
+                self.emit_segment_added(&segment);
+            }
+        }
         Ok(())
     }
 
-    pub fn get_tags(&self) -> Result<Vec<Tag>> {
-        let conn = self.get_connection()?;
-        // Only return tags that are used by at least one session
-        let mut stmt = conn.prepare(
-            "SELECT DISTINCT t.id, t.name, t.color FROM tags t
-             INNER JOIN session_tags st ON t.id = st.tag_id
-             ORDER BY t.name ASC",
-        )?;
+    fn emit_segment_added(&self, segment: &TranscriptSegment) {
+        let _ = self.app_handle.emit(
+            "transcript-segment",
+            TranscriptSegmentEvent {
+                session_id: segment.session_id.clone(),
+                segment: segment.clone(),
+            },
+        );
+    }
 
-        let rows = stmt.query_map([], |row| {
-            Ok(Tag {
-                id: row.get("id")?,
-                name: row.get("name")?,
-                color: row.get("color")?,
-            })
-        })?;
+    fn emit_segment_updated(&self, segment: &TranscriptSegment) {
+        let _ = self.app_handle.emit(
+            "transcript-segment-updated",
+            TranscriptSegmentEvent {
+                session_id: segment.session_id.clone(),
+                segment: segment.clone(),
+            },
+        );
+    }
 
-        let mut tags = Vec::new();
-        for row in rows {
-            tags.push(row?);
-        }
-        Ok(tags)
+    fn emit_segment_deleted(&self, session_id: &str, segment_id: i64) {
+        let _ = self.app_handle.emit(
+            "transcript-segment-deleted",
+            TranscriptSegmentDeletedEvent {
+                session_id: session_id.to_string(),
+                segment_id,
+            },
+        );
     }
 
-    pub fn add_tag_to_session(&self, session_id: &str, tag_id: &str) -> Result<()> {
+    // ==================== Transcript Revisions ====================
+
+    pub fn create_transcript_revision(
+        &self,
+        session_id: &str,
+        kind: &str,
+        model_id: Option<&str>,
+    ) -> Result<i64> {
         let conn = self.get_connection()?;
         conn.execute(
-            "INSERT OR IGNORE INTO session_tags (session_id, tag_id) VALUES (?1, ?2)",
-            params![session_id, tag_id],
+            "INSERT INTO transcript_revisions (session_id, kind, model_id, created_at) VALUES (?1, ?2, ?3, ?4)",
+            params![session_id, kind, model_id, Utc::now().timestamp()],
         )?;
-        Ok(())
+        Ok(conn.last_insert_rowid())
     }
 
-    pub fn remove_tag_from_session(&self, session_id: &str, tag_id: &str) -> Result<()> {
+    /// Append a segment to a revision. `segment.id` is ignored; the new id is returned.
+    pub fn add_revision_segment(
+        &self,
+        segment: &TranscriptRevisionSegment,
+        words: &[TimedWord],
+    ) -> Result<i64> {
         let conn = self.get_connection()?;
         conn.execute(
-            "DELETE FROM session_tags WHERE session_id = ?1 AND tag_id = ?2",
-            params![session_id, tag_id],
+            "INSERT INTO transcript_revision_segments (revision_id, text, source, start_ms, end_ms, speaker, words)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                segment.revision_id,
+                segment.text,
+                segment.source,
+                segment.start_ms,
+                segment.end_ms,
+                segment.speaker,
+                words_to_json(words)
+            ],
         )?;
-        Ok(())
+        Ok(conn.last_insert_rowid())
     }
 
-    pub fn get_session_tags(&self, session_id: &str) -> Result<Vec<Tag>> {
+    pub fn get_transcript_revisions(&self, session_id: &str) -> Result<Vec<TranscriptRevision>> {
         let conn = self.get_connection()?;
         let mut stmt = conn.prepare(
-            "SELECT t.id, t.name, t.color FROM tags t
-             INNER JOIN session_tags st ON st.tag_id = t.id
-             WHERE st.session_id = ?1
-             ORDER BY t.name ASC",
+            "SELECT r.id, r.session_id, r.kind, r.model_id, r.created_at,
+                    (SELECT COUNT(*) FROM transcript_revision_segments s WHERE s.revision_id = r.id) AS segment_count
+             FROM transcript_revisions r
+             WHERE r.session_id = ?1
+             ORDER BY r.created_at DESC, r.id DESC",
         )?;
 
         let rows = stmt.query_map(params![session_id], |row| {
-            Ok(Tag {
+            Ok(TranscriptRevision {
                 id: row.get("id")?,
-                name: row.get("name")?,
-                color: row.get("color")?,
+                session_id: row.get("session_id")?,
+                kind: row.get("kind")?,
+                model_id: row.get("model_id")?,
+                segment_count: row.get("segment_count")?,
+                created_at: row.get("created_at")?,
             })
         })?;
 
-        let mut tags = Vec::new();
+        let mut revisions = Vec::new();
+        for row in rows {
+            revisions.push(row?);
+        }
+        Ok(revisions)
+    }
+
+    pub fn get_transcript_revision_segments(
+        &self,
+        revision_id: i64,
+    ) -> Result<Vec<TranscriptRevisionSegment>> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, revision_id, text, source, start_ms, end_ms, speaker
+             FROM transcript_revision_segments
+             WHERE revision_id = ?1
+             ORDER BY start_ms ASC, id ASC",
+        )?;
+
+        let rows = stmt.query_map(params![revision_id], |row| {
+            Ok(TranscriptRevisionSegment {
+                id: row.get("id")?,
+                revision_id: row.get("revision_id")?,
+                text: row.get("text")?,
+                source: row.get("source")?,
+                start_ms: row.get("start_ms")?,
+                end_ms: row.get("end_ms")?,
+                speaker: row.get("speaker")?,
+            })
+        })?;
+
+        let mut segments = Vec::new();
+        for row in rows {
+            segments.push(row?);
+        }
+        Ok(segments)
+    }
+
+    /// Make a revision the session's transcript. The current transcript is kept
+    /// as a "replaced" revision first, so nothing is lost and the swap can be undone.
+    pub fn apply_transcript_revision(&self, revision_id: i64) -> Result<()> {
+        let mut conn = self.get_connection()?;
+        let session_id: String = conn
+            .query_row(
+                "SELECT session_id FROM transcript_revisions WHERE id = ?1",
+                params![revision_id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .ok_or_else(|| anyhow::anyhow!("Transcript revision not found: {}", revision_id))?;
+
+        if self.get_active_session_id().as_deref() == Some(session_id.as_str()) {
+            return Err(anyhow::anyhow!(
+                "Cannot replace the transcript of a session that is still active"
+            ));
+        }
+
+        let segment_count = apply_transcript_revision(&mut conn, &session_id, revision_id)?;
+
+        info!(
+            "Applied transcript revision {} to session {} ({} segments)",
+            revision_id, session_id, segment_count
+        );
+        let _ = self.app_handle.emit("transcript-replaced", &session_id);
+        Ok(())
+    }
+
+    pub fn delete_transcript_revision(&self, revision_id: i64) -> Result<()> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "DELETE FROM transcript_revision_speakers WHERE revision_id = ?1",
+            params![revision_id],
+        )?;
+        conn.execute(
+            "DELETE FROM transcript_revision_segments WHERE revision_id = ?1",
+            params![revision_id],
+        )?;
+        conn.execute(
+            "DELETE FROM transcript_revisions WHERE id = ?1",
+            params![revision_id],
+        )?;
+        Ok(())
+    }
+
+    // ==================== Speaker Diarization ====================
+
+    /// Attribute a mic segment to a speaker from its voice embedding.
+    ///
+    /// Runs one step of online clustering against the session's stored speaker
+    /// centroids, so labels stay stable across recording passes, restarts and
+    /// re-transcriptions. Returns the speaker label assigned to the segment.
+    pub fn assign_segment_speaker(
+        &self,
+        session_id: &str,
+        segment_id: i64,
+        embedding: &[f32],
+    ) -> Result<String> {
+        let label = self.identify_speaker(session_id, embedding)?;
+
+        let conn = self.get_connection()?;
+        conn.execute(
+            "UPDATE transcript_segments SET speaker = ?1 WHERE id = ?2",
+            params![label, segment_id],
+        )?;
+
+        let _ = self.app_handle.emit(
+            "transcript-speaker",
+            TranscriptSpeakerEvent {
+                session_id: session_id.to_string(),
+                segment_id,
+                speaker: label.clone(),
+            },
+        );
+
+        Ok(label)
+    }
+
+    /// Match a voice embedding against the session's speakers, updating the
+    /// matched centroid or registering a new speaker. Returns the speaker label.
+    pub fn identify_speaker(&self, session_id: &str, embedding: &[f32]) -> Result<String> {
+        let mut conn = self.get_connection()?;
+        let tx = conn.transaction()?;
+
+        let mut speakers = load_speaker_centroids(&tx, session_id)?;
+
+        let centroids: Vec<Vec<f32>> = speakers.iter().map(|(_, c, _)| c.clone()).collect();
+        let label = match match_speaker(&centroids, embedding) {
+            Some(index) => {
+                let (label, centroid, count) = &mut speakers[index];
+                update_centroid(centroid, embedding, *count as u32);
+                tx.execute(
+                    "UPDATE session_speakers SET centroid = ?1, segment_count = segment_count + 1
+                     WHERE session_id = ?2 AND label = ?3",
+                    params![embedding_to_blob(centroid), session_id, label.as_str()],
+                )?;
+                label.clone()
+            }
+            None => {
+                let label = format!("Speaker {}", speakers.len() + 1);
+                tx.execute(
+                    "INSERT INTO session_speakers (session_id, label, centroid, segment_count, created_at)
+                     VALUES (?1, ?2, ?3, 1, ?4)",
+                    params![
+                        session_id,
+                        label,
+                        embedding_to_blob(embedding),
+                        Utc::now().timestamp()
+                    ],
+                )?;
+                label
+            }
+        };
+
+        tx.commit()?;
+
+        Ok(label)
+    }
+
+    /// The session's speakers as (label, centroid, segment count), oldest first
+    pub fn get_speaker_centroids(&self, session_id: &str) -> Result<Vec<(String, Vec<f32>, i64)>> {
+        let conn = self.get_connection()?;
+        load_speaker_centroids(&conn, session_id)
+    }
+
+    /// Keep the speakers a re-transcription clustered its mic segments into
+    pub fn store_revision_speakers(
+        &self,
+        revision_id: i64,
+        speakers: &[(String, Vec<f32>, i64)],
+    ) -> Result<()> {
+        let mut conn = self.get_connection()?;
+        let tx = conn.transaction()?;
+        for (label, centroid, segment_count) in speakers {
+            tx.execute(
+                "INSERT OR REPLACE INTO transcript_revision_speakers (revision_id, label, centroid, segment_count)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![revision_id, label, embedding_to_blob(centroid), segment_count],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn get_session_speakers(&self, session_id: &str) -> Result<Vec<SessionSpeaker>> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT session_id, label, name, segment_count FROM session_speakers
+             WHERE session_id = ?1 ORDER BY created_at ASC, rowid ASC",
+        )?;
+
+        let rows = stmt.query_map(params![session_id], |row| {
+            Ok(SessionSpeaker {
+                session_id: row.get("session_id")?,
+                label: row.get("label")?,
+                name: row.get("name")?,
+                segment_count: row.get("segment_count")?,
+            })
+        })?;
+
+        let mut speakers = Vec::new();
+        for row in rows {
+            speakers.push(row?);
+        }
+        Ok(speakers)
+    }
+
+    /// Give a diarized speaker a display name. An empty name resets it to the label.
+    pub fn rename_session_speaker(
+        &self,
+        session_id: &str,
+        label: &str,
+        name: Option<&str>,
+    ) -> Result<()> {
+        let name = name.map(str::trim).filter(|n| !n.is_empty());
+        let conn = self.get_connection()?;
+        let updated = conn.execute(
+            "UPDATE session_speakers SET name = ?1 WHERE session_id = ?2 AND label = ?3",
+            params![name, session_id, label],
+        )?;
+        if updated == 0 {
+            return Err(anyhow::anyhow!("Speaker not found: {}", label));
+        }
+        Ok(())
+    }
+
+    // ==================== Tag CRUD ====================
+
+    pub fn create_tag(&self, name: String, color: Option<String>) -> Result<Tag> {
+        let id = Uuid::new_v4().to_string();
+        let conn = self.get_connection()?;
+
+        conn.execute(
+            "INSERT INTO tags (id, name, color) VALUES (?1, ?2, ?3)",
+            params![id, name, color],
+        )?;
+
+        Ok(Tag { id, name, color })
+    }
+
+    pub fn update_tag(&self, tag_id: &str, name: String, color: Option<String>) -> Result<()> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "UPDATE tags SET name = ?1, color = ?2 WHERE id = ?3",
+            params![name, color, tag_id],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_tag(&self, tag_id: &str) -> Result<()> {
+        let conn = self.get_connection()?;
+        // session_tags will cascade delete
+        conn.execute("DELETE FROM tags WHERE id = ?1", params![tag_id])?;
+        Ok(())
+    }
+
+    pub fn get_tags(&self) -> Result<Vec<Tag>> {
+        let conn = self.get_connection()?;
+        // Only return tags that are used by at least one session
+        let mut stmt = conn.prepare(
+            "SELECT DISTINCT t.id, t.name, t.color FROM tags t
+             INNER JOIN session_tags st ON t.id = st.tag_id
+             ORDER BY t.name ASC",
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            Ok(Tag {
+                id: row.get("id")?,
+                name: row.get("name")?,
+                color: row.get("color")?,
+            })
+        })?;
+
+        let mut tags = Vec::new();
+        for row in rows {
+            tags.push(row?);
+        }
+        Ok(tags)
+    }
+
+    pub fn add_tag_to_session(&self, session_id: &str, tag_id: &str) -> Result<()> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "INSERT OR IGNORE INTO session_tags (session_id, tag_id) VALUES (?1, ?2)",
+            params![session_id, tag_id],
+        )?;
+        Ok(())
+    }
+
+    pub fn remove_tag_from_session(&self, session_id: &str, tag_id: &str) -> Result<()> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "DELETE FROM session_tags WHERE session_id = ?1 AND tag_id = ?2",
+            params![session_id, tag_id],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_session_tags(&self, session_id: &str) -> Result<Vec<Tag>> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT t.id, t.name, t.color FROM tags t
+             INNER JOIN session_tags st ON st.tag_id = t.id
+             WHERE st.session_id = ?1
+             ORDER BY t.name ASC",
+        )?;
+
+        let rows = stmt.query_map(params![session_id], |row| {
+            Ok(Tag {
+                id: row.get("id")?,
+                name: row.get("name")?,
+                color: row.get("color")?,
+            })
+        })?;
+
+        let mut tags = Vec::new();
         for row in rows {
             tags.push(row?);
         }
@@ -959,10 +2915,10 @@ impl SessionManager {
     pub fn get_sessions_by_tag(&self, tag_id: &str) -> Result<Vec<Session>> {
         let conn = self.get_connection()?;
         let mut stmt = conn.prepare(
-            "SELECT s.id, s.title, s.started_at, s.ended_at, s.status, s.folder_id, s.environment_id
+            "SELECT s.id, s.title, s.started_at, s.ended_at, s.status, s.folder_id, s.environment_id, s.deleted_at
              FROM sessions s
              INNER JOIN session_tags st ON st.session_id = s.id
-             WHERE st.tag_id = ?1
+             WHERE st.tag_id = ?1 AND s.status != 'deleted'
              ORDER BY s.started_at DESC",
         )?;
 
@@ -975,6 +2931,7 @@ impl SessionManager {
                 status: row.get("status")?,
                 folder_id: row.get("folder_id")?,
                 environment_id: row.get("environment_id")?,
+                deleted_at: row.get("deleted_at")?,
             })
         })?;
 
@@ -995,96 +2952,229 @@ impl SessionManager {
         Ok(count)
     }
 
-    // ==================== Attachment CRUD ====================
-
-    /// Get the attachments directory for a session, creating it if needed
-    fn get_attachments_dir(&self, session_id: &str) -> Result<PathBuf> {
-        let app_data_dir = self.app_handle.path().app_data_dir()?;
-        let attachments_dir = app_data_dir.join("attachments").join(session_id);
-        if !attachments_dir.exists() {
-            fs::create_dir_all(&attachments_dir)?;
-        }
-        Ok(attachments_dir)
-    }
+    // ==================== Chat CRUD ====================
 
-    /// Add an attachment to a session by copying the source file to app data
-    pub fn add_attachment(
+    pub fn create_chat_thread(
         &self,
         session_id: &str,
-        source_path: &str,
-        filename: &str,
-        mime_type: &str,
-    ) -> Result<Attachment> {
-        let source = std::path::Path::new(source_path);
-        if !source.exists() {
-            return Err(anyhow::anyhow!(
-                "Source file does not exist: {}",
-                source_path
-            ));
-        }
-
-        let metadata = fs::metadata(source)?;
-        let file_size = metadata.len() as i64;
-
-        // Generate unique ID and destination path
+        title: Option<String>,
+    ) -> Result<ChatThread> {
         let id = Uuid::new_v4().to_string();
-        let attachments_dir = self.get_attachments_dir(session_id)?;
-        let dest_filename = format!("{}_{}", id, filename);
-        let dest_path = attachments_dir.join(&dest_filename);
-
-        // Copy file to app data
-        fs::copy(source, &dest_path)?;
-
         let now = Utc::now().timestamp();
-        let file_path_str = dest_path.to_string_lossy().to_string();
+        let title = title.unwrap_or_else(|| "New Chat".to_string());
 
         let conn = self.get_connection()?;
         conn.execute(
-            "INSERT INTO session_attachments (id, session_id, filename, file_path, mime_type, file_size, created_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-            params![id, session_id, filename, file_path_str, mime_type, file_size, now],
+            "INSERT INTO chat_threads (id, session_id, title, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?4)",
+            params![id, session_id, title, now],
         )?;
 
-        info!("Attachment added: {} -> {}", filename, id);
-
-        Ok(Attachment {
+        Ok(ChatThread {
             id,
             session_id: session_id.to_string(),
-            filename: filename.to_string(),
-            file_path: file_path_str,
-            mime_type: mime_type.to_string(),
-            file_size,
-            extracted_text: None,
+            title,
             created_at: now,
+            updated_at: now,
         })
     }
 
-    /// Get all attachments for a session
-    pub fn get_attachments(&self, session_id: &str) -> Result<Vec<Attachment>> {
+    /// Get all chat threads for a session, most recently active first
+    pub fn get_chat_threads(&self, session_id: &str) -> Result<Vec<ChatThread>> {
         let conn = self.get_connection()?;
         let mut stmt = conn.prepare(
-            "SELECT id, session_id, filename, file_path, mime_type, file_size, extracted_text, created_at
-             FROM session_attachments
+            "SELECT id, session_id, title, created_at, updated_at
+             FROM chat_threads
              WHERE session_id = ?1
-             ORDER BY created_at ASC",
+             ORDER BY updated_at DESC",
         )?;
 
         let rows = stmt.query_map(params![session_id], |row| {
-            Ok(Attachment {
+            Ok(ChatThread {
                 id: row.get("id")?,
                 session_id: row.get("session_id")?,
-                filename: row.get("filename")?,
-                file_path: row.get("file_path")?,
-                mime_type: row.get("mime_type")?,
-                file_size: row.get("file_size")?,
-                extracted_text: row.get("extracted_text")?,
+                title: row.get("title")?,
                 created_at: row.get("created_at")?,
+                updated_at: row.get("updated_at")?,
             })
         })?;
 
-        let mut attachments = Vec::new();
+        let mut threads = Vec::new();
         for row in rows {
-            attachments.push(row?);
+            threads.push(row?);
+        }
+        Ok(threads)
+    }
+
+    pub fn rename_chat_thread(&self, thread_id: &str, title: &str) -> Result<()> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "UPDATE chat_threads SET title = ?1 WHERE id = ?2",
+            params![title, thread_id],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_chat_thread(&self, thread_id: &str) -> Result<()> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "DELETE FROM chat_messages WHERE thread_id = ?1",
+            params![thread_id],
+        )?;
+        conn.execute("DELETE FROM chat_threads WHERE id = ?1", params![thread_id])?;
+        Ok(())
+    }
+
+    /// Append a message to a thread and bump the thread's updated_at
+    pub fn add_chat_message(
+        &self,
+        thread_id: &str,
+        role: &str,
+        content: String,
+    ) -> Result<ChatThreadMessage> {
+        if role != "user" && role != "assistant" {
+            return Err(anyhow::anyhow!("Invalid chat message role: {}", role));
+        }
+
+        let now = Utc::now().timestamp();
+        let conn = self.get_connection()?;
+
+        conn.execute(
+            "INSERT INTO chat_messages (thread_id, role, content, created_at) VALUES (?1, ?2, ?3, ?4)",
+            params![thread_id, role, content, now],
+        )?;
+        let id = conn.last_insert_rowid();
+
+        conn.execute(
+            "UPDATE chat_threads SET updated_at = ?1 WHERE id = ?2",
+            params![now, thread_id],
+        )?;
+
+        Ok(ChatThreadMessage {
+            id,
+            thread_id: thread_id.to_string(),
+            role: role.to_string(),
+            content,
+            created_at: now,
+        })
+    }
+
+    /// Get all messages in a thread in the order they were sent
+    pub fn get_chat_messages(&self, thread_id: &str) -> Result<Vec<ChatThreadMessage>> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, thread_id, role, content, created_at
+             FROM chat_messages
+             WHERE thread_id = ?1
+             ORDER BY id ASC",
+        )?;
+
+        let rows = stmt.query_map(params![thread_id], |row| {
+            Ok(ChatThreadMessage {
+                id: row.get("id")?,
+                thread_id: row.get("thread_id")?,
+                role: row.get("role")?,
+                content: row.get("content")?,
+                created_at: row.get("created_at")?,
+            })
+        })?;
+
+        let mut messages = Vec::new();
+        for row in rows {
+            messages.push(row?);
+        }
+        Ok(messages)
+    }
+
+    // ==================== Attachment CRUD ====================
+
+    /// Get the attachments directory for a session, creating it if needed
+    fn get_attachments_dir(&self, session_id: &str) -> Result<PathBuf> {
+        let app_data_dir = self.app_handle.path().app_data_dir()?;
+        let attachments_dir = app_data_dir.join("attachments").join(session_id);
+        if !attachments_dir.exists() {
+            fs::create_dir_all(&attachments_dir)?;
+        }
+        Ok(attachments_dir)
+    }
+
+    /// Add an attachment to a session by copying the source file to app data
+    pub fn add_attachment(
+        &self,
+        session_id: &str,
+        source_path: &str,
+        filename: &str,
+        mime_type: &str,
+    ) -> Result<Attachment> {
+        let source = std::path::Path::new(source_path);
+        if !source.exists() {
+            return Err(anyhow::anyhow!(
+                "Source file does not exist: {}",
+                source_path
+            ));
+        }
+
+        let metadata = fs::metadata(source)?;
+        let file_size = metadata.len() as i64;
+
+        // Generate unique ID and destination path
+        let id = Uuid::new_v4().to_string();
+        let attachments_dir = self.get_attachments_dir(session_id)?;
+        let dest_filename = format!("{}_{}", id, filename);
+        let dest_path = attachments_dir.join(&dest_filename);
+
+        // Copy file to app data
+        self.store_file(source, &dest_path)?;
+
+        let now = Utc::now().timestamp();
+        let file_path_str = dest_path.to_string_lossy().to_string();
+
+        let conn = self.get_connection()?;
+        conn.execute(
+            "INSERT INTO session_attachments (id, session_id, filename, file_path, mime_type, file_size, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![id, session_id, filename, file_path_str, mime_type, file_size, now],
+        )?;
+
+        info!("Attachment added: {} -> {}", filename, id);
+
+        Ok(Attachment {
+            id,
+            session_id: session_id.to_string(),
+            filename: filename.to_string(),
+            file_path: file_path_str,
+            mime_type: mime_type.to_string(),
+            file_size,
+            extracted_text: None,
+            created_at: now,
+        })
+    }
+
+    /// Get all attachments for a session
+    pub fn get_attachments(&self, session_id: &str) -> Result<Vec<Attachment>> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, session_id, filename, file_path, mime_type, file_size, extracted_text, created_at
+             FROM session_attachments
+             WHERE session_id = ?1
+             ORDER BY created_at ASC",
+        )?;
+
+        let rows = stmt.query_map(params![session_id], |row| {
+            Ok(Attachment {
+                id: row.get("id")?,
+                session_id: row.get("session_id")?,
+                filename: row.get("filename")?,
+                file_path: row.get("file_path")?,
+                mime_type: row.get("mime_type")?,
+                file_size: row.get("file_size")?,
+                extracted_text: row.get("extracted_text")?,
+                created_at: row.get("created_at")?,
+            })
+        })?;
+
+        let mut attachments = Vec::new();
+        for row in rows {
+            attachments.push(row?);
         }
         Ok(attachments)
     }
@@ -1187,4 +3277,2005 @@ impl SessionManager {
         )?;
         Ok(())
     }
+
+    /// Copy a file into the attachments or recordings tree, encrypting it when
+    /// storage is encrypted. Returns the plaintext size.
+    fn store_file(&self, source: &std::path::Path, dest: &std::path::Path) -> Result<i64> {
+        match self.storage_key() {
+            Some(key) => {
+                let data = fs::read(source)?;
+                fs::write(dest, key.encrypt(&data)?)?;
+                Ok(data.len() as i64)
+            }
+            None => Ok(fs::copy(source, dest)? as i64),
+        }
+    }
+
+    /// Read an attachment's contents, decrypting them if needed
+    pub fn read_attachment(&self, attachment: &Attachment) -> Result<Vec<u8>> {
+        encryption::read_file(
+            std::path::Path::new(&attachment.file_path),
+            self.storage_key().as_deref(),
+        )
+    }
+
+    /// Path of a plaintext copy of an attachment for handing to other programs.
+    /// Unencrypted attachments are returned as-is; encrypted ones are decrypted
+    /// into `dir`.
+    pub fn plaintext_attachment_path(
+        &self,
+        attachment: &Attachment,
+        dir: &std::path::Path,
+    ) -> Result<PathBuf> {
+        let copy_name = format!(
+            "{}_{}",
+            attachment.id,
+            sanitize_bundle_name(&attachment.filename)
+        );
+        self.plaintext_path(std::path::Path::new(&attachment.file_path), &copy_name, dir)
+    }
+
+    /// Path of a plaintext copy of an archived recording, decrypted into `dir`
+    /// when encrypted
+    pub fn plaintext_recording_path(
+        &self,
+        recording: &AudioRecording,
+        dir: &std::path::Path,
+    ) -> Result<PathBuf> {
+        let copy_name = format!(
+            "{}_{}",
+            recording.id,
+            sanitize_bundle_name(&recording.file_name)
+        );
+        self.plaintext_path(std::path::Path::new(&recording.file_path), &copy_name, dir)
+    }
+
+    fn plaintext_path(
+        &self,
+        path: &std::path::Path,
+        copy_name: &str,
+        dir: &std::path::Path,
+    ) -> Result<PathBuf> {
+        if !encryption::is_encrypted_file(path)? {
+            return Ok(path.to_path_buf());
+        }
+        let key = self
+            .storage_key()
+            .ok_or_else(|| anyhow::anyhow!("Session storage is locked"))?;
+        fs::create_dir_all(dir)?;
+        let copy = dir.join(copy_name);
+        fs::write(&copy, key.decrypt(&fs::read(path)?)?)?;
+        Ok(copy)
+    }
+
+    /// Plaintext copy of an attachment for opening it in its default app.
+    /// Copies are cleared when storage is locked or decrypted, on exit and on
+    /// the next start.
+    pub fn attachment_open_path(&self, attachment: &Attachment) -> Result<PathBuf> {
+        self.plaintext_attachment_path(attachment, &decrypted_attachments_dir())
+    }
+
+    /// Delete the plaintext copies handed out by `attachment_open_path`
+    pub fn remove_plaintext_copies(&self) {
+        let dir = decrypted_attachments_dir();
+        if dir.exists() {
+            if let Err(e) = fs::remove_dir_all(&dir) {
+                warn!("Failed to remove decrypted attachment copies: {}", e);
+            }
+        }
+    }
+
+    // ==================== Storage Encryption ====================
+
+    fn db_dir(&self) -> PathBuf {
+        self.db_path
+            .parent()
+            .map(|p| p.to_path_buf())
+            .unwrap_or_default()
+    }
+
+    fn storage_key(&self) -> Option<Arc<StorageKey>> {
+        self.storage_key.lock_or_recover().clone()
+    }
+
+    /// Key to open sessions.db with when copying it, None when it is plaintext.
+    /// Fails while encrypted storage is locked.
+    pub fn database_key(&self) -> Result<Option<Arc<StorageKey>>> {
+        let key = self.storage_key();
+        if key.is_none() && KeyFile::exists(&self.db_dir()) {
+            return Err(anyhow::anyhow!("Session storage is locked"));
+        }
+        Ok(key)
+    }
+
+    pub fn get_storage_encryption_status(&self) -> StorageEncryptionStatus {
+        StorageEncryptionStatus {
+            supported: encryption::is_supported(),
+            enabled: KeyFile::exists(&self.db_dir()),
+            locked: self.pool.lock_or_recover().is_none(),
+        }
+    }
+
+    /// Unlock encrypted storage with the user's passphrase. A conversion that was
+    /// interrupted (crash, power loss) is finished first.
+    pub fn unlock_storage(&self, passphrase: &str) -> Result<()> {
+        let dir = self.db_dir();
+        let key_file =
+            KeyFile::load(&dir)?.ok_or_else(|| anyhow::anyhow!("Storage is not encrypted"))?;
+        let key = Arc::new(key_file.unlock(passphrase)?);
+
+        match key_file.pending {
+            Some(StorageMigration::Encrypt) => {
+                info!("Resuming interrupted storage encryption");
+                self.encrypt_storage(key_file, key)?;
+            }
+            Some(StorageMigration::Decrypt) => {
+                info!("Resuming interrupted storage decryption");
+                self.decrypt_storage(key)?;
+            }
+            None => {
+                if self.pool.lock_or_recover().is_some() {
+                    return Ok(());
+                }
+                self.open_storage(Some(key.clone()))?;
+                // A recording pass cut short by a crash was never encrypted
+                for path in self.recording_file_paths()? {
+                    if !encryption::is_encrypted_file(&path)? {
+                        encryption::encrypt_file_in_place(&path, &key)?;
+                    }
+                }
+            }
+        }
+
+        let _ = self.app_handle.emit("storage-unlocked", ());
+        info!("Session storage unlocked");
+        Ok(())
+    }
+
+    /// Close encrypted storage and forget the key until the passphrase is entered
+    /// again. The caller must make sure nothing is recording or importing.
+    pub fn lock_storage(&self) -> Result<()> {
+        if !KeyFile::exists(&self.db_dir()) {
+            return Err(anyhow::anyhow!("Storage is not encrypted"));
+        }
+        self.close_storage();
+        self.remove_plaintext_copies();
+
+        let _ = self.app_handle.emit("storage-locked", ());
+        info!("Session storage locked");
+        Ok(())
+    }
+
+    /// Encrypt sessions.db, every attachment and archived recording with a key
+    /// derived from `passphrase`. The caller must make sure nothing is recording
+    /// or importing.
+    pub fn enable_storage_encryption(&self, passphrase: &str) -> Result<()> {
+        let dir = self.db_dir();
+        encryption::ensure_supported()?;
+        if KeyFile::exists(&dir) {
+            return Err(anyhow::anyhow!("Storage is already encrypted"));
+        }
+        if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+            return Err(anyhow::anyhow!(
+                "Passphrase must be at least {} characters",
+                MIN_PASSPHRASE_LEN
+            ));
+        }
+
+        let (mut key_file, key) = KeyFile::create(passphrase)?;
+        // Written before anything is converted: without its salt an encrypted
+        // database could never be opened again
+        key_file.pending = Some(StorageMigration::Encrypt);
+        key_file.save(&dir)?;
+
+        self.encrypt_storage(key_file, Arc::new(key))?;
+        info!("Session storage encrypted");
+        Ok(())
+    }
+
+    /// Convert back to the plaintext layout. Needs the passphrase even when
+    /// unlocked. The caller must make sure nothing is recording or importing.
+    pub fn disable_storage_encryption(&self, passphrase: &str) -> Result<()> {
+        let dir = self.db_dir();
+        let mut key_file =
+            KeyFile::load(&dir)?.ok_or_else(|| anyhow::anyhow!("Storage is not encrypted"))?;
+        let key = Arc::new(key_file.unlock(passphrase)?);
+
+        key_file.pending = Some(StorageMigration::Decrypt);
+        key_file.save(&dir)?;
+
+        self.decrypt_storage(key)?;
+        info!("Session storage decrypted");
+        Ok(())
+    }
+
+    /// Each step skips work that is already done, so this can resume a conversion
+    fn encrypt_storage(&self, mut key_file: KeyFile, key: Arc<StorageKey>) -> Result<()> {
+        if encryption::is_plaintext_database(&self.db_path) {
+            self.close_storage();
+            encryption::convert_database(&self.db_path, None, Some(&key))?;
+        }
+        self.open_storage(Some(key.clone()))?;
+
+        for path in self.stored_file_paths()? {
+            encryption::encrypt_file_in_place(&path, &key)?;
+        }
+
+        key_file.pending = None;
+        key_file.save(&self.db_dir())?;
+        Ok(())
+    }
+
+    /// Each step skips work that is already done, so this can resume a conversion
+    fn decrypt_storage(&self, key: Arc<StorageKey>) -> Result<()> {
+        let database_encrypted =
+            self.db_path.exists() && !encryption::is_plaintext_database(&self.db_path);
+        self.close_storage();
+        self.open_storage(database_encrypted.then(|| key.clone()))?;
+
+        // Files first: their paths come from the database
+        for path in self.stored_file_paths()? {
+            encryption::decrypt_file_in_place(&path, &key)?;
+        }
+
+        if database_encrypted {
+            self.close_storage();
+            encryption::convert_database(&self.db_path, Some(&key), None)?;
+            self.open_storage(None)?;
+        }
+
+        KeyFile::remove(&self.db_dir())?;
+        self.remove_plaintext_copies();
+        Ok(())
+    }
+
+    /// Attachment and recording files that follow the storage encryption
+    fn stored_file_paths(&self) -> Result<Vec<PathBuf>> {
+        let mut paths = self.attachment_file_paths()?;
+        paths.extend(self.recording_file_paths()?);
+        Ok(paths)
+    }
+
+    fn attachment_file_paths(&self) -> Result<Vec<PathBuf>> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare("SELECT file_path FROM session_attachments")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>("file_path"))?;
+
+        let mut paths = Vec::new();
+        for row in rows {
+            let path = PathBuf::from(row?);
+            if path.is_file() {
+                paths.push(path);
+            }
+        }
+        Ok(paths)
+    }
+
+    fn recording_file_paths(&self) -> Result<Vec<PathBuf>> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare("SELECT session_id, file_name FROM audio_recordings")?;
+        let rows = stmt.query_map([], |row| {
+            Ok(self.recording_path(
+                &row.get::<_, String>("session_id")?,
+                &row.get::<_, String>("file_name")?,
+            ))
+        })?;
+
+        let mut paths = Vec::new();
+        for row in rows {
+            let path = row?;
+            if path.is_file() {
+                paths.push(path);
+            }
+        }
+        Ok(paths)
+    }
+
+    // ==================== Audio Recording Archive ====================
+
+    /// Get the archived recordings directory for a session, creating it if needed
+    fn get_recordings_dir(&self, session_id: &str) -> Result<PathBuf> {
+        let app_data_dir = self.app_handle.path().app_data_dir()?;
+        let recordings_dir = app_data_dir.join("recordings").join(session_id);
+        if !recordings_dir.exists() {
+            fs::create_dir_all(&recordings_dir)?;
+        }
+        Ok(recordings_dir)
+    }
+
+    /// Create the mic and speaker WAV files for a new recording pass and register
+    /// them in audio_recordings. Durations are filled in by `finish_recording_archive`.
+    pub fn start_recording_archive(
+        &self,
+        session_id: &str,
+        offset_ms: i64,
+    ) -> Result<RecordingArchive> {
+        let recordings_dir = self.get_recordings_dir(session_id)?;
+        let now = Utc::now();
+        let conn = self.get_connection()?;
+
+        let open_channel = |channel: &str| -> Result<ArchivedChannel> {
+            let file_name = format!("{}_{}.wav", now.timestamp_millis(), channel);
+            let path = recordings_dir.join(&file_name);
+            let writer = WavStreamWriter::create(&path)?;
+            conn.execute(
+                "INSERT INTO audio_recordings (session_id, file_name, channel, offset_ms, duration_ms, created_at)
+                 VALUES (?1, ?2, ?3, ?4, 0, ?5)",
+                params![session_id, file_name, channel, offset_ms, now.timestamp()],
+            )?;
+            Ok(ArchivedChannel {
+                recording_id: conn.last_insert_rowid(),
+                path,
+                writer,
+            })
+        };
+
+        let mic = open_channel("mic")?;
+        let speaker = open_channel("speaker")?;
+
+        debug!(
+            "Recording archive started for session {} at offset {}ms",
+            session_id, offset_ms
+        );
+
+        Ok(RecordingArchive {
+            mic: Some(mic),
+            speaker: Some(speaker),
+        })
+    }
+
+    /// Finalize the WAV files of a recording pass and store their durations.
+    /// With encrypted storage the files are encrypted once complete, since
+    /// they are streamed to disk while recording.
+    pub fn finish_recording_archive(&self, archive: RecordingArchive) -> Result<()> {
+        let conn = self.get_connection()?;
+        let key = self.storage_key();
+        for channel in [archive.mic, archive.speaker].into_iter().flatten() {
+            let recording_id = channel.recording_id;
+            let samples = match channel.writer.finalize() {
+                Ok(samples) => samples,
+                Err(e) => {
+                    warn!("Failed to finalize recording {}: {}", recording_id, e);
+                    continue;
+                }
+            };
+            let duration_ms = (samples * 1000 / 16000) as i64;
+            conn.execute(
+                "UPDATE audio_recordings SET duration_ms = ?1 WHERE id = ?2",
+                params![duration_ms, recording_id],
+            )?;
+            if let Some(key) = &key {
+                if let Err(e) = encryption::encrypt_file_in_place(&channel.path, key) {
+                    warn!("Failed to encrypt recording {}: {}", recording_id, e);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn recording_path(&self, session_id: &str, file_name: &str) -> PathBuf {
+        self.app_handle
+            .path()
+            .app_data_dir()
+            .map(|d| d.join("recordings").join(session_id).join(file_name))
+            .unwrap_or_default()
+    }
+
+    fn map_audio_recording(&self, row: &rusqlite::Row) -> rusqlite::Result<AudioRecording> {
+        let session_id: String = row.get("session_id")?;
+        let file_name: String = row.get("file_name")?;
+        let file_path = self
+            .recording_path(&session_id, &file_name)
+            .to_string_lossy()
+            .to_string();
+
+        Ok(AudioRecording {
+            id: row.get("id")?,
+            session_id,
+            file_name,
+            file_path,
+            channel: row.get("channel")?,
+            offset_ms: row.get("offset_ms")?,
+            duration_ms: row.get("duration_ms")?,
+            created_at: row.get("created_at")?,
+        })
+    }
+
+    /// Get all archived recordings for a session in timeline order
+    pub fn get_audio_recordings(&self, session_id: &str) -> Result<Vec<AudioRecording>> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, session_id, file_name, channel, offset_ms, duration_ms, created_at
+             FROM audio_recordings
+             WHERE session_id = ?1
+             ORDER BY offset_ms ASC, id ASC",
+        )?;
+
+        let rows = stmt.query_map(params![session_id], |row| self.map_audio_recording(row))?;
+
+        let mut recordings = Vec::new();
+        for row in rows {
+            recordings.push(row?);
+        }
+        Ok(recordings)
+    }
+
+    /// Remove recording files from disk and their audio_recordings rows
+    fn delete_recordings(&self, recordings: &[AudioRecording]) -> Result<usize> {
+        if recordings.is_empty() {
+            return Ok(0);
+        }
+
+        let conn = self.get_connection()?;
+        for recording in recordings {
+            let path = std::path::Path::new(&recording.file_path);
+            if path.exists() {
+                if let Err(e) = fs::remove_file(path) {
+                    warn!(
+                        "Failed to delete recording file {}: {}",
+                        recording.file_path, e
+                    );
+                }
+            }
+            conn.execute(
+                "DELETE FROM audio_recordings WHERE id = ?1",
+                params![recording.id],
+            )?;
+
+            // Remove the session directory once its last recording is gone
+            if let Some(dir) = path.parent() {
+                let _ = fs::remove_dir(dir); // Ignore error if not empty
+            }
+        }
+
+        Ok(recordings.len())
+    }
+
+    /// Delete all archived recordings for a session (called when deleting a session)
+    pub fn delete_session_recordings(&self, session_id: &str) -> Result<()> {
+        let recordings = self.get_audio_recordings(session_id)?;
+        self.delete_recordings(&recordings)?;
+        Ok(())
+    }
+
+    /// Prune archived audio according to the recording retention setting.
+    /// Recordings belonging to the active session are never pruned.
+    pub fn cleanup_old_recordings(&self) -> Result<()> {
+        use crate::settings::RecordingRetentionPeriod;
+
+        let retention_period = crate::settings::get_recording_retention_period(&self.app_handle);
+        let active_session = self.get_active_session_id().unwrap_or_default();
+        let conn = self.get_connection()?;
+
+        let expired = match retention_period {
+            RecordingRetentionPeriod::Never => {
+                // Don't delete anything
+                return Ok(());
+            }
+            RecordingRetentionPeriod::PreserveLimit => {
+                // Keep audio for the most recently recorded `history_limit` sessions
+                let limit = crate::settings::get_history_limit(&self.app_handle) as i64;
+                let mut stmt = conn.prepare(
+                    "SELECT id, session_id, file_name, channel, offset_ms, duration_ms, created_at
+                     FROM audio_recordings
+                     WHERE session_id != ?1
+                       AND session_id NOT IN (
+                           SELECT session_id FROM audio_recordings
+                           GROUP BY session_id
+                           ORDER BY MAX(created_at) DESC
+                           LIMIT ?2
+                       )",
+                )?;
+                let rows = stmt.query_map(params![active_session, limit], |row| {
+                    self.map_audio_recording(row)
+                })?;
+                let mut recordings = Vec::new();
+                for row in rows {
+                    recordings.push(row?);
+                }
+                recordings
+            }
+            _ => {
+                let now = Utc::now().timestamp();
+                let cutoff_timestamp = match retention_period {
+                    RecordingRetentionPeriod::Days3 => now - (3 * 24 * 60 * 60),
+                    RecordingRetentionPeriod::Weeks2 => now - (2 * 7 * 24 * 60 * 60),
+                    RecordingRetentionPeriod::Months3 => now - (3 * 30 * 24 * 60 * 60),
+                    _ => unreachable!("Should not reach here"),
+                };
+                let mut stmt = conn.prepare(
+                    "SELECT id, session_id, file_name, channel, offset_ms, duration_ms, created_at
+                     FROM audio_recordings
+                     WHERE session_id != ?1 AND created_at < ?2",
+                )?;
+                let rows = stmt.query_map(params![active_session, cutoff_timestamp], |row| {
+                    self.map_audio_recording(row)
+                })?;
+                let mut recordings = Vec::new();
+                for row in rows {
+                    recordings.push(row?);
+                }
+                recordings
+            }
+        };
+
+        let deleted_count = self.delete_recordings(&expired)?;
+        if deleted_count > 0 {
+            debug!(
+                "Cleaned up {} archived recordings based on retention period",
+                deleted_count
+            );
+        }
+
+        Ok(())
+    }
+
+    // ==================== Session Bundles ====================
+
+    /// Write a session to a `.talky` bundle at `dest`: transcript with word timings
+    /// and speakers, notes, folder and tag names, attachment files and archived audio
+    pub fn export_session_bundle(&self, session_id: &str, dest: &std::path::Path) -> Result<()> {
+        if self.get_active_session_id().as_deref() == Some(session_id) {
+            return Err(anyhow::anyhow!(
+                "Stop recording before exporting this session"
+            ));
+        }
+        let session = self
+            .get_session(session_id)?
+            .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
+
+        let folder = match &session.folder_id {
+            Some(folder_id) => self
+                .get_folders()?
+                .into_iter()
+                .find(|f| &f.id == folder_id)
+                .map(|f| BundleFolder {
+                    name: f.name,
+                    color: f.color,
+                }),
+            None => None,
+        };
+        let tags = self
+            .get_session_tags(session_id)?
+            .into_iter()
+            .map(|t| BundleTag {
+                name: t.name,
+                color: t.color,
+            })
+            .collect();
+        let transcript = self.get_session_transcript(session_id)?;
+        let segment_ids: Vec<i64> = transcript.iter().map(|s| s.id).collect();
+        let segments = transcript
+            .into_iter()
+            .map(|s| BundleSegment {
+                text: s.text,
+                source: s.source,
+                start_ms: s.start_ms,
+                end_ms: s.end_ms,
+                created_at: s.created_at,
+                speaker: s.speaker,
+                words: s
+                    .words
+                    .into_iter()
+                    .map(|w| BundleWord {
+                        text: w.text,
+                        start_ms: w.start_ms,
+                        end_ms: w.end_ms,
+                        estimated: w.estimated,
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        let speakers = {
+            let conn = self.get_connection()?;
+            let mut stmt = conn.prepare(
+                "SELECT label, name, centroid, segment_count FROM session_speakers
+                 WHERE session_id = ?1 ORDER BY created_at ASC, rowid ASC",
+            )?;
+            let rows = stmt.query_map(params![session_id], |row| {
+                let centroid: Vec<u8> = row.get("centroid")?;
+                Ok(BundleSpeaker {
+                    label: row.get("label")?,
+                    name: row.get("name")?,
+                    centroid: blob_to_embedding(&centroid),
+                    segment_count: row.get("segment_count")?,
+                })
+            })?;
+            let mut speakers = Vec::new();
+            for row in rows {
+                speakers.push(row?);
+            }
+            speakers
+        };
+
+        let (action_items, bookmarks) = {
+            let conn = self.get_connection()?;
+            (
+                bundle_action_items(&conn, session_id, &segment_ids)?,
+                bundle_bookmarks(&conn, session_id)?,
+            )
+        };
+
+        let notes = self.get_meeting_notes(session_id)?.map(|n| BundleNotes {
+            summary: n.summary,
+            action_items: n.action_items,
+            decisions: n.decisions,
+            user_notes: n.user_notes,
+            enhanced_notes: n.enhanced_notes,
+            enhanced_notes_edited: n.enhanced_notes_edited,
+        });
+
+        // Files are stored under their row position so duplicate names can't clash.
+        // Encrypted attachments and recordings are decrypted into a scratch dir
+        // for the export.
+        let plaintext_dir = std::env::temp_dir().join(format!("talky-export-{}", Uuid::new_v4()));
+        let mut files = Vec::new();
+        let mut attachments = Vec::new();
+        for (i, attachment) in self.get_attachments(session_id)?.into_iter().enumerate() {
+            if !std::path::Path::new(&attachment.file_path).is_file() {
+                warn!(
+                    "Attachment file missing, leaving it out of the bundle: {}",
+                    attachment.file_path
+                );
+                continue;
+            }
+            let source = match self.plaintext_attachment_path(&attachment, &plaintext_dir) {
+                Ok(source) => source,
+                Err(e) => {
+                    let _ = fs::remove_dir_all(&plaintext_dir);
+                    return Err(e);
+                }
+            };
+            let path = format!(
+                "attachments/{}/{}",
+                i,
+                sanitize_bundle_name(&attachment.filename)
+            );
+            files.push((path.clone(), source));
+            attachments.push(BundleAttachment {
+                filename: attachment.filename,
+                mime_type: attachment.mime_type,
+                extracted_text: attachment.extracted_text,
+                created_at: attachment.created_at,
+                path,
+            });
+        }
+        let mut recordings = Vec::new();
+        for (i, recording) in self
+            .get_audio_recordings(session_id)?
+            .into_iter()
+            .enumerate()
+        {
+            if !std::path::Path::new(&recording.file_path).is_file() {
+                warn!(
+                    "Recording file missing, leaving it out of the bundle: {}",
+                    recording.file_path
+                );
+                continue;
+            }
+            let source = match self.plaintext_recording_path(&recording, &plaintext_dir) {
+                Ok(source) => source,
+                Err(e) => {
+                    let _ = fs::remove_dir_all(&plaintext_dir);
+                    return Err(e);
+                }
+            };
+            let path = format!("audio/{}/{}", i, sanitize_bundle_name(&recording.file_name));
+            files.push((path.clone(), source));
+            recordings.push(BundleRecording {
+                channel: recording.channel,
+                offset_ms: recording.offset_ms,
+                duration_ms: recording.duration_ms,
+                created_at: recording.created_at,
+                path,
+            });
+        }
+
+        let bundle = SessionBundle {
+            format: BUNDLE_FORMAT.to_string(),
+            version: BUNDLE_VERSION,
+            exported_at: Utc::now().timestamp(),
+            session: BundleSession {
+                title: session.title,
+                started_at: session.started_at,
+                ended_at: session.ended_at,
+            },
+            folder,
+            tags,
+            segments,
+            speakers,
+            notes,
+            attachments,
+            recordings,
+            action_items,
+            bookmarks,
+        };
+
+        let result = write_bundle(dest, &bundle, &files);
+        let _ = fs::remove_dir_all(&plaintext_dir);
+        if let Err(e) = result {
+            let _ = fs::remove_file(dest);
+            return Err(e);
+        }
+
+        info!("Session {} exported to {:?}", session_id, dest);
+        Ok(())
+    }
+
+    /// Import a `.talky` bundle as a new completed session. Every row gets a fresh ID;
+    /// the folder and tags are matched to existing ones by name (case-insensitive)
+    /// and only created when no match exists.
+    pub fn import_session_bundle(&self, src: &std::path::Path) -> Result<Session> {
+        let unpack_dir = std::env::temp_dir().join(format!("talky-import-{}", Uuid::new_v4()));
+        fs::create_dir_all(&unpack_dir)?;
+        let result = self.import_unpacked_bundle(src, &unpack_dir);
+        if let Err(e) = fs::remove_dir_all(&unpack_dir) {
+            warn!("Failed to clean up {:?}: {}", unpack_dir, e);
+        }
+
+        let session = result?;
+        let _ = self.app_handle.emit("session-imported", &session);
+        info!("Session bundle {:?} imported as {}", src, session.id);
+        Ok(session)
+    }
+
+    fn import_unpacked_bundle(
+        &self,
+        src: &std::path::Path,
+        unpack_dir: &std::path::Path,
+    ) -> Result<Session> {
+        let bundle = unpack_bundle(src, unpack_dir)?;
+
+        // Check every referenced file before touching the database
+        let attachment_files = bundle
+            .attachments
+            .iter()
+            .map(|a| bundle_file(unpack_dir, &a.path))
+            .collect::<Result<Vec<_>>>()?;
+        let recording_files = bundle
+            .recordings
+            .iter()
+            .map(|r| bundle_file(unpack_dir, &r.path))
+            .collect::<Result<Vec<_>>>()?;
+
+        let session_id = Uuid::new_v4().to_string();
+        let attachments_dir = self.get_attachments_dir(&session_id)?;
+        let recordings_dir = self.get_recordings_dir(&session_id)?;
+
+        let result = self.insert_bundle(
+            &session_id,
+            &bundle,
+            &attachment_files,
+            &recording_files,
+            &attachments_dir,
+            &recordings_dir,
+        );
+        if result.is_err() {
+            let _ = fs::remove_dir_all(&attachments_dir);
+            let _ = fs::remove_dir_all(&recordings_dir);
+        }
+        result
+    }
+
+    fn insert_bundle(
+        &self,
+        session_id: &str,
+        bundle: &SessionBundle,
+        attachment_files: &[PathBuf],
+        recording_files: &[PathBuf],
+        attachments_dir: &std::path::Path,
+        recordings_dir: &std::path::Path,
+    ) -> Result<Session> {
+        let now = Utc::now().timestamp();
+        let mut conn = self.get_connection()?;
+        let tx = conn.transaction()?;
+
+        let folder_id = match &bundle.folder {
+            Some(folder) => {
+                let existing: Option<String> = tx
+                    .query_row(
+                        "SELECT id FROM folders WHERE name = ?1 COLLATE NOCASE ORDER BY sort_order LIMIT 1",
+                        params![folder.name],
+                        |row| row.get(0),
+                    )
+                    .optional()?;
+                match existing {
+                    Some(id) => Some(id),
+                    None => {
+                        let id = Uuid::new_v4().to_string();
+                        tx.execute(
+                            "INSERT INTO folders (id, name, color, sort_order, created_at)
+                             VALUES (?1, ?2, ?3, (SELECT COALESCE(MAX(sort_order), 0) + 1 FROM folders), ?4)",
+                            params![id, folder.name, folder.color, now],
+                        )?;
+                        Some(id)
+                    }
+                }
+            }
+            None => None,
+        };
+
+        let session = Session {
+            id: session_id.to_string(),
+            title: bundle.session.title.clone(),
+            started_at: bundle.session.started_at,
+            ended_at: bundle.session.ended_at.or(Some(bundle.session.started_at)),
+            status: "completed".to_string(),
+            folder_id,
+            environment_id: None,
+            deleted_at: None,
+        };
+        tx.execute(
+            "INSERT INTO sessions (id, title, started_at, ended_at, status, folder_id) VALUES (?1, ?2, ?3, ?4, 'completed', ?5)",
+            params![
+                session.id,
+                session.title,
+                session.started_at,
+                session.ended_at,
+                session.folder_id
+            ],
+        )?;
+
+        for tag in &bundle.tags {
+            let existing: Option<String> = tx
+                .query_row(
+                    "SELECT id FROM tags WHERE name = ?1 COLLATE NOCASE LIMIT 1",
+                    params![tag.name],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            let tag_id = match existing {
+                Some(id) => id,
+                None => {
+                    let id = Uuid::new_v4().to_string();
+                    tx.execute(
+                        "INSERT INTO tags (id, name, color) VALUES (?1, ?2, ?3)",
+                        params![id, tag.name, tag.color],
+                    )?;
+                    id
+                }
+            };
+            tx.execute(
+                "INSERT OR IGNORE INTO session_tags (session_id, tag_id) VALUES (?1, ?2)",
+                params![session_id, tag_id],
+            )?;
+        }
+
+        let mut segment_ids = Vec::with_capacity(bundle.segments.len());
+        for segment in &bundle.segments {
+            tx.execute(
+                "INSERT INTO transcript_segments (session_id, text, source, start_ms, end_ms, created_at, speaker)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    session_id,
+                    segment.text,
+                    segment.source,
+                    segment.start_ms,
+                    segment.end_ms,
+                    segment.created_at,
+                    segment.speaker
+                ],
+            )?;
+            let segment_id = tx.last_insert_rowid();
+            segment_ids.push(segment_id);
+            for word in &segment.words {
+                tx.execute(
+                    "INSERT INTO transcript_words (segment_id, text, start_ms, end_ms, estimated) VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![
+                        segment_id,
+                        word.text,
+                        word.start_ms,
+                        word.end_ms,
+                        word.estimated
+                    ],
+                )?;
+            }
+        }
+
+        for speaker in &bundle.speakers {
+            tx.execute(
+                "INSERT INTO session_speakers (session_id, label, name, centroid, segment_count, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    session_id,
+                    speaker.label,
+                    speaker.name,
+                    embedding_to_blob(&speaker.centroid),
+                    speaker.segment_count,
+                    now
+                ],
+            )?;
+        }
+
+        if let Some(notes) = &bundle.notes {
+            tx.execute(
+                "INSERT INTO meeting_notes (session_id, summary, action_items, decisions, user_notes, enhanced_notes, enhanced_notes_edited, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?8)",
+                params![
+                    session_id,
+                    notes.summary,
+                    notes.action_items,
+                    notes.decisions,
+                    notes.user_notes,
+                    notes.enhanced_notes,
+                    notes.enhanced_notes_edited,
+                    now
+                ],
+            )?;
+            snapshot_initial_notes(&tx, session_id)?;
+        }
+
+        insert_bundle_action_items(&tx, session_id, &bundle.action_items, &segment_ids)?;
+        insert_bundle_bookmarks(&tx, session_id, &bundle.bookmarks)?;
+
+        for (attachment, source) in bundle.attachments.iter().zip(attachment_files) {
+            let id = Uuid::new_v4().to_string();
+            let dest_path = attachments_dir.join(format!(
+                "{}_{}",
+                id,
+                sanitize_bundle_name(&attachment.filename)
+            ));
+            let file_size = self.store_file(source, &dest_path)?;
+            tx.execute(
+                "INSERT INTO session_attachments (id, session_id, filename, file_path, mime_type, file_size, extracted_text, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    id,
+                    session_id,
+                    attachment.filename,
+                    dest_path.to_string_lossy().to_string(),
+                    attachment.mime_type,
+                    file_size,
+                    attachment.extracted_text,
+                    attachment.created_at
+                ],
+            )?;
+        }
+
+        for (recording, source) in bundle.recordings.iter().zip(recording_files) {
+            // Keep the bundle's file name unless two passes collide
+            let mut file_name = source
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| format!("{}_{}.wav", recording.created_at, recording.channel));
+            if recordings_dir.join(&file_name).exists() {
+                file_name = format!("{}_{}", Uuid::new_v4(), file_name);
+            }
+            self.store_file(source, &recordings_dir.join(&file_name))?;
+            tx.execute(
+                "INSERT INTO audio_recordings (session_id, file_name, channel, offset_ms, duration_ms, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    session_id,
+                    file_name,
+                    recording.channel,
+                    recording.offset_ms,
+                    recording.duration_ms,
+                    recording.created_at
+                ],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(session)
+    }
+}
+
+/// Audio files being written for one recording pass.
+/// Write errors are logged once and that channel is dropped, so a full disk
+/// never interrupts live transcription.
+pub struct RecordingArchive {
+    mic: Option<ArchivedChannel>,
+    speaker: Option<ArchivedChannel>,
+}
+
+struct ArchivedChannel {
+    recording_id: i64,
+    path: PathBuf,
+    writer: WavStreamWriter,
+}
+
+impl RecordingArchive {
+    pub fn write_mic(&mut self, samples: &[f32]) {
+        Self::write_channel(&mut self.mic, samples);
+    }
+
+    pub fn write_speaker(&mut self, samples: &[f32]) {
+        Self::write_channel(&mut self.speaker, samples);
+    }
+
+    /// Rewrite WAV headers so the files stay playable if the app exits mid-pass
+    pub fn flush(&mut self) {
+        for channel in [&mut self.mic, &mut self.speaker] {
+            if let Some(ch) = channel.as_mut() {
+                if let Err(e) = ch.writer.flush() {
+                    warn!("Failed to flush recording {}: {}", ch.recording_id, e);
+                }
+            }
+        }
+    }
+
+    fn write_channel(channel: &mut Option<ArchivedChannel>, samples: &[f32]) {
+        if samples.is_empty() {
+            return;
+        }
+        if let Some(ch) = channel.as_mut() {
+            if let Err(e) = ch.writer.write(samples) {
+                warn!(
+                    "Failed to write recording {}, stopping archive for this channel: {}",
+                    ch.recording_id, e
+                );
+                *channel = None;
+            }
+        }
+    }
+}
+
+fn load_speaker_centroids(
+    conn: &Connection,
+    session_id: &str,
+) -> Result<Vec<(String, Vec<f32>, i64)>> {
+    let mut stmt = conn.prepare(
+        "SELECT label, centroid, segment_count FROM session_speakers
+         WHERE session_id = ?1 ORDER BY created_at ASC, rowid ASC",
+    )?;
+    let rows = stmt.query_map(params![session_id], |row| {
+        let blob: Vec<u8> = row.get("centroid")?;
+        Ok((
+            row.get::<_, String>("label")?,
+            blob_to_embedding(&blob),
+            row.get::<_, i64>("segment_count")?,
+        ))
+    })?;
+
+    let mut speakers = Vec::new();
+    for row in rows {
+        speakers.push(row?);
+    }
+    Ok(speakers)
+}
+
+/// Load a single segment with its word timings
+fn load_segment(conn: &Connection, segment_id: i64) -> Result<Option<TranscriptSegment>> {
+    let segment = conn
+        .query_row(
+            "SELECT id, session_id, text, source, start_ms, end_ms, created_at, speaker FROM transcript_segments WHERE id = ?1",
+            params![segment_id],
+            |row| {
+                Ok(TranscriptSegment {
+                    id: row.get("id")?,
+                    session_id: row.get("session_id")?,
+                    text: row.get("text")?,
+                    source: row.get("source")?,
+                    start_ms: row.get("start_ms")?,
+                    end_ms: row.get("end_ms")?,
+                    created_at: row.get("created_at")?,
+                    speaker: row.get("speaker")?,
+                    words: Vec::new(),
+                })
+            },
+        )
+        .optional()?;
+
+    let Some(mut segment) = segment else {
+        return Ok(None);
+    };
+
+    let mut stmt = conn.prepare(
+        "SELECT id, segment_id, text, start_ms, end_ms, estimated FROM transcript_words WHERE segment_id = ?1 ORDER BY id",
+    )?;
+    let rows = stmt.query_map(params![segment_id], |row| {
+        Ok(TranscriptWord {
+            id: row.get("id")?,
+            segment_id: row.get("segment_id")?,
+            text: row.get("text")?,
+            start_ms: row.get("start_ms")?,
+            end_ms: row.get("end_ms")?,
+            estimated: row.get("estimated")?,
+        })
+    })?;
+    for row in rows {
+        segment.words.push(row?);
+    }
+
+    Ok(Some(segment))
+}
+
+fn segment_edits(conn: &Connection, session_id: &str) -> Result<Vec<TranscriptSegmentEdit>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, session_id, action, segments_before, segment_ids_after, created_at, revision_id
+         FROM transcript_segment_edits
+         WHERE session_id = ?1
+         ORDER BY id DESC",
+    )?;
+
+    let rows = stmt.query_map(params![session_id], |row| {
+        Ok(TranscriptSegmentEdit {
+            id: row.get("id")?,
+            session_id: row.get("session_id")?,
+            action: row.get("action")?,
+            segments_before: serde_json::from_str(&row.get::<_, String>("segments_before")?)
+                .unwrap_or_default(),
+            segment_ids_after: serde_json::from_str(&row.get::<_, String>("segment_ids_after")?)
+                .unwrap_or_default(),
+            created_at: row.get("created_at")?,
+            revision_id: row.get("revision_id")?,
+        })
+    })?;
+
+    let mut edits = Vec::new();
+    for row in rows {
+        edits.push(row?);
+    }
+    Ok(edits)
+}
+
+/// Put back the segments of the session's most recent edit. Returns the
+/// session, the segments restored and the ids the edit had left behind.
+fn revert_segment_edit(
+    conn: &mut Connection,
+    edit_id: i64,
+) -> Result<(String, Vec<TranscriptSegment>, Vec<i64>)> {
+    let tx = conn.transaction()?;
+
+    let (session_id, before_json, after_json, revision_id) = tx
+        .query_row(
+            "SELECT session_id, segments_before, segment_ids_after, revision_id
+             FROM transcript_segment_edits WHERE id = ?1",
+            params![edit_id],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, Option<i64>>(3)?,
+                ))
+            },
+        )
+        .optional()?
+        .ok_or_else(|| anyhow::anyhow!("Transcript edit not found: {}", edit_id))?;
+    if revision_id.is_some() {
+        return Err(anyhow::anyhow!(
+            "This edit belongs to a transcript that has since been replaced"
+        ));
+    }
+
+    let latest: i64 = tx.query_row(
+        "SELECT MAX(id) FROM transcript_segment_edits WHERE session_id = ?1 AND revision_id IS NULL",
+        params![session_id],
+        |row| row.get(0),
+    )?;
+    if latest != edit_id {
+        return Err(anyhow::anyhow!(
+            "Only the most recent transcript edit can be reverted"
+        ));
+    }
+
+    let before: Vec<TranscriptSegment> = serde_json::from_str(&before_json)?;
+    let after_ids: Vec<i64> = serde_json::from_str(&after_json)?;
+
+    for id in &after_ids {
+        tx.execute(
+            "DELETE FROM transcript_words WHERE segment_id = ?1",
+            params![id],
+        )?;
+        tx.execute("DELETE FROM transcript_segments WHERE id = ?1", params![id])?;
+    }
+    for segment in &before {
+        tx.execute(
+            "INSERT INTO transcript_segments (id, session_id, text, source, start_ms, end_ms, created_at, speaker)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                segment.id,
+                segment.session_id,
+                segment.text,
+                segment.source,
+                segment.start_ms,
+                segment.end_ms,
+                segment.created_at,
+                segment.speaker
+            ],
+        )?;
+        replace_segment_words(&tx, segment.id, &timed_words(&segment.words))?;
+    }
+    tx.execute(
+        "DELETE FROM transcript_segment_edits WHERE id = ?1",
+        params![edit_id],
+    )?;
+    tx.commit()?;
+    Ok((session_id, before, after_ids))
+}
+
+/// Make a revision the live transcript of its session, keeping the current
+/// one as a "replaced" revision. Returns the number of segments applied.
+fn apply_transcript_revision(
+    conn: &mut Connection,
+    session_id: &str,
+    revision_id: i64,
+) -> Result<usize> {
+    let tx = conn.transaction()?;
+
+    // Keep the current transcript as a revision
+    tx.execute(
+        "INSERT INTO transcript_revisions (session_id, kind, model_id, created_at) VALUES (?1, 'replaced', NULL, ?2)",
+        params![session_id, Utc::now().timestamp()],
+    )?;
+    let replaced_id = tx.last_insert_rowid();
+    tx.execute(
+        "INSERT INTO transcript_revision_segments (revision_id, text, source, start_ms, end_ms, speaker, words)
+         SELECT ?1, t.text, t.source, t.start_ms, t.end_ms, t.speaker,
+                COALESCE((SELECT json_group_array(json_object('text', w.text, 'start_ms', w.start_ms, 'end_ms', w.end_ms, 'estimated', json(CASE WHEN w.estimated THEN 'true' ELSE 'false' END)))
+                          FROM (SELECT * FROM transcript_words WHERE segment_id = t.id ORDER BY id) w), '[]')
+         FROM transcript_segments t
+         WHERE t.session_id = ?2
+         ORDER BY t.start_ms, t.id",
+        params![replaced_id, session_id],
+    )?;
+
+    tx.execute(
+        "DELETE FROM transcript_words WHERE segment_id IN (SELECT id FROM transcript_segments WHERE session_id = ?1)",
+        params![session_id],
+    )?;
+    tx.execute(
+        "DELETE FROM transcript_segments WHERE session_id = ?1",
+        params![session_id],
+    )?;
+    // Edits refer to segments that no longer exist. They stay in the
+    // history, marked with the revision that holds the edited transcript,
+    // so the engine's original text isn't lost.
+    tx.execute(
+        "UPDATE transcript_segment_edits SET revision_id = ?1 WHERE session_id = ?2 AND revision_id IS NULL",
+        params![replaced_id, session_id],
+    )?;
+
+    // Copy the revision in as the live transcript
+    let now = Utc::now().timestamp();
+    let mut revision_segments: Vec<(TranscriptRevisionSegment, String)> = Vec::new();
+    {
+        let mut stmt = tx.prepare(
+            "SELECT id, revision_id, text, source, start_ms, end_ms, speaker, words
+             FROM transcript_revision_segments
+             WHERE revision_id = ?1
+             ORDER BY start_ms ASC, id ASC",
+        )?;
+        let rows = stmt.query_map(params![revision_id], |row| {
+            Ok((
+                TranscriptRevisionSegment {
+                    id: row.get("id")?,
+                    revision_id: row.get("revision_id")?,
+                    text: row.get("text")?,
+                    source: row.get("source")?,
+                    start_ms: row.get("start_ms")?,
+                    end_ms: row.get("end_ms")?,
+                    speaker: row.get("speaker")?,
+                },
+                row.get::<_, String>("words")?,
+            ))
+        })?;
+        for row in rows {
+            revision_segments.push(row?);
+        }
+    }
+
+    for (segment, words_json) in &revision_segments {
+        tx.execute(
+            "INSERT INTO transcript_segments (session_id, text, source, start_ms, end_ms, created_at, speaker)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                session_id,
+                segment.text,
+                segment.source,
+                segment.start_ms,
+                segment.end_ms,
+                now,
+                segment.speaker
+            ],
+        )?;
+        let segment_id = tx.last_insert_rowid();
+        for word in words_from_json(words_json) {
+            tx.execute(
+                "INSERT INTO transcript_words (segment_id, text, start_ms, end_ms, estimated) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    segment_id,
+                    word.text,
+                    word.start_ms,
+                    word.end_ms,
+                    word.estimated
+                ],
+            )?;
+        }
+    }
+
+    // Speakers the revision introduced; existing ones keep their centroid
+    tx.execute(
+        "INSERT OR IGNORE INTO session_speakers (session_id, label, centroid, segment_count, created_at)
+         SELECT ?1, label, centroid, segment_count, ?2 FROM transcript_revision_speakers
+         WHERE revision_id = ?3 ORDER BY rowid",
+        params![session_id, now, revision_id],
+    )?;
+
+    // The applied revision now lives in transcript_segments
+    tx.execute(
+        "DELETE FROM transcript_revision_speakers WHERE revision_id = ?1",
+        params![revision_id],
+    )?;
+    tx.execute(
+        "DELETE FROM transcript_revision_segments WHERE revision_id = ?1",
+        params![revision_id],
+    )?;
+    tx.execute(
+        "DELETE FROM transcript_revisions WHERE id = ?1",
+        params![revision_id],
+    )?;
+    tx.commit()?;
+    Ok(revision_segments.len())
+}
+
+fn replace_segment_words(conn: &Connection, segment_id: i64, words: &[TimedWord]) -> Result<()> {
+    conn.execute(
+        "DELETE FROM transcript_words WHERE segment_id = ?1",
+        params![segment_id],
+    )?;
+    let mut stmt = conn.prepare(
+        "INSERT INTO transcript_words (segment_id, text, start_ms, end_ms, estimated) VALUES (?1, ?2, ?3, ?4, ?5)",
+    )?;
+    for word in words {
+        stmt.execute(params![
+            segment_id,
+            word.text,
+            word.start_ms,
+            word.end_ms,
+            word.estimated
+        ])?;
+    }
+    Ok(())
+}
+
+fn record_segment_edit(
+    conn: &Connection,
+    session_id: &str,
+    action: &str,
+    before: &[TranscriptSegment],
+    after_ids: &[i64],
+) -> Result<i64> {
+    conn.execute(
+        "INSERT INTO transcript_segment_edits (session_id, action, segments_before, segment_ids_after, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            session_id,
+            action,
+            serde_json::to_string(before)?,
+            serde_json::to_string(after_ids)?,
+            Utc::now().timestamp()
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+fn timed_words(words: &[TranscriptWord]) -> Vec<TimedWord> {
+    words
+        .iter()
+        .map(|w| TimedWord {
+            text: w.text.clone(),
+            start_ms: w.start_ms,
+            end_ms: w.end_ms,
+            estimated: w.estimated,
+        })
+        .collect()
+}
+
+/// The two halves of a segment split before a given word
+#[derive(Debug, PartialEq)]
+struct SegmentSplit {
+    first_text: String,
+    second_text: String,
+    split_ms: i64,
+    first_words: Vec<TimedWord>,
+    second_words: Vec<TimedWord>,
+}
+
+/// Split segment text before the word at `index`. The split time comes from the
+/// word timings when they line up with the text, otherwise from spreading the
+/// text across the segment. Returns None if either half would be empty.
+fn split_at_word(
+    text: &str,
+    words: &[TimedWord],
+    start_ms: i64,
+    end_ms: i64,
+    index: usize,
+) -> Option<SegmentSplit> {
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    if index == 0 || index >= tokens.len() {
+        return None;
+    }
+
+    let timed = if words.len() == tokens.len() {
+        words.to_vec()
+    } else {
+        spread_words(text, start_ms, end_ms)
+    };
+    let split_ms = timed[index].start_ms.clamp(start_ms, end_ms);
+
+    Some(SegmentSplit {
+        first_text: tokens[..index].join(" "),
+        second_text: tokens[index..].join(" "),
+        split_ms,
+        first_words: timed[..index].to_vec(),
+        second_words: timed[index..].to_vec(),
+    })
+}
+
+/// Per-connection setup for the pool. `foreign_keys` is off by default in
+/// SQLite and has to be enabled on every connection for the ON DELETE CASCADE
+/// clauses to take effect.
+/// Scheduled sessions whose meeting ended before `ended_before` and that have
+/// no notes or attachments. Sessions whose event left the calendar go by their
+/// scheduled start.
+fn expired_scheduled_session_ids(conn: &Connection, ended_before: i64) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(
+        "SELECT s.id FROM sessions s
+         WHERE s.status = 'scheduled'
+           AND COALESCE(
+                 (SELECT MAX(MAX(e.starts_at, e.ends_at)) FROM calendar_events e WHERE e.session_id = s.id),
+                 s.started_at
+               ) < ?1
+           AND NOT EXISTS (SELECT 1 FROM meeting_notes n WHERE n.session_id = s.id AND COALESCE(n.user_notes, '') != '')
+           AND NOT EXISTS (SELECT 1 FROM session_attachments a WHERE a.session_id = s.id)",
+    )?;
+    let rows = stmt.query_map(params![ended_before], |row| row.get::<_, String>(0))?;
+
+    let mut ids = Vec::new();
+    for row in rows {
+        ids.push(row?);
+    }
+    Ok(ids)
+}
+
+fn configure_connection(conn: &mut Connection) -> rusqlite::Result<()> {
+    conn.pragma_update(None, "foreign_keys", true)?;
+    // Recording, the UI and background jobs write concurrently; wait for the
+    // lock instead of failing with SQLITE_BUSY
+    conn.busy_timeout(std::time::Duration::from_secs(5))?;
+    conn.set_prepared_statement_cache_capacity(DB_STATEMENT_CACHE_CAPACITY);
+    Ok(())
+}
+
+fn decrypted_attachments_dir() -> PathBuf {
+    std::env::temp_dir().join("talky-attachments")
+}
+
+fn map_notes_revision(row: &rusqlite::Row) -> rusqlite::Result<MeetingNotesRevision> {
+    Ok(MeetingNotesRevision {
+        id: row.get("id")?,
+        session_id: row.get("session_id")?,
+        kind: row.get("kind")?,
+        user_notes: row.get("user_notes")?,
+        enhanced_notes: row.get("enhanced_notes")?,
+        created_at: row.get("created_at")?,
+        updated_at: row.get("updated_at")?,
+    })
+}
+
+/// Notes saved before history existed get an "initial" snapshot the first time
+/// they change, so the pre-upgrade content isn't lost.
+fn snapshot_initial_notes(conn: &Connection, session_id: &str) -> Result<()> {
+    conn.execute(
+        "INSERT INTO meeting_notes_revisions (session_id, kind, user_notes, enhanced_notes, created_at, updated_at)
+         SELECT session_id, 'initial', user_notes, enhanced_notes, updated_at, updated_at
+         FROM meeting_notes
+         WHERE session_id = ?1
+           AND (COALESCE(user_notes, '') != '' OR COALESCE(enhanced_notes, '') != '')
+           AND NOT EXISTS (SELECT 1 FROM meeting_notes_revisions WHERE session_id = ?1)",
+        params![session_id],
+    )?;
+    Ok(())
+}
+
+/// Snapshot the session's current user notes and enhanced notes.
+/// An "edit" shortly after another "edit" updates that snapshot in place,
+/// unless it removes most of the text, so an accidental clear can be undone.
+fn snapshot_meeting_notes(conn: &Connection, session_id: &str, kind: &str, now: i64) -> Result<()> {
+    let (user_notes, enhanced_notes): (Option<String>, Option<String>) = conn.query_row(
+        "SELECT user_notes, enhanced_notes FROM meeting_notes WHERE session_id = ?1",
+        params![session_id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+
+    let latest = conn
+        .query_row(
+            "SELECT id, session_id, kind, user_notes, enhanced_notes, created_at, updated_at
+             FROM meeting_notes_revisions WHERE session_id = ?1 ORDER BY id DESC LIMIT 1",
+            params![session_id],
+            map_notes_revision,
+        )
+        .optional()?;
+
+    if let Some(latest) = latest {
+        if latest.user_notes == user_notes && latest.enhanced_notes == enhanced_notes {
+            return Ok(());
+        }
+
+        let notes_len = |u: &Option<String>, e: &Option<String>| {
+            u.as_deref().map_or(0, str::len) + e.as_deref().map_or(0, str::len)
+        };
+        let coalesce = kind == "edit"
+            && latest.kind == "edit"
+            && now - latest.created_at < NOTES_REVISION_COALESCE_SECS
+            && notes_len(&user_notes, &enhanced_notes) * 2
+                >= notes_len(&latest.user_notes, &latest.enhanced_notes);
+        if coalesce {
+            conn.execute(
+                "UPDATE meeting_notes_revisions SET user_notes = ?1, enhanced_notes = ?2, updated_at = ?3 WHERE id = ?4",
+                params![user_notes, enhanced_notes, now, latest.id],
+            )?;
+            return Ok(());
+        }
+    }
+
+    conn.execute(
+        "INSERT INTO meeting_notes_revisions (session_id, kind, user_notes, enhanced_notes, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?5)",
+        params![session_id, kind, user_notes, enhanced_notes, now],
+    )?;
+    Ok(())
+}
+
+fn words_to_json(words: &[TimedWord]) -> String {
+    serde_json::Value::Array(
+        words
+            .iter()
+            .map(|w| {
+                serde_json::json!({
+                    "text": w.text,
+                    "start_ms": w.start_ms,
+                    "end_ms": w.end_ms,
+                    "estimated": w.estimated,
+                })
+            })
+            .collect(),
+    )
+    .to_string()
+}
+
+fn words_from_json(json: &str) -> Vec<TimedWord> {
+    let values: Vec<serde_json::Value> = serde_json::from_str(json).unwrap_or_default();
+    values
+        .iter()
+        .filter_map(|v| {
+            Some(TimedWord {
+                text: v.get("text")?.as_str()?.to_string(),
+                start_ms: v.get("start_ms")?.as_i64()?,
+                end_ms: v.get("end_ms")?.as_i64()?,
+                estimated: v
+                    .get("estimated")
+                    .and_then(|e| e.as_bool())
+                    .unwrap_or(false),
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn embedding_to_blob(embedding: &[f32]) -> Vec<u8> {
+    embedding.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+pub(crate) fn blob_to_embedding(blob: &[u8]) -> Vec<f32> {
+    blob.chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect()
+}
+
+/// Turn free-form search input into an FTS5 MATCH expression.
+/// Every word is quoted so punctuation can't break the query syntax, and the
+/// last word is matched as a prefix so results show up while the user types.
+pub(crate) fn build_fts_query(input: &str) -> Option<String> {
+    let terms: Vec<String> = input
+        .split_whitespace()
+        .map(|term| term.replace('"', ""))
+        .filter(|term| !term.is_empty())
+        .map(|term| format!("\"{}\"", term))
+        .collect();
+
+    if terms.is_empty() {
+        return None;
+    }
+
+    Some(format!("{}*", terms.join(" ")))
+}
+
+/// Full-text hits per index, best first. bm25 depends on each index's own
+/// term statistics, so scores are only compared within an index.
+fn search_full_text(conn: &Connection, query: &str, limit: u32) -> Result<Vec<SearchHit>> {
+    let Some(fts_query) = build_fts_query(query) else {
+        return Ok(Vec::new());
+    };
+
+    let queries = [
+        "SELECT s.id AS session_id, s.title AS session_title, 'transcript' AS kind,
+                ts.id AS segment_id, ts.source AS source, ts.start_ms AS start_ms,
+                NULL AS attachment_id,
+                snippet(transcript_fts, 0, '<mark>', '</mark>', '…', 16) AS snippet,
+                bm25(transcript_fts) AS score
+         FROM transcript_fts
+         JOIN transcript_segments ts ON ts.id = transcript_fts.rowid
+         JOIN sessions s ON s.id = ts.session_id
+         WHERE transcript_fts MATCH ?1 AND s.status != 'deleted'
+         ORDER BY score
+         LIMIT ?2",
+        "SELECT s.id AS session_id, s.title AS session_title, 'notes' AS kind,
+                NULL AS segment_id, NULL AS source, NULL AS start_ms, NULL AS attachment_id,
+                snippet(notes_fts, -1, '<mark>', '</mark>', '…', 16) AS snippet,
+                bm25(notes_fts) AS score
+         FROM notes_fts
+         JOIN meeting_notes mn ON mn.id = notes_fts.rowid
+         JOIN sessions s ON s.id = mn.session_id
+         WHERE notes_fts MATCH ?1 AND s.status != 'deleted'
+         ORDER BY score
+         LIMIT ?2",
+        "SELECT s.id AS session_id, s.title AS session_title, 'attachment' AS kind,
+                NULL AS segment_id, NULL AS source, NULL AS start_ms,
+                attachments_fts.attachment_id AS attachment_id,
+                snippet(attachments_fts, -1, '<mark>', '</mark>', '…', 16) AS snippet,
+                bm25(attachments_fts) AS score
+         FROM attachments_fts
+         JOIN sessions s ON s.id = attachments_fts.session_id
+         WHERE attachments_fts MATCH ?1 AND s.status != 'deleted'
+         ORDER BY score
+         LIMIT ?2",
+    ];
+
+    let mut sources = Vec::new();
+    for sql in queries {
+        let mut stmt = conn.prepare_cached(sql)?;
+        let rows = stmt.query_map(params![fts_query, limit], |row| {
+            Ok(SearchHit {
+                session_id: row.get("session_id")?,
+                session_title: row.get("session_title")?,
+                kind: row.get("kind")?,
+                segment_id: row.get("segment_id")?,
+                source: row.get("source")?,
+                start_ms: row.get("start_ms")?,
+                attachment_id: row.get("attachment_id")?,
+                snippet: row.get("snippet")?,
+                score: row.get("score")?,
+            })
+        })?;
+        let mut hits = Vec::new();
+        for row in rows {
+            hits.push(row?);
+        }
+        sources.push(hits);
+    }
+    Ok(interleave(sources, limit as usize))
+}
+
+/// Merge lists that are each ordered best first but whose scores can't be
+/// compared with each other: the first of every list, then the second of
+/// every list, and so on, up to `limit` items
+pub(crate) fn interleave<T>(sources: Vec<Vec<T>>, limit: usize) -> Vec<T> {
+    let mut sources: Vec<_> = sources.into_iter().map(Vec::into_iter).collect();
+    let mut merged = Vec::new();
+    loop {
+        let before = merged.len();
+        for source in &mut sources {
+            if merged.len() == limit {
+                return merged;
+            }
+            merged.extend(source.next());
+        }
+        if merged.len() == before {
+            return merged;
+        }
+    }
+}
+
+/// An in-memory sessions.db with every migration applied
+#[cfg(test)]
+pub(crate) fn test_connection() -> Connection {
+    let mut conn = Connection::open_in_memory().unwrap();
+    configure_connection(&mut conn).unwrap();
+    Migrations::new(SESSION_MIGRATIONS.to_vec())
+        .to_latest(&mut conn)
+        .unwrap();
+    conn
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_fts_query_quotes_terms() {
+        assert_eq!(
+            build_fts_query("who said pric"),
+            Some("\"who\" \"said\" \"pric\"*".to_string())
+        );
+    }
+
+    #[test]
+    fn test_build_fts_query_strips_quotes_and_operators() {
+        assert_eq!(
+            build_fts_query("\"roadmap\" OR -x"),
+            Some("\"roadmap\" \"OR\" \"-x\"*".to_string())
+        );
+    }
+
+    #[test]
+    fn test_build_fts_query_empty() {
+        assert_eq!(build_fts_query("   "), None);
+        assert_eq!(build_fts_query("\"\""), None);
+    }
+
+    #[test]
+    fn test_interleave() {
+        assert_eq!(
+            interleave(vec![vec![1, 2, 3], vec![], vec![10, 20]], 4),
+            vec![1, 10, 2, 20]
+        );
+        assert_eq!(interleave(vec![vec![1], vec![10, 20]], 10), vec![1, 10, 20]);
+    }
+
+    #[test]
+    fn test_search_full_text_ranks_each_index_separately() {
+        let conn = test_connection();
+        conn.execute_batch(
+            "INSERT INTO sessions (id, title, started_at) VALUES ('s1', 'Call', 0), ('s2', 'Old', 0);
+             UPDATE sessions SET status = 'deleted' WHERE id = 's2';
+             INSERT INTO transcript_segments (session_id, text, start_ms, end_ms, created_at) VALUES
+                ('s1', 'we mentioned pricing once', 0, 1000, 0),
+                ('s1', 'pricing pricing pricing', 1000, 2000, 0),
+                ('s1', 'nothing to see here', 2000, 3000, 0),
+                ('s2', 'pricing in the trash', 0, 1000, 0);
+             INSERT INTO meeting_notes (session_id, user_notes, created_at, updated_at)
+                VALUES ('s1', 'Pricing is $49 per seat', 0, 0);
+             INSERT INTO session_attachments
+                (id, session_id, filename, file_path, mime_type, file_size, extracted_text, created_at)
+                VALUES ('a1', 's1', 'deck.pdf', 'deck.pdf', 'application/pdf', 1, 'Pricing tiers', 0);",
+        )
+        .unwrap();
+
+        let hits = search_full_text(&conn, "pricing", 10).unwrap();
+        let kinds: Vec<_> = hits.iter().map(|h| h.kind.as_str()).collect();
+        assert_eq!(
+            kinds,
+            vec!["transcript", "notes", "attachment", "transcript"]
+        );
+        assert!(hits.iter().all(|h| h.session_id == "s1"));
+        assert_eq!(hits[0].start_ms, Some(1000));
+        assert_eq!(hits[3].start_ms, Some(0));
+        assert_eq!(hits[2].attachment_id.as_deref(), Some("a1"));
+        assert!(hits[1].snippet.contains("<mark>Pricing</mark>"));
+
+        assert_eq!(search_full_text(&conn, "pricing", 2).unwrap().len(), 2);
+        assert!(search_full_text(&conn, " ", 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_split_at_word_uses_word_timings() {
+        let words = spread_words("one two three four", 0, 4000);
+        let split = split_at_word("one two three four", &words, 0, 4000, 2).unwrap();
+        assert_eq!(split.first_text, "one two");
+        assert_eq!(split.second_text, "three four");
+        assert_eq!(split.split_ms, words[2].start_ms);
+        assert_eq!(split.first_words.len(), 2);
+        assert_eq!(split.second_words.len(), 2);
+    }
+
+    #[test]
+    fn test_apply_transcript_revision_keeps_edit_history() {
+        let mut conn = test_connection();
+        conn.execute_batch(
+            "INSERT INTO sessions (id, title, started_at) VALUES ('call', 'Call', 0);
+             INSERT INTO transcript_segments (id, session_id, text, start_ms, end_ms, created_at)
+                VALUES (1, 'call', 'engine output', 0, 1000, 0);
+             INSERT INTO transcript_revisions (id, session_id, kind, model_id, created_at)
+                VALUES (7, 'call', 'retranscription', 'other-model', 0);
+             INSERT INTO transcript_revision_segments (revision_id, text, source, start_ms, end_ms)
+                VALUES (7, 'new model output', 'mic', 0, 1000);",
+        )
+        .unwrap();
+        let original = load_segment(&conn, 1).unwrap().unwrap();
+        let edit_id = record_segment_edit(&conn, "call", "update", &[original], &[1]).unwrap();
+        conn.execute(
+            "UPDATE transcript_segments SET text = 'edited output' WHERE id = 1",
+            [],
+        )
+        .unwrap();
+
+        assert_eq!(apply_transcript_revision(&mut conn, "call", 7).unwrap(), 1);
+
+        let edits = segment_edits(&conn, "call").unwrap();
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].segments_before[0].text, "engine output");
+        let replaced_id = edits[0].revision_id.unwrap();
+        let kept: String = conn
+            .query_row(
+                "SELECT text FROM transcript_revision_segments WHERE revision_id = ?1",
+                params![replaced_id],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(kept, "edited output");
+
+        // The edit's segments are gone, so it can't be reverted into the new transcript
+        assert!(revert_segment_edit(&mut conn, edit_id).is_err());
+        let live: String = conn
+            .query_row(
+                "SELECT text FROM transcript_segments WHERE session_id = 'call'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(live, "new model output");
+    }
+
+    /// Save `user_notes` as the session's notes and snapshot them as `kind`
+    fn save_notes(conn: &Connection, user_notes: &str, kind: &str, now: i64) {
+        conn.execute(
+            "INSERT INTO meeting_notes (session_id, user_notes, created_at, updated_at)
+             VALUES ('call', ?1, ?2, ?2)
+             ON CONFLICT(session_id) DO UPDATE SET user_notes = ?1, updated_at = ?2",
+            params![user_notes, now],
+        )
+        .unwrap();
+        snapshot_meeting_notes(conn, "call", kind, now).unwrap();
+    }
+
+    fn notes_revisions(conn: &Connection) -> Vec<(String, String, i64)> {
+        let mut stmt = conn
+            .prepare(
+                "SELECT kind, user_notes, updated_at FROM meeting_notes_revisions
+                 WHERE session_id = 'call' ORDER BY id",
+            )
+            .unwrap();
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap();
+        rows.collect::<rusqlite::Result<_>>().unwrap()
+    }
+
+    #[test]
+    fn test_snapshot_meeting_notes_coalesces_quick_edits() {
+        let conn = test_connection();
+        conn.execute(
+            "INSERT INTO sessions (id, title, started_at) VALUES ('call', 'Call', 0)",
+            [],
+        )
+        .unwrap();
+
+        save_notes(&conn, "Pricing", "edit", 1000);
+        save_notes(&conn, "Pricing is $49", "edit", 1030);
+        // Nothing changed, nothing recorded
+        snapshot_meeting_notes(&conn, "call", "edit", 1040).unwrap();
+        assert_eq!(
+            notes_revisions(&conn),
+            vec![("edit".to_string(), "Pricing is $49".to_string(), 1030)]
+        );
+
+        // The window runs from when the snapshot was taken, not its last update
+        save_notes(
+            &conn,
+            "Pricing is $49 per seat",
+            "edit",
+            1000 + NOTES_REVISION_COALESCE_SECS,
+        );
+        assert_eq!(notes_revisions(&conn).len(), 2);
+
+        // Clearing most of the text keeps what was there
+        let cleared_at = 1000 + NOTES_REVISION_COALESCE_SECS + 10;
+        save_notes(&conn, "P", "edit", cleared_at);
+        let revisions = notes_revisions(&conn);
+        assert_eq!(revisions.len(), 3);
+        assert_eq!(revisions[1].1, "Pricing is $49 per seat");
+
+        // Enhanced output is never merged with edits, in either direction
+        save_notes(&conn, "Pricing: $49 per seat", "enhance", cleared_at + 5);
+        save_notes(
+            &conn,
+            "Pricing: $49 per seat, annual",
+            "edit",
+            cleared_at + 10,
+        );
+        let kinds: Vec<_> = notes_revisions(&conn)
+            .into_iter()
+            .map(|(kind, _, _)| kind)
+            .collect();
+        assert_eq!(kinds, vec!["edit", "edit", "edit", "enhance", "edit"]);
+    }
+
+    #[test]
+    fn test_snapshot_initial_notes_only_before_history() {
+        let conn = test_connection();
+        conn.execute_batch(
+            "INSERT INTO sessions (id, title, started_at) VALUES ('call', 'Call', 0), ('empty', 'Empty', 0);
+             INSERT INTO meeting_notes (session_id, user_notes, created_at, updated_at)
+                VALUES ('call', 'Old notes', 0, 50), ('empty', '', 0, 50);",
+        )
+        .unwrap();
+
+        snapshot_initial_notes(&conn, "call").unwrap();
+        snapshot_initial_notes(&conn, "call").unwrap();
+        snapshot_initial_notes(&conn, "empty").unwrap();
+        assert_eq!(
+            notes_revisions(&conn),
+            vec![("initial".to_string(), "Old notes".to_string(), 50)]
+        );
+        let empty: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM meeting_notes_revisions WHERE session_id = 'empty'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(empty, 0);
+    }
+
+    #[test]
+    fn test_words_json_keeps_estimated_flag() {
+        let mut words = spread_words("one two", 0, 1000);
+        words[0].estimated = false;
+        assert_eq!(words_from_json(&words_to_json(&words)), words);
+
+        // Revisions stored before the flag existed
+        let old = words_from_json(r#"[{"text": "one", "start_ms": 0, "end_ms": 500}]"#);
+        assert!(!old[0].estimated);
+    }
+
+    #[test]
+    fn test_split_at_word_out_of_range() {
+        assert_eq!(split_at_word("one two", &[], 0, 1000, 0), None);
+        assert_eq!(split_at_word("one two", &[], 0, 1000, 2), None);
+    }
+
+    #[test]
+    fn test_expired_scheduled_sessions_skip_used_and_upcoming() {
+        let conn = test_connection();
+        for (id, status, started_at) in [
+            ("missed", "scheduled", 1000),
+            ("running", "scheduled", 1000),
+            ("upcoming", "scheduled", 5000),
+            ("noted", "scheduled", 1000),
+            ("attached", "scheduled", 1000),
+            ("left-calendar", "scheduled", 1000),
+            ("recorded", "completed", 1000),
+        ] {
+            conn.execute(
+                "INSERT INTO sessions (id, title, started_at, status) VALUES (?1, ?1, ?2, ?3)",
+                params![id, started_at, status],
+            )
+            .unwrap();
+        }
+        for (session_id, starts_at, ends_at) in [
+            ("missed", 1000, 1800),
+            ("running", 1000, 4000),
+            ("upcoming", 5000, 5600),
+            ("noted", 1000, 1800),
+            ("attached", 1000, 1800),
+            ("recorded", 1000, 1800),
+        ] {
+            conn.execute(
+                "INSERT INTO calendar_events (id, source, uid, title, session_id, starts_at, ends_at, updated_at)
+                 VALUES (?1, 'work.ics', ?1, ?1, ?1, ?2, ?3, 0)",
+                params![session_id, starts_at, ends_at],
+            )
+            .unwrap();
+        }
+        conn.execute(
+            "INSERT INTO meeting_notes (session_id, user_notes, created_at, updated_at)
+             VALUES ('noted', 'Prep questions', 0, 0)",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO session_attachments (id, session_id, filename, file_path, mime_type, file_size, created_at)
+             VALUES ('a', 'attached', 'agenda.pdf', 'a.pdf', 'application/pdf', 1, 0)",
+            [],
+        )
+        .unwrap();
+
+        let mut ids = expired_scheduled_session_ids(&conn, 3000).unwrap();
+        ids.sort();
+        assert_eq!(ids, vec!["left-calendar", "missed"]);
+    }
 }
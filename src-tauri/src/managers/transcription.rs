@@ -1,4 +1,6 @@
-use crate::audio_toolkit::{apply_custom_words, filter_transcription_output};
+use crate::audio_toolkit::{
+    align_word_timings, apply_custom_words, filter_transcription_output, spread_words, TimedWord,
+};
 use crate::managers::model::{EngineType, ModelManager};
 use crate::settings::{get_settings, ModelUnloadTimeout};
 use crate::utils::MutexExt;
@@ -17,7 +19,7 @@ use transcribe_rs::{
         },
         whisper::{WhisperEngine, WhisperInferenceParams},
     },
-    TranscriptionEngine,
+    TranscriptionEngine, TranscriptionResult,
 };
 
 /// Returns the current timestamp in milliseconds since UNIX epoch.
@@ -37,6 +39,34 @@ pub struct ModelStateEvent {
     pub error: Option<String>,
 }
 
+/// Transcribed text together with per-word timings relative to the start of the chunk
+#[derive(Clone, Debug, Default)]
+pub struct TimedTranscription {
+    pub text: String,
+    pub words: Vec<TimedWord>,
+}
+
+/// Collect word timings from an engine result. Parakeet reports one segment per
+/// word at `TimestampGranularity::Word`; Whisper only reports sentence-level
+/// segments, so their words are spread across each segment's span and marked
+/// as estimated.
+fn engine_words(result: &TranscriptionResult) -> Vec<TimedWord> {
+    let to_ms = |secs: f32| (secs * 1000.0).round() as i64;
+    result
+        .segments
+        .iter()
+        .flatten()
+        .flat_map(|seg| {
+            let mut words = spread_words(&seg.text, to_ms(seg.start), to_ms(seg.end));
+            // A segment of one word carries that word's own timing
+            if let [word] = words.as_mut_slice() {
+                word.estimated = false;
+            }
+            words
+        })
+        .collect()
+}
+
 enum LoadedEngine {
     Whisper(WhisperEngine),
     Parakeet(ParakeetEngine),
@@ -276,6 +306,19 @@ impl TranscriptionManager {
                     })?;
                 LoadedEngine::Parakeet(engine)
             }
+            EngineType::Embedding | EngineType::SpeakerEmbedding => {
+                let error_msg = "Not a transcription model - use Whisper or Parakeet";
+                let _ = self.app_handle.emit(
+                    "model-state-changed",
+                    ModelStateEvent {
+                        event_type: "loading_failed".to_string(),
+                        model_id: Some(model_id.to_string()),
+                        model_name: Some(model_info.name.clone()),
+                        error: Some(error_msg.to_string()),
+                    },
+                );
+                return Err(anyhow::anyhow!(error_msg));
+            }
             EngineType::Moonshine => {
                 let error_msg = "Moonshine models no longer supported - use Whisper or Parakeet";
                 let _ = self.app_handle.emit(
@@ -354,7 +397,23 @@ impl TranscriptionManager {
         current_model.clone()
     }
 
-    pub fn transcribe_chunk(&self, audio: Vec<f32>) -> Result<String> {
+    pub fn transcribe_chunk(
+        &self,
+        audio: Vec<f32>,
+        session_vocabulary: &[String],
+    ) -> Result<String> {
+        self.transcribe_chunk_timed(audio, session_vocabulary)
+            .map(|t| t.text)
+    }
+
+    /// Like `transcribe_chunk`, but also returns the engine's word timings,
+    /// aligned to the corrected and filtered text. `session_vocabulary` (e.g.
+    /// meeting attendees) is corrected towards alongside the custom words.
+    pub fn transcribe_chunk_timed(
+        &self,
+        audio: Vec<f32>,
+        session_vocabulary: &[String],
+    ) -> Result<TimedTranscription> {
         info!(
             "transcribe_chunk called with {} samples ({:.2}s)",
             audio.len(),
@@ -367,7 +426,7 @@ impl TranscriptionManager {
 
         if audio.is_empty() {
             debug!("transcribe_chunk: empty audio, returning empty string");
-            return Ok(String::new());
+            return Ok(TimedTranscription::default());
         }
 
         // Wait for any ongoing model loading to complete
@@ -388,7 +447,7 @@ impl TranscriptionManager {
 
         // Acquire engine lock for the entire transcription to prevent the idle
         // watcher from unloading the model mid-transcription (fixes TOCTOU race)
-        let result = {
+        let (result, words) = {
             let mut engine_guard = self.engine.lock_or_recover();
 
             // Update activity timestamp again while holding lock to prevent
@@ -445,13 +504,14 @@ impl TranscriptionManager {
                         },
                         result.text.len()
                     );
-                    result.text
+                    let words = engine_words(&result);
+                    (result.text, words)
                 }
                 LoadedEngine::Parakeet(parakeet_engine) => {
                     debug!("Calling parakeet.transcribe: {} samples", audio.len());
 
                     let params = ParakeetInferenceParams {
-                        timestamp_granularity: TimestampGranularity::Segment,
+                        timestamp_granularity: TimestampGranularity::Word,
                         ..Default::default()
                     };
 
@@ -469,17 +529,23 @@ impl TranscriptionManager {
                         },
                         result.text.len()
                     );
-                    result.text
+                    let words = engine_words(&result);
+                    (result.text, words)
                 }
             }
         };
 
-        // Apply word correction if custom words or user name are configured
+        // Apply word correction if custom words, user name or session words are configured
         let mut vocab = settings.custom_words.clone();
         let user_name = settings.user_name.trim().to_string();
         if !user_name.is_empty() && !vocab.contains(&user_name) {
             vocab.push(user_name);
         }
+        for word in session_vocabulary {
+            if !vocab.contains(word) {
+                vocab.push(word.clone());
+            }
+        }
         let corrected = if !vocab.is_empty() {
             apply_custom_words(
                 &result,
@@ -496,7 +562,8 @@ impl TranscriptionManager {
             debug!("Chunk transcribed: {}", text);
         }
 
-        Ok(text)
+        let words = align_word_timings(&text, &words);
+        Ok(TimedTranscription { text, words })
     }
 }
 
@@ -517,3 +584,38 @@ impl Drop for TranscriptionManager {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use transcribe_rs::TranscriptionSegment;
+
+    #[test]
+    fn test_engine_words_marks_spread_timings_as_estimated() {
+        let segment = |start: f32, end: f32, text: &str| TranscriptionSegment {
+            start,
+            end,
+            text: text.to_string(),
+        };
+        let result = TranscriptionResult {
+            text: String::new(),
+            segments: Some(vec![
+                segment(0.0, 0.4, "Hello"),
+                segment(0.5, 1.5, "two words"),
+            ]),
+        };
+
+        let words: Vec<_> = engine_words(&result)
+            .into_iter()
+            .map(|w| (w.text, w.start_ms, w.estimated))
+            .collect();
+        assert_eq!(
+            words,
+            vec![
+                ("Hello".to_string(), 0, false),
+                ("two".to_string(), 500, true),
+                ("words".to_string(), 875, true)
+            ]
+        );
+    }
+}
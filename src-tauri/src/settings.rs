@@ -81,6 +81,22 @@ pub struct LLMPrompt {
     pub prompt: String,
 }
 
+/// A user-defined export layout; see `document::template::TemplateContext`
+/// for what `template` can use
+#[derive(Serialize, Deserialize, Debug, Clone, Type)]
+pub struct ExportTemplate {
+    pub id: String,
+    pub name: String,
+    /// Extension of the exported files, without the dot
+    #[serde(default = "default_export_template_extension")]
+    pub extension: String,
+    pub template: String,
+}
+
+fn default_export_template_extension() -> String {
+    "md".to_string()
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Type)]
 pub struct PostProcessProvider {
     pub id: String,
@@ -169,6 +185,30 @@ impl ModelUnloadTimeout {
     }
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupInterval {
+    Never,
+    Daily,
+    Weekly,
+}
+
+impl Default for BackupInterval {
+    fn default() -> Self {
+        BackupInterval::Never
+    }
+}
+
+impl BackupInterval {
+    pub fn to_seconds(self) -> Option<u64> {
+        match self {
+            BackupInterval::Never => None,
+            BackupInterval::Daily => Some(24 * 60 * 60),
+            BackupInterval::Weekly => Some(7 * 24 * 60 * 60),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
 #[serde(rename_all = "snake_case")]
 pub enum FontSize {
@@ -237,6 +277,24 @@ pub struct AppSettings {
     pub history_limit: usize,
     #[serde(default = "default_recording_retention_period")]
     pub recording_retention_period: RecordingRetentionPeriod,
+    /// Days a deleted session stays in the trash before it is purged (0 = forever)
+    #[serde(default = "default_trash_retention_days")]
+    pub trash_retention_days: u32,
+    /// Folder scheduled backups are written to; no scheduled backups while unset
+    #[serde(default)]
+    pub backup_directory: Option<String>,
+    #[serde(default)]
+    pub backup_interval: BackupInterval,
+    /// Backups kept in `backup_directory` when rotating (0 = keep all)
+    #[serde(default = "default_backup_keep_count")]
+    pub backup_keep_count: u32,
+    /// Folder watched for .ics files whose events become scheduled sessions
+    #[serde(default)]
+    pub calendar_folder: Option<String>,
+    /// Global shortcut that bookmarks the running recording. Off (None) until
+    /// the user sets one, since it takes the key combination from every other app
+    #[serde(default)]
+    pub bookmark_shortcut: Option<String>,
     #[serde(default = "default_post_process_enabled")]
     pub post_process_enabled: bool,
     #[serde(default = "default_post_process_providers")]
@@ -245,6 +303,8 @@ pub struct AppSettings {
     pub post_process_prompts: Vec<LLMPrompt>,
     #[serde(default)]
     pub post_process_selected_prompt_id: Option<String>,
+    #[serde(default)]
+    pub export_templates: Vec<ExportTemplate>,
     #[serde(default = "default_app_language")]
     pub app_language: String,
     #[serde(default)]
@@ -343,6 +403,14 @@ fn default_recording_retention_period() -> RecordingRetentionPeriod {
     RecordingRetentionPeriod::PreserveLimit
 }
 
+fn default_trash_retention_days() -> u32 {
+    30
+}
+
+fn default_backup_keep_count() -> u32 {
+    7
+}
+
 fn default_post_process_enabled() -> bool {
     false
 }
@@ -595,10 +663,17 @@ pub fn get_default_settings() -> AppSettings {
         word_correction_threshold: default_word_correction_threshold(),
         history_limit: default_history_limit(),
         recording_retention_period: default_recording_retention_period(),
+        trash_retention_days: default_trash_retention_days(),
+        backup_directory: None,
+        backup_interval: BackupInterval::default(),
+        backup_keep_count: default_backup_keep_count(),
+        calendar_folder: None,
+        bookmark_shortcut: None,
         post_process_enabled: default_post_process_enabled(),
         post_process_providers: default_post_process_providers(),
         post_process_prompts: default_post_process_prompts(),
         post_process_selected_prompt_id: None,
+        export_templates: Vec::new(),
         app_language: default_app_language(),
         experimental_enabled: false,
         copy_as_bullets_enabled: false,
@@ -0,0 +1,96 @@
+//! Global shortcut that bookmarks the current moment of a recording, so the
+//! user can flag a moment without leaving the call window.
+
+use crate::managers::session::SessionManager;
+use crate::settings::get_settings;
+use log::{debug, info, warn};
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+/// Install the global shortcut plugin and register the configured bookmark shortcut
+pub fn init(app: &AppHandle) {
+    let plugin = tauri_plugin_global_shortcut::Builder::new()
+        .with_handler(|app, shortcut, event| {
+            if event.state() == ShortcutState::Pressed && is_bookmark_shortcut(app, shortcut) {
+                add_bookmark(app);
+            }
+        })
+        .build();
+    if let Err(e) = app.plugin(plugin) {
+        warn!("Failed to set up global shortcuts: {}", e);
+        return;
+    }
+
+    if let Some(shortcut) = get_settings(app).bookmark_shortcut {
+        if let Err(e) = register(app, &shortcut) {
+            warn!("Failed to register bookmark shortcut {}: {}", shortcut, e);
+        }
+    }
+}
+
+fn parse(shortcut: &str) -> Result<Shortcut, String> {
+    shortcut
+        .parse()
+        .map_err(|e| format!("Invalid shortcut {}: {}", shortcut, e))
+}
+
+fn register(app: &AppHandle, shortcut: &str) -> Result<(), String> {
+    app.global_shortcut()
+        .register(parse(shortcut)?)
+        .map_err(|e| e.to_string())
+}
+
+fn unregister(app: &AppHandle, shortcut: &str) {
+    let result = parse(shortcut).and_then(|shortcut| {
+        app.global_shortcut()
+            .unregister(shortcut)
+            .map_err(|e| e.to_string())
+    });
+    if let Err(e) = result {
+        debug!("Failed to unregister shortcut {}: {}", shortcut, e);
+    }
+}
+
+/// Swap the registered bookmark shortcut from `previous` to `shortcut` (None
+/// turns it off). If the new one can't be registered, e.g. because another app
+/// holds it, the previous one is put back.
+pub fn change_bookmark_shortcut(
+    app: &AppHandle,
+    previous: Option<&str>,
+    shortcut: Option<&str>,
+) -> Result<(), String> {
+    if let Some(previous) = previous {
+        unregister(app, previous);
+    }
+    let Some(shortcut) = shortcut else {
+        info!("Bookmark shortcut turned off");
+        return Ok(());
+    };
+    if let Err(e) = register(app, shortcut) {
+        if let Some(previous) = previous {
+            let _ = register(app, previous);
+        }
+        return Err(e);
+    }
+    info!("Bookmark shortcut set to {}", shortcut);
+    Ok(())
+}
+
+fn is_bookmark_shortcut(app: &AppHandle, shortcut: &Shortcut) -> bool {
+    get_settings(app)
+        .bookmark_shortcut
+        .is_some_and(|bookmark| parse(&bookmark).is_ok_and(|bookmark| bookmark == *shortcut))
+}
+
+/// Bookmark the current moment of the running recording, if there is one
+fn add_bookmark(app: &AppHandle) {
+    let sm = app.state::<Arc<SessionManager>>();
+    let Some(session_id) = sm.get_active_session_id() else {
+        debug!("Bookmark shortcut pressed while not recording");
+        return;
+    };
+    if let Err(e) = sm.create_bookmark(&session_id, None, None, None) {
+        warn!("Failed to bookmark session {}: {}", session_id, e);
+    }
+}
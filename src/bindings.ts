@@ -96,6 +96,30 @@ async deletePostProcessPrompt(id: string) : Promise<Result<null, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+async addExportTemplate(name: string, extension: string, template: string) : Promise<Result<ExportTemplate, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("add_export_template", { name, extension, template }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateExportTemplate(id: string, name: string, extension: string, template: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_export_template", { id, name, extension, template }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async deleteExportTemplate(id: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_export_template", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async setPostProcessSelectedPrompt(id: string) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("set_post_process_selected_prompt", { id }) };
@@ -179,6 +203,68 @@ async changeUpdateChecksSetting(enabled: boolean) : Promise<Result<null, string>
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Set how many days deleted sessions stay in the trash (0 keeps them forever)
+ * and purge anything that is now past that.
+ */
+async changeTrashRetentionDaysSetting(days: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_trash_retention_days_setting", { days }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeBackupDirectorySetting(dir: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_backup_directory_setting", { dir }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeBackupIntervalSetting(interval: BackupInterval) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_backup_interval_setting", { interval }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set how many backups to keep (0 keeps all) and rotate the backup folder
+ */
+async changeBackupKeepCountSetting(count: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_backup_keep_count_setting", { count }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set the folder watched for .ics files and import it right away
+ */
+async changeCalendarFolderSetting(dir: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_calendar_folder_setting", { dir }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set the global shortcut that bookmarks the running recording. The setting is
+ * only saved once the shortcut is registered.
+ */
+async changeBookmarkShortcutSetting(shortcut: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_bookmark_shortcut_setting", { shortcut }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async changeCopyAsBulletsSetting(enabled: boolean) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("change_copy_as_bullets_setting", { enabled }) };
@@ -347,6 +433,132 @@ async openUserDataDirectory() : Promise<Result<null, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Back up both databases, attachments and archived audio into a timestamped
+ * archive. Old backups in the folder are rotated per `backup_keep_count`.
+ */
+async createBackup(dir: string | null) : Promise<Result<BackupInfo, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_backup", { dir }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async listBackups(dir: string | null) : Promise<Result<BackupInfo[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_backups", { dir }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Replace all current data with a backup. Refused while a session is recording
+ * or an import or re-transcription is running. `passphrase` unlocks an
+ * encrypted backup taken under a different key than the current storage.
+ */
+async restoreBackup(filePath: string, passphrase: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("restore_backup", { filePath, passphrase }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Import the events of an .ics file as scheduled sessions. Importing the same
+ * file again updates them.
+ */
+async importCalendarFile(path: string) : Promise<Result<CalendarImportSummary, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_calendar_file", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Import every .ics file in the calendar folder now, changed or not
+ */
+async syncCalendarFolder() : Promise<Result<CalendarImportSummary, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("sync_calendar_folder") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getSessionCalendarEvent(sessionId: string) : Promise<Result<SessionCalendarEvent | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_session_calendar_event", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Transcript segments and notes paragraphs closest in meaning to `query`, even
+ * when they share no words with it. Runs entirely on-device.
+ */
+async semanticSearch(query: string, limit: number | null) : Promise<Result<SemanticHit[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("semantic_search", { query, limit }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getStorageEncryptionStatus() : Promise<StorageEncryptionStatus> {
+    return await TAURI_INVOKE("get_storage_encryption_status");
+},
+/**
+ * Unlock encrypted session storage. Startup housekeeping that needs the database
+ * is run here since it was skipped while locked.
+ */
+async unlockStorage(passphrase: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("unlock_storage", { passphrase }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Lock encrypted session storage until the passphrase is entered again.
+ * Decrypted attachment copies are deleted.
+ */
+async lockStorage() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("lock_storage") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Encrypt the session database, attachments and archived recordings with a key
+ * derived from `passphrase`. The passphrase can't be recovered if it is lost.
+ */
+async enableStorageEncryption(passphrase: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("enable_storage_encryption", { passphrase }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Convert encrypted storage back to plaintext files
+ */
+async disableStorageEncryption(passphrase: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("disable_storage_encryption", { passphrase }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
  * Check if Apple Intelligence is available on this device.
  * Called by the frontend when the user selects Apple Intelligence provider.
@@ -375,6 +587,14 @@ async getAvailableModels() : Promise<Result<ModelInfo[], string>> {
     else return { status: "error", error: e  as any };
 }
 },
+async getFeatureModels() : Promise<Result<ModelInfo[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_feature_models") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async getModelInfo(modelId: string) : Promise<Result<ModelInfo | null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("get_model_info", { modelId }) };
@@ -649,6 +869,31 @@ async stopSessionRecording(sessionId: string) : Promise<Result<null, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Import an audio file (WAV, FLAC, MP3 or OGG) as a new, completed session.
+ * Transcription runs in the background and reports through the
+ * `audio-import-progress` and `audio-import-complete` events.
+ * With `split_stereo`, the left channel is treated as mic and the right as speaker.
+ */
+async importAudioFile(filePath: string, title: string | null, splitStereo: boolean) : Promise<Result<Session, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_audio_file", { filePath, title, splitStereo }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Cancel the running audio import. Segments transcribed so far are kept.
+ */
+async cancelAudioImport() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cancel_audio_import") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
  * Reactivate a completed session so it can record again.
  */
@@ -676,6 +921,18 @@ async searchSessions(query: string) : Promise<Result<Session[], string>> {
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Ranked full-text search over transcripts, notes and attachment text.
+ * Transcript hits carry the segment id and start_ms so the UI can jump to them.
+ */
+async searchFullText(query: string, limit: number | null) : Promise<Result<SearchHit[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("search_full_text", { query, limit }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async getSessions() : Promise<Result<Session[], string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("get_sessions") };
@@ -700,193 +957,611 @@ async getSessionTranscript(sessionId: string) : Promise<Result<TranscriptSegment
     else return { status: "error", error: e  as any };
 }
 },
-async getActiveSession() : Promise<Result<Session | null, string>> {
+/**
+ * Fix a segment's text and/or move it to the other source. Omitted fields
+ * are left unchanged.
+ */
+async updateTranscriptSegment(segmentId: number, text: string | null, source: string | null) : Promise<Result<TranscriptSegment, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_active_session") };
+    return { status: "ok", data: await TAURI_INVOKE("update_transcript_segment", { segmentId, text, source }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async deleteSession(sessionId: string) : Promise<Result<null, string>> {
+async deleteTranscriptSegment(segmentId: number) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("delete_session", { sessionId }) };
+    return { status: "ok", data: await TAURI_INVOKE("delete_transcript_segment", { segmentId }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async updateSessionTitle(sessionId: string, title: string) : Promise<Result<null, string>> {
+/**
+ * Split a segment before the word at `word_index` (0-based, whitespace-separated).
+ */
+async splitTranscriptSegment(segmentId: number, wordIndex: number) : Promise<Result<TranscriptSegment[], string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("update_session_title", { sessionId, title }) };
+    return { status: "ok", data: await TAURI_INVOKE("split_transcript_segment", { segmentId, wordIndex }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async updateSessionEnvironment(sessionId: string, environmentId: string | null) : Promise<Result<null, string>> {
+async mergeTranscriptSegments(segmentIds: number[]) : Promise<Result<TranscriptSegment, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("update_session_environment", { sessionId, environmentId }) };
+    return { status: "ok", data: await TAURI_INVOKE("merge_transcript_segments", { segmentIds }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async getMeetingNotes(sessionId: string) : Promise<Result<MeetingNotes | null, string>> {
+async getTranscriptSegmentEdits(sessionId: string) : Promise<Result<TranscriptSegmentEdit[], string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_meeting_notes", { sessionId }) };
+    return { status: "ok", data: await TAURI_INVOKE("get_transcript_segment_edits", { sessionId }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async saveMeetingNotes(sessionId: string, summary: string | null, actionItems: string | null, decisions: string | null, userNotes: string | null) : Promise<Result<null, string>> {
+/**
+ * Undo a transcript edit. Only the session's most recent edit can be reverted.
+ */
+async revertTranscriptSegmentEdit(editId: number) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("save_meeting_notes", { sessionId, summary, actionItems, decisions, userNotes }) };
+    return { status: "ok", data: await TAURI_INVOKE("revert_transcript_segment_edit", { editId }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async saveUserNotes(sessionId: string, notes: string) : Promise<Result<null, string>> {
+/**
+ * List the speakers diarization found in a session's mic audio.
+ */
+async getSessionSpeakers(sessionId: string) : Promise<Result<SessionSpeaker[], string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("save_user_notes", { sessionId, notes }) };
+    return { status: "ok", data: await TAURI_INVOKE("get_session_speakers", { sessionId }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async saveEnhancedNotes(sessionId: string, notes: string) : Promise<Result<null, string>> {
+/**
+ * Rename a diarized speaker. Pass `None` or an empty name to reset it.
+ */
+async renameSessionSpeaker(sessionId: string, label: string, name: string | null) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("save_enhanced_notes", { sessionId, notes }) };
+    return { status: "ok", data: await TAURI_INVOKE("rename_session_speaker", { sessionId, label, name }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async getUserNotes(sessionId: string) : Promise<Result<string | null, string>> {
+/**
+ * Re-transcribe a session's archived audio with another model. Runs in the
+ * background and produces a transcript revision; the live transcript only
+ * changes once the revision is applied.
+ */
+async retranscribeSession(sessionId: string, modelId: string) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_user_notes", { sessionId }) };
+    return { status: "ok", data: await TAURI_INVOKE("retranscribe_session", { sessionId, modelId }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async generateSessionSummary(sessionId: string) : Promise<Result<string, string>> {
+/**
+ * Cancel the running re-transcription. The partial revision is discarded.
+ */
+async cancelRetranscription() : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("generate_session_summary", { sessionId }) };
+    return { status: "ok", data: await TAURI_INVOKE("cancel_retranscription") };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-/**
- * Streaming version of generate_session_summary
- * Emits enhance-notes-chunk events for progressive UI updates
- */
-async generateSessionSummaryStream(sessionId: string) : Promise<Result<null, string>> {
+async getTranscriptRevisions(sessionId: string) : Promise<Result<TranscriptRevision[], string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("generate_session_summary_stream", { sessionId }) };
+    return { status: "ok", data: await TAURI_INVOKE("get_transcript_revisions", { sessionId }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async getSessionSummary(sessionId: string) : Promise<Result<string | null, string>> {
+async getTranscriptRevisionSegments(revisionId: number) : Promise<Result<TranscriptRevisionSegment[], string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_session_summary", { sessionId }) };
+    return { status: "ok", data: await TAURI_INVOKE("get_transcript_revision_segments", { revisionId }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Force-flush any buffered audio through the transcription pipeline.
- * Called before chat so the transcript is as up-to-date as possible.
+ * Replace the session's transcript with a revision. The current transcript
+ * is kept as a revision of its own so the swap can be undone.
  */
-async flushPendingAudio(sessionId: string) : Promise<Result<null, string>> {
+async applyTranscriptRevision(revisionId: number) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("flush_pending_audio", { sessionId }) };
+    return { status: "ok", data: await TAURI_INVOKE("apply_transcript_revision", { revisionId }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async createFolder(name: string, color: string | null) : Promise<Result<Folder, string>> {
+async deleteTranscriptRevision(revisionId: number) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("create_folder", { name, color }) };
+    return { status: "ok", data: await TAURI_INVOKE("delete_transcript_revision", { revisionId }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async updateFolder(folderId: string, name: string, color: string | null) : Promise<Result<null, string>> {
+async getActiveSession() : Promise<Result<Session | null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("update_folder", { folderId, name, color }) };
+    return { status: "ok", data: await TAURI_INVOKE("get_active_session") };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async deleteFolder(folderId: string) : Promise<Result<null, string>> {
+/**
+ * Move a session to the trash. It can be restored until it is purged.
+ */
+async deleteSession(sessionId: string) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("delete_folder", { folderId }) };
+    return { status: "ok", data: await TAURI_INVOKE("delete_session", { sessionId }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async getFolders() : Promise<Result<Folder[], string>> {
+async getTrashedSessions() : Promise<Result<Session[], string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_folders") };
+    return { status: "ok", data: await TAURI_INVOKE("get_trashed_sessions") };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async moveSessionToFolder(sessionId: string, folderId: string | null) : Promise<Result<null, string>> {
+async restoreSession(sessionId: string) : Promise<Result<Session, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("move_session_to_folder", { sessionId, folderId }) };
+    return { status: "ok", data: await TAURI_INVOKE("restore_session", { sessionId }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async getSessionsByFolder(folderId: string | null) : Promise<Result<Session[], string>> {
+/**
+ * Permanently delete a session from the trash, including its attachment files.
+ */
+async purgeSession(sessionId: string) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_sessions_by_folder", { folderId }) };
+    return { status: "ok", data: await TAURI_INVOKE("purge_session", { sessionId }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async createTag(name: string, color: string | null) : Promise<Result<Tag, string>> {
+/**
+ * Permanently delete everything in the trash. Returns how many sessions were removed.
+ */
+async emptyTrash() : Promise<Result<number, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("create_tag", { name, color }) };
+    return { status: "ok", data: await TAURI_INVOKE("empty_trash") };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async updateTag(tagId: string, name: string, color: string | null) : Promise<Result<null, string>> {
+/**
+ * Export a session to a `.talky` bundle. The extension is added when missing.
+ */
+async exportSessionBundle(sessionId: string, filePath: string) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("update_tag", { tagId, name, color }) };
+    return { status: "ok", data: await TAURI_INVOKE("export_session_bundle", { sessionId, filePath }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async deleteTag(tagId: string) : Promise<Result<null, string>> {
+/**
+ * Import a `.talky` bundle as a new session
+ */
+async importSessionBundle(filePath: string) : Promise<Result<Session, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("delete_tag", { tagId }) };
+    return { status: "ok", data: await TAURI_INVOKE("import_session_bundle", { filePath }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async getTags() : Promise<Result<Tag[], string>> {
+async updateSessionTitle(sessionId: string, title: string) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_tags") };
+    return { status: "ok", data: await TAURI_INVOKE("update_session_title", { sessionId, title }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateSessionEnvironment(sessionId: string, environmentId: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_session_environment", { sessionId, environmentId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getMeetingNotes(sessionId: string) : Promise<Result<MeetingNotes | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_meeting_notes", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async saveMeetingNotes(sessionId: string, summary: string | null, actionItems: string | null, decisions: string | null, userNotes: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("save_meeting_notes", { sessionId, summary, actionItems, decisions, userNotes }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async saveUserNotes(sessionId: string, notes: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("save_user_notes", { sessionId, notes }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async saveEnhancedNotes(sessionId: string, notes: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("save_enhanced_notes", { sessionId, notes }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getUserNotes(sessionId: string) : Promise<Result<string | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_user_notes", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getMeetingNotesRevisions(sessionId: string) : Promise<Result<MeetingNotesRevision[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_meeting_notes_revisions", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getMeetingNotesRevision(revisionId: number) : Promise<Result<MeetingNotesRevision | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_meeting_notes_revision", { revisionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Restore user notes and enhanced notes from a revision.
+ */
+async restoreMeetingNotesRevision(revisionId: number) : Promise<Result<MeetingNotes, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("restore_meeting_notes_revision", { revisionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Line diff between two notes revisions, from `from_revision_id` to `to_revision_id`.
+ */
+async diffMeetingNotesRevisions(fromRevisionId: number, toRevisionId: number) : Promise<Result<MeetingNotesDiff, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("diff_meeting_notes_revisions", { fromRevisionId, toRevisionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async generateSessionSummary(sessionId: string) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("generate_session_summary", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Streaming version of generate_session_summary
+ * Emits enhance-notes-chunk events for progressive UI updates
+ */
+async generateSessionSummaryStream(sessionId: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("generate_session_summary_stream", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getSessionSummary(sessionId: string) : Promise<Result<string | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_session_summary", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Force-flush any buffered audio through the transcription pipeline.
+ * Called before chat so the transcript is as up-to-date as possible.
+ */
+async flushPendingAudio(sessionId: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("flush_pending_audio", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Extract action items from the transcript with the session's summarisation
+ * model, replacing earlier extracted items the user hasn't touched
+ */
+async extractActionItems(sessionId: string) : Promise<Result<ActionItem[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("extract_action_items", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getSessionActionItems(sessionId: string) : Promise<Result<ActionItem[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_session_action_items", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Action items across all sessions, e.g. everything open for one owner
+ */
+async queryActionItems(filter: ActionItemFilter) : Promise<Result<ActionItem[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("query_action_items", { filter }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async createActionItem(sessionId: string, text: string, owner: string | null, dueDate: string | null) : Promise<Result<ActionItem, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_action_item", { sessionId, text, owner, dueDate }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateActionItem(itemId: string, text: string, owner: string | null, dueDate: string | null, status: string) : Promise<Result<ActionItem, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_action_item", { itemId, text, owner, dueDate, status }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setActionItemStatus(itemId: string, status: string) : Promise<Result<ActionItem, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_action_item_status", { itemId, status }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async deleteActionItem(itemId: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_action_item", { itemId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Bookmark a moment of a session. Leave `offset_ms` unset to bookmark the
+ * current moment of the running recording.
+ */
+async createBookmark(sessionId: string, offsetMs: number | null, label: string | null, color: string | null) : Promise<Result<Bookmark, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_bookmark", { sessionId, offsetMs, label, color }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateBookmark(bookmarkId: string, label: string | null, color: string | null) : Promise<Result<Bookmark, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_bookmark", { bookmarkId, label, color }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async deleteBookmark(bookmarkId: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_bookmark", { bookmarkId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getSessionBookmarks(sessionId: string) : Promise<Result<Bookmark[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_session_bookmarks", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Transcript segments and bookmarks of a session, for showing them together
+ */
+async getSessionTimeline(sessionId: string) : Promise<Result<SessionTimeline, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_session_timeline", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async createFolder(name: string, color: string | null, parentId: string | null) : Promise<Result<Folder, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_folder", { name, color, parentId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateFolder(folderId: string, name: string, color: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_folder", { folderId, name, color }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async deleteFolder(folderId: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_folder", { folderId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getFolders() : Promise<Result<Folder[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_folders") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async moveFolder(folderId: string, parentId: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("move_folder", { folderId, parentId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async moveSessionToFolder(sessionId: string, folderId: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("move_session_to_folder", { sessionId, folderId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getSessionsByFolder(folderId: string | null) : Promise<Result<Session[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_sessions_by_folder", { folderId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getSessionsInFolderTree(folderId: string) : Promise<Result<Session[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_sessions_in_folder_tree", { folderId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async createSmartFolder(name: string, color: string | null, query: SmartFolderQuery) : Promise<Result<SmartFolder, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_smart_folder", { name, color, query }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateSmartFolder(smartFolderId: string, name: string, color: string | null, query: SmartFolderQuery) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_smart_folder", { smartFolderId, name, color, query }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async deleteSmartFolder(smartFolderId: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_smart_folder", { smartFolderId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getSmartFolders() : Promise<Result<SmartFolder[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_smart_folders") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getSmartFolderSessions(smartFolderId: string) : Promise<Result<Session[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_smart_folder_sessions", { smartFolderId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Run a smart folder query without saving it, for previews while editing
+ */
+async previewSmartFolder(query: SmartFolderQuery) : Promise<Result<Session[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("preview_smart_folder", { query }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async createTag(name: string, color: string | null) : Promise<Result<Tag, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_tag", { name, color }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateTag(tagId: string, name: string, color: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_tag", { tagId, name, color }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async deleteTag(tagId: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_tag", { tagId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getTags() : Promise<Result<Tag[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_tags") };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
@@ -932,6 +1607,86 @@ async getSessionsByTag(tagId: string) : Promise<Result<Session[], string>> {
     else return { status: "error", error: e  as any };
 }
 },
+async getAudioRecordings(sessionId: string) : Promise<Result<AudioRecording[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_audio_recordings", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async createChatThread(sessionId: string, title: string | null) : Promise<Result<ChatThread, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_chat_thread", { sessionId, title }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getChatThreads(sessionId: string) : Promise<Result<ChatThread[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_chat_threads", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async renameChatThread(threadId: string, title: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("rename_chat_thread", { threadId, title }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async deleteChatThread(threadId: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_chat_thread", { threadId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async addChatMessage(threadId: string, role: string, content: string) : Promise<Result<ChatThreadMessage, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("add_chat_message", { threadId, role, content }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getChatMessages(threadId: string) : Promise<Result<ChatThreadMessage[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_chat_messages", { threadId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Transcript segments and notes across sessions that are relevant to a question
+ */
+async retrieveSessionPassages(question: string, scope: SessionScope) : Promise<Result<RetrievedPassage[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("retrieve_session_passages", { question, scope }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Answer a question over all sessions in `scope` with the chat model. The answer
+ * cites its sources with `[n]` markers, which `citations` resolves to the session,
+ * transcript segment and timestamp they came from.
+ */
+async askAcrossSessions(question: string, scope: SessionScope) : Promise<Result<CrossSessionAnswer, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("ask_across_sessions", { question, scope }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async addAttachment(sessionId: string, sourcePath: string, filename: string, mimeType: string) : Promise<Result<Attachment, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("add_attachment", { sessionId, sourcePath, filename, mimeType }) };
@@ -980,17 +1735,101 @@ async extractPdfText(attachmentId: string) : Promise<Result<string, string>> {
     else return { status: "error", error: e  as any };
 }
 },
-async exportNoteAsMarkdown(sessionId: string, filePath: string, includeNotes: boolean, includeEnhanced: boolean, includeTranscript: boolean) : Promise<Result<null, string>> {
+/**
+ * Export a session as Markdown, or through one of the user's export
+ * templates if `template_id` is set, in which case the template decides what
+ * is included
+ */
+async exportNoteAsMarkdown(sessionId: string, filePath: string, includeNotes: boolean, includeEnhanced: boolean, includeTranscript: boolean, includeChat: boolean | null, templateId: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_note_as_markdown", { sessionId, filePath, includeNotes, includeEnhanced, includeTranscript, includeChat, templateId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Export every session as Markdown, or through one of the user's export
+ * templates if `template_id` is set, named with the template's extension
+ */
+async exportAllNotesAsMarkdown(directoryPath: string, includeNotes: boolean, includeEnhanced: boolean, includeTranscript: boolean, includeChat: boolean | null, templateId: string | null) : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_all_notes_as_markdown", { directoryPath, includeNotes, includeEnhanced, includeTranscript, includeChat, templateId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Export a session as a Word or PDF document. Unlike Markdown export, notes
+ * keep the [noted]/[ai] distinction, with [ai] lines shown in grey.
+ */
+async exportNoteAsDocument(sessionId: string, filePath: string, format: DocumentFormat, sections: ExportSections) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_note_as_document", { sessionId, filePath, format, sections }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async exportAllNotesAsDocuments(directoryPath: string, format: DocumentFormat, sections: ExportSections) : Promise<Result<number, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("export_note_as_markdown", { sessionId, filePath, includeNotes, includeEnhanced, includeTranscript }) };
+    return { status: "ok", data: await TAURI_INVOKE("export_all_notes_as_documents", { directoryPath, format, sections }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async exportAllNotesAsMarkdown(directoryPath: string, includeNotes: boolean, includeEnhanced: boolean, includeTranscript: boolean) : Promise<Result<number, string>> {
+/**
+ * Export a session as JSON for other tools, with its JSON Schema written
+ * next to it
+ */
+async exportNoteAsJson(sessionId: string, filePath: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_note_as_json", { sessionId, filePath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Export every session into one NDJSON file, one session per line, with the
+ * JSON Schema of a line written next to it. Sessions are written as they are
+ * read, so large libraries are never held in memory at once. Sessions that
+ * fail to export are logged and skipped. Returns the number exported.
+ */
+async exportAllNotesAsNdjson(filePath: string) : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_all_notes_as_ndjson", { filePath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Sync every session into the Obsidian or Logseq vault at `vault_path`: one
+ * note per session with YAML frontmatter, in directories mirroring its folder.
+ * Only notes whose session changed since the last sync are rewritten, notes of
+ * deleted sessions are removed unless they were edited in the vault, and
+ * notes the user renamed or moved are found again by their frontmatter id.
+ * Notes edited in the vault are never overwritten; they are reported as
+ * conflicts instead.
+ */
+async syncNotesToVault(vaultPath: string, tagStyle: TagStyle, sections: ExportSections) : Promise<Result<VaultSyncResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("sync_notes_to_vault", { vaultPath, tagStyle, sections }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Export a session's transcript as SRT or WebVTT captions. `source` ("mic" or
+ * "speaker") limits the captions to one audio source.
+ */
+async exportSessionSubtitles(sessionId: string, filePath: string, format: SubtitleFormat, source: string | null) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("export_all_notes_as_markdown", { directoryPath, includeNotes, includeEnhanced, includeTranscript }) };
+    return { status: "ok", data: await TAURI_INVOKE("export_session_subtitles", { sessionId, filePath, format, source }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
@@ -1008,22 +1847,137 @@ async exportAllNotesAsMarkdown(directoryPath: string, includeNotes: boolean, inc
 
 /** user-defined types **/
 
+export type ActionItem = { id: string; session_id: string; session_title: string; text: string; owner: string | null; 
+/**
+ * YYYY-MM-DD
+ */
+due_date: string | null; status: string; 
+/**
+ * Transcript segment the item was taken from, if it still exists
+ */
+source_segment_id: number | null; edited: boolean; created_at: number; updated_at: number }
+/**
+ * Filters for listing action items across sessions; unset fields match everything.
+ * Due dates are YYYY-MM-DD and inclusive; items without a due date are left out
+ * when either bound is set.
+ */
+export type ActionItemFilter = { session_id: string | null; status: string | null; 
+/**
+ * Case-insensitive substring of the owner
+ */
+owner: string | null; due_from: string | null; due_to: string | null }
+/**
+ * Where an answer to a cross-session question came from. `label` is the
+ * `[n]` marker used in the answer text.
+ */
+export type AnswerCitation = { label: number; session_id: string; session_title: string; 
+/**
+ * Unset when citing the session's notes
+ */
+segment_id: number | null; start_ms: number | null }
 export type AppSettings = { 
 /**
  * Custom directory for user data (sessions.db, history.db).
  * When None, uses the default app data directory.
  * This allows storing data in iCloud Drive or other backup-friendly locations.
  */
-user_name?: string; data_directory?: string | null; font_size?: FontSize; autostart_enabled?: boolean; update_checks_enabled?: boolean; selected_model?: string; selected_microphone?: string | null; clamshell_microphone?: string | null; selected_output_device?: string | null; translate_to_english?: boolean; selected_language?: string; debug_mode?: boolean; hide_cloud_models?: boolean; log_level?: LogLevel; custom_words?: string[]; model_unload_timeout?: ModelUnloadTimeout; word_correction_threshold?: number; history_limit?: number; recording_retention_period?: RecordingRetentionPeriod; post_process_enabled?: boolean; post_process_providers?: PostProcessProvider[]; post_process_prompts?: LLMPrompt[]; post_process_selected_prompt_id?: string | null; app_language?: string; experimental_enabled?: boolean; copy_as_bullets_enabled?: boolean; word_suggestions?: WordSuggestion[]; dismissed_suggestions?: string[]; word_suggestions_enabled?: boolean; speaker_energy_threshold?: number; skip_mic_on_speaker_energy?: boolean; model_environments?: ModelEnvironment[]; default_environment_id?: string | null; debug_disable_speaker_capture?: boolean; debug_disable_model_loading?: boolean; debug_disable_pill_window?: boolean }
+user_name?: string; data_directory?: string | null; font_size?: FontSize; autostart_enabled?: boolean; update_checks_enabled?: boolean; selected_model?: string; selected_microphone?: string | null; clamshell_microphone?: string | null; selected_output_device?: string | null; translate_to_english?: boolean; selected_language?: string; debug_mode?: boolean; hide_cloud_models?: boolean; log_level?: LogLevel; custom_words?: string[]; model_unload_timeout?: ModelUnloadTimeout; word_correction_threshold?: number; history_limit?: number; recording_retention_period?: RecordingRetentionPeriod; 
+/**
+ * Days a deleted session stays in the trash before it is purged (0 = forever)
+ */
+trash_retention_days?: number; 
+/**
+ * Folder scheduled backups are written to; no scheduled backups while unset
+ */
+backup_directory?: string | null; backup_interval?: BackupInterval; 
+/**
+ * Backups kept in `backup_directory` when rotating (0 = keep all)
+ */
+backup_keep_count?: number; 
+/**
+ * Folder watched for .ics files whose events become scheduled sessions
+ */
+calendar_folder?: string | null; 
+/**
+ * Global shortcut that bookmarks the running recording. Off (None) until
+ * the user sets one, since it takes the key combination from every other app
+ */
+bookmark_shortcut?: string | null; post_process_enabled?: boolean; post_process_providers?: PostProcessProvider[]; post_process_prompts?: LLMPrompt[]; post_process_selected_prompt_id?: string | null; export_templates?: ExportTemplate[]; app_language?: string; experimental_enabled?: boolean; copy_as_bullets_enabled?: boolean; word_suggestions?: WordSuggestion[]; dismissed_suggestions?: string[]; word_suggestions_enabled?: boolean; speaker_energy_threshold?: number; skip_mic_on_speaker_energy?: boolean; model_environments?: ModelEnvironment[]; default_environment_id?: string | null; debug_disable_speaker_capture?: boolean; debug_disable_model_loading?: boolean; debug_disable_pill_window?: boolean }
 export type Attachment = { id: string; session_id: string; filename: string; file_path: string; mime_type: string; file_size: number; extracted_text: string | null; created_at: number }
 export type AudioDevice = { index: string; name: string; is_default: boolean }
-export type EngineType = "Whisper" | "Parakeet" | "Moonshine"
-export type Folder = { id: string; name: string; color: string | null; sort_order: number; created_at: number }
+/**
+ * One archived audio file for a recording pass. Mic and speaker are stored
+ * as separate 16 kHz mono WAV files; `offset_ms` is where the pass starts on
+ * the session timeline (same clock as transcript segment start_ms).
+ */
+export type AudioRecording = { id: number; session_id: string; file_name: string; file_path: string; channel: string; offset_ms: number; duration_ms: number; created_at: number }
+export type BackupInfo = { file_name: string; file_path: string; size_bytes: number; created_at: number }
+export type BackupInterval = "never" | "daily" | "weekly"
+/**
+ * A moment of a session the user marked as important. `offset_ms` is on the
+ * session timeline, like a transcript segment's `start_ms`.
+ */
+export type Bookmark = { id: string; session_id: string; offset_ms: number; label: string | null; color: string | null; created_at: number }
+export type CalendarAttendee = { name: string | null; email: string | null }
+/**
+ * What a calendar import changed
+ */
+export type CalendarImportSummary = { created: number; updated: number; removed: number }
+export type ChatThread = { id: string; session_id: string; title: string; created_at: number; updated_at: number }
+export type ChatThreadMessage = { id: number; thread_id: string; 
+/**
+ * "user" or "assistant"
+ */
+role: string; content: string; created_at: number }
+export type CrossSessionAnswer = { answer: string; citations: AnswerCitation[] }
+/**
+ * One line of a line-based diff. `kind` is "equal", "added" or "removed".
+ */
+export type DiffLine = { kind: string; text: string }
+/**
+ * Formats notes can be exported to besides Markdown
+ */
+export type DocumentFormat = "docx" | "pdf"
+export type EngineType = "Whisper" | "Parakeet" | "Moonshine" | 
+/**
+ * Sentence embeddings for semantic search, not a transcription engine
+ */
+"Embedding" | 
+/**
+ * Voice embeddings for diarization, not a transcription engine
+ */
+"SpeakerEmbedding"
+/**
+ * Which parts of a session an export includes
+ */
+export type ExportSections = { notes: boolean; enhanced: boolean; transcript: boolean; chat?: boolean }
+/**
+ * A user-defined export layout; see `TemplateContext`
+ * for what `template` can use
+ */
+export type ExportTemplate = { id: string; name: string; 
+/**
+ * Extension of the exported files, without the dot
+ */
+extension?: string; template: string }
+export type Folder = { id: string; name: string; color: string | null; sort_order: number; created_at: number; 
+/**
+ * None for top-level folders
+ */
+parent_id: string | null }
 export type FontSize = "small" | "medium" | "large"
 export type HistoryEntry = { id: number; file_name: string; timestamp: number; saved: boolean; title: string; transcription_text: string; post_processed_text: string | null; post_process_prompt: string | null }
 export type LLMPrompt = { id: string; name: string; prompt: string }
 export type LogLevel = "trace" | "debug" | "info" | "warn" | "error"
 export type MeetingNotes = { id: number; session_id: string; summary: string | null; action_items: string | null; decisions: string | null; user_notes: string | null; enhanced_notes: string | null; enhanced_notes_edited: boolean; created_at: number; updated_at: number }
+export type MeetingNotesDiff = { user_notes: DiffLine[]; enhanced_notes: DiffLine[] }
+/**
+ * A snapshot of a session's user notes and enhanced notes. `kind` is "edit"
+ * for user saves (coalesced while typing), "enhance" for AI output, "restore"
+ * when an older revision was restored, or "initial" for notes that existed
+ * before history was kept.
+ */
+export type MeetingNotesRevision = { id: number; session_id: string; kind: string; user_notes: string | null; enhanced_notes: string | null; created_at: number; updated_at: number }
 export type ModelEnvironment = { id: string; name: string; color: string; base_url: string; api_key: string; summarisation_model?: string; chat_model?: string }
 export type ModelInfo = { id: string; name: string; description: string; filename: string; url: string | null; size_mb: number; is_downloaded: boolean; is_downloading: boolean; partial_size: number; is_directory: boolean; engine_type: EngineType; accuracy_score: number; speed_score: number }
 export type ModelLoadStatus = { is_loaded: boolean; current_model: string | null }
@@ -1058,9 +2012,138 @@ appleIntelligence: boolean;
 os: string }
 export type PostProcessProvider = { id: string; label: string; base_url: string; allow_base_url_edit?: boolean; models_endpoint?: string | null }
 export type RecordingRetentionPeriod = "never" | "preserve_limit" | "days_3" | "weeks_2" | "months_3"
-export type Session = { id: string; title: string; started_at: number; ended_at: number | null; status: string; folder_id: string | null; environment_id: string | null }
+/**
+ * A transcript segment or notes excerpt retrieved for a cross-session question.
+ * `kind` is "transcript" or "notes"; segment fields are only set for transcripts.
+ */
+export type RetrievedPassage = { session_id: string; session_title: string; session_started_at: number; kind: string; segment_id: number | null; source: string | null; speaker: string | null; start_ms: number | null; text: string }
+/**
+ * A single ranked full-text search result.
+ * `kind` is "transcript", "notes" or "attachment"; segment fields are only set
+ * for transcript hits and `attachment_id` only for attachment hits.
+ */
+export type SearchHit = { session_id: string; session_title: string; kind: string; segment_id: number | null; source: string | null; start_ms: number | null; attachment_id: string | null; 
+/**
+ * Matched text with hits wrapped in `<mark>` tags
+ */
+snippet: string; 
+/**
+ * bm25 score, lower is more relevant. Only comparable between hits of
+ * the same kind.
+ */
+score: number }
+/**
+ * A transcript segment or notes paragraph close in meaning to a semantic
+ * search query. `kind` is "transcript" or "notes".
+ */
+export type SemanticHit = { session_id: string; session_title: string; kind: string; segment_id: number | null; source: string | null; start_ms: number | null; text: string; 
+/**
+ * Cosine similarity to the query, higher is more relevant
+ */
+score: number }
+export type Session = { id: string; title: string; started_at: number; ended_at: number | null; status: string; folder_id: string | null; environment_id: string | null; 
+/**
+ * Set while the session is in the trash
+ */
+deleted_at: number | null }
+/**
+ * The calendar event a session was scheduled from
+ */
+export type SessionCalendarEvent = { id: string; title: string; description: string | null; location: string | null; starts_at: number; ends_at: number; attendees: CalendarAttendee[] }
+/**
+ * The sessions a cross-session question is asked over; unset fields match everything.
+ * Dates are unix seconds on `started_at`, `date_to` exclusive.
+ */
+export type SessionScope = { 
+/**
+ * Sessions in this folder or any folder below it
+ */
+folder_id: string | null; 
+/**
+ * Sessions must carry all of these tags
+ */
+tag_ids: string[]; date_from: number | null; date_to: number | null }
+export type SessionSpeaker = { session_id: string; label: string; name: string | null; segment_count: number }
+/**
+ * A session's transcript with its bookmarks, both in timeline order
+ */
+export type SessionTimeline = { segments: TranscriptSegment[]; bookmarks: Bookmark[] }
+export type SmartFolder = { id: string; name: string; color: string | null; query: SmartFolderQuery; sort_order: number; created_at: number }
+/**
+ * Filters a smart folder applies; every field that is set must match.
+ * Dates are unix seconds on `started_at`, `date_to` exclusive.
+ */
+export type SmartFolderQuery = { 
+/**
+ * Sessions must carry all of these tags
+ */
+tag_ids: string[]; date_from: number | null; date_to: number | null; environment_id: string | null; 
+/**
+ * Matched against titles, notes, transcripts and attachment text like `search_sessions`
+ */
+text: string | null }
+export type StorageEncryptionStatus = { 
+/**
+ * Whether this build can encrypt storage at all
+ */
+supported: boolean; enabled: boolean; 
+/**
+ * Nothing can be read until `unlock_storage` succeeds
+ */
+locked: boolean }
+export type SubtitleFormat = "srt" | "vtt"
 export type Tag = { id: string; name: string; color: string | null }
-export type TranscriptSegment = { id: number; session_id: string; text: string; source: string; start_ms: number; end_ms: number; created_at: number }
+/**
+ * How tags are written in the body of synced notes
+ */
+export type TagStyle = 
+/**
+ * `#tag`, as Obsidian links tags
+ */
+"hashtags" | 
+/**
+ * `[[tag]]` page links, as in Logseq
+ */
+"wikilinks"
+/**
+ * An alternate transcript for a session. `kind` is "retranscription" for output
+ * of `retranscribe_session`, or "replaced" for a transcript that was swapped
+ * out when another revision was applied.
+ */
+export type TranscriptRevision = { id: number; session_id: string; kind: string; model_id: string | null; segment_count: number; created_at: number }
+export type TranscriptRevisionSegment = { id: number; revision_id: number; text: string; source: string; start_ms: number; end_ms: number; speaker: string | null }
+export type TranscriptSegment = { id: number; session_id: string; text: string; source: string; start_ms: number; end_ms: number; created_at: number; 
+/**
+ * Diarized speaker label (mic segments only), see `session_speakers`
+ */
+speaker: string | null; 
+/**
+ * Word timings on the session timeline. Only loaded by `get_session_transcript`
+ */
+words?: TranscriptWord[] }
+/**
+ * One manual edit of a session's transcript. `action` is "update", "delete",
+ * "split" or "merge". `segments_before` holds the touched segments exactly as
+ * they were, so walking the history back recovers the engine's output.
+ */
+export type TranscriptSegmentEdit = { id: number; session_id: string; action: string; segments_before: TranscriptSegment[]; segment_ids_after: number[]; created_at: number; 
+/**
+ * The "replaced" revision that kept the edited transcript when another
+ * revision was applied. Such edits can no longer be reverted.
+ */
+revision_id: number | null }
+export type TranscriptWord = { id: number; segment_id: number; text: string; start_ms: number; end_ms: number; 
+/**
+ * Spread over the segment rather than timed by the engine, see `TimedWord`
+ */
+estimated?: boolean }
+export type VaultSyncResult = { written: number; unchanged: number; removed: number; 
+/**
+ * Notes edited in the vault since they were synced, which were left as
+ * they are instead of taking the session's changes. Relative to the vault
+ * root.
+ */
+conflicts: string[] }
 export type WordSuggestion = { word: string; source_session_title: string; source_session_id: string }
 
 /** tauri-specta globals **/
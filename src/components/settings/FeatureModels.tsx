@@ -0,0 +1,80 @@
+import React from "react";
+import { useTranslation } from "react-i18next";
+import { SettingsGroup } from "../ui/SettingsGroup";
+import { SettingContainer } from "../ui/SettingContainer";
+import { Button } from "../ui/Button";
+import { useModelStore } from "../../stores/modelStore";
+import { formatModelSize } from "../../lib/utils/format";
+import {
+  getTranslatedModelName,
+  getTranslatedModelDescription,
+} from "../../lib/utils/modelTranslation";
+
+export const FeatureModels: React.FC = () => {
+  const { t } = useTranslation();
+  const {
+    featureModels,
+    downloadModel,
+    deleteModel,
+    isModelDownloading,
+    isModelExtracting,
+    getDownloadProgress,
+  } = useModelStore();
+
+  if (featureModels.length === 0) {
+    return null;
+  }
+
+  return (
+    <SettingsGroup
+      title={t("settings.featureModels.title")}
+      description={t("settings.featureModels.description")}
+    >
+      {featureModels.map((model) => {
+        const progress = getDownloadProgress(model.id);
+
+        let status: string | null = null;
+        if (isModelExtracting(model.id)) {
+          status = t("modelSelector.extractingGeneric");
+        } else if (isModelDownloading(model.id)) {
+          status = t("modelSelector.downloading", {
+            percentage: Math.round(progress?.percentage ?? 0),
+          });
+        }
+
+        return (
+          <SettingContainer
+            key={model.id}
+            title={getTranslatedModelName(model, t)}
+            description={getTranslatedModelDescription(model, t)}
+            descriptionMode="inline"
+            grouped
+          >
+            {status ? (
+              <span className="text-xs text-text/60 tabular-nums">
+                {status}
+              </span>
+            ) : model.is_downloaded ? (
+              <Button
+                variant="secondary"
+                size="sm"
+                onClick={() => deleteModel(model.id)}
+              >
+                {t("settings.featureModels.delete")}
+              </Button>
+            ) : (
+              <Button
+                variant="primary"
+                size="sm"
+                onClick={() => downloadModel(model.id)}
+              >
+                {t("modelSelector.download")} ·{" "}
+                {formatModelSize(Number(model.size_mb))}
+              </Button>
+            )}
+          </SettingContainer>
+        );
+      })}
+    </SettingsGroup>
+  );
+};
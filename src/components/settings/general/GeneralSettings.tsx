@@ -9,6 +9,7 @@ import { useModelStore } from "../../../stores/modelStore";
 import ModelSelector from "../../model-selector";
 import { UserNameSetting } from "./UserNameSetting";
 import { EnvironmentsSection } from "../environments/EnvironmentsSection";
+import { FeatureModels } from "../FeatureModels";
 import { UpdateBanner } from "../../update-checker";
 
 export const GeneralSettings: React.FC = () => {
@@ -33,6 +34,7 @@ export const GeneralSettings: React.FC = () => {
         )}
         <CustomWords descriptionMode="tooltip" grouped />
       </SettingsGroup>
+      <FeatureModels />
       <EnvironmentsSection />
     </div>
   );
@@ -20,3 +20,4 @@ export { RecordingRetentionPeriodSelector } from "./RecordingRetentionPeriod";
 export { AutostartToggle } from "./AutostartToggle";
 export { UpdateChecksToggle } from "./UpdateChecksToggle";
 export { EnvironmentsSection } from "./environments/EnvironmentsSection";
+export { FeatureModels } from "./FeatureModels";
@@ -1,6 +1,10 @@
-import React from "react";
+import React, { useEffect, useState } from "react";
 import { useTranslation } from "react-i18next";
+import { toast } from "sonner";
+import { commands } from "@/bindings";
 import { SettingsGroup } from "../../ui/SettingsGroup";
+import { Button } from "../../ui/Button";
+import { useSettings } from "../../../hooks/useSettings";
 
 const isMac = navigator.platform.toUpperCase().includes("MAC");
 const modKey = isMac ? "\u2318" : "Ctrl";
@@ -29,8 +33,62 @@ const shortcuts: Shortcut[] = [
   { keys: "Esc", actionKey: "settings.keyboard.actions.escape" },
 ];
 
+// Accelerator strings as stored in settings, e.g. "CommandOrControl+Alt+B"
+const formatAccelerator = (accelerator: string) =>
+  accelerator
+    .replace(/CommandOrControl|CmdOrCtrl/g, modKey)
+    .replace(/Control/g, isMac ? "\u2303" : "Ctrl")
+    .replace(/Alt|Option/g, isMac ? "\u2325" : "Alt")
+    .replace(/Shift/g, isMac ? "\u21e7" : "Shift");
+
+// The accelerator for a key press, or null while only modifiers are held.
+// Global shortcuts need a modifier so they don't take plain typing away.
+const toAccelerator = (e: KeyboardEvent): string | null => {
+  if (/^(Shift|Control|Alt|Meta|OS)(Left|Right)?$/.test(e.code)) return null;
+  const modifiers = [
+    (isMac ? e.metaKey : e.ctrlKey) && "CommandOrControl",
+    isMac && e.ctrlKey && "Control",
+    !isMac && e.metaKey && "Super",
+    e.altKey && "Alt",
+    e.shiftKey && "Shift",
+  ].filter(Boolean);
+  if (modifiers.length === 0) return null;
+  const key = e.code.replace(/^(Key|Digit)/, "");
+  return [...modifiers, key].join("+");
+};
+
 export const KeyboardShortcutsSettings: React.FC = () => {
   const { t } = useTranslation();
+  const { getSetting, refreshSettings } = useSettings();
+  const bookmarkShortcut = getSetting("bookmark_shortcut");
+  const [recording, setRecording] = useState(false);
+
+  const saveBookmarkShortcut = async (shortcut: string | null) => {
+    const result = await commands.changeBookmarkShortcutSetting(shortcut);
+    if (result.status === "error") {
+      toast.error(result.error);
+    }
+    await refreshSettings();
+  };
+
+  useEffect(() => {
+    if (!recording) return;
+    const handleKeyDown = (e: KeyboardEvent) => {
+      e.preventDefault();
+      e.stopPropagation();
+      if (e.key === "Escape") {
+        setRecording(false);
+        return;
+      }
+      const accelerator = toAccelerator(e);
+      if (accelerator) {
+        setRecording(false);
+        saveBookmarkShortcut(accelerator);
+      }
+    };
+    window.addEventListener("keydown", handleKeyDown, true);
+    return () => window.removeEventListener("keydown", handleKeyDown, true);
+  }, [recording]);
 
   return (
     <div className="max-w-3xl w-full mx-auto space-y-6">
@@ -49,6 +107,49 @@ export const KeyboardShortcutsSettings: React.FC = () => {
           ))}
         </div>
       </SettingsGroup>
+      <SettingsGroup
+        title={t("settings.keyboard.global.title")}
+        description={t("settings.keyboard.global.description")}
+      >
+        <div className="flex items-center justify-between gap-3 py-3 px-4">
+          <span className="text-sm">
+            {t("settings.keyboard.global.bookmark")}
+          </span>
+          <div className="flex items-center gap-2">
+            {recording ? (
+              <span className="text-xs text-mid-gray">
+                {t("settings.keyboard.global.recording")}
+              </span>
+            ) : (
+              bookmarkShortcut && (
+                <kbd className="px-2 py-1 text-xs font-mono bg-mid-gray/10 rounded border border-mid-gray/20">
+                  {formatAccelerator(bookmarkShortcut)}
+                </kbd>
+              )
+            )}
+            <Button
+              variant="secondary"
+              size="sm"
+              onClick={() => setRecording(!recording)}
+            >
+              {recording
+                ? t("settings.keyboard.global.cancel")
+                : bookmarkShortcut
+                  ? t("settings.keyboard.global.change")
+                  : t("settings.keyboard.global.set")}
+            </Button>
+            {bookmarkShortcut && !recording && (
+              <Button
+                variant="ghost"
+                size="sm"
+                onClick={() => saveBookmarkShortcut(null)}
+              >
+                {t("settings.keyboard.global.clear")}
+              </Button>
+            )}
+          </div>
+        </div>
+      </SettingsGroup>
     </div>
   );
 };
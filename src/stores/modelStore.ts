@@ -12,6 +12,8 @@ interface DownloadProgress {
 
 interface ModelStore {
   models: ModelInfo[];
+  // Models behind features such as semantic search, not for transcription
+  featureModels: ModelInfo[];
   currentModel: string;
   downloadingModels: Set<string>;
   extractingModels: Set<string>;
@@ -54,6 +56,7 @@ interface ModelStore {
 export const useModelStore = create<ModelStore>()(
   subscribeWithSelector((set, get) => ({
     models: [],
+    featureModels: [],
     currentModel: "",
     downloadingModels: new Set(),
     extractingModels: new Set(),
@@ -114,12 +117,18 @@ export const useModelStore = create<ModelStore>()(
     // Actions
     loadModels: async () => {
       try {
-        const result = await commands.getAvailableModels();
+        const [result, featureResult] = await Promise.all([
+          commands.getAvailableModels(),
+          commands.getFeatureModels(),
+        ]);
         if (result.status === "ok") {
           set({ models: result.data, error: null });
         } else {
           set({ error: `Failed to load models: ${result.error}` });
         }
+        if (featureResult.status === "ok") {
+          set({ featureModels: featureResult.data });
+        }
       } catch (err) {
         set({ error: `Failed to load models: ${err}` });
       } finally {
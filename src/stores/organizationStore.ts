@@ -17,7 +17,11 @@ interface OrganizationStore {
   loadTags: () => Promise<void>;
 
   // Folder actions
-  createFolder: (name: string, color?: string) => Promise<Folder | null>;
+  createFolder: (
+    name: string,
+    color?: string,
+    parentId?: string,
+  ) => Promise<Folder | null>;
   updateFolder: (
     folderId: string,
     name: string,
@@ -77,8 +81,12 @@ export const useOrganizationStore = create<OrganizationStore>((set, get) => ({
     }
   },
 
-  createFolder: async (name, color) => {
-    const result = await commands.createFolder(name, color ?? null);
+  createFolder: async (name, color, parentId) => {
+    const result = await commands.createFolder(
+      name,
+      color ?? null,
+      parentId ?? null,
+    );
     if (result.status === "ok") {
       set((s) => ({ folders: [...s.folders, result.data] }));
       return result.data;
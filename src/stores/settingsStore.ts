@@ -120,6 +120,8 @@ const settingUpdaters: {
     commands.changeSpeakerEnergyThresholdSetting(value as number),
   skip_mic_on_speaker_energy: (value) =>
     commands.changeSkipMicOnSpeakerEnergySetting(value as boolean),
+  bookmark_shortcut: (value) =>
+    commands.changeBookmarkShortcutSetting(value as string | null),
 };
 
 export const useSettingsStore = create<SettingsStore>()(